@@ -0,0 +1,599 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Recording draw implementation
+//!
+//! Instead of rendering, all draw operations are recorded as a list of
+//! [`Command`]s, which may be inspected after drawing a frame.
+
+use std::any::Any;
+
+use kas::draw::{
+    Colour, Draw, DrawGradient, DrawImage, DrawPath, DrawRounded, DrawShaded, DrawShared, DrawText,
+    FillRule, Gradient, ImageId, Pass, Path, Stroke,
+};
+use kas::geom::{Quad, Rect, Size, Vec2};
+use kas::text::PreparedText;
+
+/// A recorded draw operation
+///
+/// Each variant corresponds to a method of [`Draw`] or one of its extension
+/// traits, with the same parameters.
+#[derive(Clone, Debug)]
+pub enum Command {
+    /// [`Draw::rect`]
+    Rect { pass: Pass, rect: Quad, col: Colour },
+    /// [`Draw::frame`]
+    Frame {
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        col: Colour,
+    },
+    /// [`DrawRounded::rounded_line`]
+    RoundedLine {
+        pass: Pass,
+        p1: Vec2,
+        p2: Vec2,
+        radius: f32,
+        col: Colour,
+    },
+    /// [`DrawRounded::circle`]
+    Circle {
+        pass: Pass,
+        rect: Quad,
+        inner_radius: f32,
+        col: Colour,
+    },
+    /// [`DrawRounded::rounded_frame`]
+    RoundedFrame {
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        inner_radius: f32,
+        col: Colour,
+    },
+    /// [`DrawShaded::shaded_square`]
+    ShadedSquare {
+        pass: Pass,
+        rect: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    },
+    /// [`DrawShaded::shaded_circle`]
+    ShadedCircle {
+        pass: Pass,
+        rect: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    },
+    /// [`DrawShaded::shaded_square_frame`]
+    ShadedSquareFrame {
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    },
+    /// [`DrawShaded::shaded_round_frame`]
+    ShadedRoundFrame {
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    },
+    /// [`DrawImage::image`]
+    Image { pass: Pass, id: ImageId, rect: Quad },
+    /// [`DrawPath::fill_path`]
+    FillPath {
+        pass: Pass,
+        path: Path,
+        rule: FillRule,
+        col: Colour,
+    },
+    /// [`DrawPath::stroke_path`]
+    StrokePath {
+        pass: Pass,
+        path: Path,
+        stroke: Stroke,
+        col: Colour,
+    },
+    /// [`DrawGradient::gradient_rect`]
+    GradientRect {
+        pass: Pass,
+        rect: Quad,
+        gradient: Gradient,
+    },
+    /// [`DrawGradient::gradient_frame`]
+    GradientFrame {
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        gradient: Gradient,
+    },
+    /// [`DrawGradient::shadow`]
+    Shadow {
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        col: Colour,
+    },
+    /// [`DrawText::text`]
+    ///
+    /// The text is recorded as a plain string; `bounds` is the size of the
+    /// text's bounding box. `prepared` is a copy of the input, used by
+    /// [`DrawWindow::replay`].
+    Text {
+        pass: Pass,
+        pos: Vec2,
+        bounds: Vec2,
+        col: Colour,
+        text: String,
+        prepared: PreparedText,
+    },
+}
+
+impl Command {
+    /// The pass to which this command was issued
+    pub fn pass(&self) -> Pass {
+        match self {
+            Command::Rect { pass, .. }
+            | Command::Frame { pass, .. }
+            | Command::RoundedLine { pass, .. }
+            | Command::Circle { pass, .. }
+            | Command::RoundedFrame { pass, .. }
+            | Command::ShadedSquare { pass, .. }
+            | Command::ShadedCircle { pass, .. }
+            | Command::ShadedSquareFrame { pass, .. }
+            | Command::ShadedRoundFrame { pass, .. }
+            | Command::Image { pass, .. }
+            | Command::FillPath { pass, .. }
+            | Command::StrokePath { pass, .. }
+            | Command::GradientRect { pass, .. }
+            | Command::GradientFrame { pass, .. }
+            | Command::Shadow { pass, .. }
+            | Command::Text { pass, .. } => *pass,
+        }
+    }
+
+    /// Sort key giving the order of drawing, as in `kas-wgpu`
+    ///
+    /// This is the pass index then the index of the pipe drawing the
+    /// command. Text is drawn after all passes.
+    fn batch(&self) -> (usize, u8) {
+        let pipe = match self {
+            Command::Rect { .. }
+            | Command::Frame { .. }
+            | Command::ShadedSquare { .. }
+            | Command::ShadedSquareFrame { .. } => 0,
+            Command::GradientRect { .. }
+            | Command::GradientFrame { .. }
+            | Command::Shadow { .. } => 1,
+            Command::ShadedCircle { .. } | Command::ShadedRoundFrame { .. } => 2,
+            Command::RoundedLine { .. }
+            | Command::Circle { .. }
+            | Command::RoundedFrame { .. }
+            | Command::FillPath { .. }
+            | Command::StrokePath { .. } => 3,
+            Command::Image { .. } => 4,
+            Command::Text { .. } => return (usize::MAX, 0),
+        };
+        (self.pass().pass(), pipe)
+    }
+
+    /// The colour used by this command
+    ///
+    /// Images have no colour; white is returned. Gradients return the colour
+    /// of their first stop.
+    pub fn colour(&self) -> Colour {
+        match self {
+            Command::Image { .. } => Colour::grey(1.0),
+            Command::GradientRect { gradient, .. } | Command::GradientFrame { gradient, .. } => {
+                gradient.colour_at_offset(std::f32::NEG_INFINITY)
+            }
+            Command::Rect { col, .. }
+            | Command::Frame { col, .. }
+            | Command::RoundedLine { col, .. }
+            | Command::Circle { col, .. }
+            | Command::RoundedFrame { col, .. }
+            | Command::ShadedSquare { col, .. }
+            | Command::ShadedCircle { col, .. }
+            | Command::ShadedSquareFrame { col, .. }
+            | Command::ShadedRoundFrame { col, .. }
+            | Command::FillPath { col, .. }
+            | Command::StrokePath { col, .. }
+            | Command::Shadow { col, .. }
+            | Command::Text { col, .. } => *col,
+        }
+    }
+
+    /// Get the text, if this is a [`Command::Text`]
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Command::Text { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Shared draw data
+///
+/// The recording backend does not have any shared state; this type merely
+/// exists to satisfy the [`DrawShared`] bound used by themes.
+#[derive(Debug, Default)]
+pub struct DrawPipe;
+
+impl DrawPipe {
+    /// Construct
+    pub fn new() -> Self {
+        DrawPipe
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self, size: Size) -> DrawWindow {
+        DrawWindow {
+            clip_regions: vec![(Rect::new(Default::default(), size), 0.0)],
+            commands: vec![],
+        }
+    }
+
+    /// Process window resize
+    pub fn resize(&self, window: &mut DrawWindow, size: Size) {
+        window.clip_regions[0].0.size = size;
+    }
+}
+
+impl DrawShared for DrawPipe {
+    type Draw = DrawWindow;
+}
+
+/// Per-window draw data
+///
+/// This records all draw operations of the last frame.
+#[derive(Debug)]
+pub struct DrawWindow {
+    clip_regions: Vec<(Rect, f32)>,
+    commands: Vec<Command>,
+}
+
+impl DrawWindow {
+    /// Clear all recorded commands and clip regions
+    ///
+    /// This is called automatically before drawing each frame.
+    pub fn clear(&mut self) {
+        self.clip_regions.truncate(1);
+        self.commands.clear();
+    }
+
+    /// Access clip regions, with their depth
+    ///
+    /// The first region (pass 0) is always the whole window.
+    pub fn clip_regions(&self) -> &[(Rect, f32)] {
+        &self.clip_regions
+    }
+
+    /// Access recorded commands, in order of issue
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Iterate over all recorded text
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.commands.iter().filter_map(|cmd| cmd.text())
+    }
+
+    /// Replay recorded clip regions and commands to another draw target
+    ///
+    /// This may be used to render a recorded frame via
+    /// [`crate::raster::Canvas`]. Pass 0 of `draw` is assumed to cover the
+    /// whole window; all other clip regions are added to `draw`.
+    ///
+    /// As in `kas-wgpu`, commands are replayed pass by pass (in order of pass
+    /// creation), and within each pass batched by kind in the order documented
+    /// on [`Draw`], otherwise in order of issue. Text is replayed after all
+    /// passes.
+    pub fn replay<D>(&self, draw: &mut D)
+    where
+        D: Draw
+            + DrawRounded
+            + DrawShaded
+            + DrawText
+            + DrawImage
+            + DrawPath
+            + DrawGradient
+            + ?Sized,
+    {
+        let mut passes = Vec::with_capacity(self.clip_regions.len());
+        passes.push(0);
+        for (rect, depth) in &self.clip_regions[1..] {
+            passes.push(draw.add_clip_region(*rect, *depth).pass() as u32);
+        }
+        let map = |pass: Pass| Pass::new_pass_with_depth(passes[pass.pass()], pass.depth());
+
+        let mut commands: Vec<&Command> = self.commands.iter().collect();
+        commands.sort_by_key(|cmd| cmd.batch());
+
+        for cmd in commands {
+            match cmd {
+                Command::Rect { pass, rect, col } => draw.rect(map(*pass), *rect, *col),
+                Command::Frame {
+                    pass,
+                    outer,
+                    inner,
+                    col,
+                } => draw.frame(map(*pass), *outer, *inner, *col),
+                Command::RoundedLine {
+                    pass,
+                    p1,
+                    p2,
+                    radius,
+                    col,
+                } => draw.rounded_line(map(*pass), *p1, *p2, *radius, *col),
+                Command::Circle {
+                    pass,
+                    rect,
+                    inner_radius,
+                    col,
+                } => draw.circle(map(*pass), *rect, *inner_radius, *col),
+                Command::RoundedFrame {
+                    pass,
+                    outer,
+                    inner,
+                    inner_radius,
+                    col,
+                } => draw.rounded_frame(map(*pass), *outer, *inner, *inner_radius, *col),
+                Command::ShadedSquare {
+                    pass,
+                    rect,
+                    norm,
+                    col,
+                } => draw.shaded_square(map(*pass), *rect, *norm, *col),
+                Command::ShadedCircle {
+                    pass,
+                    rect,
+                    norm,
+                    col,
+                } => draw.shaded_circle(map(*pass), *rect, *norm, *col),
+                Command::ShadedSquareFrame {
+                    pass,
+                    outer,
+                    inner,
+                    norm,
+                    col,
+                } => draw.shaded_square_frame(map(*pass), *outer, *inner, *norm, *col),
+                Command::ShadedRoundFrame {
+                    pass,
+                    outer,
+                    inner,
+                    norm,
+                    col,
+                } => draw.shaded_round_frame(map(*pass), *outer, *inner, *norm, *col),
+                Command::Image { pass, id, rect } => draw.image(map(*pass), *id, *rect),
+                Command::FillPath {
+                    pass,
+                    path,
+                    rule,
+                    col,
+                } => draw.fill_path(map(*pass), path, *rule, *col),
+                Command::StrokePath {
+                    pass,
+                    path,
+                    stroke,
+                    col,
+                } => draw.stroke_path(map(*pass), path, stroke, *col),
+                Command::GradientRect {
+                    pass,
+                    rect,
+                    gradient,
+                } => draw.gradient_rect(map(*pass), *rect, gradient),
+                Command::GradientFrame {
+                    pass,
+                    outer,
+                    inner,
+                    gradient,
+                } => draw.gradient_frame(map(*pass), *outer, *inner, gradient),
+                Command::Shadow {
+                    pass,
+                    outer,
+                    inner,
+                    col,
+                } => draw.shadow(map(*pass), *outer, *inner, *col),
+                Command::Text {
+                    pass,
+                    pos,
+                    col,
+                    prepared,
+                    ..
+                } => draw.text(map(*pass), *pos, *col, prepared),
+            }
+        }
+    }
+}
+
+impl Draw for DrawWindow {
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn add_clip_region(&mut self, rect: Rect, depth: f32) -> Pass {
+        let pass = self.clip_regions.len();
+        self.clip_regions.push((rect, depth));
+        Pass::new_pass_with_depth(pass as u32, depth)
+    }
+
+    fn rect(&mut self, pass: Pass, rect: Quad, col: Colour) {
+        self.commands.push(Command::Rect { pass, rect, col });
+    }
+
+    fn frame(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour) {
+        self.commands.push(Command::Frame {
+            pass,
+            outer,
+            inner,
+            col,
+        });
+    }
+}
+
+impl DrawRounded for DrawWindow {
+    fn rounded_line(&mut self, pass: Pass, p1: Vec2, p2: Vec2, radius: f32, col: Colour) {
+        self.commands.push(Command::RoundedLine {
+            pass,
+            p1,
+            p2,
+            radius,
+            col,
+        });
+    }
+
+    fn circle(&mut self, pass: Pass, rect: Quad, inner_radius: f32, col: Colour) {
+        self.commands.push(Command::Circle {
+            pass,
+            rect,
+            inner_radius,
+            col,
+        });
+    }
+
+    fn rounded_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        inner_radius: f32,
+        col: Colour,
+    ) {
+        self.commands.push(Command::RoundedFrame {
+            pass,
+            outer,
+            inner,
+            inner_radius,
+            col,
+        });
+    }
+}
+
+impl DrawShaded for DrawWindow {
+    fn shaded_square(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
+        self.commands.push(Command::ShadedSquare {
+            pass,
+            rect,
+            norm,
+            col,
+        });
+    }
+
+    fn shaded_circle(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
+        self.commands.push(Command::ShadedCircle {
+            pass,
+            rect,
+            norm,
+            col,
+        });
+    }
+
+    fn shaded_square_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    ) {
+        self.commands.push(Command::ShadedSquareFrame {
+            pass,
+            outer,
+            inner,
+            norm,
+            col,
+        });
+    }
+
+    fn shaded_round_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    ) {
+        self.commands.push(Command::ShadedRoundFrame {
+            pass,
+            outer,
+            inner,
+            norm,
+            col,
+        });
+    }
+}
+
+impl DrawImage for DrawWindow {
+    fn image(&mut self, pass: Pass, id: ImageId, rect: Quad) {
+        self.commands.push(Command::Image { pass, id, rect });
+    }
+}
+
+impl DrawPath for DrawWindow {
+    fn fill_path(&mut self, pass: Pass, path: &Path, rule: FillRule, col: Colour) {
+        self.commands.push(Command::FillPath {
+            pass,
+            path: path.clone(),
+            rule,
+            col,
+        });
+    }
+
+    fn stroke_path(&mut self, pass: Pass, path: &Path, stroke: &Stroke, col: Colour) {
+        self.commands.push(Command::StrokePath {
+            pass,
+            path: path.clone(),
+            stroke: *stroke,
+            col,
+        });
+    }
+}
+
+impl DrawGradient for DrawWindow {
+    fn gradient_rect(&mut self, pass: Pass, rect: Quad, gradient: &Gradient) {
+        self.commands.push(Command::GradientRect {
+            pass,
+            rect,
+            gradient: gradient.clone(),
+        });
+    }
+
+    fn gradient_frame(&mut self, pass: Pass, outer: Quad, inner: Quad, gradient: &Gradient) {
+        self.commands.push(Command::GradientFrame {
+            pass,
+            outer,
+            inner,
+            gradient: gradient.clone(),
+        });
+    }
+
+    fn shadow(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour) {
+        self.commands.push(Command::Shadow {
+            pass,
+            outer,
+            inner,
+            col,
+        });
+    }
+}
+
+impl DrawText for DrawWindow {
+    fn text(&mut self, pass: Pass, pos: Vec2, col: Colour, text: &PreparedText) {
+        self.commands.push(Command::Text {
+            pass,
+            pos,
+            bounds: text.bounds(),
+            col,
+            text: text.clone_text().to_string(),
+            prepared: text.clone(),
+        });
+    }
+}
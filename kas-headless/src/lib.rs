@@ -0,0 +1,583 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Headless toolkit for KAS
+//!
+//! This crate provides an implementation of KAS requiring neither a graphics
+//! adapter nor a window system, intended for testing widgets and UIs.
+//!
+//! Input is synthesised via methods on [`Toolkit`]. Each input method
+//! immediately processes resulting actions (including pop-ups and window
+//! closure) such that the effects may be inspected directly afterwards.
+//! Input may also be recorded and replayed (see [`Toolkit::replay`]).
+//!
+//! All windows use a virtual clock, advanced only by
+//! [`Toolkit::advance_clock`], thus timer updates are deterministic.
+//!
+//! Widgets may be located by type name, text, state or path via a
+//! [`query::Query`], then inspected or interacted with (see
+//! [`Toolkit::find`], [`Toolkit::click_widget`] and [`Toolkit::type_into`]).
+//!
+//! An accessibility tree of each window is available via
+//! [`Toolkit::access_tree`]; layout information via [`Toolkit::layout_dump`].
+//!
+//! Drawing is recorded via [`draw::DrawWindow`]: call [`Toolkit::draw`], then
+//! inspect [`Window::draw`]. Alternatively, [`Toolkit::render`] renders a
+//! window to a [`raster::Canvas`] using a software rasteriser, which may be
+//! written to or compared against a PNG file (with feature `png`).
+//!
+//! ```
+//! use kas::widget::{Label, Window};
+//! use kas_headless::Toolkit;
+//!
+//! let window = Window::new("Hello", Label::new("Hello, world!"));
+//! let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+//! let id = toolkit.add(window);
+//! toolkit.draw(id);
+//! let window = toolkit.window(id).unwrap();
+//! assert!(window.draw().texts().any(|text| text == "Hello, world!"));
+//! ```
+
+#![cfg_attr(feature = "gat", feature(generic_associated_types))]
+
+pub mod draw;
+pub mod query;
+pub mod raster;
+mod shared;
+mod window;
+
+use log::debug;
+use std::rc::Rc;
+use std::time::Duration;
+
+use kas::access::AccessNode;
+use kas::event::{
+    ElementState, InputRecording, Manager, ModifiersState, MouseButton, ScrollDelta, Shortcuts,
+    TouchPhase, UpdateHandle, VirtualKeyCode,
+};
+use kas::geom::{Coord, Size};
+use kas::layout::LayoutDump;
+use kas::persist::State;
+use kas::{TkAction, WindowId};
+use kas_theme::Theme;
+
+use crate::draw::DrawPipe;
+use crate::query::{Query, QueryError, WidgetInfo};
+use crate::raster::Canvas;
+use crate::shared::{PendingAction, SharedState};
+
+pub use window::Window;
+
+pub use kas;
+pub use kas_theme as theme;
+
+/// A headless toolkit
+///
+/// Windows are added via [`Toolkit::add`]. Input is delivered to a window by
+/// calling methods such as [`Toolkit::click`], [`Toolkit::key`] and
+/// [`Toolkit::type_text`], after which all resulting actions are processed.
+pub struct Toolkit<T: Theme<DrawPipe>> {
+    windows: Vec<Window<T::Window>>,
+    shared: SharedState<T>,
+}
+
+impl<T: Theme<DrawPipe> + 'static> Toolkit<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct a new instance with scale factor 1
+    pub fn new(theme: T) -> Self {
+        Self::new_with_scale_factor(theme, 1.0)
+    }
+
+    /// Construct a new instance with the given scale factor
+    pub fn new_with_scale_factor(theme: T, scale_factor: f64) -> Self {
+        Toolkit {
+            windows: vec![],
+            shared: SharedState::new(theme, scale_factor),
+        }
+    }
+
+    /// Set persistent state
+    ///
+    /// State is restored to windows added subsequently which have a
+    /// [`kas::Window::state_key`]; the state of these windows is saved on
+    /// window closure (see [`Toolkit::state`]). The saved size of a window is
+    /// used unless a size is given (see [`Toolkit::add_with_size`]).
+    pub fn set_state(&mut self, state: State) {
+        self.shared.state = state;
+    }
+
+    /// Access persistent state
+    ///
+    /// See [`Toolkit::set_state`].
+    #[inline]
+    pub fn state(&self) -> &State {
+        &self.shared.state
+    }
+
+    /// Assume ownership of and "display" a window
+    ///
+    /// The window is sized to its saved size (see [`Toolkit::set_state`]), if
+    /// any, otherwise to its ideal size.
+    pub fn add<W: kas::Window + 'static>(&mut self, window: W) -> WindowId {
+        self.add_boxed(Box::new(window), None)
+    }
+
+    /// Assume ownership of and "display" a window with the given size
+    pub fn add_with_size<W: kas::Window + 'static>(&mut self, window: W, size: Size) -> WindowId {
+        self.add_boxed(Box::new(window), Some(size))
+    }
+
+    /// Add a boxed window directly
+    ///
+    /// If `size` is `None`, the window is sized to its saved size, if any,
+    /// otherwise to its ideal size.
+    pub fn add_boxed(&mut self, widget: Box<dyn kas::Window>, size: Option<Size>) -> WindowId {
+        let id = self.shared.next_window_id();
+        let win = Window::new(&mut self.shared, id, widget, size);
+        self.windows.push(win);
+        self.process();
+        id
+    }
+
+    /// Number of open windows
+    #[inline]
+    pub fn num_windows(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Access a window
+    ///
+    /// Returns `None` if the window does not exist or has been closed.
+    pub fn window(&self, id: WindowId) -> Option<&Window<T::Window>> {
+        self.windows.iter().find(|w| w.id() == id)
+    }
+
+    /// Construct the accessibility tree of a window
+    ///
+    /// Returns `None` if the window does not exist. The tree may be printed:
+    /// ```
+    /// use kas::widget::{TextButton, Window};
+    /// use kas_headless::Toolkit;
+    ///
+    /// let window = Window::new("Example", TextButton::new("OK", ()));
+    /// let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    /// let id = toolkit.add(window);
+    /// print!("{}", toolkit.access_tree(id).unwrap());
+    /// ```
+    pub fn access_tree(&self, id: WindowId) -> Option<AccessNode> {
+        self.window(id).map(|w| w.access_tree())
+    }
+
+    /// Construct accessibility trees for all windows
+    ///
+    /// With feature `atspi`, these may be served via
+    /// [`kas::access::atspi::AtspiServer::update`].
+    pub fn access_trees(&self) -> Vec<AccessNode> {
+        self.windows.iter().map(|w| w.access_tree()).collect()
+    }
+
+    /// Access the theme
+    #[inline]
+    pub fn theme(&self) -> &T {
+        &self.shared.theme
+    }
+
+    /// Get the clipboard contents
+    #[inline]
+    pub fn clipboard(&self) -> Option<&str> {
+        self.shared.clipboard.as_deref()
+    }
+
+    /// Set the clipboard contents
+    #[inline]
+    pub fn set_clipboard(&mut self, content: Option<String>) {
+        self.shared.clipboard = content;
+    }
+
+    /// Set the keyboard shortcuts
+    ///
+    /// These apply to all windows, including those already open. See also
+    /// [`kas::event::Commands::bind_shortcuts`].
+    pub fn set_shortcuts(&mut self, shortcuts: Shortcuts) {
+        self.shared.shortcuts = Rc::new(shortcuts);
+        for window in &mut self.windows {
+            window
+                .manager_mut()
+                .set_shortcuts(self.shared.shortcuts.clone());
+        }
+    }
+
+    /// Resize a window
+    pub fn resize(&mut self, id: WindowId, size: Size) {
+        let shared = &mut self.shared;
+        if let Some(window) = self.windows.iter_mut().find(|w| w.id() == id) {
+            window.resize(shared, size);
+        }
+        self.process();
+    }
+
+    /// Collect layout information on a window's widgets
+    ///
+    /// Returns `None` if the window does not exist. The result may be printed
+    /// (one widget per line) or compared against expected output:
+    /// ```
+    /// use kas::widget::{TextButton, Window};
+    /// use kas_headless::Toolkit;
+    ///
+    /// let window = Window::new("Example", TextButton::new("OK", ()));
+    /// let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    /// let id = toolkit.add(window);
+    /// print!("{}", toolkit.layout_dump(id).unwrap());
+    /// ```
+    pub fn layout_dump(&mut self, id: WindowId) -> Option<LayoutDump> {
+        let window = self.windows.iter_mut().find(|w| w.id() == id)?;
+        Some(window.layout_dump())
+    }
+
+    /// Draw a window, recording all draw commands
+    ///
+    /// Recorded commands may be accessed via [`Window::draw`].
+    pub fn draw(&mut self, id: WindowId) {
+        let shared = &self.shared;
+        if let Some(window) = self.windows.iter_mut().find(|w| w.id() == id) {
+            window.do_draw(shared);
+        }
+    }
+
+    /// Draw a window, then render it via the software rasteriser
+    ///
+    /// Returns `None` if the window does not exist.
+    pub fn render(&mut self, id: WindowId) -> Option<Canvas> {
+        self.draw(id);
+        let window = self.window(id)?;
+        let mut canvas = Canvas::new(window.size(), self.shared.theme.clear_colour());
+        window.draw().replay(&mut canvas);
+        Some(canvas)
+    }
+
+    /// Trigger an update handle
+    pub fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        for window in &mut self.windows {
+            window.update_handle(&mut self.shared, handle, payload);
+        }
+        self.process();
+    }
+
+    /// Deliver timer updates which are due
+    pub fn update_timers(&mut self) {
+        for window in &mut self.windows {
+            window.update_timer(&mut self.shared);
+        }
+        self.process();
+    }
+
+    /// Advance the virtual clock, then deliver timer updates which are due
+    ///
+    /// The clock of all windows is advanced.
+    pub fn advance_clock(&mut self, duration: Duration) {
+        for window in &mut self.windows {
+            window.manager_mut().advance_clock(duration);
+        }
+        self.update_timers();
+    }
+
+    /// Start recording input to a window
+    ///
+    /// See [`kas::event::ManagerState::start_recording`].
+    pub fn start_recording(&mut self, id: WindowId) {
+        if let Some(window) = self.windows.iter_mut().find(|w| w.id() == id) {
+            window.manager_mut().start_recording();
+        }
+    }
+
+    /// Stop recording input to a window, returning the recording
+    ///
+    /// Returns `None` if the window does not exist or was not recording.
+    pub fn stop_recording(&mut self, id: WindowId) -> Option<InputRecording> {
+        let window = self.windows.iter_mut().find(|w| w.id() == id)?;
+        window.manager_mut().stop_recording()
+    }
+
+    /// Replay recorded input to a window
+    ///
+    /// Before delivering each event, the clock is advanced (see
+    /// [`Toolkit::advance_clock`]) to match the event's recorded time.
+    /// Replay stops early if the window is closed.
+    pub fn replay(&mut self, id: WindowId, recording: &InputRecording) {
+        let mut time = Duration::from_secs(0);
+        for (event_time, event) in &recording.events {
+            if self.window(id).is_none() {
+                return;
+            }
+            if *event_time > time {
+                self.advance_clock(*event_time - time);
+                time = *event_time;
+            }
+            self.input(id, |mgr, widget| mgr.handle_input(widget, *event));
+        }
+    }
+
+    /// Deliver input to a window via its [`Manager`]
+    ///
+    /// This is the most general input method; other input methods are
+    /// convenience wrappers around this. All resulting actions are processed
+    /// before returning.
+    ///
+    /// Does nothing if no window `id` exists.
+    pub fn input<F>(&mut self, id: WindowId, f: F)
+    where
+        F: FnOnce(&mut Manager, &mut dyn kas::Window),
+    {
+        let shared = &mut self.shared;
+        if let Some(window) = self.windows.iter_mut().find(|w| w.id() == id) {
+            window.with_manager(shared, f);
+        }
+        self.process();
+    }
+
+    /// Request that a window close (as with the window's close button)
+    pub fn close_requested(&mut self, id: WindowId) {
+        self.input(id, |mgr, _| mgr.handle_close_requested());
+    }
+
+    /// Set the state of modifier keys
+    pub fn set_modifiers(&mut self, id: WindowId, state: ModifiersState) {
+        self.input(id, |mgr, _| mgr.handle_modifiers(state));
+    }
+
+    /// Press a key
+    ///
+    /// The key's scancode is assumed to be `vkey as u32`.
+    pub fn key_press(&mut self, id: WindowId, vkey: VirtualKeyCode) {
+        self.input(id, |mgr, widget| {
+            mgr.handle_key(widget, ElementState::Pressed, Some(vkey), vkey as u32)
+        });
+    }
+
+    /// Release a key
+    pub fn key_release(&mut self, id: WindowId, vkey: VirtualKeyCode) {
+        self.input(id, |mgr, widget| {
+            mgr.handle_key(widget, ElementState::Released, Some(vkey), vkey as u32)
+        });
+    }
+
+    /// Press then release a key
+    pub fn key(&mut self, id: WindowId, vkey: VirtualKeyCode) {
+        self.key_press(id, vkey);
+        self.key_release(id, vkey);
+    }
+
+    /// Send a received character
+    pub fn send_char(&mut self, id: WindowId, c: char) {
+        self.input(id, |mgr, widget| mgr.handle_char(widget, c));
+    }
+
+    /// Send each character of `text`
+    ///
+    /// Note: no key press events are generated.
+    pub fn type_text(&mut self, id: WindowId, text: &str) {
+        for c in text.chars() {
+            self.send_char(id, c);
+        }
+    }
+
+    /// Move the mouse cursor to `coord`
+    pub fn mouse_move(&mut self, id: WindowId, coord: Coord) {
+        self.input(id, |mgr, widget| mgr.handle_cursor_moved(widget, coord));
+    }
+
+    /// Move the mouse cursor out of the window
+    pub fn mouse_leave(&mut self, id: WindowId) {
+        self.input(id, |mgr, widget| mgr.handle_cursor_left(widget));
+    }
+
+    /// Press a mouse button
+    pub fn mouse_press(&mut self, id: WindowId, button: MouseButton) {
+        self.input(id, |mgr, widget| {
+            mgr.handle_mouse_input(widget, ElementState::Pressed, button)
+        });
+    }
+
+    /// Release a mouse button
+    pub fn mouse_release(&mut self, id: WindowId, button: MouseButton) {
+        self.input(id, |mgr, widget| {
+            mgr.handle_mouse_input(widget, ElementState::Released, button)
+        });
+    }
+
+    /// Move the mouse cursor to `coord`, then click the left mouse button
+    pub fn click(&mut self, id: WindowId, coord: Coord) {
+        self.mouse_move(id, coord);
+        self.mouse_press(id, MouseButton::Left);
+        self.mouse_release(id, MouseButton::Left);
+    }
+
+    /// Scroll (via mouse wheel) over the hovered widget
+    pub fn scroll(&mut self, id: WindowId, delta: ScrollDelta) {
+        self.input(id, |mgr, widget| mgr.handle_wheel(widget, delta));
+    }
+
+    /// Send a touch event
+    pub fn touch(&mut self, id: WindowId, touch_id: u64, phase: TouchPhase, coord: Coord) {
+        self.input(id, |mgr, widget| {
+            mgr.handle_touch(widget, touch_id, phase, coord)
+        });
+    }
+
+    /// Find a widget in window `id`
+    ///
+    /// Returns `None` if the window does not exist or no widget matches.
+    pub fn find(&self, id: WindowId, query: &Query) -> Option<WidgetInfo> {
+        query.find(self.window(id)?.widget().as_widget())
+    }
+
+    /// Find all widgets matching `query` in window `id`
+    pub fn find_all(&self, id: WindowId, query: &Query) -> Vec<WidgetInfo> {
+        match self.window(id) {
+            Some(window) => query.find_all(window.widget().as_widget()),
+            None => vec![],
+        }
+    }
+
+    /// Find a widget or return an error
+    pub fn try_find(&self, id: WindowId, query: &Query) -> Result<WidgetInfo, QueryError> {
+        let window = self.window(id).ok_or(QueryError::NoWindow)?;
+        query
+            .find(window.widget().as_widget())
+            .ok_or_else(|| QueryError::NotFound(query.clone()))
+    }
+
+    /// Click the centre of the widget matching `query`
+    ///
+    /// Returns information on the widget, as found before clicking.
+    pub fn click_widget(&mut self, id: WindowId, query: &Query) -> Result<WidgetInfo, QueryError> {
+        let info = self.try_find(id, query)?;
+        self.click(id, info.centre());
+        Ok(info)
+    }
+
+    /// Click the widget matching `query`, then type `text`
+    ///
+    /// Clicking normally gives an edit field character focus.
+    pub fn type_into(
+        &mut self,
+        id: WindowId,
+        query: &Query,
+        text: &str,
+    ) -> Result<WidgetInfo, QueryError> {
+        let info = self.click_widget(id, query)?;
+        self.type_text(id, text);
+        Ok(info)
+    }
+
+    /// Assert that the widget matching `query` has the given text
+    ///
+    /// Panics with a descriptive message if no widget matches or the text
+    /// differs.
+    pub fn assert_text(&self, id: WindowId, query: &Query, text: &str) {
+        match self.try_find(id, query) {
+            Ok(info) => assert_eq!(
+                info.text.as_deref(),
+                Some(text),
+                "unexpected text for {} at path {:?}",
+                query,
+                info.path
+            ),
+            Err(e) => panic!("assert_text: {}", e),
+        }
+    }
+
+    /// Assert that the widget matching `query` has the given boolean state
+    ///
+    /// Panics with a descriptive message if no widget matches or the state
+    /// differs.
+    pub fn assert_state(&self, id: WindowId, query: &Query, state: bool) {
+        match self.try_find(id, query) {
+            Ok(info) => assert_eq!(
+                info.state,
+                Some(state),
+                "unexpected state for {} at path {:?}",
+                query,
+                info.path
+            ),
+            Err(e) => panic!("assert_state: {}", e),
+        }
+    }
+
+    /// Process pending actions until none remain
+    fn process(&mut self) {
+        loop {
+            let mut close_all = false;
+            let mut i = 0;
+            while i < self.windows.len() {
+                let action = self.windows[i].update(&mut self.shared);
+                if action == TkAction::CloseAll {
+                    close_all = true;
+                }
+                if action >= TkAction::Close {
+                    let window = self.windows.remove(i);
+                    let action = window.handle_closure(&mut self.shared);
+                    if action == TkAction::CloseAll {
+                        close_all = true;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+
+            if close_all {
+                for window in self.windows.drain(..) {
+                    let _ = window.handle_closure(&mut self.shared);
+                }
+                self.shared.pending.clear();
+                return;
+            }
+
+            if self.shared.pending.is_empty() {
+                return;
+            }
+
+            for pending in std::mem::take(&mut self.shared.pending) {
+                match pending {
+                    PendingAction::AddPopup(parent_id, id, popup) => {
+                        debug!("Adding popup {:?} to window {:?}", id, parent_id);
+                        let shared = &mut self.shared;
+                        if let Some(window) = self.windows.iter_mut().find(|w| w.id() == parent_id)
+                        {
+                            window.add_popup(shared, id, popup);
+                        }
+                    }
+                    PendingAction::AddWindow(id, widget) => {
+                        debug!("Adding window {:?}", id);
+                        let win = Window::new(&mut self.shared, id, widget, None);
+                        self.windows.push(win);
+                    }
+                    PendingAction::CloseWindow(id) => {
+                        for window in &mut self.windows {
+                            window.send_close(&mut self.shared, id);
+                        }
+                    }
+                    PendingAction::ThemeResize => {
+                        for window in &mut self.windows {
+                            window.theme_resize(&mut self.shared);
+                        }
+                    }
+                    PendingAction::RedrawAll => {
+                        for window in &mut self.windows {
+                            window.send_action(TkAction::Redraw);
+                        }
+                    }
+                    PendingAction::Update(handle, payload) => {
+                        for window in &mut self.windows {
+                            window.update_handle(&mut self.shared, handle, payload);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
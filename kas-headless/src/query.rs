@@ -0,0 +1,317 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget queries
+//!
+//! A [`Query`] locates widgets within a window without requiring knowledge of
+//! [`WidgetId`]s (which are not stable across reconfiguration). Widgets may be
+//! matched by type name ([`kas::WidgetCore::widget_name`]), by text (via
+//! [`kas::WidgetConfig::as_clone_text`] or
+//! [`kas::WidgetConfig::as_has_string`]), by state (via
+//! [`kas::WidgetConfig::as_has_bool`]) or by path.
+//!
+//! Queries are evaluated against a window via [`crate::Toolkit::find`] and
+//! related methods.
+
+use std::error::Error;
+use std::fmt;
+
+use kas::geom::{Coord, Rect};
+use kas::{StableId, WidgetConfig, WidgetId};
+
+/// A path to a widget: a sequence of child indices from the window root
+///
+/// The empty path refers to the root widget.
+pub type WidgetPath = Vec<usize>;
+
+/// A widget query
+///
+/// All specified criteria must match. The default query (via [`Query::new`])
+/// matches all widgets. When multiple widgets match, the first in pre-order
+/// (parents before children, then in order of child index) is used unless
+/// [`Query::nth`] is specified.
+///
+/// ```
+/// use kas_headless::query::Query;
+///
+/// let save_button = Query::new().name("TextButton").text("Save");
+/// let first_checkbox = Query::new().name("CheckBox").nth(0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    name: Option<String>,
+    text: Option<String>,
+    state: Option<bool>,
+    path: Option<WidgetPath>,
+    within: Option<WidgetPath>,
+    enabled: bool,
+    nth: usize,
+}
+
+impl Query {
+    /// Construct a query matching all widgets
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a query matching the widget at `path`
+    #[inline]
+    pub fn path(path: &[usize]) -> Self {
+        Query {
+            path: Some(path.to_vec()),
+            ..Default::default()
+        }
+    }
+
+    /// Match widgets by type name
+    ///
+    /// See [`kas::WidgetCore::widget_name`]. Type parameters are not included
+    /// in the name, e.g. `TextButton`.
+    #[inline]
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Match widgets by text
+    ///
+    /// The text (see [`WidgetInfo::text`]) must equal `text` exactly.
+    /// Accelerator key prefixes (`&`) are not included in label text.
+    #[inline]
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    /// Match widgets by boolean state
+    ///
+    /// See [`WidgetInfo::state`].
+    #[inline]
+    pub fn state(mut self, state: bool) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Only match descendants of the widget at `path`
+    #[inline]
+    pub fn within(mut self, path: &[usize]) -> Self {
+        self.within = Some(path.to_vec());
+        self
+    }
+
+    /// Only match widgets which are not disabled (directly or by inheritance)
+    #[inline]
+    pub fn enabled(mut self) -> Self {
+        self.enabled = true;
+        self
+    }
+
+    /// Select the `n`-th match (from 0)
+    #[inline]
+    pub fn nth(mut self, n: usize) -> Self {
+        self.nth = n;
+        self
+    }
+
+    /// Find the selected match
+    pub fn find(&self, root: &dyn WidgetConfig) -> Option<WidgetInfo> {
+        let mut n = self.nth;
+        let mut result = None;
+        self.visit(root, &mut vec![], false, &mut |info| {
+            if n == 0 {
+                result = Some(info);
+                true
+            } else {
+                n -= 1;
+                false
+            }
+        });
+        result
+    }
+
+    /// Find all matches, in pre-order
+    ///
+    /// [`Query::nth`] is ignored.
+    pub fn find_all(&self, root: &dyn WidgetConfig) -> Vec<WidgetInfo> {
+        let mut result = vec![];
+        self.visit(root, &mut vec![], false, &mut |info| {
+            result.push(info);
+            false
+        });
+        result
+    }
+
+    // Visit all widgets in pre-order, calling f on matches until f returns true
+    fn visit(
+        &self,
+        widget: &dyn WidgetConfig,
+        path: &mut WidgetPath,
+        disabled: bool,
+        f: &mut dyn FnMut(WidgetInfo) -> bool,
+    ) -> bool {
+        let disabled = disabled || widget.is_disabled();
+        if self.path_matches(path) {
+            let info = WidgetInfo::new(widget, path.clone(), disabled);
+            if self.matches(&info) && f(info) {
+                return true;
+            }
+        }
+
+        if let Some(ref target) = self.path {
+            if !target.starts_with(path) || target.len() == path.len() {
+                return false;
+            }
+        }
+        for i in 0..widget.len() {
+            if let Some(child) = widget.get(i) {
+                path.push(i);
+                let done = self.visit(child, path, disabled, f);
+                path.pop();
+                if done {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn path_matches(&self, path: &[usize]) -> bool {
+        if let Some(ref target) = self.path {
+            if target.as_slice() != path {
+                return false;
+            }
+        }
+        if let Some(ref within) = self.within {
+            if path.len() <= within.len() || !path.starts_with(within) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Check whether `info` matches (excluding path criteria)
+    pub fn matches(&self, info: &WidgetInfo) -> bool {
+        if let Some(ref name) = self.name {
+            if info.name != name {
+                return false;
+            }
+        }
+        if let Some(ref text) = self.text {
+            if info.text.as_ref() != Some(text) {
+                return false;
+            }
+        }
+        if self.state.is_some() && info.state != self.state {
+            return false;
+        }
+        !(self.enabled && info.disabled)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "widget")?;
+        if let Some(ref name) = self.name {
+            write!(f, " {}", name)?;
+        }
+        if let Some(ref text) = self.text {
+            write!(f, " with text {:?}", text)?;
+        }
+        if let Some(state) = self.state {
+            write!(f, " with state {}", state)?;
+        }
+        if let Some(ref path) = self.path {
+            write!(f, " at path {:?}", path)?;
+        }
+        if let Some(ref path) = self.within {
+            write!(f, " within {:?}", path)?;
+        }
+        if self.enabled {
+            write!(f, " (enabled)")?;
+        }
+        if self.nth > 0 {
+            write!(f, " (match {})", self.nth)?;
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of a widget's properties
+///
+/// This is constructed by [`Query::find`]. It remains valid until the widget
+/// tree is next modified.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidgetInfo {
+    /// Path from the window root
+    pub path: WidgetPath,
+    /// Widget identifier (valid until reconfigure)
+    pub id: WidgetId,
+    /// Stable widget identifier
+    pub stable_id: StableId,
+    /// Type name (see [`kas::WidgetCore::widget_name`])
+    pub name: &'static str,
+    /// Widget rect
+    ///
+    /// This is in window coordinates, except within scroll regions where the
+    /// scroll offset is not accounted for.
+    pub rect: Rect,
+    /// Disabled state, including inherited state
+    pub disabled: bool,
+    /// Text, if available via [`kas::class::CloneText`] or
+    /// [`kas::class::HasString`]
+    pub text: Option<String>,
+    /// Boolean state, if available via [`kas::class::HasBool`]
+    pub state: Option<bool>,
+    /// Whether the widget is navigable via the Tab key
+    pub key_nav: bool,
+}
+
+impl WidgetInfo {
+    fn new(widget: &dyn WidgetConfig, path: WidgetPath, disabled: bool) -> Self {
+        let text = if let Some(w) = widget.as_clone_text() {
+            Some(w.clone_string())
+        } else {
+            widget.as_has_string().map(|w| w.get_string())
+        };
+        WidgetInfo {
+            path,
+            id: widget.id(),
+            stable_id: widget.stable_id(),
+            name: widget.widget_name(),
+            rect: widget.rect(),
+            disabled,
+            text,
+            state: widget.as_has_bool().map(|w| w.get_bool()),
+            key_nav: widget.key_nav(),
+        }
+    }
+
+    /// The centre of the widget's rect
+    pub fn centre(&self) -> Coord {
+        let size = Coord::from(self.rect.size);
+        self.rect.pos + Coord(size.0 / 2, size.1 / 2)
+    }
+}
+
+/// Error type
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryError {
+    /// The window does not exist
+    NoWindow,
+    /// No widget matched the query
+    NotFound(Query),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryError::NoWindow => write!(f, "window not found"),
+            QueryError::NotFound(query) => write!(f, "not found: {}", query),
+        }
+    }
+}
+
+impl Error for QueryError {}
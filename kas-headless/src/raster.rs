@@ -0,0 +1,649 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Software rasteriser
+//!
+//! [`Canvas`] implements [`Draw`] and its extension traits by rendering
+//! directly into an RGBA buffer on the CPU. The output approximates that of
+//! `kas-wgpu`'s shaders closely enough for golden-image tests; it is not
+//! intended to be pixel-identical to GPU output.
+//!
+//! A recorded frame may be rendered via [`crate::draw::DrawWindow::replay`] or,
+//! more conveniently, [`crate::Toolkit::render`].
+
+use std::any::Any;
+use std::f32::consts::FRAC_PI_2;
+
+use ab_glyph::{Font, FontArc};
+use kas::draw::{
+    Colour, CoverageMask, Draw, DrawGradient, DrawImage, DrawPath, DrawRounded, DrawShaded,
+    DrawText, FillRule, Gradient, ImageData, ImageId, Pass, Path, Stroke,
+};
+use kas::geom::{Coord, Quad, Rect, Size, Vec2};
+use kas::text::PreparedText;
+
+/// Sample offsets, as used by the `kas-wgpu` shaders for round shapes
+const SAMPLES: [Vec2; 4] = [
+    Vec2(0.125, 0.375),
+    Vec2(-0.125, -0.375),
+    Vec2(0.375, 0.125),
+    Vec2(-0.375, -0.125),
+];
+
+/// A CPU render target
+///
+/// Colours are stored in linear space with a depth value per pixel. Clip
+/// regions and depth are handled as in `kas-wgpu`: shapes are clipped to the
+/// region of their [`Pass`] and always drawn, writing depth, while text is
+/// clipped to its bounds and only drawn where its depth is not less than
+/// that already drawn. Translucent colours are blended over existing content.
+pub struct Canvas {
+    size: Size,
+    pixels: Vec<[f32; 3]>,
+    depth: Vec<f32>,
+    clip_regions: Vec<Rect>,
+    light_norm: [f32; 3],
+    fonts: Vec<FontArc>,
+}
+
+impl Canvas {
+    /// Construct, clearing to colour `clear`
+    pub fn new(size: Size, clear: Colour) -> Self {
+        // Light dir: as in kas-wgpu, `(a, b)` where `a` is the angle to the
+        // screen normal and `b` is the bearing (from UP, clockwise).
+        let dir: (f32, f32) = (0.3, 0.4);
+        let a = (dir.0.sin(), dir.0.cos());
+        let f = a.0 / a.1;
+        let light_norm = [dir.1.sin() * f, -dir.1.cos() * f, 1.0];
+
+        let len = size.0 as usize * size.1 as usize;
+        Canvas {
+            size,
+            pixels: vec![[clear.r, clear.g, clear.b]; len],
+            depth: vec![0.0; len],
+            clip_regions: vec![Rect::new(Coord::ZERO, size)],
+            light_norm,
+            fonts: kas::text::fonts().fonts_vec(),
+        }
+    }
+
+    /// The canvas size
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the (linear) colour of pixel `(x, y)`
+    ///
+    /// Panics if out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Colour {
+        assert!(x < self.size.0 && y < self.size.1);
+        let [r, g, b] = self.pixels[(y * self.size.0 + x) as usize];
+        Colour { r, g, b, a: 1.0 }
+    }
+
+    /// Get the image as 8-bit sRGB RGBA data, row-major
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.pixels.len() * 4);
+        for px in &self.pixels {
+            data.extend_from_slice(&[srgb8(px[0]), srgb8(px[1]), srgb8(px[2]), 255]);
+        }
+        data
+    }
+
+    /// Write the image to a PNG file
+    #[cfg(feature = "png")]
+    pub fn write_png<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), png::EncodingError> {
+        let file = std::fs::File::create(path)?;
+        let w = std::io::BufWriter::new(file);
+        let mut encoder = png::Encoder::new(w, self.size.0, self.size.1);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.to_rgba8())
+    }
+
+    /// Compare against a PNG file, returning the number of differing pixels
+    ///
+    /// Pixels differ if any channel differs by more than `tolerance`. If the
+    /// image size differs, all pixels are considered to differ.
+    #[cfg(feature = "png")]
+    pub fn compare_png<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        tolerance: u8,
+    ) -> Result<usize, png::DecodingError> {
+        let file = std::fs::File::open(path)?;
+        let mut decoder = png::Decoder::new(file);
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let (info, mut reader) = decoder.read_info()?;
+        let mut buf = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buf)?;
+
+        let len = self.pixels.len();
+        if Size(info.width, info.height) != self.size || info.bit_depth != png::BitDepth::Eight {
+            return Ok(len);
+        }
+        let channels = match info.color_type {
+            png::ColorType::RGB => 3,
+            png::ColorType::RGBA => 4,
+            _ => return Ok(len),
+        };
+
+        let data = self.to_rgba8();
+        let diff = |a: u8, b: u8| (a as i16 - b as i16).abs() > tolerance as i16;
+        let n = data
+            .chunks(4)
+            .zip(buf.chunks(channels))
+            .filter(|(a, b)| (0..3).any(|i| diff(a[i], b[i])))
+            .count();
+        Ok(n)
+    }
+
+    /// Get the clip rect for `pass` as `(x0, y0, x1, y1)`
+    fn clip(&self, pass: Pass) -> (i32, i32, i32, i32) {
+        let rect = self.clip_regions[pass.pass()];
+        let x0 = rect.pos.0.max(0);
+        let y0 = rect.pos.1.max(0);
+        let x1 = (rect.pos.0 + rect.size.0 as i32).min(self.size.0 as i32);
+        let y1 = (rect.pos.1 + rect.size.1 as i32).min(self.size.1 as i32);
+        (x0, y0, x1, y1)
+    }
+
+    /// Fill pixels within `bounds` and the clip region of `pass`
+    ///
+    /// `coverage` is evaluated at each pixel centre and should return the
+    /// fraction of the pixel covered, `0 ≤ coverage ≤ 1`. `colour` is
+    /// evaluated only where coverage is non-zero and returns linear RGBA.
+    fn fill<C, F>(&mut self, pass: Pass, bounds: Quad, coverage: C, colour: F)
+    where
+        C: Fn(Vec2) -> f32,
+        F: Fn(Vec2) -> [f32; 4],
+    {
+        let (x0, y0, x1, y1) = self.clip(pass);
+        let x0 = x0.max(bounds.a.0.floor() as i32);
+        let y0 = y0.max(bounds.a.1.floor() as i32);
+        let x1 = x1.min(bounds.b.0.ceil() as i32);
+        let y1 = y1.min(bounds.b.1.ceil() as i32);
+
+        let depth = pass.depth();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let p = Vec2(x as f32 + 0.5, y as f32 + 0.5);
+                let cov = coverage(p);
+                if cov <= 0.0 {
+                    continue;
+                }
+                let c = colour(p);
+                let alpha = cov * c[3];
+                let i = (y as u32 * self.size.0 + x as u32) as usize;
+                let px = &mut self.pixels[i];
+                for j in 0..3 {
+                    px[j] = c[j] * alpha + px[j] * (1.0 - alpha);
+                }
+                self.depth[i] = depth;
+            }
+        }
+    }
+
+    /// Fill polygons (all implicitly closed) with anti-aliased edges
+    fn fill_polygons(&mut self, pass: Pass, polygons: &[Vec<Vec2>], rule: FillRule, col: Colour) {
+        let mut bounds = Quad {
+            a: Vec2::splat(std::f32::INFINITY),
+            b: Vec2::splat(std::f32::NEG_INFINITY),
+        };
+        for p in polygons.iter().flatten() {
+            bounds.a = Vec2(bounds.a.0.min(p.0), bounds.a.1.min(p.1));
+            bounds.b = Vec2(bounds.b.0.max(p.0), bounds.b.1.max(p.1));
+        }
+        let (x0, y0, x1, y1) = self.clip(pass);
+        let x0 = x0.max(bounds.a.0.floor() as i32);
+        let y0 = y0.max(bounds.a.1.floor() as i32);
+        let x1 = x1.min(bounds.b.0.ceil() as i32);
+        let y1 = y1.min(bounds.b.1.ceil() as i32);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        let size = Size((x1 - x0) as u32, (y1 - y0) as u32);
+        let mut mask = CoverageMask::new(Rect::new(Coord(x0, y0), size));
+        mask.fill(polygons, rule);
+        let coverage = |p: Vec2| mask.get(p.0 as i32, p.1 as i32);
+        let col: [f32; 4] = col.into();
+        self.fill(pass, bounds, coverage, |_| col);
+    }
+}
+
+/// Colour shaded by a surface normal, given light direction `l`
+fn shade(l: [f32; 3], col: Colour, norm: [f32; 3]) -> [f32; 4] {
+    let f = norm[0] * l[0] + norm[1] * l[1] + norm[2] * l[2];
+    [col.r * f, col.g * f, col.b * f, col.a]
+}
+
+/// Convert a linear colour component to 8-bit sRGB
+fn srgb8(x: f32) -> u8 {
+    let x = x.max(0.0).min(1.0);
+    let s = if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
+/// Convert an 8-bit sRGB colour component to linear
+fn linear(x: u8) -> f32 {
+    let x = x as f32 / 255.0;
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Sample an image with bilinear filtering, returning linear RGBA
+///
+/// Coordinates are in texels (`(0, 0)` is the top-left corner); sampling is
+/// clamped to the edge, as in `kas-wgpu`.
+fn sample_image(image: &ImageData, t: Vec2) -> [f32; 4] {
+    let size = image.size();
+    let texel = |x: i32, y: i32| {
+        let x = x.max(0).min(size.0 as i32 - 1) as u32;
+        let y = y.max(0).min(size.1 as i32 - 1) as u32;
+        let p = image.pixel(x, y);
+        [
+            linear(p[0]),
+            linear(p[1]),
+            linear(p[2]),
+            p[3] as f32 / 255.0,
+        ]
+    };
+    let (x, y) = (t.0 - 0.5, t.1 - 0.5);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let (a, b) = (texel(x0, y0), texel(x0 + 1, y0));
+    let (c, d) = (texel(x0, y0 + 1), texel(x0 + 1, y0 + 1));
+    let mut out = [0.0; 4];
+    for i in 0..4 {
+        let top = a[i] + (b[i] - a[i]) * fx;
+        let bottom = c[i] + (d[i] - c[i]) * fx;
+        out[i] = top + (bottom - top) * fy;
+    }
+    out
+}
+
+/// Multi-sample coverage, as in the `kas-wgpu` shaders
+fn sample<F: Fn(Vec2) -> bool>(p: Vec2, inside: F) -> f32 {
+    SAMPLES.iter().filter(|off| inside(p + **off)).count() as f32 * 0.25
+}
+
+fn in_quad(q: Quad, p: Vec2) -> bool {
+    q.a.le(p) && p.lt(q.b)
+}
+
+/// Validate frame inputs as in `kas-wgpu`, returning the adjusted inner quad
+fn frame_inner(outer: Quad, inner: Quad) -> Option<Quad> {
+    let (aa, bb) = (outer.a, outer.b);
+    let (mut cc, mut dd) = (inner.a, inner.b);
+    if !aa.lt(bb) {
+        return None;
+    }
+    if !aa.le(cc) || !cc.le(bb) {
+        cc = aa;
+    }
+    if !aa.le(dd) || !dd.le(bb) {
+        dd = bb;
+    }
+    if !cc.le(dd) {
+        dd = cc;
+    }
+    Some(Quad { a: cc, b: dd })
+}
+
+/// Normalise a shaded norm value as in `kas-wgpu`
+fn valid_norm(norm: (f32, f32)) -> Vec2 {
+    let norm = Vec2::from(norm);
+    if Vec2::splat(-1.0).le(norm) && norm.le(Vec2::splat(1.0)) {
+        norm
+    } else {
+        Vec2::splat(0.0)
+    }
+}
+
+/// Direction within a frame from `inner` towards `outer`
+///
+/// Each component is scaled such that the inner edge is at 0 and the outer
+/// edge at ±1. Returns zero within `inner`.
+fn frame_dir(outer: Quad, inner: Quad, p: Vec2) -> Vec2 {
+    let f = |x: f32, a: f32, c: f32, d: f32, b: f32| {
+        if x < c {
+            if a < c {
+                (x - c) / (c - a)
+            } else {
+                -1.0
+            }
+        } else if x > d {
+            if d < b {
+                (x - d) / (b - d)
+            } else {
+                1.0
+            }
+        } else {
+            0.0
+        }
+    };
+    Vec2(
+        f(p.0, outer.a.0, inner.a.0, inner.b.0, outer.b.0),
+        f(p.1, outer.a.1, inner.a.1, inner.b.1, outer.b.1),
+    )
+}
+
+/// Surface normal of a round shaded shape (see `shaded_round.frag`)
+fn round_norm(dir: Vec2, adjust: Vec2) -> [f32; 3] {
+    let ss = dir.sum_square();
+    let mut z = (1.0 - ss).max(0.0).sqrt();
+    let h = ss.sqrt();
+    let t = adjust.0 + adjust.1 * h.atan2(z);
+    let mut normh = Vec2::splat(0.0);
+    if h > 0.0 {
+        normh = dir * (t.sin() / h);
+        z = t.cos();
+    }
+    [normh.0, normh.1, z]
+}
+
+/// Surface normal of a square shaded shape (see `shaded_square.frag`)
+fn square_norm(norm2: Vec2) -> [f32; 3] {
+    let n3 = 1.0 - norm2.sum_square().sqrt();
+    [norm2.0, norm2.1, n3]
+}
+
+impl Draw for Canvas {
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn add_clip_region(&mut self, rect: Rect, depth: f32) -> Pass {
+        let pass = self.clip_regions.len();
+        self.clip_regions.push(rect);
+        Pass::new_pass_with_depth(pass as u32, depth)
+    }
+
+    fn rect(&mut self, pass: Pass, rect: Quad, col: Colour) {
+        let col: [f32; 4] = col.into();
+        let coverage = |p| if in_quad(rect, p) { 1.0 } else { 0.0 };
+        self.fill(pass, rect, coverage, |_| col);
+    }
+
+    fn frame(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour) {
+        self.shaded_square_frame(pass, outer, inner, (0.0, 0.0), col);
+    }
+}
+
+impl DrawRounded for Canvas {
+    fn rounded_line(&mut self, pass: Pass, p1: Vec2, p2: Vec2, radius: f32, col: Colour) {
+        let bounds = Quad {
+            a: Vec2(p1.0.min(p2.0), p1.1.min(p2.1)) - radius,
+            b: Vec2(p1.0.max(p2.0), p1.1.max(p2.1)) + radius,
+        };
+        let v = p2 - p1;
+        let len2 = v.sum_square();
+        let r2 = radius * radius;
+        let inside = |p: Vec2| {
+            let w = p - p1;
+            let t = if len2 > 0.0 {
+                ((w.0 * v.0 + w.1 * v.1) / len2).max(0.0).min(1.0)
+            } else {
+                0.0
+            };
+            (w - v * t).sum_square() <= r2
+        };
+        let col: [f32; 4] = col.into();
+        self.fill(pass, bounds, |p| sample(p, inside), |_| col);
+    }
+
+    fn circle(&mut self, pass: Pass, rect: Quad, inner_radius: f32, col: Colour) {
+        if !rect.a.lt(rect.b) {
+            return;
+        }
+        let inner = inner_radius.max(0.0).min(1.0);
+        let mid = (rect.a + rect.b) * 0.5;
+        let half = rect.b - mid;
+        let inside = |p: Vec2| {
+            let ss = ((p - mid) / half).sum_square();
+            inner <= ss && ss <= 1.0
+        };
+        let col: [f32; 4] = col.into();
+        self.fill(pass, rect, |p| sample(p, inside), |_| col);
+    }
+
+    fn rounded_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        inner_radius: f32,
+        col: Colour,
+    ) {
+        let inner = match frame_inner(outer, inner) {
+            Some(inner) => inner,
+            None => return,
+        };
+        let r = inner_radius.max(0.0).min(1.0);
+        let inside = |p: Vec2| {
+            let ss = frame_dir(outer, inner, p).sum_square();
+            r <= ss && ss <= 1.0
+        };
+        let coverage = |p| {
+            if in_quad(inner, p) {
+                0.0
+            } else {
+                sample(p, inside)
+            }
+        };
+        let col: [f32; 4] = col.into();
+        self.fill(pass, outer, coverage, |_| col);
+    }
+}
+
+impl DrawShaded for Canvas {
+    fn shaded_square(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
+        if !rect.a.lt(rect.b) {
+            return;
+        }
+        let norm = valid_norm(norm);
+        let light = self.light_norm;
+        let mid = (rect.a + rect.b) * 0.5;
+        let half = rect.b - mid;
+        let coverage = |p| if in_quad(rect, p) { 1.0 } else { 0.0 };
+        let colour = |p: Vec2| {
+            // Interpolate between norm.0 at the centre and norm.1 at the
+            // edges within the triangle from the nearest edge to the centre.
+            let d = (p - mid) / half;
+            let s = d.abs();
+            let norm2 = if s.1 >= s.0 {
+                Vec2(0.0, d.1.signum() * (norm.0 + s.1 * (norm.1 - norm.0)))
+            } else {
+                Vec2(d.0.signum() * (norm.0 + s.0 * (norm.1 - norm.0)), 0.0)
+            };
+            shade(light, col, square_norm(norm2))
+        };
+        self.fill(pass, rect, coverage, colour);
+    }
+
+    fn shaded_circle(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
+        if !rect.a.lt(rect.b) {
+            return;
+        }
+        let norm = valid_norm(norm);
+        let light = self.light_norm;
+        let adjust = Vec2(FRAC_PI_2 * norm.0, norm.1 - norm.0);
+        let mid = (rect.a + rect.b) * 0.5;
+        let half = rect.b - mid;
+        let inside = |p: Vec2| ((p - mid) / half).sum_square() <= 1.0;
+        let colour = |p: Vec2| shade(light, col, round_norm((p - mid) / half, adjust));
+        self.fill(pass, rect, |p| sample(p, inside), colour);
+    }
+
+    fn shaded_square_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    ) {
+        let inner = match frame_inner(outer, inner) {
+            Some(inner) => inner,
+            None => return,
+        };
+        let norm = valid_norm(norm);
+        let light = self.light_norm;
+        let coverage = |p| {
+            if in_quad(outer, p) && !in_quad(inner, p) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        let colour = |p: Vec2| {
+            // Each bar interpolates from norm.0 at the inner edge to norm.1
+            // at the outer edge; bars meet along the diagonals.
+            let d = frame_dir(outer, inner, p);
+            let s = d.abs();
+            let norm2 = if s.1 >= s.0 {
+                Vec2(0.0, d.1.signum() * (norm.0 + s.1 * (norm.1 - norm.0)))
+            } else {
+                Vec2(d.0.signum() * (norm.0 + s.0 * (norm.1 - norm.0)), 0.0)
+            };
+            shade(light, col, square_norm(norm2))
+        };
+        self.fill(pass, outer, coverage, colour);
+    }
+
+    fn shaded_round_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    ) {
+        let inner = match frame_inner(outer, inner) {
+            Some(inner) => inner,
+            None => return,
+        };
+        let norm = valid_norm(norm);
+        let light = self.light_norm;
+        let adjust = Vec2(FRAC_PI_2 * norm.0, norm.1 - norm.0);
+        let inside = |p: Vec2| frame_dir(outer, inner, p).sum_square() <= 1.0;
+        let coverage = |p| {
+            if in_quad(inner, p) {
+                0.0
+            } else {
+                sample(p, inside)
+            }
+        };
+        let colour = |p: Vec2| shade(light, col, round_norm(frame_dir(outer, inner, p), adjust));
+        self.fill(pass, outer, coverage, colour);
+    }
+}
+
+impl DrawImage for Canvas {
+    fn image(&mut self, pass: Pass, id: ImageId, rect: Quad) {
+        let image = match id.data() {
+            Some(image) => image,
+            None => return,
+        };
+        let size = image.size();
+        if !rect.a.lt(rect.b) || size.0 == 0 || size.1 == 0 {
+            return;
+        }
+        let scale = Vec2::from(size) / rect.size();
+        let tex = |p: Vec2| (p - rect.a) * scale;
+        let coverage = |p| if in_quad(rect, p) { 1.0 } else { 0.0 };
+        let colour = |p| sample_image(&image, tex(p));
+        self.fill(pass, rect, coverage, colour);
+    }
+}
+
+impl DrawPath for Canvas {
+    fn fill_path(&mut self, pass: Pass, path: &Path, rule: FillRule, col: Colour) {
+        let polygons: Vec<_> = path.flatten().into_iter().map(|c| c.points).collect();
+        self.fill_polygons(pass, &polygons, rule, col);
+    }
+
+    fn stroke_path(&mut self, pass: Pass, path: &Path, stroke: &Stroke, col: Colour) {
+        let polygons = stroke.outline(&path.flatten());
+        self.fill_polygons(pass, &polygons, FillRule::NonZero, col);
+    }
+}
+
+impl DrawGradient for Canvas {
+    fn gradient_rect(&mut self, pass: Pass, rect: Quad, gradient: &Gradient) {
+        let coverage = |p| if in_quad(rect, p) { 1.0 } else { 0.0 };
+        let colour = |p| gradient.colour_at(p).into();
+        self.fill(pass, rect, coverage, colour);
+    }
+
+    fn gradient_frame(&mut self, pass: Pass, outer: Quad, inner: Quad, gradient: &Gradient) {
+        let inner = match frame_inner(outer, inner) {
+            Some(inner) => inner,
+            None => return,
+        };
+        let coverage = |p| {
+            if in_quad(outer, p) && !in_quad(inner, p) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        let colour = |p| gradient.colour_at(p).into();
+        self.fill(pass, outer, coverage, colour);
+    }
+}
+
+impl DrawText for Canvas {
+    fn text(&mut self, pass: Pass, pos: Vec2, col: Colour, text: &PreparedText) {
+        let bounds = Quad {
+            a: pos,
+            b: pos + text.bounds(),
+        };
+        let (w, h) = (self.size.0 as i32, self.size.1 as i32);
+        let depth = pass.depth();
+        for sg in text.positioned_glyphs(pos) {
+            let font = match self.fonts.get(sg.font_id.0) {
+                Some(font) => font,
+                None => continue,
+            };
+            let glyph = match font.outline_glyph(sg.glyph) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let min = glyph.px_bounds().min;
+            let pixels = &mut self.pixels;
+            let depths = &self.depth;
+            glyph.draw(|x, y, c| {
+                let x = min.x as i32 + x as i32;
+                let y = min.y as i32 + y as i32;
+                let p = Vec2(x as f32 + 0.5, y as f32 + 0.5);
+                if x < 0 || y < 0 || x >= w || y >= h || !in_quad(bounds, p) {
+                    return;
+                }
+                let i = (y * w + x) as usize;
+                if depth < depths[i] {
+                    return;
+                }
+                let alpha = c.max(0.0).min(1.0) * col.a;
+                let px = &mut pixels[i];
+                px[0] = col.r * alpha + px[0] * (1.0 - alpha);
+                px[1] = col.g * alpha + px[1] * (1.0 - alpha);
+                px[2] = col.b * alpha + px[2] * (1.0 - alpha);
+            });
+        }
+    }
+}
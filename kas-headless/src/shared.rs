@@ -0,0 +1,64 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Shared state
+
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use crate::draw::DrawPipe;
+use kas::event::{Shortcuts, UpdateHandle};
+use kas::persist::State;
+use kas::WindowId;
+use kas_theme::Theme;
+
+/// State shared between windows
+pub struct SharedState<T> {
+    pub clipboard: Option<String>,
+    pub draw: DrawPipe,
+    pub theme: T,
+    pub pending: Vec<PendingAction>,
+    pub scale_factor: f64,
+    pub shortcuts: Rc<Shortcuts>,
+    /// Persistent state of windows, restored on window creation
+    pub state: State,
+    window_id: u32,
+}
+
+impl<T: Theme<DrawPipe>> SharedState<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct
+    pub fn new(mut theme: T, scale_factor: f64) -> Self {
+        let mut draw = DrawPipe::new();
+        theme.init(&mut draw);
+
+        SharedState {
+            clipboard: None,
+            draw,
+            theme,
+            pending: vec![],
+            scale_factor,
+            shortcuts: Default::default(),
+            state: State::new(),
+            window_id: 0,
+        }
+    }
+
+    pub fn next_window_id(&mut self) -> WindowId {
+        self.window_id += 1;
+        WindowId::new(NonZeroU32::new(self.window_id).unwrap())
+    }
+}
+
+pub enum PendingAction {
+    AddPopup(WindowId, WindowId, kas::Popup),
+    AddWindow(WindowId, Box<dyn kas::Window>),
+    CloseWindow(WindowId),
+    ThemeResize,
+    RedrawAll,
+    Update(UpdateHandle, u64),
+}
@@ -0,0 +1,504 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Window` type
+
+use log::{debug, trace};
+
+use kas::access::AccessNode;
+use kas::draw::SizeHandle;
+use kas::event::{CursorIcon, Manager, ManagerState, UpdateHandle};
+use kas::geom::{Coord, Rect, Size};
+use kas::layout::{LayoutDump, SolveCache};
+use kas::{ThemeAction, ThemeApi, TkAction, WidgetCore, WindowId};
+use kas_theme::Theme;
+
+use crate::draw::{DrawPipe, DrawWindow};
+use crate::shared::{PendingAction, SharedState};
+
+/// A headless window
+///
+/// This is constructed via [`crate::Toolkit::add`] and may be inspected via
+/// [`crate::Toolkit::window`].
+pub struct Window<TW> {
+    widget: Box<dyn kas::Window>,
+    window_id: WindowId,
+    mgr: ManagerState,
+    solve_cache: SolveCache,
+    size: Size,
+    draw: DrawWindow,
+    theme_window: TW,
+    cursor_icon: CursorIcon,
+    redraw: bool,
+    layout_dump: Option<LayoutDump>,
+}
+
+// Public functions, for use by the user
+impl<TW> Window<TW> {
+    /// The window's identifier
+    #[inline]
+    pub fn id(&self) -> WindowId {
+        self.window_id
+    }
+
+    /// The window's title
+    #[inline]
+    pub fn title(&self) -> &str {
+        self.widget.title()
+    }
+
+    /// The current size of the window
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Access the root widget
+    ///
+    /// The widget may be downcast via [`kas::WidgetCore::as_any`].
+    #[inline]
+    pub fn widget(&self) -> &dyn kas::Window {
+        &*self.widget
+    }
+
+    /// Access the event manager state
+    ///
+    /// This may be used to query input state, e.g. via
+    /// [`ManagerState::nav_focus`].
+    #[inline]
+    pub fn manager(&self) -> &ManagerState {
+        &self.mgr
+    }
+
+    /// Construct the window's accessibility tree
+    ///
+    /// The root node is named by the window's title.
+    pub fn access_tree(&self) -> AccessNode {
+        let mut tree = AccessNode::new(self.widget.as_widget(), &self.mgr);
+        tree.name = Some(self.title().to_string());
+        tree
+    }
+
+    /// Access the recording of the last frame drawn
+    ///
+    /// Frames are drawn by [`crate::Toolkit::draw`].
+    #[inline]
+    pub fn draw(&self) -> &DrawWindow {
+        &self.draw
+    }
+
+    /// The cursor icon last set by the event manager
+    #[inline]
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.cursor_icon
+    }
+
+    /// True if a redraw has been requested since the last frame was drawn
+    #[inline]
+    pub fn needs_redraw(&self) -> bool {
+        self.redraw
+    }
+}
+
+// Public functions, for use by the toolkit
+impl<TW: kas_theme::Window + 'static> Window<TW> {
+    /// Construct a window
+    ///
+    /// If `size` is `None`, the saved size (see [`kas::persist`]) is used if
+    /// available, otherwise the widget's ideal size.
+    pub(crate) fn new<T>(
+        shared: &mut SharedState<T>,
+        window_id: WindowId,
+        mut widget: Box<dyn kas::Window>,
+        size: Option<Size>,
+    ) -> Self
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        let scale_factor = shared.scale_factor as f32;
+        let mut draw = shared.draw.new_window(Size::ZERO);
+        let mut theme_window = shared.theme.new_window(&mut draw, scale_factor);
+
+        let mut size_handle = unsafe { theme_window.size_handle() };
+        let solve_cache = SolveCache::find_constraints(widget.as_widget_mut(), &mut size_handle);
+        drop(size_handle);
+
+        // Restore saved geometry, if any
+        let state_key = widget.state_key().map(|key| key.to_string());
+        let geometry = state_key
+            .as_ref()
+            .and_then(|key| shared.state.window_geometry(key));
+        let size = size
+            .or_else(|| geometry.map(|(size, _)| size))
+            .unwrap_or_else(|| solve_cache.ideal(true));
+        debug!("Constucted new headless window with size {:?}", size);
+        shared.draw.resize(&mut draw, size);
+
+        let mut mgr = ManagerState::new(shared.scale_factor);
+        mgr.use_virtual_clock(true);
+        mgr.set_shortcuts(shared.shortcuts.clone());
+        let mut cursor_icon = CursorIcon::Default;
+        let mut tkw = TkWindow::new(shared, window_id, &mut theme_window, &mut cursor_icon);
+        mgr.configure(&mut tkw, &mut *widget);
+        if let Some(key) = state_key {
+            // Restored state is applied by the first layout (below); any
+            // further action (e.g. to re-solve layout) is handled on update.
+            let action = shared.state.restore_widgets(&key, widget.as_widget_mut());
+            mgr.send_action(action);
+        }
+
+        let mut r = Window {
+            widget,
+            window_id,
+            mgr,
+            solve_cache,
+            size,
+            draw,
+            theme_window,
+            cursor_icon,
+            redraw: true,
+            layout_dump: None,
+        };
+        r.apply_size(shared);
+        r
+    }
+
+    /// Access the event manager state
+    #[inline]
+    pub(crate) fn manager_mut(&mut self) -> &mut ManagerState {
+        &mut self.mgr
+    }
+
+    /// Collect layout information on all widgets
+    pub(crate) fn layout_dump(&mut self) -> LayoutDump {
+        let mut size_handle = unsafe { self.theme_window.size_handle() };
+        LayoutDump::new(self.widget.as_widget_mut(), &mut size_handle)
+    }
+
+    /// Recompute layout of widgets and redraw
+    fn reconfigure<T>(&mut self, shared: &mut SharedState<T>)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        debug!("Window::reconfigure");
+
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            &mut self.theme_window,
+            &mut self.cursor_icon,
+        );
+        self.mgr.configure(&mut tkw, &mut *self.widget);
+
+        self.solve_cache.invalidate_rule_cache();
+        self.apply_size(shared);
+    }
+
+    pub(crate) fn theme_resize<T>(&mut self, shared: &mut SharedState<T>)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        debug!("Window::theme_resize");
+        let scale_factor = shared.scale_factor as f32;
+        shared
+            .theme
+            .update_window(&mut self.theme_window, scale_factor);
+        self.solve_cache.invalidate_rule_cache();
+        self.apply_size(shared);
+    }
+
+    /// Resize the window
+    pub(crate) fn resize<T>(&mut self, shared: &mut SharedState<T>, size: Size)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        if size == self.size {
+            return;
+        }
+        shared.draw.resize(&mut self.draw, size);
+        self.size = size;
+        self.apply_size(shared);
+    }
+
+    /// Run a closure over the event manager and root widget
+    ///
+    /// This is used to deliver input.
+    pub(crate) fn with_manager<T, F>(&mut self, shared: &mut SharedState<T>, f: F)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+        F: FnOnce(&mut Manager, &mut dyn kas::Window),
+    {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            &mut self.theme_window,
+            &mut self.cursor_icon,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| f(mgr, widget));
+    }
+
+    /// Update, after receiving all events
+    pub(crate) fn update<T>(&mut self, shared: &mut SharedState<T>) -> TkAction
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        let mut result = TkAction::None;
+        loop {
+            let mut tkw = TkWindow::new(
+                shared,
+                self.window_id,
+                &mut self.theme_window,
+                &mut self.cursor_icon,
+            );
+            let action = self.mgr.update(&mut tkw, &mut *self.widget);
+            result += action;
+
+            match action {
+                TkAction::None => (),
+                TkAction::Redraw => self.redraw = true,
+                TkAction::RegionMoved => {
+                    self.mgr.region_moved(&mut tkw, &mut *self.widget);
+                    self.redraw = true;
+                }
+                TkAction::Popup => {
+                    let mut size_handle = unsafe { self.theme_window.size_handle() };
+                    self.widget.resize_popups(&mut size_handle);
+                    drop(size_handle);
+
+                    let mut tkw = TkWindow::new(
+                        shared,
+                        self.window_id,
+                        &mut self.theme_window,
+                        &mut self.cursor_icon,
+                    );
+                    self.mgr.region_moved(&mut tkw, &mut *self.widget);
+                    self.redraw = true;
+                }
+                TkAction::ResetSize => self.apply_size(shared),
+                TkAction::Resize => {
+                    self.solve_cache.invalidate_rule_cache();
+                    self.apply_size(shared);
+                }
+                TkAction::Reconfigure => self.reconfigure(shared),
+                TkAction::Close | TkAction::CloseAll => (),
+            }
+
+            // Widgets notified of a resize may require further action
+            if action < TkAction::ResetSize || action > TkAction::Reconfigure {
+                return result;
+            }
+        }
+    }
+
+    pub(crate) fn handle_closure<T>(mut self, shared: &mut SharedState<T>) -> TkAction
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            &mut self.theme_window,
+            &mut self.cursor_icon,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mut mgr| {
+            widget.handle_closure(&mut mgr);
+        });
+        let action = self.mgr.update(&mut tkw, &mut *self.widget);
+
+        if let Some(key) = self.widget.state_key().map(|key| key.to_string()) {
+            shared.state.set_window_geometry(&key, self.size, None);
+            shared.state.save_widgets(&key, self.widget.as_widget());
+        }
+
+        action
+    }
+
+    pub(crate) fn update_timer<T>(&mut self, shared: &mut SharedState<T>)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        self.with_manager(shared, |mgr, widget| mgr.update_timer(widget));
+    }
+
+    pub(crate) fn update_handle<T>(
+        &mut self,
+        shared: &mut SharedState<T>,
+        handle: UpdateHandle,
+        payload: u64,
+    ) where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        self.with_manager(shared, |mgr, widget| {
+            mgr.update_handle(widget, handle, payload)
+        });
+    }
+
+    pub(crate) fn add_popup<T>(
+        &mut self,
+        shared: &mut SharedState<T>,
+        id: WindowId,
+        popup: kas::Popup,
+    ) where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        self.with_manager(shared, |mgr, widget| {
+            kas::Window::add_popup(widget, mgr, id, popup)
+        });
+    }
+
+    pub(crate) fn send_action(&mut self, action: TkAction) {
+        self.mgr.send_action(action);
+    }
+
+    pub(crate) fn send_close<T>(&mut self, shared: &mut SharedState<T>, id: WindowId)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        if id == self.window_id {
+            self.mgr.send_action(TkAction::Close);
+        } else {
+            self.with_manager(shared, |mgr, widget| widget.remove_popup(mgr, id));
+        }
+    }
+
+    /// Draw a frame, recording all draw commands
+    pub(crate) fn do_draw<T>(&mut self, shared: &SharedState<T>)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        trace!("Window::do_draw");
+        let rect = Rect::new(Coord::ZERO, self.size);
+        self.draw.clear();
+        let mut draw_handle = unsafe {
+            shared
+                .theme
+                .draw_handle(&mut self.draw, &mut self.theme_window, rect)
+        };
+        self.widget.draw(&mut draw_handle, &self.mgr, false);
+        if let Some(dump) = self.layout_dump.as_ref() {
+            dump.draw(&mut draw_handle);
+        }
+        self.redraw = false;
+    }
+}
+
+// Internal functions
+impl<TW: kas_theme::Window + 'static> Window<TW> {
+    fn apply_size<T>(&mut self, shared: &mut SharedState<T>)
+    where
+        T: Theme<DrawPipe, Window = TW>,
+    {
+        let rect = Rect::new(Coord::ZERO, self.size);
+        debug!("Resizing window to rect = {:?}", rect);
+
+        let mut size_handle = unsafe { self.theme_window.size_handle() };
+        self.solve_cache
+            .apply_rect(self.widget.as_widget_mut(), &mut size_handle, rect, true);
+        self.widget.resize_popups(&mut size_handle);
+        self.layout_dump = match self.mgr.layout_debug() {
+            true => Some(LayoutDump::new(
+                self.widget.as_widget_mut(),
+                &mut size_handle,
+            )),
+            false => None,
+        };
+        drop(size_handle);
+        self.redraw = true;
+
+        self.with_manager(shared, |mgr, widget| mgr.update_resize(widget));
+    }
+}
+
+struct TkWindow<'a, T: Theme<DrawPipe>>
+where
+    T::Window: kas_theme::Window,
+{
+    shared: &'a mut SharedState<T>,
+    window_id: WindowId,
+    theme_window: &'a mut T::Window,
+    cursor_icon: &'a mut CursorIcon,
+}
+
+impl<'a, T: Theme<DrawPipe>> TkWindow<'a, T>
+where
+    T::Window: kas_theme::Window,
+{
+    fn new(
+        shared: &'a mut SharedState<T>,
+        window_id: WindowId,
+        theme_window: &'a mut T::Window,
+        cursor_icon: &'a mut CursorIcon,
+    ) -> Self {
+        TkWindow {
+            shared,
+            window_id,
+            theme_window,
+            cursor_icon,
+        }
+    }
+}
+
+impl<'a, T> kas::TkWindow for TkWindow<'a, T>
+where
+    T: Theme<DrawPipe>,
+    T::Window: kas_theme::Window,
+{
+    fn add_popup(&mut self, popup: kas::Popup) -> WindowId {
+        let id = self.shared.next_window_id();
+        self.shared
+            .pending
+            .push(PendingAction::AddPopup(self.window_id, id, popup));
+        id
+    }
+
+    fn add_window(&mut self, widget: Box<dyn kas::Window>) -> WindowId {
+        let id = self.shared.next_window_id();
+        self.shared
+            .pending
+            .push(PendingAction::AddWindow(id, widget));
+        id
+    }
+
+    fn close_window(&mut self, id: WindowId) {
+        self.shared.pending.push(PendingAction::CloseWindow(id));
+    }
+
+    fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.shared
+            .pending
+            .push(PendingAction::Update(handle, payload));
+    }
+
+    #[inline]
+    fn get_clipboard(&mut self) -> Option<String> {
+        self.shared.clipboard.clone()
+    }
+
+    #[inline]
+    fn set_clipboard<'c>(&mut self, content: std::borrow::Cow<'c, str>) {
+        self.shared.clipboard = Some(content.into());
+    }
+
+    fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> ThemeAction) {
+        match f(&mut self.shared.theme) {
+            ThemeAction::None => (),
+            ThemeAction::RedrawAll => self.shared.pending.push(PendingAction::RedrawAll),
+            ThemeAction::ThemeResize => self.shared.pending.push(PendingAction::ThemeResize),
+        }
+    }
+
+    fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        use kas_theme::Window;
+        let mut size_handle = unsafe { self.theme_window.size_handle() };
+        f(&mut size_handle);
+    }
+
+    #[inline]
+    fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        *self.cursor_icon = icon;
+    }
+}
@@ -0,0 +1,152 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Accessibility tree tests
+#![feature(proc_macro_hygiene)]
+
+use kas::access::{AccessNode, AccessState, Role};
+use kas::event::{Manager, VirtualKeyCode, VoidMsg, VoidResponse};
+use kas::macros::make_widget;
+use kas::prelude::*;
+use kas::widget::{CheckBox, EditBox, EditBoxVoid, Label, TextButton, Window};
+use kas_headless::query::Query;
+use kas_headless::Toolkit;
+
+/// Find the first node with the given role, in pre-order
+fn find(tree: &AccessNode, role: Role) -> AccessNode {
+    let mut result = None;
+    tree.walk(|node| {
+        if result.is_none() && node.role == role {
+            result = Some(node.clone());
+        }
+    });
+    result.unwrap_or_else(|| panic!("no node with role {:?}", role))
+}
+
+#[test]
+fn access_tree() {
+    let window = Window::new(
+        "Access",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] _ = Label::new("Name:"),
+                #[widget] edit: EditBoxVoid = EditBox::new("Ferris"),
+                #[widget(handler = lock)] _ = TextButton::new("&Lock", ()),
+                #[widget] _ = CheckBox::new("&Check"),
+            }
+            impl {
+                fn lock(&mut self, mgr: &mut Manager, _: ()) -> VoidResponse {
+                    *mgr += self.edit.set_disabled(true);
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    let tree = toolkit.access_tree(id).unwrap();
+
+    assert_eq!(tree.role, Role::Window);
+    assert_eq!(tree.name.as_deref(), Some("Access"));
+    let content = &tree.children[0];
+    assert_eq!(content.role, Role::Group);
+    let roles: Vec<Role> = content.children.iter().map(|node| node.role).collect();
+    assert_eq!(
+        roles,
+        vec![Role::Label, Role::TextEntry, Role::Button, Role::CheckBox]
+    );
+
+    let label = &content.children[0];
+    assert_eq!(label.name.as_deref(), Some("Name:"));
+    assert_eq!(label.state, AccessState::default());
+
+    // The text of an entry is its value, not its name
+    let entry = &content.children[1];
+    assert_eq!(
+        (entry.name.as_deref(), entry.value.as_deref()),
+        (None, Some("Ferris"))
+    );
+    assert!(entry.state.focusable && entry.state.editable && !entry.state.focused);
+
+    // Controls are single nodes, named by their label
+    let button = &content.children[2];
+    assert_eq!(button.name.as_deref(), Some("Lock"));
+    assert!(button.state.focusable);
+    let check = &content.children[3];
+    assert_eq!(check.name.as_deref(), Some("Check"));
+    assert_eq!(check.state.checked, Some(false));
+    assert!(check.children.is_empty());
+
+    let text = tree.to_string();
+    assert!(text.starts_with("Window \"Access\"\n"));
+    assert!(text.contains("\n    Label \"Name:\"\n"));
+    assert!(text.contains("\n    TextEntry value=\"Ferris\" [focusable, editable]\n"));
+
+    // The title names the window only; it is not the window's text
+    assert!(toolkit.find(id, &Query::new().text("Access")).is_none());
+
+    // State changes are reflected in a new snapshot
+    toolkit
+        .click_widget(id, &Query::new().name("CheckBox"))
+        .unwrap();
+    toolkit
+        .click_widget(id, &Query::new().name("EditBox"))
+        .unwrap();
+    let tree = toolkit.access_tree(id).unwrap();
+    assert_eq!(tree.focused().map(|node| node.role), Some(Role::TextEntry));
+    assert_eq!(find(&tree, Role::CheckBox).state.checked, Some(true));
+
+    toolkit
+        .click_widget(id, &Query::new().text("Lock"))
+        .unwrap();
+    let tree = toolkit.access_tree(id).unwrap();
+    let entry = find(&tree, Role::TextEntry);
+    assert!(entry.state.disabled && !entry.state.editable);
+}
+
+fn count(tree: &AccessNode, role: Role) -> usize {
+    let mut n = 0;
+    tree.walk(|node| {
+        if node.role == role {
+            n += 1;
+        }
+    });
+    n
+}
+
+#[test]
+fn access_popup() {
+    let edit: EditBoxVoid = EditBox::new("Ferris");
+    let window = Window::new("Popup", edit);
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+
+    // The window's context menu is not shown until opened
+    let tree = toolkit.access_tree(id).unwrap();
+    assert_eq!(count(&tree, Role::PopupMenu), 0);
+    assert_eq!(count(&tree, Role::MenuItem), 0);
+
+    toolkit
+        .click_widget(id, &Query::new().name("EditBox"))
+        .unwrap();
+    toolkit.key(id, VirtualKeyCode::Apps);
+    let tree = toolkit.access_tree(id).unwrap();
+    assert_eq!(count(&tree, Role::PopupMenu), 1);
+    let menu = find(&tree, Role::PopupMenu);
+    let mut names = vec![];
+    menu.walk(|node| {
+        if node.role == Role::MenuItem {
+            names.extend(node.name.clone());
+        }
+    });
+    assert!(names.iter().any(|name| name == "Paste"));
+
+    toolkit.key(id, VirtualKeyCode::Escape);
+    let tree = toolkit.access_tree(id).unwrap();
+    assert_eq!(count(&tree, Role::PopupMenu), 0);
+}
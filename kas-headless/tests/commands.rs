@@ -0,0 +1,86 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Command and shortcut tests
+#![feature(proc_macro_hygiene)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use kas::event::{
+    Commands, Event, Handler, Manager, ModifiersState, Response, Shortcuts, VirtualKeyCode, VoidMsg,
+};
+use kas::macros::{make_widget, VoidMsg};
+use kas::widget::{TextButton, Window};
+use kas_headless::Toolkit;
+
+#[derive(Clone, Debug, PartialEq, VoidMsg)]
+enum Msg {
+    Save,
+    Top,
+}
+
+#[test]
+fn shortcut_to_command() {
+    let commands = Commands::new();
+    commands.add("save", "&Save", Msg::Save);
+    commands.set_shortcut("save", ModifiersState::CTRL, VirtualKeyCode::S);
+    commands.add("top", "&Top", Msg::Top);
+    commands.set_shortcut("top", ModifiersState::CTRL, VirtualKeyCode::Home);
+    let mut shortcuts = Shortcuts::default();
+    commands.bind_shortcuts(&mut shortcuts);
+
+    // "save" is handled by a bound button; "top" by the root widget
+    let log = Rc::new(RefCell::new(vec![]));
+    let window = Window::new(
+        "Commands",
+        make_widget! {
+            #[layout(column)]
+            struct {
+                #[widget(handler = on_msg)] _ = TextButton::new_cmd(&commands, "save"),
+                commands: Commands<Msg> = commands.clone(),
+                log: Rc<RefCell<Vec<Msg>>> = log.clone(),
+            }
+            impl {
+                fn on_msg(&mut self, _: &mut Manager, msg: Msg) -> Response<VoidMsg> {
+                    self.log.borrow_mut().push(msg);
+                    Response::None
+                }
+            }
+            impl Handler {
+                type Msg = VoidMsg;
+                fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<VoidMsg> {
+                    match event {
+                        Event::Command(name) => match self.commands.msg(&name) {
+                            Some(msg) => self.on_msg(mgr, msg),
+                            None => Response::None,
+                        },
+                        event => Response::Unhandled(event),
+                    }
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    toolkit.set_shortcuts(shortcuts);
+    let id = toolkit.add(window);
+
+    toolkit.set_modifiers(id, ModifiersState::CTRL);
+    toolkit.key(id, VirtualKeyCode::S);
+    toolkit.key(id, VirtualKeyCode::Home);
+    toolkit.set_modifiers(id, ModifiersState::empty());
+    assert_eq!(*log.borrow(), vec![Msg::Save, Msg::Top]);
+
+    // Without modifiers, Home is not a command
+    toolkit.key(id, VirtualKeyCode::Home);
+    assert_eq!(log.borrow().len(), 2);
+
+    // Disabled commands send no message
+    toolkit.input(id, |mgr, _| commands.set_enabled(mgr, "save", false));
+    toolkit.set_modifiers(id, ModifiersState::CTRL);
+    toolkit.key(id, VirtualKeyCode::S);
+    assert_eq!(log.borrow().len(), 2);
+}
@@ -0,0 +1,54 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Layout tests
+
+use kas::event::VoidMsg;
+use kas::geom::{Coord, Rect, Size};
+use kas::widget::{BoxColumn, Filler, Separator, Window};
+use kas_headless::Toolkit;
+
+#[test]
+fn layout_dump() {
+    // Fillers and separators are sized independently of fonts
+    let column: BoxColumn<VoidMsg> = BoxColumn::new(vec![
+        Box::new(Filler::new()),
+        Box::new(Separator::new()),
+        Box::new(Filler::new()),
+    ]);
+    let window = Window::new("Layout", column);
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add_with_size(window, Size(100, 60));
+
+    // Widgets are numbered in post-order; the window reserves identifiers
+    // for its context menu (#5..#1028), which is not open, thus not sized.
+    let dump = toolkit.layout_dump(id).unwrap();
+    let expected = "\
+Window #1031 rect=(0, 0) 100x60; horiz: 4..4, m=(0, 0), Filler; vert: 4..4, m=(0, 0), Filler
+  List #4 rect=(0, 0) 100x60; horiz: 4..4, m=(0, 0), Filler; vert: 4..4, m=(0, 0), Filler
+    Filler #1 rect=(0, 0) 100x28; horiz: 0..0, m=(0, 0), Filler; vert: 0..0, m=(0, 0), Filler
+    Separator #2 rect=(0, 28) 100x4; horiz: 4..4, m=(0, 0), Fixed; vert: 4..4, m=(0, 0), Fixed
+    Filler #3 rect=(0, 32) 100x28; horiz: 0..0, m=(0, 0), Filler; vert: 0..0, m=(0, 0), Filler
+  MenuFrame #1030 rect=(0, 0) 0x0; horiz: 8..8, m=(0, 0), Fixed; vert: 8..8, m=(0, 0), Fixed
+    List #1029 rect=(0, 0) 0x0; horiz: 0..0, m=(0, 0), Fixed; vert: 0..0, m=(0, 0), Fixed
+";
+    assert_eq!(dump.to_string(), expected);
+
+    // The dump reflects the latest layout
+    toolkit.resize(id, Size(80, 100));
+    let dump = toolkit.layout_dump(id).unwrap();
+    let rects: Vec<Rect> = dump
+        .entries()
+        .iter()
+        .filter(|entry| entry.depth == 2 && entry.name != "List")
+        .map(|entry| entry.rect)
+        .collect();
+    let expected = vec![
+        Rect::new(Coord(0, 0), Size(80, 48)),
+        Rect::new(Coord(0, 48), Size(80, 4)),
+        Rect::new(Coord(0, 52), Size(80, 48)),
+    ];
+    assert_eq!(rects, expected);
+}
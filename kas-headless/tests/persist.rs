@@ -0,0 +1,146 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Persistent state tests
+#![feature(proc_macro_hygiene)]
+
+use kas::event::{MouseButton, ScrollDelta, VoidMsg};
+use kas::geom::{Coord, Size};
+use kas::macros::make_widget;
+use kas::persist::{State, StateValue};
+use kas::widget::{Column, Label, RowSplitter, ScrollRegion, Stack, Window};
+use kas::{TkAction, WidgetChildren, WidgetCore, WindowId};
+use kas_headless::query::Query;
+use kas_headless::theme::FlatTheme;
+use kas_headless::Toolkit;
+
+fn add_window(toolkit: &mut Toolkit<FlatTheme>) -> WindowId {
+    let lines: Vec<Label> = (0..50).map(|i| Label::new(format!("Line {}", i))).collect();
+    let window = Window::new(
+        "Persist",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] _ = RowSplitter::new(vec![Label::new("Left"), Label::new("Right")])
+                    .with_state_key("split"),
+                #[widget] _ = Stack::new(
+                    vec![Label::new("Page 0"), Label::new("Page 1"), Label::new("Page 2")],
+                    0,
+                )
+                .with_state_key("stack"),
+                #[widget] _ = ScrollRegion::new(Column::new(lines)).with_state_key("scroll"),
+            }
+        },
+    )
+    .with_state_key("main");
+    toolkit.add_with_size(window, Size(300, 200))
+}
+
+fn save(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> State {
+    let mut state = State::new();
+    state.save_widgets("main", toolkit.window(id).unwrap().widget().as_widget());
+    state
+}
+
+fn left_width(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> u32 {
+    let left = toolkit.try_find(id, &Query::new().text("Left")).unwrap();
+    left.rect.size.0
+}
+
+/// Drag the splitter's handle (the first in the window)
+fn drag_splitter(toolkit: &mut Toolkit<FlatTheme>, id: WindowId, dx: i32) {
+    let handle = toolkit.find(id, &Query::new().name("DragHandle")).unwrap();
+    let start = handle.centre();
+    toolkit.mouse_move(id, start);
+    toolkit.mouse_press(id, MouseButton::Left);
+    toolkit.mouse_move(id, start + Coord(dx, 0));
+    toolkit.mouse_release(id, MouseButton::Left);
+}
+
+#[test]
+fn save_restore() {
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    let id = add_window(&mut toolkit);
+    let initial = save(&toolkit, id);
+
+    let width = left_width(&toolkit, id);
+    drag_splitter(&mut toolkit, id, 30);
+    assert_eq!(left_width(&toolkit, id), width + 30);
+
+    let stack = toolkit.try_find(id, &Query::new().name("Stack")).unwrap();
+    toolkit.input(id, |mgr, widget| {
+        let stack = widget.find_mut(stack.id).unwrap().as_any_mut();
+        *mgr += stack.downcast_mut::<Stack<Label>>().unwrap().set_active(2);
+    });
+
+    let region = toolkit
+        .try_find(id, &Query::new().name("ScrollRegion"))
+        .unwrap();
+    toolkit.mouse_move(id, region.centre());
+    toolkit.scroll(id, ScrollDelta::LineDelta(0.0, -1.0));
+
+    let state = save(&toolkit, id);
+    match state.get("main/split") {
+        Some(StateValue::Ints(v)) => assert_eq!(v[0], (width + 30) as i64),
+        v => panic!("unexpected value: {:?}", v),
+    }
+    assert_eq!(state.get("main/stack"), Some(&StateValue::Int(2)));
+    match state.get("main/scroll") {
+        Some(StateValue::Ints(v)) => assert!(v[0] == 0 && v[1] > 0),
+        v => panic!("unexpected value: {:?}", v),
+    }
+
+    // Restoring to a new window reproduces the saved state
+    let id = add_window(&mut toolkit);
+    assert_eq!(save(&toolkit, id), initial);
+    toolkit.input(id, |mgr, widget| {
+        *mgr += state.restore_widgets("main", widget.as_widget_mut());
+    });
+    assert_eq!(save(&toolkit, id), state);
+    assert_eq!(left_width(&toolkit, id), width + 30);
+
+    // Restored sizes survive layout being solved again
+    toolkit.input(id, |mgr, _| *mgr += TkAction::ResetSize);
+    assert_eq!(save(&toolkit, id), state);
+
+    // ... but are replaced once the user drags the handle
+    drag_splitter(&mut toolkit, id, -10);
+    assert_eq!(left_width(&toolkit, id), width + 20);
+    toolkit.input(id, |mgr, _| *mgr += TkAction::ResetSize);
+    assert_eq!(left_width(&toolkit, id), width + 20);
+}
+
+#[test]
+fn restore_on_add() {
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    let id = add_window(&mut toolkit);
+    let width = left_width(&toolkit, id);
+    drag_splitter(&mut toolkit, id, 30);
+    let state = save(&toolkit, id);
+
+    // State and geometry are saved on closure
+    toolkit.close_requested(id);
+    assert!(toolkit.window(id).is_none());
+    assert_eq!(
+        toolkit.state().window_geometry("main"),
+        Some((Size(300, 200), None))
+    );
+    assert_eq!(toolkit.state().get("main/split"), state.get("main/split"));
+
+    // ... and restored when a window with the same key is added
+    let mut saved = toolkit.state().clone();
+    saved.set_window_geometry("main", Size(320, 240), None);
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    toolkit.set_state(saved);
+    let id = add_window(&mut toolkit);
+    assert_eq!(save(&toolkit, id), state);
+    assert_eq!(left_width(&toolkit, id), width + 30);
+    assert_eq!(toolkit.window(id).unwrap().size(), Size(300, 200));
+
+    let window = Window::new("Persist", Label::new("Resized")).with_state_key("main");
+    let id = toolkit.add(window);
+    assert_eq!(toolkit.window(id).unwrap().size(), Size(320, 240));
+}
@@ -0,0 +1,142 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget query tests
+#![feature(proc_macro_hygiene)]
+
+use kas::event::{Manager, VoidMsg, VoidResponse};
+use kas::geom::{Coord, Rect};
+use kas::macros::make_widget;
+use kas::widget::{CheckBox, EditBox, Label, TextButton, Window};
+use kas::WindowId;
+use kas_headless::query::{Query, QueryError};
+use kas_headless::theme::FlatTheme;
+use kas_headless::Toolkit;
+
+fn toolkit() -> (Toolkit<FlatTheme>, WindowId) {
+    let buttons = make_widget! {
+        #[layout(row)]
+        #[handler(msg = ())]
+        struct {
+            #[widget] _ = TextButton::new("&OK", ()),
+            #[widget] _ = TextButton::new("Cancel", ()),
+        }
+    };
+    let window = Window::new(
+        "Query",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] _ = Label::new("Name:"),
+                #[widget] _ = EditBox::new("text"),
+                #[widget(handler = button)] _ = buttons,
+                #[widget] _ = CheckBox::new("&Check"),
+            }
+            impl {
+                fn button(&mut self, _: &mut Manager, _: ()) -> VoidResponse {
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    let id = toolkit.add(window);
+    (toolkit, id)
+}
+
+/// True if `a` is entirely above `b`
+fn above(a: Rect, b: Rect) -> bool {
+    a.pos.1 + a.size.1 as i32 <= b.pos.1
+}
+
+#[test]
+fn find_by_name_and_text() {
+    let (toolkit, id) = toolkit();
+
+    let label = toolkit.try_find(id, &Query::new().text("Name:")).unwrap();
+    assert_eq!(label.name, "Label");
+    let edit = toolkit.try_find(id, &Query::new().name("EditBox")).unwrap();
+    assert_eq!(edit.text.as_deref(), Some("text"));
+    assert!(edit.key_nav);
+
+    // Accelerator prefixes are not included in text
+    let ok = Query::new().name("TextButton").text("OK");
+    let ok = toolkit.try_find(id, &ok).unwrap();
+    let buttons = toolkit.find_all(id, &Query::new().name("TextButton"));
+    assert_eq!(buttons.len(), 2);
+    assert_eq!(buttons[0], ok);
+    assert_eq!(buttons[1].text.as_deref(), Some("Cancel"));
+    let second = Query::new().name("TextButton").nth(1);
+    assert_eq!(toolkit.find(id, &second), Some(buttons[1].clone()));
+    assert_eq!(toolkit.find(id, &second.nth(2)), None);
+
+    let query = Query::new().name("TextButton").text("Save");
+    assert_eq!(
+        toolkit.try_find(id, &query),
+        Err(QueryError::NotFound(query.clone()))
+    );
+}
+
+#[test]
+fn find_by_path() {
+    let (toolkit, id) = toolkit();
+    let ok = toolkit.try_find(id, &Query::new().text("OK")).unwrap();
+    let cancel = toolkit.try_find(id, &Query::new().text("Cancel")).unwrap();
+
+    // Paths locate the same widgets
+    assert_eq!(toolkit.find(id, &Query::path(&ok.path)), Some(ok.clone()));
+    let row = &ok.path[..ok.path.len() - 1];
+    assert_eq!(cancel.path[..row.len()], *row);
+
+    // Only the buttons are within their row
+    let within = toolkit.find_all(id, &Query::new().within(row));
+    assert_eq!(within, vec![ok.clone(), cancel]);
+    let labels = Query::new().name("Label").within(row);
+    assert_eq!(toolkit.find(id, &labels), None);
+}
+
+#[test]
+fn geometry() {
+    let (toolkit, id) = toolkit();
+    let window = Rect::new(Coord::ZERO, toolkit.window(id).unwrap().size());
+    let find = |query: Query| toolkit.try_find(id, &query).unwrap();
+    let label = find(Query::new().name("Label"));
+    let edit = find(Query::new().name("EditBox"));
+    let ok = find(Query::new().text("OK"));
+    let cancel = find(Query::new().text("Cancel"));
+    let check = find(Query::new().name("CheckBox"));
+
+    for info in &[&label, &edit, &ok, &cancel, &check] {
+        let rect = info.rect;
+        assert!(rect.size.0 > 0 && rect.size.1 > 0);
+        assert!(window.contains(rect.pos));
+        assert!(window.contains(rect.pos + rect.size - Coord(1, 1)));
+        assert!(rect.contains(info.centre()));
+    }
+
+    // The column places widgets top to bottom, the row left to right
+    assert!(above(label.rect, edit.rect));
+    assert!(above(edit.rect, ok.rect));
+    assert!(above(ok.rect, check.rect));
+    assert_eq!(ok.rect.pos.1, cancel.rect.pos.1);
+    assert!(ok.rect.pos.0 + ok.rect.size.0 as i32 <= cancel.rect.pos.0);
+}
+
+#[test]
+fn find_by_state() {
+    let (mut toolkit, id) = toolkit();
+    let unchecked = Query::new().name("CheckBox").state(false);
+    let checked = Query::new().name("CheckBox").state(true);
+    assert_eq!(toolkit.find(id, &checked), None);
+
+    let info = toolkit.click_widget(id, &unchecked).unwrap();
+    assert_eq!(info.text.as_deref(), Some("Check"));
+    assert_eq!(toolkit.find(id, &unchecked), None);
+    let found = toolkit.try_find(id, &checked).unwrap();
+    assert_eq!((found.path, found.rect), (info.path, info.rect));
+    toolkit.assert_state(id, &Query::new().name("CheckBox"), true);
+}
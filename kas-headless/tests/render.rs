@@ -0,0 +1,333 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Software rendering tests
+#![feature(proc_macro_hygiene)]
+
+use kas::draw::{Colour, Draw, DrawGradient, DrawRounded, DrawShaded, Elevation, Gradient};
+use kas::event::VoidMsg;
+use kas::geom::{Coord, Quad, Rect, Size, Vec2};
+use kas::macros::make_widget;
+use kas::widget::{Label, MessageBox, Window};
+use kas_headless::draw::DrawPipe;
+use kas_headless::query::Query;
+use kas_headless::raster::Canvas;
+use kas_headless::theme::{FlatTheme, ShadedTheme, Theme, START_PASS};
+use kas_headless::Toolkit;
+
+/// Assert that colours are equal, within a small tolerance
+fn assert_colour(a: Colour, b: Colour) {
+    let close = |x: f32, y: f32| (x - y).abs() < 1e-3;
+    assert!(
+        close(a.r, b.r) && close(a.g, b.g) && close(a.b, b.b),
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+/// Sum of colour components, a crude measure of brightness
+fn brightness(col: Colour) -> f32 {
+    col.r + col.g + col.b
+}
+
+/// Iterate over all pixel coordinates of `canvas`
+fn pixels(canvas: &Canvas) -> impl Iterator<Item = (u32, u32)> {
+    let size = canvas.size();
+    (0..size.1).flat_map(move |y| (0..size.0).map(move |x| (x, y)))
+}
+
+#[test]
+fn rect() {
+    let size = Size(8, 8);
+    let red = Colour::new(1.0, 0.0, 0.0);
+    let black = Colour::grey(0.0);
+    let mut canvas = Canvas::new(size, black);
+    canvas.rect(
+        START_PASS,
+        Quad::with_coords(Vec2(2.0, 2.0), Vec2(6.0, 6.0)),
+        red,
+    );
+
+    for (x, y) in pixels(&canvas) {
+        let inside = 2 <= x && x < 6 && 2 <= y && y < 6;
+        let expected = if inside { red } else { black };
+        assert_colour(canvas.pixel(x, y), expected);
+    }
+}
+
+#[test]
+fn circle() {
+    let size = Size(16, 16);
+    let white = Colour::grey(1.0);
+    let black = Colour::grey(0.0);
+    let quad = Quad::with_coords(Vec2(0.0, 0.0), Vec2(16.0, 16.0));
+
+    let mut canvas = Canvas::new(size, black);
+    canvas.circle(START_PASS, quad, 0.0, white);
+    assert_colour(canvas.pixel(8, 8), white);
+    assert_colour(canvas.pixel(0, 8), white);
+    assert_colour(canvas.pixel(15, 8), white);
+    assert_colour(canvas.pixel(0, 0), black);
+    assert_colour(canvas.pixel(15, 15), black);
+
+    // Edges are anti-aliased: some pixels are only partially covered
+    let partial = pixels(&canvas)
+        .map(|(x, y)| canvas.pixel(x, y).r)
+        .filter(|r| 0.0 < *r && *r < 1.0)
+        .count();
+    assert!(partial > 0);
+
+    // With an inner radius, the centre is not drawn
+    let mut canvas = Canvas::new(size, black);
+    canvas.circle(START_PASS, quad, 0.5, white);
+    assert_colour(canvas.pixel(8, 8), black);
+    assert_colour(canvas.pixel(1, 8), white);
+    assert_colour(canvas.pixel(0, 0), black);
+}
+
+#[test]
+fn clip_region() {
+    let size = Size(8, 8);
+    let red = Colour::new(1.0, 0.0, 0.0);
+    let black = Colour::grey(0.0);
+    let mut canvas = Canvas::new(size, black);
+    let clip = Rect::new(Coord(2, 3), Size(4, 2));
+    let pass = canvas.add_clip_region(clip, 0.1);
+    canvas.rect(pass, Quad::with_coords(Vec2(0.0, 0.0), Vec2(8.0, 8.0)), red);
+
+    for (x, y) in pixels(&canvas) {
+        let inside = clip.contains(Coord(x as i32, y as i32));
+        let expected = if inside { red } else { black };
+        assert_colour(canvas.pixel(x, y), expected);
+    }
+}
+
+#[test]
+fn text_placement() {
+    let window = Window::new(
+        "Text",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] _ = Label::new("Hello"),
+                #[widget] _ = Label::new("World"),
+            }
+        },
+    );
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    let canvas = toolkit.render(id).unwrap();
+    let clear = toolkit.theme().clear_colour();
+    let hello = toolkit.try_find(id, &Query::new().text("Hello")).unwrap();
+    let world = toolkit.try_find(id, &Query::new().text("World")).unwrap();
+
+    // Each label is drawn within its own rect and nothing is drawn elsewhere
+    let (mut in_hello, mut in_world) = (0, 0);
+    for (x, y) in pixels(&canvas) {
+        let px = canvas.pixel(x, y);
+        if (px.r - clear.r).abs() < 1e-3
+            && (px.g - clear.g).abs() < 1e-3
+            && (px.b - clear.b).abs() < 1e-3
+        {
+            continue;
+        }
+        let coord = Coord(x as i32, y as i32);
+        if hello.rect.contains(coord) {
+            in_hello += 1;
+        } else if world.rect.contains(coord) {
+            in_world += 1;
+        } else {
+            panic!("unexpected pixel drawn at {:?}", coord);
+        }
+    }
+    assert!(in_hello > 0);
+    assert!(in_world > 0);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn compare_png() {
+    let size = Size(4, 4);
+    let black = Colour::grey(0.0);
+    let path = std::env::temp_dir().join("kas-headless-compare_png.png");
+    let mut canvas = Canvas::new(size, black);
+    canvas.write_png(&path).unwrap();
+    assert_eq!(canvas.compare_png(&path, 0).unwrap(), 0);
+
+    // A single pixel of value 2 (in 8-bit sRGB) differs only at tolerance < 2
+    let grey = Colour::grey(2.0 / (12.92 * 255.0));
+    let pixel = Quad::with_coords(Vec2(1.0, 1.0), Vec2(2.0, 2.0));
+    canvas.rect(START_PASS, pixel, grey);
+    assert_eq!(canvas.to_rgba8()[4 * 5], 2);
+    assert_eq!(canvas.compare_png(&path, 0).unwrap(), 1);
+    assert_eq!(canvas.compare_png(&path, 1).unwrap(), 1);
+    assert_eq!(canvas.compare_png(&path, 2).unwrap(), 0);
+
+    // All pixels differ if the size differs
+    let canvas = Canvas::new(Size(4, 2), black);
+    assert_eq!(canvas.compare_png(&path, 255).unwrap(), 8);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn render_png() {
+    // Rendering is deterministic: a widget matches its own golden image
+    let window = Window::new("Golden", Label::new("Golden image"));
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    let path = std::env::temp_dir().join("kas-headless-render_png.png");
+    toolkit.render(id).unwrap().write_png(&path).unwrap();
+    let canvas = toolkit.render(id).unwrap();
+    assert_eq!(canvas.compare_png(&path, 0).unwrap(), 0);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn batch_order() {
+    let size = Size(4, 4);
+    let quad = Quad::with_coords(Vec2(0.0, 0.0), Vec2(4.0, 4.0));
+    let red = Colour::new(1.0, 0.0, 0.0);
+    let blue = Colour::new(0.0, 0.0, 1.0).with_alpha(0.5);
+    let gradient = Gradient::linear(Vec2(0.0, 0.0), Vec2(4.0, 0.0), blue, blue);
+
+    // The gradient is issued first, but (as with kas-wgpu) the rect batch is
+    // drawn first, thus the translucent gradient is blended over it.
+    let mut draw = DrawPipe::new().new_window(size);
+    draw.gradient_rect(START_PASS, quad, &gradient);
+    draw.rect(START_PASS, quad, red);
+
+    let mut canvas = Canvas::new(size, Colour::grey(0.0));
+    draw.replay(&mut canvas);
+    assert_colour(canvas.pixel(1, 1), Colour::new(0.5, 0.0, 0.5));
+}
+
+#[test]
+fn shadow() {
+    let size = Size(32, 32);
+    let white = Colour::grey(1.0);
+    let col = Colour::grey(0.0).with_alpha(0.5);
+    let outer = Quad::with_coords(Vec2(2.0, 2.0), Vec2(30.0, 30.0));
+    let inner = Quad::with_coords(Vec2(8.0, 8.0), Vec2(24.0, 24.0));
+    let mut canvas = Canvas::new(size, white);
+    canvas.shadow(START_PASS, outer, inner, col);
+
+    // Neither the inner quad nor anything outside the outer quad is drawn
+    assert_colour(canvas.pixel(16, 16), white);
+    assert_colour(canvas.pixel(1, 16), white);
+    assert_colour(canvas.pixel(30, 16), white);
+    assert_colour(canvas.pixel(16, 0), white);
+    assert_colour(canvas.pixel(16, 31), white);
+
+    // The shadow is darkest at the inner edge, fading with distance
+    let b = |x, y| brightness(canvas.pixel(x, y));
+    assert!(b(7, 16) < 1.8);
+    assert!(b(7, 16) < b(5, 16));
+    assert!(b(5, 16) < b(3, 16));
+    assert!(b(3, 16) < 3.0);
+    assert!(b(24, 16) < b(27, 16));
+    assert!(b(16, 7) < b(16, 4));
+    assert!(b(16, 24) < b(16, 28));
+
+    // The shadow is symmetric and its corners are rounded
+    assert!((b(5, 16) - b(26, 16)).abs() < 1e-3);
+    assert!((b(16, 5) - b(16, 26)).abs() < 1e-3);
+    assert!(b(4, 16) < b(4, 4));
+    assert_colour(canvas.pixel(2, 2), white);
+    assert_colour(canvas.pixel(29, 29), white);
+}
+
+#[test]
+fn shaded() {
+    let size = Size(16, 16);
+    let white = Colour::grey(1.0);
+    let black = Colour::grey(0.0);
+    let quad = Quad::with_coords(Vec2(0.0, 0.0), Vec2(16.0, 16.0));
+    let inner = Quad::with_coords(Vec2(4.0, 4.0), Vec2(12.0, 12.0));
+
+    // The light comes from above and slightly to the right
+    let mut canvas = Canvas::new(size, black);
+    canvas.shaded_square(START_PASS, quad, (0.0, 0.7), white);
+    let b = |canvas: &Canvas, x, y| brightness(canvas.pixel(x, y));
+    assert!(b(&canvas, 8, 1) > b(&canvas, 8, 14));
+    assert!(b(&canvas, 14, 8) > b(&canvas, 1, 8));
+    assert!(b(&canvas, 8, 8) > b(&canvas, 8, 14));
+
+    let mut canvas = Canvas::new(size, black);
+    canvas.shaded_circle(START_PASS, quad, (0.0, 0.7), white);
+    assert!(b(&canvas, 8, 1) > b(&canvas, 8, 14));
+    assert!(b(&canvas, 8, 14) > 0.0);
+    assert_colour(canvas.pixel(0, 0), black);
+
+    // A raised frame: the inner quad is not drawn
+    for round in &[false, true] {
+        let mut canvas = Canvas::new(size, black);
+        if *round {
+            canvas.shaded_round_frame(START_PASS, quad, inner, (0.7, 0.0), white);
+        } else {
+            canvas.shaded_square_frame(START_PASS, quad, inner, (0.7, 0.0), white);
+        }
+        assert_colour(canvas.pixel(8, 8), black);
+        assert!(b(&canvas, 8, 2) > b(&canvas, 8, 13));
+        assert!(b(&canvas, 13, 8) > b(&canvas, 2, 8));
+        assert!(b(&canvas, 8, 13) > 0.0);
+    }
+}
+
+/// Check the shadow cast by a [`MessageBox`] over the window background
+fn dialog_shadow<T: Theme<DrawPipe> + 'static>(theme: T)
+where
+    T::Window: kas_headless::theme::Window,
+{
+    let mut toolkit = Toolkit::new(theme);
+    let id = toolkit.add(MessageBox::new("Shadow", "A raised surface"));
+    let mut shadow = (Size::ZERO, Size::ZERO);
+    toolkit.input(id, |mgr, _| {
+        shadow = mgr.size_handle(|size_handle| size_handle.shadow(Elevation::Dialog));
+    });
+    let (tl, br) = shadow;
+    assert!(tl.1 > 0 && br.1 > tl.1, "shadow not offset downward");
+    assert_eq!(tl.0, br.0);
+
+    let canvas = toolkit.render(id).unwrap();
+    let clear = brightness(toolkit.theme().clear_colour());
+    let size = canvas.size();
+    let (x, y) = (size.0 / 2, size.1 / 2);
+    let b = |x, y| brightness(canvas.pixel(x, y));
+
+    // The shadow darkens the background around the surface, fading outwards
+    let bottom = size.1 - br.1;
+    assert!(b(x, bottom) < clear - 1e-3);
+    for y in bottom..(size.1 - 1) {
+        assert!(b(x, y) <= b(x, y + 1) + 1e-3);
+    }
+    for y in 1..tl.1 {
+        assert!(b(x, y - 1) + 1e-3 >= b(x, y));
+    }
+    let right = size.0 - br.0;
+    assert!(b(right, y) < clear - 1e-3);
+    assert!(b(tl.0 - 1, y) < clear - 1e-3);
+    assert!((b(tl.0 - 1, y) - b(right, y)).abs() < 1e-3);
+
+    // Being offset downward, the shadow is darker below than above
+    let d = tl.1 / 2;
+    assert!(b(x, bottom + d) < b(x, tl.1 - 1 - d));
+
+    // The window corners are beyond the rounded corners of the shadow
+    let corner = canvas.pixel(0, 0);
+    assert!((brightness(corner) - clear).abs() < 1e-3);
+}
+
+#[test]
+fn dialog_shadow_flat() {
+    dialog_shadow(FlatTheme::new());
+}
+
+#[test]
+fn dialog_shadow_shaded() {
+    dialog_shadow(ShadedTheme::new());
+}
@@ -0,0 +1,71 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Input recording and replay tests
+#![feature(proc_macro_hygiene)]
+
+use std::time::Duration;
+
+use kas::class::SetText;
+use kas::event::{Manager, VoidMsg, VoidResponse};
+use kas::macros::make_widget;
+use kas::widget::{EditBox, Label, TextButton, Window};
+use kas_headless::query::Query;
+use kas_headless::Toolkit;
+
+fn window() -> Box<dyn kas::Window> {
+    Box::new(Window::new(
+        "Replay",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] _ = EditBox::new(""),
+                #[widget(handler = add)] _ = TextButton::new("Add", ()),
+                #[widget] label: Label = Label::new("0"),
+                count: u32 = 0,
+            }
+            impl {
+                fn add(&mut self, mgr: &mut Manager, _: ()) -> VoidResponse {
+                    self.count += 1;
+                    *mgr += self.label.set_text(self.count.to_string());
+                    VoidResponse::None
+                }
+            }
+        },
+    ))
+}
+
+#[test]
+fn record_replay() {
+    let edit = Query::new().name("EditBox");
+    let add = Query::new().name("TextButton").text("Add");
+    let label = Query::new().name("Label");
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add_boxed(window(), None);
+    toolkit.start_recording(id);
+    toolkit.type_into(id, &edit, "abc").unwrap();
+    toolkit.click_widget(id, &add).unwrap();
+    toolkit.advance_clock(Duration::from_millis(100));
+    toolkit.click_widget(id, &add).unwrap();
+    let recording = toolkit.stop_recording(id).unwrap();
+    toolkit.assert_text(id, &edit, "abc");
+    toolkit.assert_text(id, &label, "2");
+
+    // Event times are taken from the virtual clock
+    assert_eq!(recording.duration(), Duration::from_millis(100));
+    assert!(recording.events.len() > 3);
+
+    // Replaying the recording to a new window reproduces both the state and
+    // the recording itself
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add_boxed(window(), None);
+    toolkit.start_recording(id);
+    toolkit.replay(id, &recording);
+    assert_eq!(toolkit.stop_recording(id), Some(recording));
+    toolkit.assert_text(id, &edit, "abc");
+    toolkit.assert_text(id, &label, "2");
+}
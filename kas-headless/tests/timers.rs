@@ -0,0 +1,143 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Timer update tests
+#![feature(proc_macro_hygiene)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use kas::event::{Manager, VoidMsg, VoidResponse};
+use kas::macros::make_widget;
+use kas::prelude::*;
+use kas::widget::{Column, Label, TextButton, Window};
+use kas_headless::query::Query;
+use kas_headless::Toolkit;
+
+/// Counts timer updates
+///
+/// A timer is requested on first configure, or on every configure if
+/// `every_configure` is true.
+#[handler(handle=noauto)]
+#[widget(config = noauto)]
+#[derive(Debug, kas::macros::Widget)]
+struct Timer {
+    #[widget_core]
+    core: kas::CoreData,
+    delay: Duration,
+    every_configure: bool,
+    configured: bool,
+    count: Rc<Cell<u32>>,
+}
+
+impl Timer {
+    fn new(delay: Duration, every_configure: bool, count: Rc<Cell<u32>>) -> Self {
+        Timer {
+            core: Default::default(),
+            delay,
+            every_configure,
+            configured: false,
+            count,
+        }
+    }
+}
+
+impl Layout for Timer {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+        SizeRules::fixed(10, (0, 0))
+    }
+
+    fn draw(&self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+}
+
+impl WidgetConfig for Timer {
+    fn configure(&mut self, mgr: &mut Manager) {
+        if self.every_configure || !self.configured {
+            mgr.update_on_timer(self.delay, self.id());
+        }
+        self.configured = true;
+    }
+}
+
+impl Handler for Timer {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::TimerUpdate => {
+                self.count.set(self.count.get() + 1);
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+#[test]
+fn timer_on_clock_advance() {
+    let count = Rc::new(Cell::new(0));
+    let ms = Duration::from_millis;
+    let window = Window::new("Timer", Timer::new(ms(30), false, count.clone()));
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    toolkit.add(window);
+
+    // Timers fire only when the virtual clock reaches their time
+    toolkit.update_timers();
+    toolkit.advance_clock(ms(29));
+    assert_eq!(count.get(), 0);
+    toolkit.advance_clock(ms(1));
+    assert_eq!(count.get(), 1);
+    toolkit.advance_clock(ms(100));
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn timers_across_reconfigure() {
+    let a = Rc::new(Cell::new(0));
+    let b = Rc::new(Cell::new(0));
+    let ms = Duration::from_millis;
+
+    // Adding a label to `list` reconfigures the window and changes the ids
+    // of `a` and `b`.
+    let window = Window::new(
+        "Timers",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget(handler = add)] _ = TextButton::new("Add", ()),
+                #[widget] list: Column<Label> = Column::new(vec![]),
+                #[widget] _ = Timer::new(ms(10), true, a.clone()),
+                #[widget] _ = Timer::new(ms(50), false, b.clone()),
+            }
+            impl {
+                fn add(&mut self, mgr: &mut Manager, _: ()) -> VoidResponse {
+                    *mgr += self.list.push(Label::new("item"));
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    toolkit
+        .click_widget(id, &Query::new().name("TextButton").text("Add"))
+        .unwrap();
+    assert_eq!(toolkit.find_all(id, &Query::new().name("Label")).len(), 1);
+
+    // `a` re-requested its timer during reconfigure; it fires once only
+    toolkit.advance_clock(ms(10));
+    assert_eq!((a.get(), b.get()), (1, 0));
+
+    // The timer of `b` was requested before reconfigure and survives it
+    toolkit.advance_clock(ms(40));
+    assert_eq!((a.get(), b.get()), (1, 1));
+
+    toolkit.advance_clock(ms(100));
+    assert_eq!((a.get(), b.get()), (1, 1));
+}
@@ -0,0 +1,508 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! View widget tests
+#![feature(proc_macro_hygiene)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use kas::event::{Manager, MouseButton, ScrollDelta, VirtualKeyCode, VoidMsg, VoidResponse};
+use kas::geom::{Coord, Size};
+use kas::macros::make_widget;
+use kas::widget::{Label, ListView, Table, TreeAccessor, TreeView, ViewMsg, Window};
+use kas::{Down, WidgetChildren, WidgetCore, WindowId};
+use kas_headless::query::{Query, WidgetInfo};
+use kas_headless::theme::FlatTheme;
+use kas_headless::Toolkit;
+
+type List = ListView<Down, Vec<String>, Label>;
+type Grid = Table<Vec<Vec<i32>>, Label>;
+
+const LEN: usize = 1000;
+
+fn list_toolkit() -> (Toolkit<FlatTheme>, WindowId) {
+    let data = (0..LEN).map(|i| format!("Item {}", i)).collect();
+    let list = List::new(data).with_num_visible(4);
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    let id = toolkit.add(Window::new("List", list));
+    (toolkit, id)
+}
+
+fn list(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> &List {
+    let window = toolkit.window(id).unwrap().widget();
+    let child = window.get(0).unwrap();
+    child.as_any().downcast_ref::<List>().unwrap()
+}
+
+// All item widgets, in order of the item viewed
+fn items(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> Vec<WidgetInfo> {
+    let mut items = toolkit.find_all(id, &Query::new().name("Label"));
+    items.sort_by_key(|info| info.rect.pos.1);
+    items
+}
+
+fn texts(items: &[WidgetInfo]) -> Vec<String> {
+    items
+        .iter()
+        .map(|info| info.text.clone().unwrap())
+        .collect()
+}
+
+fn expected(range: std::ops::Range<usize>) -> Vec<String> {
+    range.map(|i| format!("Item {}", i)).collect()
+}
+
+#[test]
+fn list_view_scroll() {
+    let (mut toolkit, id) = list_toolkit();
+    let initial = items(&toolkit, id);
+    let pool = initial.len();
+    assert_eq!(pool, 4 + 2);
+    assert_eq!(texts(&initial), expected(0..pool));
+    let row_len = (initial[1].rect.pos.1 - initial[0].rect.pos.1) as u32;
+    assert!(row_len > 0);
+
+    // Scrolling down by mouse wheel
+    let view = toolkit.find(id, &Query::new().name("ListView")).unwrap();
+    toolkit.mouse_move(id, view.centre());
+    toolkit.scroll(id, ScrollDelta::LineDelta(0.0, -1.0));
+    let offset = list(&toolkit, id).offset();
+    assert!(offset > 0);
+    let first = (offset / row_len) as usize;
+    assert_eq!(texts(&items(&toolkit, id)), expected(first..first + pool));
+
+    // Scrolling up past the start clamps the offset
+    toolkit.scroll(id, ScrollDelta::LineDelta(0.0, 100.0));
+    assert_eq!(list(&toolkit, id).offset(), 0);
+    assert_eq!(texts(&items(&toolkit, id)), expected(0..pool));
+
+    // Keyboard navigation scrolls to the cursor
+    toolkit.click(id, initial[0].centre());
+    toolkit.key(id, VirtualKeyCode::End);
+    let view = list(&toolkit, id);
+    assert!(view.max_offset() > 0);
+    assert_eq!(view.offset(), view.max_offset());
+    assert_eq!(texts(&items(&toolkit, id)), expected(LEN - pool..LEN));
+    toolkit.key(id, VirtualKeyCode::Home);
+    assert_eq!(list(&toolkit, id).offset(), 0);
+    assert_eq!(texts(&items(&toolkit, id)), expected(0..pool));
+}
+
+#[test]
+fn list_view_recycling() {
+    let (mut toolkit, id) = list_toolkit();
+    let initial = items(&toolkit, id);
+    let pool = initial.len();
+    let mut ids: Vec<_> = initial.iter().map(|info| info.id).collect();
+    ids.sort();
+
+    // Scrolling re-assigns existing widgets without reconfiguring
+    let view = toolkit.find(id, &Query::new().name("ListView")).unwrap();
+    toolkit.mouse_move(id, view.centre());
+    for _ in 0..10 {
+        toolkit.scroll(id, ScrollDelta::LineDelta(0.0, -1.0));
+        let items = items(&toolkit, id);
+        assert_eq!(items.len(), pool);
+        let mut new_ids: Vec<_> = items.iter().map(|info| info.id).collect();
+        new_ids.sort();
+        assert_eq!(new_ids, ids);
+    }
+    assert!(list(&toolkit, id).offset() > 0);
+
+    // Enlarging the view grows the pool synchronously
+    toolkit.scroll(id, ScrollDelta::LineDelta(0.0, 100.0));
+    let size = toolkit.window(id).unwrap().size();
+    toolkit.resize(id, Size(size.0, size.1 * 4));
+    let grown = items(&toolkit, id);
+    assert!(grown.len() > pool);
+    assert_eq!(texts(&grown), expected(0..grown.len()));
+    assert!(grown.iter().all(|info| info.rect.size.1 > 0));
+
+    // Further resizing does not reconfigure again
+    let ids: Vec<_> = grown.iter().map(|info| info.id).collect();
+    toolkit.resize(id, Size(size.0, size.1 * 4 - 1));
+    let new_ids: Vec<_> = items(&toolkit, id).iter().map(|info| info.id).collect();
+    assert_eq!(new_ids, ids);
+}
+
+// Value of the second table column for data row `i` (distinct for i < 101)
+fn value(i: usize) -> i32 {
+    (i * 37 % 101) as i32
+}
+
+fn table_toolkit() -> (Toolkit<FlatTheme>, WindowId) {
+    let data = (0..LEN).map(|i| vec![i as i32, value(i)]).collect();
+    let table = Grid::new(&["Index", "Value"], data).with_num_visible(4);
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    let id = toolkit.add(Window::new("Table", table));
+    (toolkit, id)
+}
+
+fn table(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> &Grid {
+    let window = toolkit.window(id).unwrap().widget();
+    let child = window.get(0).unwrap();
+    child.as_any().downcast_ref::<Grid>().unwrap()
+}
+
+fn header(col: usize) -> Query {
+    Query::new().name("TextButton").nth(col)
+}
+
+// Cell widgets of column `col`, in order of the row viewed
+fn cells(toolkit: &Toolkit<FlatTheme>, id: WindowId, col: usize) -> Vec<WidgetInfo> {
+    let x = toolkit.find(id, &header(col)).unwrap().rect.pos.0;
+    let mut cells = toolkit.find_all(id, &Query::new().name("Label"));
+    cells.retain(|info| info.rect.pos.0 == x);
+    cells.sort_by_key(|info| info.rect.pos.1);
+    cells
+}
+
+fn header_widths(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> Vec<u32> {
+    let headers = toolkit.find_all(id, &Query::new().name("TextButton"));
+    headers.iter().map(|info| info.rect.size.0).collect()
+}
+
+// Drag the handle between the two columns
+fn drag_handle(toolkit: &mut Toolkit<FlatTheme>, id: WindowId, dx: i32) {
+    let handle = toolkit.find(id, &Query::new().name("DragHandle")).unwrap();
+    let start = handle.centre();
+    toolkit.mouse_move(id, start);
+    toolkit.mouse_press(id, MouseButton::Left);
+    toolkit.mouse_move(id, start + Coord(dx, 0));
+    toolkit.mouse_release(id, MouseButton::Left);
+}
+
+fn numbers<I: IntoIterator<Item = i32>>(iter: I) -> Vec<String> {
+    iter.into_iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn table_column_sizing() {
+    let (mut toolkit, id) = table_toolkit();
+    let headers = toolkit.find_all(id, &Query::new().name("TextButton"));
+    assert_eq!(texts(&headers), vec!["Index", "Value"]);
+    let (h0, h1) = (headers[0].rect, headers[1].rect);
+    assert!(h0.pos.0 + h0.size.0 as i32 <= h1.pos.0);
+
+    // Cells share the width of their column's header
+    for col in 0..2 {
+        let cells = cells(&toolkit, id, col);
+        assert_eq!(cells.len(), 4 + 2);
+        let width = headers[col].rect.size.0;
+        assert!(cells.iter().all(|info| info.rect.size.0 == width));
+    }
+
+    // Dragging the handle between headers moves the column boundary
+    drag_handle(&mut toolkit, id, 10);
+    let headers = toolkit.find_all(id, &Query::new().name("TextButton"));
+    assert_eq!(headers[0].rect.pos.0, h0.pos.0);
+    assert_eq!(headers[0].rect.size.0, h0.size.0 + 10);
+    assert_eq!(headers[1].rect.pos.0, h1.pos.0 + 10);
+    assert_eq!(headers[1].rect.size.0, h1.size.0 - 10);
+    for col in 0..2 {
+        let cells = cells(&toolkit, id, col);
+        assert_eq!(cells.len(), 4 + 2);
+        let width = headers[col].rect.size.0;
+        assert!(cells.iter().all(|info| info.rect.size.0 == width));
+    }
+
+    // The dragged width is kept when the table is resized
+    let size = toolkit.window(id).unwrap().size();
+    toolkit.resize(id, Size(size.0 + 40, size.1));
+    let headers = toolkit.find_all(id, &Query::new().name("TextButton"));
+    assert_eq!(headers[0].rect.size.0, h0.size.0 + 10);
+    assert_eq!(headers[1].rect.pos.0, h1.pos.0 + 10);
+    assert_eq!(headers[1].rect.size.0, h1.size.0 - 10 + 40);
+}
+
+#[test]
+fn table_sort() {
+    let (mut toolkit, id) = table_toolkit();
+    assert_eq!(table(&toolkit, id).sort_column(), None);
+    assert_eq!(texts(&cells(&toolkit, id, 0)), numbers(0..6));
+
+    let mut sorted: Vec<usize> = (0..LEN).collect();
+    sorted.sort_by_key(|i| value(*i));
+
+    // Clicking a header sorts by that column, without affecting column widths
+    drag_handle(&mut toolkit, id, 10);
+    let widths = header_widths(&toolkit, id);
+    toolkit.click_widget(id, &header(1)).unwrap();
+    assert_eq!(header_widths(&toolkit, id), widths);
+    assert_eq!(table(&toolkit, id).sort_column(), Some((1, false)));
+    toolkit.assert_text(id, &header(1), "Value ▲");
+    toolkit.assert_text(id, &header(0), "Index");
+    let rows = &sorted[..6];
+    assert_eq!(
+        texts(&cells(&toolkit, id, 1)),
+        numbers(rows.iter().map(|i| value(*i)))
+    );
+    assert_eq!(
+        texts(&cells(&toolkit, id, 0)),
+        numbers(rows.iter().map(|i| *i as i32))
+    );
+
+    // Clicking again reverses the order
+    toolkit.click_widget(id, &header(1)).unwrap();
+    assert_eq!(table(&toolkit, id).sort_column(), Some((1, true)));
+    toolkit.assert_text(id, &header(1), "Value ▼");
+    let rows: Vec<usize> = sorted.iter().rev().take(6).cloned().collect();
+    assert_eq!(
+        texts(&cells(&toolkit, id, 1)),
+        numbers(rows.iter().map(|i| value(*i)))
+    );
+    assert_eq!(
+        texts(&cells(&toolkit, id, 0)),
+        numbers(rows.iter().map(|i| *i as i32))
+    );
+}
+
+#[test]
+fn table_recycling() {
+    let (mut toolkit, id) = table_toolkit();
+    let pool = cells(&toolkit, id, 0).len();
+    let all = Query::new().name("Label");
+    let mut ids: Vec<_> = toolkit.find_all(id, &all).iter().map(|i| i.id).collect();
+    ids.sort();
+    assert_eq!(ids.len(), 2 * pool);
+
+    // Scrolling re-assigns existing cells without reconfiguring
+    let body = cells(&toolkit, id, 0)[0].centre();
+    toolkit.mouse_move(id, body);
+    for _ in 0..5 {
+        toolkit.scroll(id, ScrollDelta::LineDelta(0.0, -1.0));
+        let mut new_ids: Vec<_> = toolkit.find_all(id, &all).iter().map(|i| i.id).collect();
+        new_ids.sort();
+        assert_eq!(new_ids, ids);
+
+        let rows: Vec<i32> = cells(&toolkit, id, 0)
+            .iter()
+            .map(|info| info.text.as_ref().unwrap().parse().unwrap())
+            .collect();
+        let start = rows[0] as usize;
+        assert!(table(&toolkit, id).offset() > 0 && start > 0);
+        assert_eq!(
+            rows,
+            (start..start + pool).map(|i| i as i32).collect::<Vec<_>>()
+        );
+        let values = texts(&cells(&toolkit, id, 1));
+        assert_eq!(values, numbers((start..start + pool).map(value)));
+    }
+
+    // Enlarging the table grows the pool synchronously
+    toolkit.scroll(id, ScrollDelta::LineDelta(0.0, 100.0));
+    let size = toolkit.window(id).unwrap().size();
+    toolkit.resize(id, Size(size.0, size.1 * 3));
+    for col in 0..2 {
+        let cells = cells(&toolkit, id, col);
+        assert!(cells.len() > pool);
+        assert!(cells.iter().all(|info| info.rect.size.1 > 0));
+    }
+    let n = cells(&toolkit, id, 0).len();
+    assert_eq!(
+        texts(&cells(&toolkit, id, 0)),
+        numbers((0..n).map(|i| i as i32))
+    );
+}
+
+// Nodes 1, 2 and 3 each have three children; so do their children
+#[derive(Debug)]
+struct Tree;
+
+impl TreeAccessor for Tree {
+    type Key = u32;
+    type Item = String;
+
+    fn children(&self, parent: Option<&u32>) -> Vec<u32> {
+        match parent {
+            None => vec![1, 2, 3],
+            Some(key) if *key < 100 => (1..=3).map(|i| key * 10 + i).collect(),
+            Some(_) => vec![],
+        }
+    }
+
+    fn has_children(&self, key: &u32) -> bool {
+        *key < 100
+    }
+
+    fn get(&self, key: &u32) -> String {
+        key.to_string()
+    }
+}
+
+fn tree_toolkit(log: Rc<RefCell<Vec<u32>>>) -> (Toolkit<FlatTheme>, WindowId) {
+    let window = Window::new(
+        "Tree",
+        make_widget! {
+            #[layout(single)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget(handler = activate)] _ = TreeView::<Tree, Label>::new(Tree),
+                log: Rc<RefCell<Vec<u32>>> = log,
+            }
+            impl {
+                fn activate(&mut self, _: &mut Manager, msg: ViewMsg<u32, VoidMsg>)
+                    -> VoidResponse
+                {
+                    if let ViewMsg::Activate(key) = msg {
+                        self.log.borrow_mut().push(key);
+                    }
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+    let mut toolkit = Toolkit::new(FlatTheme::new());
+    let id = toolkit.add(window);
+    (toolkit, id)
+}
+
+fn tree_rows(toolkit: &Toolkit<FlatTheme>, id: WindowId) -> Vec<String> {
+    texts(&toolkit.find_all(id, &Query::new().name("TreeRow")))
+}
+
+fn keys(keys: &[u32]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+// Click the expander of the row viewing `key`
+//
+// The expander lies between the start of the row and its label.
+fn click_expander(toolkit: &mut Toolkit<FlatTheme>, id: WindowId, key: u32) {
+    let text = key.to_string();
+    let row = toolkit.find(id, &Query::new().name("TreeRow").text(&text));
+    let label = toolkit.find(id, &Query::new().name("Label").text(&text));
+    let (row, label) = (row.unwrap(), label.unwrap());
+    let indent = row.rect.pos.0 + (label.rect.pos.0 - row.rect.pos.0) / 2;
+    // Avoid a double-click
+    toolkit.advance_clock(Duration::from_secs(1));
+    toolkit.click(id, Coord(indent, row.centre().1));
+}
+
+#[test]
+fn tree_expand_collapse() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let (mut toolkit, id) = tree_toolkit(log.clone());
+    assert_eq!(tree_rows(&toolkit, id), keys(&[1, 2, 3]));
+
+    click_expander(&mut toolkit, id, 3);
+    assert_eq!(tree_rows(&toolkit, id), keys(&[1, 2, 3, 31, 32, 33]));
+    click_expander(&mut toolkit, id, 31);
+    let expanded = keys(&[1, 2, 3, 31, 311, 312, 313, 32, 33]);
+    assert_eq!(tree_rows(&toolkit, id), expanded);
+
+    // Nested rows are indented
+    let depth = |toolkit: &Toolkit<FlatTheme>, key: u32| {
+        let query = Query::new().name("Label").text(&key.to_string());
+        toolkit.find(id, &query).unwrap().rect.pos.0
+    };
+    assert!(depth(&toolkit, 3) < depth(&toolkit, 31));
+    assert!(depth(&toolkit, 31) < depth(&toolkit, 311));
+    assert_eq!(depth(&toolkit, 31), depth(&toolkit, 33));
+
+    // Collapsing hides all descendants; expanded children remain expanded
+    click_expander(&mut toolkit, id, 3);
+    assert_eq!(tree_rows(&toolkit, id), keys(&[1, 2, 3]));
+    click_expander(&mut toolkit, id, 3);
+    assert_eq!(tree_rows(&toolkit, id), expanded);
+
+    // Leaf nodes have no expander
+    click_expander(&mut toolkit, id, 312);
+    assert_eq!(tree_rows(&toolkit, id), expanded);
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn tree_keyboard() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let (mut toolkit, id) = tree_toolkit(log.clone());
+    let label = toolkit.find(id, &Query::new().name("Label").text("1"));
+    toolkit.click(id, label.unwrap().centre());
+
+    // Right expands a node, then moves to its first child
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Right);
+    assert_eq!(tree_rows(&toolkit, id), keys(&[1, 2, 21, 22, 23, 3]));
+    toolkit.key(id, VirtualKeyCode::Right);
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![22]);
+
+    // Left moves to the parent, then collapses it
+    toolkit.key(id, VirtualKeyCode::Left);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![22, 2]);
+    toolkit.key(id, VirtualKeyCode::Left);
+    assert_eq!(tree_rows(&toolkit, id), keys(&[1, 2, 3]));
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![22, 2, 2]);
+
+    // Home and End move to the first and last rows
+    toolkit.key(id, VirtualKeyCode::End);
+    toolkit.key(id, VirtualKeyCode::Return);
+    toolkit.key(id, VirtualKeyCode::Home);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![22, 2, 2, 3, 1]);
+}
+
+#[test]
+fn tree_keyboard_tab() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let (mut toolkit, id) = tree_toolkit(log.clone());
+    click_expander(&mut toolkit, id, 1);
+    assert_eq!(tree_rows(&toolkit, id), keys(&[1, 11, 12, 13, 2, 3]));
+    let label = toolkit.find(id, &Query::new().name("Label").text("11"));
+    toolkit.advance_clock(Duration::from_secs(1));
+    toolkit.click(id, label.unwrap().centre());
+
+    // Arrow keys and Tab share the same focus
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Tab);
+    toolkit.key(id, VirtualKeyCode::Return);
+    toolkit.key(id, VirtualKeyCode::Up);
+    toolkit.key(id, VirtualKeyCode::Up);
+    toolkit.key(id, VirtualKeyCode::Tab);
+    toolkit.key(id, VirtualKeyCode::Tab);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![13, 13]);
+
+    toolkit.key(id, VirtualKeyCode::End);
+    toolkit.key(id, VirtualKeyCode::Up);
+    toolkit.key(id, VirtualKeyCode::Tab);
+    toolkit.key(id, VirtualKeyCode::Return);
+    toolkit.key(id, VirtualKeyCode::Home);
+    toolkit.key(id, VirtualKeyCode::Tab);
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![13, 13, 3, 12]);
+}
+
+#[test]
+fn tree_row_mapping() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let (mut toolkit, id) = tree_toolkit(log.clone());
+    click_expander(&mut toolkit, id, 1);
+    click_expander(&mut toolkit, id, 12);
+    let rows = keys(&[1, 11, 12, 121, 122, 123, 13, 2, 3]);
+    assert_eq!(tree_rows(&toolkit, id), rows);
+
+    // Rows are laid out in order
+    let infos = toolkit.find_all(id, &Query::new().name("TreeRow"));
+    for pair in infos.windows(2) {
+        assert!(pair[0].rect.pos.1 < pair[1].rect.pos.1);
+    }
+
+    // Messages from each row carry the key of the node viewed
+    for key in &[123, 2, 11] {
+        let query = Query::new().name("Label").text(&key.to_string());
+        let coord = toolkit.find(id, &query).unwrap().centre();
+        toolkit.advance_clock(Duration::from_secs(1));
+        toolkit.click(id, coord);
+        toolkit.click(id, coord);
+    }
+    assert_eq!(*log.borrow(), vec![123, 2, 11]);
+}
@@ -0,0 +1,182 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget interaction tests
+#![feature(proc_macro_hygiene)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use kas::class::SetText;
+use kas::event::{Manager, ModifiersState, MouseButton, VirtualKeyCode, VoidMsg, VoidResponse};
+use kas::geom::Coord;
+use kas::macros::make_widget;
+use kas::widget::{ComboBox, EditBox, Label, Slider, Window};
+use kas::Right;
+use kas_headless::query::Query;
+use kas_headless::Toolkit;
+
+/// The modifier used by standard shortcuts
+#[cfg(target_os = "macos")]
+const PRIMARY: ModifiersState = ModifiersState::LOGO;
+#[cfg(not(target_os = "macos"))]
+const PRIMARY: ModifiersState = ModifiersState::CTRL;
+
+#[test]
+fn edit_box() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let window = Window::new(
+        "EditBox",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget(handler = activate)] _ =
+                    EditBox::new("").on_activate(|text| Some(text.to_string())),
+                log: Rc<RefCell<Vec<String>>> = log.clone(),
+            }
+            impl {
+                fn activate(&mut self, _: &mut Manager, text: String) -> VoidResponse {
+                    self.log.borrow_mut().push(text);
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    let edit = Query::new().name("EditBox");
+
+    toolkit.type_into(id, &edit, "hello").unwrap();
+    toolkit.assert_text(id, &edit, "hello");
+    toolkit.key(id, VirtualKeyCode::Back);
+    toolkit.assert_text(id, &edit, "hell");
+
+    // Typing replaces the selection
+    toolkit.set_modifiers(id, PRIMARY);
+    toolkit.key(id, VirtualKeyCode::A);
+    toolkit.set_modifiers(id, ModifiersState::empty());
+    toolkit.type_text(id, "X");
+    toolkit.assert_text(id, &edit, "X");
+
+    // Each undo reverts one group of edits
+    toolkit.set_modifiers(id, PRIMARY);
+    toolkit.key(id, VirtualKeyCode::Z);
+    toolkit.assert_text(id, &edit, "hell");
+    toolkit.key(id, VirtualKeyCode::Z);
+    toolkit.assert_text(id, &edit, "hello");
+    toolkit.set_modifiers(id, ModifiersState::empty());
+
+    assert!(log.borrow().is_empty());
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec!["hello".to_string()]);
+}
+
+#[test]
+fn slider() {
+    let window = Window::new(
+        "Slider",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget(handler = set_value)] _ = Slider::<i32, Right>::new(0, 10, 1),
+                #[widget] value: Label = Label::new("0"),
+            }
+            impl {
+                fn set_value(&mut self, mgr: &mut Manager, value: i32) -> VoidResponse {
+                    *mgr += self.value.set_text(value.to_string());
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    let label = Query::new().name("Label");
+    let slider = toolkit.try_find(id, &Query::new().name("Slider")).unwrap();
+    let handle = toolkit
+        .try_find(id, &Query::new().name("DragHandle"))
+        .unwrap();
+    let y = handle.centre().1;
+    let (left, right) = (
+        slider.rect.pos.0,
+        slider.rect.pos.0 + slider.rect.size.0 as i32,
+    );
+
+    // Dragging past either end of the track clamps the value
+    toolkit.mouse_move(id, handle.centre());
+    toolkit.mouse_press(id, MouseButton::Left);
+    toolkit.mouse_move(id, Coord(right + 100, y));
+    toolkit.mouse_release(id, MouseButton::Left);
+    toolkit.assert_text(id, &label, "10");
+
+    let handle = toolkit
+        .try_find(id, &Query::new().name("DragHandle"))
+        .unwrap();
+    assert!(handle.rect.pos.0 > slider.rect.pos.0);
+    toolkit.mouse_move(id, handle.centre());
+    toolkit.mouse_press(id, MouseButton::Left);
+    toolkit.mouse_move(id, Coord(left - 100, y));
+    toolkit.mouse_release(id, MouseButton::Left);
+    toolkit.assert_text(id, &label, "0");
+
+    // Keyboard navigation
+    toolkit.key(id, VirtualKeyCode::Tab);
+    toolkit.key(id, VirtualKeyCode::End);
+    toolkit.assert_text(id, &label, "10");
+    toolkit.key(id, VirtualKeyCode::Left);
+    toolkit.assert_text(id, &label, "9");
+    toolkit.key(id, VirtualKeyCode::Home);
+    toolkit.assert_text(id, &label, "0");
+}
+
+#[test]
+fn combo_box() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let window = Window::new(
+        "ComboBox",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget(handler = select)] _ =
+                    ComboBox::<i32>::new(&[("One", 1), ("Two", 2), ("Three", 3)]),
+                log: Rc<RefCell<Vec<i32>>> = log.clone(),
+            }
+            impl {
+                fn select(&mut self, _: &mut Manager, msg: i32) -> VoidResponse {
+                    self.log.borrow_mut().push(msg);
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let mut toolkit = Toolkit::new(kas_headless::theme::FlatTheme::new());
+    let id = toolkit.add(window);
+    let combo = Query::new().name("ComboBox");
+
+    // Clicking opens the menu with the active entry focussed
+    toolkit.click_widget(id, &combo).unwrap();
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![3]);
+
+    // Navigation starts from the new active entry
+    toolkit.click_widget(id, &combo).unwrap();
+    toolkit.key(id, VirtualKeyCode::Up);
+    toolkit.key(id, VirtualKeyCode::Return);
+    assert_eq!(*log.borrow(), vec![3, 2]);
+
+    // Escape closes the menu without selection
+    toolkit.click_widget(id, &combo).unwrap();
+    toolkit.key(id, VirtualKeyCode::Down);
+    toolkit.key(id, VirtualKeyCode::Escape);
+    assert_eq!(*log.borrow(), vec![3, 2]);
+}
@@ -194,10 +194,14 @@ mod kw {
     custom_keyword!(down);
     custom_keyword!(up);
     custom_keyword!(grid);
+    custom_keyword!(flow);
+    custom_keyword!(overlay);
+    custom_keyword!(offset);
     custom_keyword!(halign);
     custom_keyword!(valign);
     custom_keyword!(key_nav);
     custom_keyword!(cursor_icon);
+    custom_keyword!(role);
     custom_keyword!(handle);
     custom_keyword!(send);
     custom_keyword!(config);
@@ -214,6 +218,7 @@ pub struct WidgetAttrArgs {
     pub rspan: Option<Lit>,
     pub halign: Option<Ident>,
     pub valign: Option<Ident>,
+    pub offset: Option<Expr>,
     pub handler: Option<Ident>,
 }
 
@@ -240,9 +245,9 @@ impl WidgetAttrArgs {
 
     fn match_align(ident: &Ident) -> Result<TokenStream> {
         Ok(match ident {
-            ident if ident == "begin" => quote! { kas::Align::Begin },
+            ident if ident == "begin" => quote! { kas::Align::TL },
             ident if ident == "centre" || ident == "center" => quote! { kas::Align::Centre },
-            ident if ident == "end" => quote! { kas::Align::End },
+            ident if ident == "end" => quote! { kas::Align::BR },
             ident if ident == "stretch" => quote! { kas::Align::Stretch },
             ident => {
                 return Err(Error::new(
@@ -277,6 +282,7 @@ impl Parse for WidgetAttrArgs {
             rspan: None,
             halign: None,
             valign: None,
+            offset: None,
             handler: None,
         };
         if input.is_empty() {
@@ -316,6 +322,10 @@ impl Parse for WidgetAttrArgs {
                 let _: kw::valign = content.parse()?;
                 let _: Eq = content.parse()?;
                 args.valign = Some(content.parse()?);
+            } else if args.offset.is_none() && lookahead.peek(kw::offset) {
+                let _: kw::offset = content.parse()?;
+                let _: Eq = content.parse()?;
+                args.offset = Some(content.parse()?);
             } else if args.handler.is_none() && lookahead.peek(kw::handler) {
                 let _: kw::handler = content.parse()?;
                 let _: Eq = content.parse()?;
@@ -342,6 +352,7 @@ impl ToTokens for WidgetAttrArgs {
             || self.rspan.is_some()
             || self.halign.is_some()
             || self.valign.is_some()
+            || self.offset.is_some()
             || self.handler.is_some()
         {
             let comma = TokenTree::from(Punct::new(',', Spacing::Alone));
@@ -379,6 +390,12 @@ impl ToTokens for WidgetAttrArgs {
                 }
                 args.append_all(quote! { valign = #ident });
             }
+            if let Some(ref expr) = self.offset {
+                if !args.is_empty() {
+                    args.append(comma.clone());
+                }
+                args.append_all(quote! { offset = #expr });
+            }
             if let Some(ref ident) = self.handler {
                 if !args.is_empty() {
                     args.append(comma);
@@ -426,6 +443,7 @@ impl Default for WidgetArgs {
 pub struct WidgetConfig {
     pub key_nav: bool,
     pub cursor_icon: Expr,
+    pub role: Expr,
 }
 
 impl Default for WidgetConfig {
@@ -433,6 +451,7 @@ impl Default for WidgetConfig {
         WidgetConfig {
             key_nav: false,
             cursor_icon: parse_quote! { kas::event::CursorIcon::Default },
+            role: parse_quote! { kas::access::Role::Group },
         }
     }
 }
@@ -475,10 +494,15 @@ impl Parse for WidgetArgs {
                         let mut conf = WidgetConfig::default();
                         let mut have_key_nav = false;
                         let mut have_cursor_icon = false;
+                        let mut have_role = false;
 
                         while !content2.is_empty() {
                             let lookahead = content2.lookahead1();
-                            if lookahead.peek(kw::noauto) && !have_key_nav && !have_cursor_icon {
+                            if lookahead.peek(kw::noauto)
+                                && !have_key_nav
+                                && !have_cursor_icon
+                                && !have_role
+                            {
                                 let _: kw::noauto = content2.parse()?;
                                 break;
                             } else if lookahead.peek(kw::key_nav) && !have_key_nav {
@@ -492,6 +516,11 @@ impl Parse for WidgetArgs {
                                 let _: Eq = content2.parse()?;
                                 conf.cursor_icon = content2.parse()?;
                                 have_cursor_icon = true;
+                            } else if lookahead.peek(kw::role) && !have_role {
+                                let _: kw::role = content2.parse()?;
+                                let _: Eq = content2.parse()?;
+                                conf.role = content2.parse()?;
+                                have_role = true;
                             } else {
                                 return Err(lookahead.error());
                             };
@@ -529,12 +558,16 @@ pub enum LayoutType {
     Down,
     Up,
     Grid,
+    Flow,
+    Overlay,
 }
 
 impl ToTokens for LayoutType {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.append_all(match self {
-            LayoutType::Single | LayoutType::Grid => unreachable!(),
+            LayoutType::Single | LayoutType::Grid | LayoutType::Flow | LayoutType::Overlay => {
+                unreachable!()
+            }
             LayoutType::Right => quote! { kas::Right },
             LayoutType::Left => quote! { kas::Left },
             LayoutType::Down => quote! { kas::Down },
@@ -591,6 +624,12 @@ impl Parse for LayoutArgs {
         } else if lookahead.peek(kw::grid) {
             let _: kw::grid = content.parse()?;
             LayoutType::Grid
+        } else if lookahead.peek(kw::flow) {
+            let _: kw::flow = content.parse()?;
+            LayoutType::Flow
+        } else if lookahead.peek(kw::overlay) {
+            let _: kw::overlay = content.parse()?;
+            LayoutType::Overlay
         } else {
             return Err(lookahead.error());
         };
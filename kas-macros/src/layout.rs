@@ -40,6 +40,7 @@ pub(crate) fn data_type(children: &Vec<Child>, layout: &LayoutArgs) -> Result<To
                 cols = 1;
                 rows += 1;
             }
+            LayoutType::Flow | LayoutType::Overlay => (),
             LayoutType::Grid => {
                 let pos = args.as_pos()?;
                 let c1 = pos.0 + pos.2;
@@ -69,7 +70,7 @@ pub(crate) fn data_type(children: &Vec<Child>, layout: &LayoutArgs) -> Result<To
 
     Ok(match layout.layout {
         LayoutType::Single => quote! {
-            type Data = ();
+            type Data = kas::layout::SingleStorage;
             type Solver = kas::layout::SingleSolver;
             type Setter = kas::layout::SingleSetter;
         },
@@ -119,6 +120,16 @@ pub(crate) fn data_type(children: &Vec<Child>, layout: &LayoutArgs) -> Result<To
                 Self::Data,
             >;
         },
+        LayoutType::Flow => quote! {
+            type Data = kas::layout::FlowStorage;
+            type Solver = kas::layout::FlowSolver;
+            type Setter = kas::layout::FlowSetter;
+        },
+        LayoutType::Overlay => quote! {
+            type Data = kas::layout::OverlayStorage;
+            type Solver = kas::layout::OverlaySolver;
+            type Setter = kas::layout::OverlaySetter;
+        },
     })
 }
 
@@ -136,7 +147,8 @@ pub(crate) fn derive(
                 "data field marked with #[layout_data] required when deriving Widget",
             ));
         }
-        quote! { () }
+        // Without storage, the child's maximum size is not enforced
+        quote! { kas::layout::SingleStorage::default() }
     };
 
     let find_id_area = layout.area.as_ref().map(|area_widget| {
@@ -150,7 +162,7 @@ pub(crate) fn derive(
     let mut size = TokenStream::new();
     let mut set_rect = TokenStream::new();
     let mut draw = TokenStream::new();
-    let mut find_id_child = TokenStream::new();
+    let mut find_id_children = Vec::with_capacity(children.len());
 
     for child in children.iter() {
         let ident = &child.ident;
@@ -172,6 +184,32 @@ pub(crate) fn derive(
 
                 quote! { #row }
             }
+            LayoutType::Flow => {
+                let index = cols;
+                cols += 1;
+
+                quote! { #index }
+            }
+            LayoutType::Overlay => {
+                let index = cols;
+                cols += 1;
+
+                let stretch = quote! { kas::Align::Stretch };
+                let horiz = args.halign_toks()?.unwrap_or_else(|| stretch.clone());
+                let vert = args.valign_toks()?.unwrap_or(stretch);
+                let offset = match args.offset {
+                    Some(ref expr) => quote! { kas::geom::Vec2::from(#expr) },
+                    None => quote! { kas::geom::Vec2::ZERO },
+                };
+
+                quote! {
+                    (#index, kas::layout::Anchor {
+                        horiz: #horiz,
+                        vert: #vert,
+                        offset: #offset,
+                    })
+                }
+            }
             LayoutType::Grid => {
                 let pos = args.as_pos()?;
                 let (c0, c1) = (pos.0, pos.0 + pos.2);
@@ -210,22 +248,43 @@ pub(crate) fn derive(
             self.#ident.set_rect(setter.child_rect(&mut #data, #child_info), align);
         });
 
+        let draw_child = if layout.layout == LayoutType::Overlay && !find_id_children.is_empty() {
+            // Each overlaid child uses a new pass, ensuring it is drawn above
+            // all previous children regardless of the primitives used.
+            quote! {
+                draw_handle.clip_region(
+                    rect,
+                    Coord::ZERO,
+                    kas::draw::ClipRegion::Overlay,
+                    &mut |draw_handle| self.#ident.draw(draw_handle, mgr, disabled),
+                );
+            }
+        } else {
+            quote! { self.#ident.draw(draw_handle, mgr, disabled); }
+        };
         draw.append_all(quote! {
             let c0 = self.#ident.rect().pos;
             let c1 = c0 + Coord::from(self.#ident.rect().size);
             if c0.0 <= pos1.0 && c1.0 >= pos0.0 && c0.1 <= pos1.1 && c1.1 >= pos0.1 {
-                self.#ident.draw(draw_handle, mgr, disabled);
+                #draw_child
             }
         });
 
         // TODO: more efficient search strategy?
-        find_id_child.append_all(quote! {
+        find_id_children.push(quote! {
             if let Some(id) = self.#ident.find_id(coord) {
                 return Some(id);
             }
         });
     }
 
+    // The topmost (last) overlay child must receive input first
+    if layout.layout == LayoutType::Overlay {
+        find_id_children.reverse();
+    }
+    let mut find_id_child = TokenStream::new();
+    find_id_child.append_all(find_id_children);
+
     let dim = match layout.layout {
         LayoutType::Single => quote! { () },
         LayoutType::Right => quote! { (kas::Right, #cols) },
@@ -233,6 +292,13 @@ pub(crate) fn derive(
         LayoutType::Down => quote! { (kas::Down, #rows) },
         LayoutType::Up => quote! { (kas::Up, #rows) },
         LayoutType::Grid => quote! { (#cols, #rows) },
+        LayoutType::Flow => quote! { #cols },
+        LayoutType::Overlay => quote! { #cols },
+    };
+    // Overlay offsets are scaled, thus the solver needs the scale factor
+    let solver_dim = match layout.layout {
+        LayoutType::Overlay => quote! { (#cols, size_handle.scale_factor()) },
+        _ => dim.clone(),
     };
 
     let find_id_body = find_id_area.unwrap_or_else(|| {
@@ -255,7 +321,7 @@ pub(crate) fn derive(
 
             let mut solver = <Self as kas::LayoutData>::Solver::new(
                 axis,
-                #dim,
+                #solver_dim,
                 &mut #data,
             );
             #size
@@ -156,6 +156,7 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     if let Some(config) = args.widget.config {
         let key_nav = config.key_nav;
         let cursor_icon = config.cursor_icon;
+        let role = config.role;
 
         toks.append_all(quote! {
             impl #impl_generics kas::WidgetConfig
@@ -167,6 +168,9 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 fn cursor_icon(&self) -> kas::event::CursorIcon {
                     #cursor_icon
                 }
+                fn access_role(&self) -> kas::access::Role {
+                    #role
+                }
             }
         });
     }
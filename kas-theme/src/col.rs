@@ -44,6 +44,8 @@ pub struct ThemeColours {
     pub button_depressed: Colour,
     /// Colour of mark within a `CheckBox` or `RadioBox`
     pub checkbox: Colour,
+    /// Colour of shadows cast by elevated surfaces (usually translucent)
+    pub shadow: Colour,
 }
 
 impl ThemeColours {
@@ -82,6 +84,7 @@ impl ThemeColours {
             button_highlighted: Colour::new(0.25, 0.8, 1.0),
             button_depressed: Colour::new(0.15, 0.525, 0.75),
             checkbox: Colour::new(0.2, 0.7, 1.0),
+            shadow: Colour::rgba(0.0, 0.0, 0.0, 0.35),
         }
     }
 
@@ -104,6 +107,7 @@ impl ThemeColours {
             button_highlighted: Colour::new(1.0, 0.95, 0.6),
             button_depressed: Colour::new(0.8, 0.72, 0.24),
             checkbox: Colour::grey(0.4),
+            shadow: Colour::rgba(0.0, 0.0, 0.1, 0.25),
         }
     }
 
@@ -126,6 +130,7 @@ impl ThemeColours {
             button_highlighted: Colour::new(0.6, 0.3, 0.1),
             button_depressed: Colour::new(0.3, 0.1, 0.1),
             checkbox: Colour::new(0.5, 0.1, 0.1),
+            shadow: Colour::rgba(0.0, 0.0, 0.0, 0.6),
         }
     }
 
@@ -10,7 +10,7 @@
 use std::any::Any;
 use std::f32;
 
-use kas::draw::{self, TextClass};
+use kas::draw::{self, Elevation, IconCache, TextClass};
 use kas::geom::{Size, Vec2};
 use kas::layout::{AxisInfo, Margins, SizeRules, StretchPolicy};
 use kas::text::{FontId, PreparedText};
@@ -31,6 +31,12 @@ pub struct DimensionsParams {
     pub scrollbar_size: Vec2,
     /// Slider minimum handle size
     pub slider_size: Vec2,
+    /// Shadow blur size, per level of [`Elevation`]
+    pub shadow_size: f32,
+    /// Downward offset of shadows, per level of [`Elevation`]
+    ///
+    /// This should not exceed `shadow_size`.
+    pub shadow_offset: f32,
 }
 
 /// Dimensions available within [`DimensionsWindow`]
@@ -49,6 +55,8 @@ pub struct Dimensions {
     pub checkbox: u32,
     pub scrollbar: Size,
     pub slider: Size,
+    pub shadow_size: u32,
+    pub shadow_offset: u32,
 }
 
 impl Dimensions {
@@ -78,9 +86,25 @@ impl Dimensions {
             checkbox: (font_scale * 0.7).round() as u32 + 2 * (margin + frame),
             scrollbar: Size::from(params.scrollbar_size * scale_factor),
             slider: Size::from(params.slider_size * scale_factor),
+            shadow_size: (params.shadow_size * scale_factor).round() as u32,
+            shadow_offset: (params.shadow_offset * scale_factor).round() as u32,
         }
     }
 
+    /// Extent of the shadow beyond a surface at `elevation`
+    ///
+    /// Returns `(top_left, bottom_right)` dimensions.
+    pub fn shadow(&self, elevation: Elevation) -> (Size, Size) {
+        let level = match elevation {
+            Elevation::Flat => 0,
+            Elevation::Menu => 1,
+            Elevation::Dialog => 2,
+        };
+        let size = self.shadow_size * level;
+        let offset = self.shadow_offset.min(self.shadow_size) * level;
+        (Size(size, size - offset), Size(size, size + offset))
+    }
+
     pub fn edit_marker_size(&self) -> Vec2 {
         Vec2(self.font_marker_width, self.font_scale)
     }
@@ -89,12 +113,15 @@ impl Dimensions {
 /// A convenient implementation of [`crate::Window`]
 pub struct DimensionsWindow {
     pub dims: Dimensions,
+    /// Rasterised vector icons (cleared when the scale factor changes)
+    pub icons: IconCache,
 }
 
 impl DimensionsWindow {
     pub fn new(dims: DimensionsParams, font_id: FontId, font_size: f32, scale_factor: f32) -> Self {
         DimensionsWindow {
             dims: Dimensions::new(dims, font_id, font_size, scale_factor),
+            icons: IconCache::new(),
         }
     }
 }
@@ -153,6 +180,10 @@ impl<'a> draw::SizeHandle for SizeHandle<'a> {
         Margins::uniform(self.dims.margin as u16)
     }
 
+    fn shadow(&self, elevation: Elevation) -> (Size, Size) {
+        self.dims.shadow(elevation)
+    }
+
     fn line_height(&self, _: TextClass) -> u32 {
         self.dims.line_height
     }
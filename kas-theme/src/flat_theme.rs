@@ -12,8 +12,8 @@ use std::ops::Range;
 
 use crate::{Dimensions, DimensionsParams, DimensionsWindow, Theme, ThemeColours, Window};
 use kas::draw::{
-    self, ClipRegion, Colour, Draw, DrawRounded, DrawShared, DrawText, InputState, Pass,
-    SizeHandle, TextClass,
+    self, ClipRegion, Colour, Draw, DrawGradient, DrawImage, DrawRounded, DrawShared, DrawText,
+    Elevation, Icon, ImageId, InputState, Pass, SizeHandle, TextClass,
 };
 use kas::geom::*;
 use kas::text::{FontId, PreparedText};
@@ -44,6 +44,8 @@ const DIMS: DimensionsParams = DimensionsParams {
     button_frame: 6.0,
     scrollbar_size: Vec2::splat(8.0),
     slider_size: Vec2(12.0, 25.0),
+    shadow_size: 6.0,
+    shadow_offset: 2.0,
 };
 
 pub struct DrawHandle<'a, D: Draw> {
@@ -57,7 +59,7 @@ pub struct DrawHandle<'a, D: Draw> {
 
 impl<D: DrawShared + 'static> Theme<D> for FlatTheme
 where
-    D::Draw: DrawRounded + DrawText,
+    D::Draw: DrawRounded + DrawGradient + DrawText + DrawImage,
 {
     type Window = DimensionsWindow;
 
@@ -76,6 +78,8 @@ where
 
     fn update_window(&self, window: &mut Self::Window, dpi_factor: f32) {
         window.dims = Dimensions::new(DIMS, self.font_id, self.font_size, dpi_factor);
+        // Vector icons must be re-rasterised for the new scale factor
+        window.icons.clear();
     }
 
     #[cfg(not(feature = "gat"))]
@@ -176,7 +180,10 @@ impl<'a, D: Draw + DrawRounded> DrawHandle<'a, D> {
     }
 }
 
-impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D> {
+impl<'a, D> draw::DrawHandle for DrawHandle<'a, D>
+where
+    D: Draw + DrawRounded + DrawGradient + DrawText + DrawImage,
+{
     fn size_handle_dyn(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
         unsafe {
             let mut size_handle = self.window.size_handle();
@@ -196,7 +203,7 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
     ) {
         let rect = rect + self.offset;
-        let depth = self.pass.depth() + super::relative_region_depth(class);
+        let depth = super::region_depth(self.pass.depth(), class);
         let pass = self.draw.add_clip_region(rect, depth);
         if depth < self.pass.depth() {
             // draw to depth buffer to enable correct text rendering
@@ -227,11 +234,25 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
     }
 
     fn menu_frame(&mut self, rect: Rect) {
+        self.surface(rect, Elevation::Menu);
+    }
+
+    fn surface(&mut self, rect: Rect, elevation: Elevation) {
         let outer = Quad::from(rect + self.offset);
-        let inner = outer.shrink(self.window.dims.frame as f32);
+        let frame = self.window.dims.frame as f32;
+        if elevation > Elevation::Flat {
+            // The shadow starts beneath the frame, filling in around its
+            // rounded corners; rounded frames are drawn after gradients.
+            let (tl, br) = self.window.dims.shadow(elevation);
+            let shadow = Quad::with_coords(outer.a - Vec2::from(tl), outer.b + Vec2::from(br));
+            let inner = outer.shrink(frame / 2.0);
+            self.draw.shadow(self.pass, shadow, inner, self.cols.shadow);
+        }
+
+        let inner = outer.shrink(frame);
         self.draw
             .rounded_frame(self.pass, outer, inner, 0.5, self.cols.frame);
-        let inner = outer.shrink(self.window.dims.frame as f32 / 3.0);
+        let inner = outer.shrink(frame / 3.0);
         self.draw.rect(self.pass, inner, self.cols.background);
     }
 
@@ -242,6 +263,19 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
             .rounded_frame(self.pass, outer, inner, 0.5, self.cols.frame);
     }
 
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.image(self.pass, id, quad);
+    }
+
+    fn icon(&mut self, icon: &Icon, rect: Rect) {
+        let id = match icon {
+            Icon::Image(id) => *id,
+            Icon::Svg(svg) => self.window.icons.get(svg, rect.size),
+        };
+        self.image(id, rect);
+    }
+
     fn text(&mut self, pos: Coord, text: &PreparedText, class: TextClass) {
         let pos = pos + self.offset;
         let col = self.cols.text_class(class);
@@ -284,6 +318,25 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         }
     }
 
+    fn view_row(&mut self, rect: Rect, selected: bool, state: InputState) {
+        let outer = Quad::from(rect + self.offset);
+        let col = match selected {
+            true => Some(self.cols.text_sel_bg),
+            false => self.cols.menu_entry(InputState {
+                nav_focus: false,
+                ..state
+            }),
+        };
+        if let Some(col) = col {
+            self.draw.rect(self.pass, outer, col);
+        }
+
+        if let Some(col) = self.cols.nav_region(state) {
+            let inner = outer.shrink(self.window.dims.button_frame as f32 / 2.0);
+            self.draw.rounded_frame(self.pass, outer, inner, 0.0, col);
+        }
+    }
+
     fn button(&mut self, rect: Rect, state: InputState) {
         let outer = Quad::from(rect + self.offset);
         let col = self.cols.button_state(state);
@@ -332,6 +385,26 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         }
     }
 
+    fn expander(&mut self, rect: Rect, expanded: bool, state: InputState) {
+        let bg_col = self.cols.bg_col(state);
+        let nav_col = self.cols.nav_region(state).or(Some(bg_col));
+
+        let inner = self.draw_edit_box(rect + self.offset, bg_col, nav_col);
+
+        if let Some(col) = self.cols.check_mark_state(state, true) {
+            let radius = inner.size().sum() * (1.0 / 16.0);
+            let inner = inner.shrink(self.window.dims.margin as f32 + radius);
+            let (pos, size) = (inner.a, inner.size());
+            let p = |x, y| pos + size * Vec2(x, y);
+            let (p1, p2, p3) = match expanded {
+                false => (p(0.25, 0.0), p(0.75, 0.5), p(0.25, 1.0)),
+                true => (p(0.0, 0.25), p(0.5, 0.75), p(1.0, 0.25)),
+            };
+            self.draw.rounded_line(self.pass, p1, p2, radius, col);
+            self.draw.rounded_line(self.pass, p2, p3, radius, col);
+        }
+    }
+
     fn scrollbar(&mut self, rect: Rect, h_rect: Rect, _dir: Direction, state: InputState) {
         // track
         let outer = Quad::from(rect + self.offset);
@@ -54,10 +54,41 @@ pub type StackDst<T> = stack_dst_::ValueA<T, [usize; 8]>;
 
 /// The initial [`Pass`] value for a window
 // NOTE: depth values between 0 and 1 are drawn.
-pub const START_PASS: Pass = Pass::new_pass_with_depth(0, 0.01);
-fn relative_region_depth(class: ClipRegion) -> f32 {
+pub const START_PASS: Pass = Pass::new_pass_with_depth(0, POPUP_DEPTH);
+const POPUP_DEPTH: f32 = 0.01;
+
+/// Depth of a new clip region within a pass of the given `depth`
+///
+/// Each pop-up is drawn [`POPUP_DEPTH`] above its parent. Each overlay takes
+/// half the remaining space below the mid-point between its parent's pop-up
+/// level and the next, thus nested overlays never reach the next pop-up.
+fn region_depth(depth: f32, class: ClipRegion) -> f32 {
     match class {
-        ClipRegion::Popup => 0.01,
-        ClipRegion::Scroll => -1e-5,
+        ClipRegion::Popup => depth + POPUP_DEPTH,
+        ClipRegion::Overlay => {
+            let level = (depth / POPUP_DEPTH).round() * POPUP_DEPTH;
+            depth + 0.5 * (level + 0.5 * POPUP_DEPTH - depth)
+        }
+        ClipRegion::Scroll => depth - 1e-5,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested_overlays() {
+        let mut depth = START_PASS.depth();
+        for _ in 0..100 {
+            let next = region_depth(depth, ClipRegion::Overlay);
+            assert!(next >= depth);
+            depth = region_depth(next, ClipRegion::Scroll);
+        }
+        let popup = region_depth(START_PASS.depth(), ClipRegion::Popup);
+        assert!(depth < popup - 0.4 * POPUP_DEPTH);
+
+        let overlay = region_depth(popup, ClipRegion::Overlay);
+        assert!(overlay > popup && overlay < popup + 0.5 * POPUP_DEPTH);
     }
 }
@@ -10,8 +10,8 @@ use std::ops::Range;
 
 use crate::{Dimensions, DimensionsParams, DimensionsWindow, Theme, ThemeColours, Window};
 use kas::draw::{
-    self, ClipRegion, Colour, Draw, DrawRounded, DrawShaded, DrawShared, DrawText, InputState,
-    Pass, SizeHandle, TextClass,
+    self, ClipRegion, Colour, Draw, DrawGradient, DrawImage, DrawRounded, DrawShaded, DrawShared,
+    DrawText, Elevation, Icon, ImageId, InputState, Pass, SizeHandle, TextClass,
 };
 use kas::geom::*;
 use kas::text::{FontId, PreparedText};
@@ -42,6 +42,8 @@ const DIMS: DimensionsParams = DimensionsParams {
     button_frame: 5.0,
     scrollbar_size: Vec2::splat(8.0),
     slider_size: Vec2(12.0, 25.0),
+    shadow_size: 6.0,
+    shadow_offset: 2.0,
 };
 
 pub struct DrawHandle<'a, D: Draw> {
@@ -55,7 +57,7 @@ pub struct DrawHandle<'a, D: Draw> {
 
 impl<D: DrawShared + 'static> Theme<D> for ShadedTheme
 where
-    D::Draw: DrawRounded + DrawShaded + DrawText,
+    D::Draw: DrawRounded + DrawShaded + DrawGradient + DrawText + DrawImage,
 {
     type Window = DimensionsWindow;
 
@@ -74,6 +76,8 @@ where
 
     fn update_window(&self, window: &mut Self::Window, dpi_factor: f32) {
         window.dims = Dimensions::new(DIMS, self.font_id, self.font_size, dpi_factor);
+        // Vector icons must be re-rasterised for the new scale factor
+        window.icons.clear();
     }
 
     #[cfg(not(feature = "gat"))]
@@ -191,7 +195,7 @@ impl<'a, D: Draw + DrawRounded + DrawShaded> DrawHandle<'a, D> {
 
 impl<'a, D> draw::DrawHandle for DrawHandle<'a, D>
 where
-    D: Draw + DrawRounded + DrawShaded + DrawText + 'static,
+    D: Draw + DrawRounded + DrawShaded + DrawGradient + DrawText + DrawImage + 'static,
 {
     fn size_handle_dyn(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
         unsafe {
@@ -212,7 +216,7 @@ where
         f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
     ) {
         let rect = rect + self.offset;
-        let depth = self.pass.depth() + super::relative_region_depth(class);
+        let depth = super::region_depth(self.pass.depth(), class);
         let pass = self.draw.add_clip_region(rect, depth);
         if depth < self.pass.depth() {
             // draw to depth buffer to enable correct text rendering
@@ -245,8 +249,21 @@ where
     }
 
     fn menu_frame(&mut self, rect: Rect) {
+        self.surface(rect, Elevation::Menu);
+    }
+
+    fn surface(&mut self, rect: Rect, elevation: Elevation) {
         let outer = Quad::from(rect + self.offset);
         let inner = outer.shrink(self.window.dims.frame as f32);
+        if elevation > Elevation::Flat {
+            // The shadow starts beneath the frame, filling in around its
+            // rounded corners; shaded frames are drawn after gradients.
+            let (tl, br) = self.window.dims.shadow(elevation);
+            let shadow = Quad::with_coords(outer.a - Vec2::from(tl), outer.b + Vec2::from(br));
+            let mid = outer.shrink(self.window.dims.frame as f32 / 2.0);
+            self.draw.shadow(self.pass, shadow, mid, self.cols.shadow);
+        }
+
         let norm = (0.7, 0.0);
         let col = self.cols.background;
         self.draw
@@ -263,6 +280,15 @@ where
             .shaded_round_frame(self.pass, outer, inner, norm, col);
     }
 
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        let quad = Quad::from(rect + self.offset);
+        self.draw.image(self.pass, id, quad);
+    }
+
+    fn icon(&mut self, icon: &Icon, rect: Rect) {
+        self.as_flat().icon(icon, rect);
+    }
+
     fn text(&mut self, pos: Coord, text: &PreparedText, class: TextClass) {
         self.as_flat().text(pos, text, class);
     }
@@ -285,6 +311,10 @@ where
         self.as_flat().menu_entry(rect, state);
     }
 
+    fn view_row(&mut self, rect: Rect, selected: bool, state: InputState) {
+        self.as_flat().view_row(rect, selected, state);
+    }
+
     fn button(&mut self, rect: Rect, state: InputState) {
         let outer = Quad::from(rect + self.offset);
         let inner = outer.shrink(self.window.dims.button_frame as f32);
@@ -327,6 +357,10 @@ where
         }
     }
 
+    fn expander(&mut self, rect: Rect, expanded: bool, state: InputState) {
+        self.as_flat().expander(rect, expanded, state);
+    }
+
     fn scrollbar(&mut self, rect: Rect, h_rect: Rect, _dir: Direction, state: InputState) {
         // track
         let outer = Quad::from(rect + self.offset);
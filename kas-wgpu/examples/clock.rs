@@ -12,7 +12,7 @@ use log::info;
 use std::f32::consts::PI;
 use std::time::Duration;
 
-use kas::draw::{Colour, DrawRounded, DrawText};
+use kas::draw::{Colour, DrawPath, DrawRounded, DrawText, FillRule, LineCap, Path, Stroke};
 use kas::geom::{Quad, Vec2};
 use kas::prelude::*;
 use kas::text::PreparedText;
@@ -96,9 +96,44 @@ impl Layout for Clock {
         let a_min = (secs % 3600) as f32 * (PI / 1800.0);
         let a_hour = (secs % (12 * 3600)) as f32 * (PI / (12.0 * 1800.0));
 
-        line_seg(a_hour, 0.0, half * 0.55, half * 0.03, col_hands);
-        line_seg(a_min, 0.0, half * 0.8, half * 0.015, col_hands);
-        line_seg(a_sec, 0.0, half * 0.9, half * 0.005, col_secs);
+        // A tapered hand with a short tail and rounded tip
+        let hand = |t: f32, len: f32, w: f32| {
+            let v = Vec2(t.sin(), -t.cos());
+            let n = Vec2(-v.1, v.0);
+            let (tail, tip) = (centre - v * (len * 0.15), centre + v * len);
+            let tw = w * 0.3;
+            let mut path = Path::new();
+            path.move_to(tail + n * w);
+            path.line_to(tip + n * tw);
+            path.quad_to(tip + v * (2.0 * tw), tip - n * tw);
+            path.line_to(tail - n * w);
+            path.close();
+            path
+        };
+        let rule = FillRule::NonZero;
+        draw.fill_path(
+            pass,
+            &hand(a_hour, half * 0.55, half * 0.04),
+            rule,
+            col_hands,
+        );
+        draw.fill_path(
+            pass,
+            &hand(a_min, half * 0.8, half * 0.025),
+            rule,
+            col_hands,
+        );
+
+        let v = Vec2(a_sec.sin(), -a_sec.cos());
+        let mut path = Path::new();
+        path.move_to(centre - v * (half * 0.2));
+        path.line_to(centre + v * (half * 0.9));
+        let stroke = Stroke::new(half * 0.01).with_cap(LineCap::Round);
+        draw.stroke_path(pass, &path, &stroke, col_secs);
+
+        let mut path = Path::new();
+        path.ellipse(centre, Vec2::splat(half * 0.035));
+        draw.fill_path(pass, &path, rule, col_secs);
 
         draw.text(pass, (self.date_pos + offset).into(), col_text, &self.date);
         draw.text(pass, (self.time_pos + offset).into(), col_text, &self.time);
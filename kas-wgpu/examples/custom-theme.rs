@@ -51,7 +51,7 @@ thread_local! {
 
 impl<D: DrawShared + 'static> Theme<D> for CustomTheme
 where
-    D::Draw: DrawRounded + DrawText,
+    D::Draw: DrawRounded + DrawGradient + DrawText + DrawImage,
 {
     type Window = <FlatTheme as Theme<D>>::Window;
 
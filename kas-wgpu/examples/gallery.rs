@@ -104,8 +104,10 @@ fn main() -> Result<(), kas_wgpu::Error> {
             #[widget(row=8, col=0)] _ = Label::new("ScrollBar"),
             #[widget(row=8, col=1, handler = handle_scroll)] sc =
                 ScrollBar::<Right>::new().with_limits(5, 2),
-            #[widget(row=9)] _ = Label::new("Child window"),
-            #[widget(row=9, col = 1)] _ = TextButton::new("&Open", Item::Popup),
+            #[widget(row=9, col=0)] _ = Label::new("TextEdit"),
+            #[widget(row=9, col=1)] _ = TextEdit::new("A multi-line\ntext editor").with_rows(3),
+            #[widget(row=10)] _ = Label::new("Child window"),
+            #[widget(row=10, col = 1)] _ = TextButton::new("&Open", Item::Popup),
         }
         impl {
             fn handle_combo(&mut self, _: &mut Manager, msg: i32) -> Response<Item> {
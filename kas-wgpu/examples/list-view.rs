@@ -0,0 +1,66 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! List view example (a virtualised list of many entries)
+#![feature(proc_macro_hygiene)]
+
+use kas::class::SetText;
+use kas::event::{Manager, VoidMsg, VoidResponse};
+use kas::macros::make_widget;
+use kas::widget::{Label, ListView, SelectionMode, SharedVec, TextButton, ViewMsg, Window};
+
+type Data = SharedVec<String>;
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let data: Data = SharedVec::new((0..500_000).map(|i| format!("Entry {}", i)).collect());
+    let list = ListView::<kas::Down, Data, Label>::new(data.clone())
+        .with_selection_mode(SelectionMode::Multiple);
+
+    let window = Window::new(
+        "List view",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] status: Label = Label::new("Nothing selected"),
+                #[widget(handler = append)] _ = TextButton::new("Append", ()),
+                #[widget(handler = list_msg)] list: ListView<kas::Down, Data, Label> = list,
+                data: Data = data,
+            }
+            impl {
+                fn append(&mut self, mgr: &mut Manager, _: ()) -> VoidResponse {
+                    self.data.update(mgr, |v| {
+                        let n = v.len();
+                        v.push(format!("Entry {}", n));
+                    });
+                    VoidResponse::None
+                }
+                fn list_msg(&mut self, mgr: &mut Manager, msg: ViewMsg<usize, VoidMsg>)
+                    -> VoidResponse
+                {
+                    if let ViewMsg::Selection = msg {
+                        let text = match self.list.selection().len() {
+                            0 => "Nothing selected".to_string(),
+                            1 => {
+                                let index = self.list.selection().iter().next().unwrap();
+                                format!("Selected: entry {}", index)
+                            }
+                            n => format!("{} entries selected", n),
+                        };
+                        *mgr += self.status.set_text(text);
+                    }
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let theme = kas_theme::ShadedTheme::new();
+    let mut toolkit = kas_wgpu::Toolkit::new(theme)?;
+    toolkit.add(window)?;
+    toolkit.run()
+}
@@ -8,6 +8,7 @@
 
 use kas::event::{Manager, VoidMsg, VoidResponse};
 use kas::macros::{make_widget, VoidMsg};
+use kas::persist::StateStore;
 use kas::widget::{Label, RowSplitter, TextButton, Window};
 
 #[derive(Clone, Debug, VoidMsg)]
@@ -27,7 +28,7 @@ fn main() -> Result<(), kas_wgpu::Error> {
             #[widget] _ = TextButton::new("+", Message::Incr),
         }
     };
-    let mut panes = RowSplitter::<Label>::default();
+    let mut panes = RowSplitter::<Label>::default().with_state_key("panes");
     let _ = panes.resize_with(2, |n| Label::new(format!("Pane {}", n)));
 
     let window = Window::new(
@@ -58,10 +59,15 @@ fn main() -> Result<(), kas_wgpu::Error> {
                 }
             }
         },
-    );
+    )
+    .with_state_key("main");
 
     let theme = kas_theme::ShadedTheme::new();
     let mut toolkit = kas_wgpu::Toolkit::new(theme)?;
+    // Pane sizes and window geometry are restored on next start
+    if let Some(store) = StateStore::new("kas-splitter-example") {
+        toolkit.set_state_store(store);
+    }
     toolkit.add(window)?;
     toolkit.run()
 }
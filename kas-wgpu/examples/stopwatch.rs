@@ -43,10 +43,10 @@ fn make_window() -> Box<dyn kas::Window> {
                     }
                     Control::Start => {
                         if let Some(start) = self.start {
-                            self.saved += Instant::now() - start;
+                            self.saved += mgr.now() - start;
                             self.start = None;
                         } else {
-                            self.start = Some(Instant::now());
+                            self.start = Some(mgr.now());
                             mgr.update_on_timer(Duration::new(0, 0), self.id());
                         }
                     }
@@ -65,7 +65,7 @@ fn make_window() -> Box<dyn kas::Window> {
                 match event {
                     Event::TimerUpdate => {
                         if let Some(start) = self.start {
-                            let dur = self.saved + (Instant::now() - start);
+                            let dur = self.saved + (mgr.now() - start);
                             *mgr += self.display.set_text(format!(
                                 "{}.{:03}",
                                 dur.as_secs(),
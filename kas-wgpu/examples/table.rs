@@ -0,0 +1,77 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Table example (a sortable table of computed data)
+#![feature(proc_macro_hygiene)]
+
+use kas::class::SetText;
+use kas::event::{Manager, VoidMsg, VoidResponse};
+use kas::macros::make_widget;
+use kas::widget::{Accessor, Label, Table, ViewMsg, Window};
+
+/// Properties of the numbers `0..len`, computed on demand
+#[derive(Debug)]
+struct Numbers {
+    len: usize,
+}
+
+impl Accessor<(usize, usize)> for Numbers {
+    type Item = u64;
+
+    fn len(&self) -> (usize, usize) {
+        (3, self.len)
+    }
+
+    fn get(&self, (col, row): (usize, usize)) -> u64 {
+        let n = row as u64;
+        match col {
+            0 => n,
+            1 => n * n,
+            _ => n.to_string().bytes().map(|b| (b - b'0') as u64).sum(),
+        }
+    }
+}
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let table =
+        Table::<Numbers, Label>::new(&["Number", "Square", "Digit sum"], Numbers { len: 10_000 });
+
+    let window = Window::new(
+        "Table",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] status: Label = Label::new("Double-click a cell"),
+                #[widget(handler = table_msg)] table: Table<Numbers, Label> = table,
+            }
+            impl {
+                fn table_msg(&mut self, mgr: &mut Manager, msg: ViewMsg<(usize, usize), VoidMsg>)
+                    -> VoidResponse
+                {
+                    let text = match msg {
+                        ViewMsg::Activate(index) => {
+                            format!("Activated {}", self.table.data().get(index))
+                        }
+                        ViewMsg::Selection => match self.table.selection().first() {
+                            Some(row) => format!("Selected row {}", row),
+                            None => "Nothing selected".to_string(),
+                        },
+                        ViewMsg::Item(..) => return VoidResponse::None,
+                    };
+                    *mgr += self.status.set_text(text);
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let theme = kas_theme::ShadedTheme::new();
+    let mut toolkit = kas_wgpu::Toolkit::new(theme)?;
+    toolkit.add(window)?;
+    toolkit.run()
+}
@@ -0,0 +1,78 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Tree view example (a lazily-loaded file system browser)
+#![feature(proc_macro_hygiene)]
+
+use std::path::PathBuf;
+
+use kas::class::SetText;
+use kas::event::{Manager, VoidMsg, VoidResponse};
+use kas::macros::make_widget;
+use kas::widget::{Label, TreeAccessor, TreeView, ViewMsg, Window};
+
+/// File system access; directories are read when expanded
+#[derive(Debug)]
+struct Files {
+    root: PathBuf,
+}
+
+impl TreeAccessor for Files {
+    type Key = PathBuf;
+    type Item = String;
+
+    fn children(&self, parent: Option<&PathBuf>) -> Vec<PathBuf> {
+        let dir = parent.unwrap_or(&self.root);
+        let mut paths: Vec<PathBuf> = match dir.read_dir() {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+            Err(_) => vec![],
+        };
+        paths.sort();
+        paths
+    }
+
+    fn has_children(&self, key: &PathBuf) -> bool {
+        key.is_dir()
+    }
+
+    fn get(&self, key: &PathBuf) -> String {
+        let name = key.file_name().unwrap_or(key.as_os_str());
+        name.to_string_lossy().into_owned()
+    }
+}
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let root = std::env::current_dir().unwrap_or(PathBuf::from("."));
+    let tree = TreeView::<Files, Label>::new(Files { root });
+
+    let window = Window::new(
+        "Tree view",
+        make_widget! {
+            #[layout(column)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] status: Label = Label::new("Double-click a file"),
+                #[widget(handler = tree_msg)] _: TreeView<Files, Label> = tree,
+            }
+            impl {
+                fn tree_msg(&mut self, mgr: &mut Manager, msg: ViewMsg<PathBuf, VoidMsg>)
+                    -> VoidResponse
+                {
+                    if let ViewMsg::Activate(path) = msg {
+                        *mgr += self.status.set_text(path.display().to_string());
+                    }
+                    VoidResponse::None
+                }
+            }
+        },
+    );
+
+    let theme = kas_theme::ShadedTheme::new();
+    let mut toolkit = kas_wgpu::Toolkit::new(theme)?;
+    toolkit.add(window)?;
+    toolkit.run()
+}
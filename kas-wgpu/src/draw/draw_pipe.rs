@@ -11,10 +11,13 @@ use wgpu::TextureView;
 use wgpu_glyph::GlyphBrushBuilder;
 
 use super::{
-    flat_round, shaded_round, shaded_square, CustomPipe, CustomPipeBuilder, CustomWindow, DrawPipe,
-    DrawWindow, ShaderManager, TEX_FORMAT,
+    flat_round, gradient, images, shaded_round, shaded_square, CustomPipe, CustomPipeBuilder,
+    CustomWindow, DrawPipe, DrawWindow, ShaderManager, TEX_FORMAT,
+};
+use kas::draw::{
+    Colour, Draw, DrawGradient, DrawImage, DrawPath, DrawRounded, DrawShaded, DrawShared, FillRule,
+    Gradient, ImageId, Pass, Path, Stroke, Tessellation,
 };
-use kas::draw::{Colour, Draw, DrawRounded, DrawShaded, DrawShared, Pass};
 use kas::geom::{Coord, Quad, Rect, Size, Vec2};
 
 fn make_depth_texture(device: &wgpu::Device, size: Size) -> Option<TextureView> {
@@ -52,12 +55,16 @@ impl<C: CustomPipe> DrawPipe<C> {
         let shaded_square = shaded_square::Pipeline::new(device, shaders);
         let shaded_round = shaded_round::Pipeline::new(device, shaders);
         let flat_round = flat_round::Pipeline::new(device, shaders);
+        let gradient = gradient::Pipeline::new(device, shaders);
+        let images = images::Pipeline::new(device, shaders);
         let custom = custom.build(&device, TEX_FORMAT, super::DEPTH_FORMAT);
 
         DrawPipe {
             shaded_square,
             shaded_round,
             flat_round,
+            gradient,
+            images,
             custom,
         }
     }
@@ -84,6 +91,8 @@ impl<C: CustomPipe> DrawPipe<C> {
         let shaded_square = self.shaded_square.new_window(device, size, norm);
         let shaded_round = self.shaded_round.new_window(device, size, norm);
         let flat_round = self.flat_round.new_window(device, size);
+        let gradient = self.gradient.new_window(device, size);
+        let images = self.images.new_window(device, size);
         let custom = self.custom.new_window(device, size);
 
         let glyph_brush = GlyphBrushBuilder::using_fonts(kas::text::fonts().fonts_vec())
@@ -96,6 +105,8 @@ impl<C: CustomPipe> DrawPipe<C> {
             shaded_square,
             shaded_round,
             flat_round,
+            gradient,
+            images,
             custom,
             glyph_brush,
         }
@@ -118,12 +129,14 @@ impl<C: CustomPipe> DrawPipe<C> {
         self.custom
             .resize(&mut window.custom, device, &mut encoder, size);
         window.flat_round.resize(device, &mut encoder, size);
+        window.gradient.resize(device, &mut encoder, size);
+        window.images.resize(device, &mut encoder, size);
         encoder.finish()
     }
 
     /// Render batched draw instructions via `rpass`
     pub fn render(
-        &self,
+        &mut self,
         window: &mut DrawWindow<C::Window>,
         device: &mut wgpu::Device,
         frame_view: &wgpu::TextureView,
@@ -134,6 +147,9 @@ impl<C: CustomPipe> DrawPipe<C> {
         });
 
         self.custom.update(&mut window.custom, device, &mut encoder);
+        self.images.prepare(&window.images, device, &mut encoder);
+        self.gradient
+            .prepare(&mut window.gradient, device, &mut encoder);
 
         let mut color_attachments = [wgpu::RenderPassColorAttachmentDescriptor {
             attachment: frame_view,
@@ -152,17 +168,23 @@ impl<C: CustomPipe> DrawPipe<C> {
             clear_stencil: 0,
         };
 
-        // We use a separate render pass for each clipped region.
+        // We use a separate render pass for each clipped region, in order of
+        // creation. Translucent content is thus blended over content of all
+        // previous passes. Within a pass, pipes render in a fixed order (not
+        // in order of issue); this order is documented on kas::draw::Draw and
+        // matched by kas-headless.
         for (pass, rect) in window.clip_regions.iter().enumerate() {
             let ss = self
                 .shaded_square
                 .render_buf(&mut window.shaded_square, device, pass);
+            let gr = self.gradient.render_buf(&mut window.gradient, device, pass);
             let sr = self
                 .shaded_round
                 .render_buf(&mut window.shaded_round, device, pass);
             let fr = self
                 .flat_round
                 .render_buf(&mut window.flat_round, device, pass);
+            let im = self.images.render_buf(&mut window.images, device, pass);
 
             {
                 let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -177,8 +199,10 @@ impl<C: CustomPipe> DrawPipe<C> {
                 );
 
                 ss.as_ref().map(|buf| buf.render(&mut rpass));
+                gr.as_ref().map(|buf| buf.render(&mut rpass));
                 sr.as_ref().map(|buf| buf.render(&mut rpass));
                 fr.as_ref().map(|buf| buf.render(&mut rpass));
+                im.as_ref().map(|buf| buf.render(&mut rpass));
                 self.custom
                     .render_pass(&mut window.custom, device, pass, &mut rpass);
             }
@@ -271,6 +295,39 @@ impl<CW: CustomWindow + 'static> DrawRounded for DrawWindow<CW> {
     }
 }
 
+impl<CW: CustomWindow + 'static> DrawPath for DrawWindow<CW> {
+    fn fill_path(&mut self, pass: Pass, path: &Path, rule: FillRule, col: Colour) {
+        let polygons: Vec<_> = path.flatten().into_iter().map(|c| c.points).collect();
+        let tess = Tessellation::new(&polygons, rule);
+        self.flat_round.tessellation(pass, &tess, col);
+    }
+
+    fn stroke_path(&mut self, pass: Pass, path: &Path, stroke: &Stroke, col: Colour) {
+        let polygons = stroke.outline(&path.flatten());
+        let tess = Tessellation::new(&polygons, FillRule::NonZero);
+        self.flat_round.tessellation(pass, &tess, col);
+    }
+}
+
+impl<CW: CustomWindow + 'static> DrawGradient for DrawWindow<CW> {
+    #[inline]
+    fn gradient_rect(&mut self, pass: Pass, rect: Quad, gradient: &Gradient) {
+        self.gradient.rect(pass, rect, gradient);
+    }
+
+    #[inline]
+    fn gradient_frame(&mut self, pass: Pass, outer: Quad, inner: Quad, gradient: &Gradient) {
+        self.gradient.frame(pass, outer, inner, gradient);
+    }
+}
+
+impl<CW: CustomWindow + 'static> DrawImage for DrawWindow<CW> {
+    #[inline]
+    fn image(&mut self, pass: Pass, id: ImageId, rect: Quad) {
+        self.images.image(pass, id, rect);
+    }
+}
+
 impl<CW: CustomWindow + 'static> DrawShaded for DrawWindow<CW> {
     #[inline]
     fn shaded_square(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
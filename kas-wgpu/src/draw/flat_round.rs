@@ -7,8 +7,8 @@
 
 use std::mem::size_of;
 
-use crate::draw::{Rgb, ShaderManager};
-use kas::draw::{Colour, Pass};
+use crate::draw::{Rgba, ShaderManager};
+use kas::draw::{Colour, Pass, Tessellation};
 use kas::geom::{Quad, Size, Vec2, Vec3};
 
 /// Offset relative to the size of a pixel used by the fragment shader to
@@ -17,12 +17,12 @@ const OFFSET: f32 = 0.125;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
-struct Vertex(Vec3, Rgb, f32, Vec2, Vec2);
+struct Vertex(Vec3, Rgba, f32, Vec2, Vec2);
 unsafe impl bytemuck::Zeroable for Vertex {}
 unsafe impl bytemuck::Pod for Vertex {}
 
 impl Vertex {
-    fn new2(v: Vec2, d: f32, col: Rgb, inner: f32, n: Vec2, p: Vec2) -> Self {
+    fn new2(v: Vec2, d: f32, col: Rgba, inner: f32, n: Vec2, p: Vec2) -> Self {
         let v = Vec3::from2(v, d);
         Vertex(v, col, inner, n, p)
     }
@@ -87,7 +87,7 @@ impl Pipeline {
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             layout: &pipeline_layout,
             vertex_stage: wgpu::ProgrammableStageDescriptor {
-                module: &shaders.vert_3122,
+                module: &shaders.vert_4122,
                 entry_point: "main",
             },
             fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
@@ -124,7 +124,7 @@ impl Pipeline {
                     step_mode: wgpu::InputStepMode::Vertex,
                     attributes: &wgpu::vertex_attr_array![
                         0 => Float3,
-                        1 => Float3,
+                        1 => Float4,
                         2 => Float,
                         3 => Float2,
                         4 => Float2
@@ -409,6 +409,44 @@ impl Window {
         ]);
     }
 
+    /// Add a tessellated path
+    ///
+    /// Triangles are fully covered. An anti-aliased fringe, one pixel wide, is
+    /// drawn outside each boundary edge, using the edge of a large "circle"
+    /// (radius 8 pixels) to approximate a straight edge.
+    pub fn tessellation(&mut self, pass: Pass, tess: &Tessellation, col: Colour) {
+        let col = col.into();
+        let depth = pass.depth();
+
+        let n0 = Vec2::splat(0.0);
+        let mut vertices = Vec::with_capacity(3 * tess.triangles.len() + 6 * tess.edges.len());
+        for t in &tess.triangles {
+            for v in t {
+                vertices.push(Vertex::new2(*v, depth, col, 0.0, n0, n0));
+            }
+        }
+
+        const R: f32 = 8.0;
+        let p = Vec2::splat(OFFSET / R);
+        let (n1, n2) = (Vec2(1.0, 0.0), Vec2(1.0 + 1.0 / R, 0.0));
+        for (a, b) in &tess.edges {
+            let d = *b - *a;
+            let len = d.sum_square().sqrt();
+            if !(len > 0.0) {
+                continue;
+            }
+            // The interior is right of a→b; this is the outward normal
+            let out = Vec2(d.1, -d.0) * (1.0 / len);
+            let aa = Vertex::new2(*a, depth, col, 0.0, n1, p);
+            let bb = Vertex::new2(*b, depth, col, 0.0, n1, p);
+            let ao = Vertex::new2(*a + out, depth, col, 0.0, n2, p);
+            let bo = Vertex::new2(*b + out, depth, col, 0.0, n2, p);
+            vertices.extend_from_slice(&[aa, bb, bo, aa, bo, ao]);
+        }
+
+        self.add_vertices(pass.pass(), &vertices);
+    }
+
     fn add_vertices(&mut self, pass: usize, slice: &[Vertex]) {
         if self.passes.len() <= pass {
             // We only need one more, but no harm in adding extra
@@ -0,0 +1,463 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Gradient pipeline
+//!
+//! Each gradient drawn in a frame is sampled into one row of a "ramp"
+//! texture. Vertices carry the gradient coordinate (offset for linear
+//! gradients, position relative to the unit circle for radial gradients) and
+//! the ramp row; the fragment shader computes the offset and interpolates
+//! between ramp texels.
+
+use std::mem::size_of;
+
+use crate::draw::ShaderManager;
+use kas::draw::{Gradient, GradientShape, Pass};
+use kas::geom::{Quad, Size, Vec2, Vec3};
+
+/// Number of samples per gradient; must match `gradient.frag`
+const RAMP_LEN: u32 = 256;
+
+/// Ramp texels
+type Texel = [f32; 4];
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct Vertex(Vec3, Vec2, Vec2);
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+/// A pipeline for rendering gradient fills
+pub struct Pipeline {
+    bind_group_layout: wgpu::BindGroupLayout,
+    ramp_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+/// Ramp texture
+struct Ramp {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    rows: u32,
+}
+
+/// Per-window state
+pub struct Window {
+    bind_group: wgpu::BindGroup,
+    scale_buf: wgpu::Buffer,
+    passes: Vec<Vec<Vertex>>,
+    texels: Vec<Texel>,
+    ramp: Option<Ramp>,
+}
+
+/// Buffer used during render pass
+///
+/// This buffer must not be dropped before the render pass.
+pub struct RenderBuffer<'a> {
+    pipe: &'a wgpu::RenderPipeline,
+    vertices: &'a mut Vec<Vertex>,
+    bind_group: &'a wgpu::BindGroup,
+    ramp_bind_group: &'a wgpu::BindGroup,
+    buffer: wgpu::Buffer,
+}
+
+impl<'a> RenderBuffer<'a> {
+    /// Do the render
+    pub fn render(&'a self, rpass: &mut wgpu::RenderPass<'a>) {
+        let count = self.vertices.len() as u32;
+        rpass.set_pipeline(self.pipe);
+        rpass.set_bind_group(0, self.bind_group, &[]);
+        rpass.set_bind_group(1, self.ramp_bind_group, &[]);
+        rpass.set_vertex_buffer(0, &self.buffer, 0, 0);
+        rpass.draw(0..count, 0..1);
+    }
+}
+
+impl<'a> Drop for RenderBuffer<'a> {
+    fn drop(&mut self) {
+        self.vertices.clear();
+    }
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(device: &wgpu::Device, shaders: &ShaderManager) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            }],
+            label: None,
+        });
+
+        let ramp_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: false,
+                            dimension: wgpu::TextureViewDimension::D2,
+                            component_type: wgpu::TextureComponentType::Float,
+                        },
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler { comparison: false },
+                    },
+                ],
+                label: None,
+            });
+
+        // The shader uses texelFetch, thus filtering is not used
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 0.0,
+            compare: wgpu::CompareFunction::Undefined,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&bind_group_layout, &ramp_bind_group_layout],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            layout: &pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &shaders.vert_gradient,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &shaders.frag_gradient,
+                entry_point: "main",
+            }),
+            rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: wgpu::CullMode::None,
+                depth_bias: 0,
+                depth_bias_slope_scale: 0.0,
+                depth_bias_clamp: 0.0,
+            }),
+            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(super::DEPTH_DESC),
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                    stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::InputStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float3, 1 => Float2, 2 => Float2],
+                }],
+            },
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        Pipeline {
+            bind_group_layout,
+            ramp_bind_group_layout,
+            sampler,
+            render_pipeline,
+        }
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self, device: &wgpu::Device, size: Size) -> Window {
+        let usage = wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST;
+
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, -2.0 / size.1 as f32];
+        let scale_buf = device.create_buffer_with_data(bytemuck::cast_slice(&scale_factor), usage);
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &scale_buf,
+                    range: 0..(size_of::<Scale>() as u64),
+                },
+            }],
+            label: None,
+        });
+
+        Window {
+            bind_group,
+            scale_buf,
+            passes: vec![],
+            texels: vec![],
+            ramp: None,
+        }
+    }
+
+    /// Upload gradient ramps of `window`
+    ///
+    /// This must be called before constructing render buffers.
+    pub fn prepare(
+        &self,
+        window: &mut Window,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let rows = window.texels.len() as u32 / RAMP_LEN;
+        if rows == 0 {
+            return;
+        }
+
+        if window
+            .ramp
+            .as_ref()
+            .map(|ramp| ramp.rows < rows)
+            .unwrap_or(true)
+        {
+            // Allocate with some spare capacity to avoid frequent reallocation
+            let rows = rows.next_power_of_two().max(16);
+            window.ramp = Some(self.new_ramp(device, rows));
+        }
+        let ramp = window.ramp.as_ref().unwrap();
+
+        let extent = wgpu::Extent3d {
+            width: RAMP_LEN,
+            height: rows,
+            depth: 1,
+        };
+        // Rows are 4096 bytes, thus already aligned to 256 bytes as required
+        let stride = RAMP_LEN * size_of::<Texel>() as u32;
+        let buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&window.texels),
+            wgpu::BufferUsage::COPY_SRC,
+        );
+        encoder.copy_buffer_to_texture(
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                offset: 0,
+                bytes_per_row: stride,
+                rows_per_image: rows,
+            },
+            wgpu::TextureCopyView {
+                texture: &ramp.texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            extent,
+        );
+
+        window.texels.clear();
+    }
+
+    fn new_ramp(&self, device: &wgpu::Device, rows: u32) -> Ramp {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: RAMP_LEN,
+                height: rows,
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba32Float,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            label: Some("gradient ramp"),
+        });
+
+        let view = texture.create_default_view();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.ramp_bind_group_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: None,
+        });
+
+        Ramp {
+            texture,
+            bind_group,
+            rows,
+        }
+    }
+
+    /// Construct a render buffer
+    pub fn render_buf<'a>(
+        &'a self,
+        window: &'a mut Window,
+        device: &wgpu::Device,
+        pass: usize,
+    ) -> Option<RenderBuffer<'a>> {
+        if pass >= window.passes.len() || window.passes[pass].len() == 0 {
+            return None;
+        }
+        let ramp = window.ramp.as_ref()?;
+
+        let vertices = &mut window.passes[pass];
+        let buffer = device
+            .create_buffer_with_data(bytemuck::cast_slice(&vertices), wgpu::BufferUsage::VERTEX);
+
+        Some(RenderBuffer {
+            pipe: &self.render_pipeline,
+            vertices,
+            bind_group: &window.bind_group,
+            ramp_bind_group: &ramp.bind_group,
+            buffer,
+        })
+    }
+}
+
+impl Window {
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        size: Size,
+    ) {
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, -2.0 / size.1 as f32];
+        let scale_buf = device.create_buffer_with_data(
+            bytemuck::cast_slice(&scale_factor),
+            wgpu::BufferUsage::COPY_SRC,
+        );
+        let byte_len = size_of::<Scale>() as u64;
+
+        encoder.copy_buffer_to_buffer(&scale_buf, 0, &self.scale_buf, 0, byte_len);
+    }
+
+    /// Add a ramp for `gradient`, returning a function mapping positions to
+    /// vertices
+    fn add_ramp<'a>(&mut self, pass: Pass, gradient: &'a Gradient) -> impl Fn(Vec2) -> Vertex + 'a {
+        let row = (self.texels.len() as u32 / RAMP_LEN) as f32;
+        let ramp = gradient.ramp(RAMP_LEN as usize);
+        self.texels
+            .extend(ramp.into_iter().map(|c| -> Texel { c.into() }));
+
+        let depth = pass.depth();
+        let shape = gradient.shape();
+        move |p: Vec2| {
+            let (coord, radial) = match shape {
+                GradientShape::Linear { .. } => (Vec2(gradient.offset(p), 0.0), 0.0),
+                GradientShape::Radial { centre, radius } => {
+                    if radius.0 > 0.0 && radius.1 > 0.0 {
+                        ((p - centre) / radius, 1.0)
+                    } else {
+                        (Vec2::splat(0.0), 1.0)
+                    }
+                }
+            };
+            Vertex(Vec3::from2(p, depth), coord, Vec2(radial, row))
+        }
+    }
+
+    /// Add a rectangle filled with `gradient`
+    pub fn rect(&mut self, pass: Pass, rect: Quad, gradient: &Gradient) {
+        let aa = rect.a;
+        let bb = rect.b;
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let vertex = self.add_ramp(pass, gradient);
+        let ab = vertex(Vec2(aa.0, bb.1));
+        let ba = vertex(Vec2(bb.0, aa.1));
+        let aa = vertex(aa);
+        let bb = vertex(bb);
+
+        #[rustfmt::skip]
+        self.add_vertices(pass.pass(), &[
+            aa, ba, ab,
+            ab, ba, bb,
+        ]);
+    }
+
+    /// Add a frame filled with `gradient`
+    ///
+    /// Bounds on input: `aa < cc < dd < bb`.
+    pub fn frame(&mut self, pass: Pass, outer: Quad, inner: Quad, gradient: &Gradient) {
+        let aa = outer.a;
+        let bb = outer.b;
+        let mut cc = inner.a;
+        let mut dd = inner.b;
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+        if !aa.le(cc) || !cc.le(bb) {
+            cc = aa;
+        }
+        if !aa.le(dd) || !dd.le(bb) {
+            dd = bb;
+        }
+        if !cc.le(dd) {
+            dd = cc;
+        }
+
+        let vertex = self.add_ramp(pass, gradient);
+        let ab = vertex(Vec2(aa.0, bb.1));
+        let ba = vertex(Vec2(bb.0, aa.1));
+        let cd = vertex(Vec2(cc.0, dd.1));
+        let dc = vertex(Vec2(dd.0, cc.1));
+        let aa = vertex(aa);
+        let bb = vertex(bb);
+        let cc = vertex(cc);
+        let dd = vertex(dd);
+
+        #[rustfmt::skip]
+        self.add_vertices(pass.pass(), &[
+            // top bar: ba - dc - cc - aa
+            ba, dc, aa,
+            aa, dc, cc,
+            // left bar: aa - cc - cd - ab
+            aa, cc, ab,
+            ab, cc, cd,
+            // bottom bar: ab - cd - dd - bb
+            ab, cd, bb,
+            bb, cd, dd,
+            // right bar: bb - dd - dc - ba
+            bb, dd, ba,
+            ba, dd, dc,
+        ]);
+    }
+
+    fn add_vertices(&mut self, pass: usize, slice: &[Vertex]) {
+        if self.passes.len() <= pass {
+            // We only need one more, but no harm in adding extra
+            self.passes.resize(pass + 8, vec![]);
+        }
+
+        self.passes[pass].extend_from_slice(slice);
+    }
+}
@@ -0,0 +1,403 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image pipeline
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::ops::Range;
+
+use crate::draw::ShaderManager;
+use kas::draw::{ImageData, ImageId, Pass};
+use kas::geom::{Quad, Size, Vec2, Vec3};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct Vertex(Vec3, Vec2);
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+/// An uploaded image
+struct Texture {
+    _texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// A pipeline for rendering images
+pub struct Pipeline {
+    bind_group_layout: wgpu::BindGroupLayout,
+    tex_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    render_pipeline: wgpu::RenderPipeline,
+    textures: HashMap<ImageId, Texture>,
+}
+
+/// Vertices and image ranges of a single pass
+#[derive(Clone, Debug, Default)]
+struct PassData {
+    vertices: Vec<Vertex>,
+    images: Vec<(ImageId, Range<u32>)>,
+}
+
+/// Per-window state
+pub struct Window {
+    bind_group: wgpu::BindGroup,
+    scale_buf: wgpu::Buffer,
+    passes: Vec<PassData>,
+}
+
+/// Buffer used during render pass
+///
+/// This buffer must not be dropped before the render pass.
+pub struct RenderBuffer<'a> {
+    pipe: &'a wgpu::RenderPipeline,
+    textures: &'a HashMap<ImageId, Texture>,
+    data: &'a mut PassData,
+    bind_group: &'a wgpu::BindGroup,
+    buffer: wgpu::Buffer,
+}
+
+impl<'a> RenderBuffer<'a> {
+    /// Do the render
+    pub fn render(&'a self, rpass: &mut wgpu::RenderPass<'a>) {
+        rpass.set_pipeline(self.pipe);
+        rpass.set_bind_group(0, self.bind_group, &[]);
+        rpass.set_vertex_buffer(0, &self.buffer, 0, 0);
+        for (id, range) in &self.data.images {
+            // Images which could not be uploaded are skipped
+            if let Some(texture) = self.textures.get(id) {
+                rpass.set_bind_group(1, &texture.bind_group, &[]);
+                rpass.draw(range.clone(), 0..1);
+            }
+        }
+    }
+}
+
+impl<'a> Drop for RenderBuffer<'a> {
+    fn drop(&mut self) {
+        self.data.vertices.clear();
+        self.data.images.clear();
+    }
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(device: &wgpu::Device, shaders: &ShaderManager) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            }],
+            label: None,
+        });
+
+        let tex_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: false,
+                            dimension: wgpu::TextureViewDimension::D2,
+                            component_type: wgpu::TextureComponentType::Float,
+                        },
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler { comparison: false },
+                    },
+                ],
+                label: None,
+            });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 0.0,
+            compare: wgpu::CompareFunction::Undefined,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&bind_group_layout, &tex_bind_group_layout],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            layout: &pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &shaders.vert_image,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &shaders.frag_image,
+                entry_point: "main",
+            }),
+            rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: wgpu::CullMode::None,
+                depth_bias: 0,
+                depth_bias_slope_scale: 0.0,
+                depth_bias_clamp: 0.0,
+            }),
+            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(super::DEPTH_DESC),
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                    stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::InputStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float3, 1 => Float2],
+                }],
+            },
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        Pipeline {
+            bind_group_layout,
+            tex_bind_group_layout,
+            sampler,
+            render_pipeline,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self, device: &wgpu::Device, size: Size) -> Window {
+        let usage = wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST;
+
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, -2.0 / size.1 as f32];
+        let scale_buf = device.create_buffer_with_data(bytemuck::cast_slice(&scale_factor), usage);
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &scale_buf,
+                    range: 0..(size_of::<Scale>() as u64),
+                },
+            }],
+            label: None,
+        });
+
+        Window {
+            bind_group,
+            scale_buf,
+            passes: vec![],
+        }
+    }
+
+    /// Upload images used by `window` and release unregistered images
+    ///
+    /// This must be called before constructing render buffers.
+    pub fn prepare(
+        &mut self,
+        window: &Window,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        self.textures.retain(|id, _| id.is_registered());
+
+        for pass in &window.passes {
+            for (id, _) in &pass.images {
+                if self.textures.contains_key(id) {
+                    continue;
+                }
+                if let Some(data) = id.data() {
+                    if let Some(texture) = self.upload(device, encoder, &data) {
+                        self.textures.insert(*id, texture);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Create a texture from `data`
+    ///
+    /// Returns `None` for zero-sized images, which wgpu does not support.
+    fn upload(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        data: &ImageData,
+    ) -> Option<Texture> {
+        let size = data.size();
+        if size.0 * size.1 == 0 {
+            return None;
+        }
+
+        let extent = wgpu::Extent3d {
+            width: size.0,
+            height: size.1,
+            depth: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: extent,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            label: Some("image"),
+        });
+
+        // wgpu requires rows to be aligned to 256 bytes
+        let row = 4 * size.0 as usize;
+        let stride = (row + 255) / 256 * 256;
+        let mut bytes = vec![0u8; stride * size.1 as usize];
+        for (y, src) in data.data().chunks(row).enumerate() {
+            bytes[y * stride..y * stride + row].copy_from_slice(src);
+        }
+        let buffer = device.create_buffer_with_data(&bytes, wgpu::BufferUsage::COPY_SRC);
+
+        encoder.copy_buffer_to_texture(
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                offset: 0,
+                bytes_per_row: stride as u32,
+                rows_per_image: size.1,
+            },
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            extent,
+        );
+
+        let view = texture.create_default_view();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.tex_bind_group_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: None,
+        });
+
+        Some(Texture {
+            _texture: texture,
+            bind_group,
+        })
+    }
+
+    /// Construct a render buffer
+    pub fn render_buf<'a>(
+        &'a self,
+        window: &'a mut Window,
+        device: &wgpu::Device,
+        pass: usize,
+    ) -> Option<RenderBuffer<'a>> {
+        if pass >= window.passes.len() || window.passes[pass].vertices.len() == 0 {
+            return None;
+        }
+
+        let data = &mut window.passes[pass];
+        let buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&data.vertices),
+            wgpu::BufferUsage::VERTEX,
+        );
+
+        Some(RenderBuffer {
+            pipe: &self.render_pipeline,
+            textures: &self.textures,
+            data,
+            bind_group: &window.bind_group,
+            buffer,
+        })
+    }
+}
+
+impl Window {
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        size: Size,
+    ) {
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, -2.0 / size.1 as f32];
+        let scale_buf = device.create_buffer_with_data(
+            bytemuck::cast_slice(&scale_factor),
+            wgpu::BufferUsage::COPY_SRC,
+        );
+        let byte_len = size_of::<Scale>() as u64;
+
+        encoder.copy_buffer_to_buffer(&scale_buf, 0, &self.scale_buf, 0, byte_len);
+    }
+
+    /// Add an image, stretched to fill `rect`
+    pub fn image(&mut self, pass: Pass, id: ImageId, rect: Quad) {
+        let aa = rect.a;
+        let bb = rect.b;
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let depth = pass.depth();
+        let ab = Vec2(aa.0, bb.1);
+        let ba = Vec2(bb.0, aa.1);
+
+        let aa = Vertex(Vec3::from2(aa, depth), Vec2(0.0, 0.0));
+        let ab = Vertex(Vec3::from2(ab, depth), Vec2(0.0, 1.0));
+        let ba = Vertex(Vec3::from2(ba, depth), Vec2(1.0, 0.0));
+        let bb = Vertex(Vec3::from2(bb, depth), Vec2(1.0, 1.0));
+
+        let pass = pass.pass();
+        if self.passes.len() <= pass {
+            // We only need one more, but no harm in adding extra
+            self.passes.resize(pass + 8, Default::default());
+        }
+
+        let data = &mut self.passes[pass];
+        let start = data.vertices.len() as u32;
+        #[rustfmt::skip]
+        data.vertices.extend_from_slice(&[
+            ba, aa, bb,
+            bb, aa, ab,
+        ]);
+        let end = data.vertices.len() as u32;
+
+        // Consecutive draws of the same image share a single draw call
+        match data.images.last_mut() {
+            Some((last, range)) if *last == id => range.end = end,
+            _ => data.images.push((id, start..end)),
+        }
+    }
+}
@@ -11,6 +11,8 @@ mod custom;
 mod draw_pipe;
 mod draw_text;
 mod flat_round;
+mod gradient;
+mod images;
 mod shaded_round;
 mod shaded_square;
 mod shaders;
@@ -39,21 +41,23 @@ const fn new_depth_desc(depth_compare: CompareFunction) -> DepthStencilStateDesc
 const DEPTH_DESC: DepthStencilStateDescriptor = new_depth_desc(CompareFunction::Always);
 const GLPYH_DEPTH_DESC: DepthStencilStateDescriptor = new_depth_desc(CompareFunction::GreaterEqual);
 
-/// 3-part colour data
+/// 4-part colour data (with alpha)
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
-pub(crate) struct Rgb {
+pub(crate) struct Rgba {
     pub r: f32,
     pub g: f32,
     pub b: f32,
+    pub a: f32,
 }
 
-impl From<kas::draw::Colour> for Rgb {
+impl From<kas::draw::Colour> for Rgba {
     fn from(c: kas::draw::Colour) -> Self {
-        Rgb {
+        Rgba {
             r: c.r,
             g: c.g,
             b: c.b,
+            a: c.a,
         }
     }
 }
@@ -63,6 +67,8 @@ pub struct DrawPipe<C> {
     shaded_square: shaded_square::Pipeline,
     shaded_round: shaded_round::Pipeline,
     flat_round: flat_round::Pipeline,
+    gradient: gradient::Pipeline,
+    images: images::Pipeline,
     custom: C,
 }
 
@@ -75,6 +81,8 @@ pub struct DrawWindow<CW: CustomWindow> {
     shaded_square: shaded_square::Window,
     shaded_round: shaded_round::Window,
     flat_round: flat_round::Window,
+    gradient: gradient::Window,
+    images: images::Window,
     custom: CW,
     glyph_brush: GlyphBrush, // TODO: should be in DrawPipe
 }
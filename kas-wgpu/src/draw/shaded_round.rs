@@ -8,7 +8,7 @@
 use std::f32::consts::FRAC_PI_2;
 use std::mem::size_of;
 
-use crate::draw::{Rgb, ShaderManager};
+use crate::draw::{Rgba, ShaderManager};
 use kas::draw::{Colour, Pass};
 use kas::geom::{Quad, Size, Vec2, Vec3};
 
@@ -18,12 +18,12 @@ const OFFSET: f32 = 0.125;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
-struct Vertex(Vec3, Rgb, Vec2, Vec2, Vec2);
+struct Vertex(Vec3, Rgba, Vec2, Vec2, Vec2);
 unsafe impl bytemuck::Zeroable for Vertex {}
 unsafe impl bytemuck::Pod for Vertex {}
 
 impl Vertex {
-    fn new2(v: Vec2, d: f32, col: Rgb, n: Vec2, adjust: Vec2, p: Vec2) -> Self {
+    fn new2(v: Vec2, d: f32, col: Rgba, n: Vec2, adjust: Vec2, p: Vec2) -> Self {
         let v = Vec3::from2(v, d);
         Vertex(v, col, n, adjust, p)
     }
@@ -94,7 +94,7 @@ impl Pipeline {
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             layout: &pipeline_layout,
             vertex_stage: wgpu::ProgrammableStageDescriptor {
-                module: &shaders.vert_3222,
+                module: &shaders.vert_4222,
                 entry_point: "main",
             },
             fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
@@ -131,7 +131,7 @@ impl Pipeline {
                     step_mode: wgpu::InputStepMode::Vertex,
                     attributes: &wgpu::vertex_attr_array![
                         0 => Float3,
-                        1 => Float3,
+                        1 => Float4,
                         2 => Float2,
                         3 => Float2,
                         4 => Float2
@@ -15,38 +15,52 @@ use wgpu::ShaderModule;
 /// Not really optimal (we could embed SPIR-V directly or load shaders from
 /// external resources), but simple to set up and use.
 pub struct ShaderManager {
-    pub vert_3122: ShaderModule,
-    pub vert_32: ShaderModule,
+    pub vert_4122: ShaderModule,
+    pub vert_42: ShaderModule,
     pub vert_322: ShaderModule,
-    pub vert_3222: ShaderModule,
+    pub vert_4222: ShaderModule,
+    pub vert_image: ShaderModule,
+    pub vert_gradient: ShaderModule,
     pub frag_flat_round: ShaderModule,
     pub frag_shaded_square: ShaderModule,
     pub frag_shaded_round: ShaderModule,
+    pub frag_image: ShaderModule,
+    pub frag_gradient: ShaderModule,
 }
 
 impl ShaderManager {
     pub fn new(device: &wgpu::Device) -> Result<Self, Error> {
         let mut compiler = Compiler::new().unwrap();
 
-        let fname = "shaders/scaled3122.vert";
-        let source = include_str!("shaders/scaled3122.vert");
+        let fname = "shaders/scaled4122.vert";
+        let source = include_str!("shaders/scaled4122.vert");
         let artifact = compiler.compile_into_spirv(source, Vertex, fname, "main", None)?;
-        let vert_3122 = device.create_shader_module(&artifact.as_binary());
+        let vert_4122 = device.create_shader_module(&artifact.as_binary());
 
-        let fname = "shaders/scaled32.vert";
-        let source = include_str!("shaders/scaled32.vert");
+        let fname = "shaders/scaled42.vert";
+        let source = include_str!("shaders/scaled42.vert");
         let artifact = compiler.compile_into_spirv(source, Vertex, fname, "main", None)?;
-        let vert_32 = device.create_shader_module(&artifact.as_binary());
+        let vert_42 = device.create_shader_module(&artifact.as_binary());
 
         let fname = "shaders/scaled322.vert";
         let source = include_str!("shaders/scaled322.vert");
         let artifact = compiler.compile_into_spirv(source, Vertex, fname, "main", None)?;
         let vert_322 = device.create_shader_module(&artifact.as_binary());
 
-        let fname = "shaders/scaled3222.vert";
-        let source = include_str!("shaders/scaled3222.vert");
+        let fname = "shaders/scaled4222.vert";
+        let source = include_str!("shaders/scaled4222.vert");
         let artifact = compiler.compile_into_spirv(source, Vertex, fname, "main", None)?;
-        let vert_3222 = device.create_shader_module(&artifact.as_binary());
+        let vert_4222 = device.create_shader_module(&artifact.as_binary());
+
+        let fname = "shaders/image.vert";
+        let source = include_str!("shaders/image.vert");
+        let artifact = compiler.compile_into_spirv(source, Vertex, fname, "main", None)?;
+        let vert_image = device.create_shader_module(&artifact.as_binary());
+
+        let fname = "shaders/gradient.vert";
+        let source = include_str!("shaders/gradient.vert");
+        let artifact = compiler.compile_into_spirv(source, Vertex, fname, "main", None)?;
+        let vert_gradient = device.create_shader_module(&artifact.as_binary());
 
         let fname = "shaders/flat_round.frag";
         let source = include_str!("shaders/flat_round.frag");
@@ -63,14 +77,28 @@ impl ShaderManager {
         let artifact = compiler.compile_into_spirv(source, Fragment, fname, "main", None)?;
         let frag_shaded_round = device.create_shader_module(&artifact.as_binary());
 
+        let fname = "shaders/image.frag";
+        let source = include_str!("shaders/image.frag");
+        let artifact = compiler.compile_into_spirv(source, Fragment, fname, "main", None)?;
+        let frag_image = device.create_shader_module(&artifact.as_binary());
+
+        let fname = "shaders/gradient.frag";
+        let source = include_str!("shaders/gradient.frag");
+        let artifact = compiler.compile_into_spirv(source, Fragment, fname, "main", None)?;
+        let frag_gradient = device.create_shader_module(&artifact.as_binary());
+
         Ok(ShaderManager {
-            vert_3122,
-            vert_32,
+            vert_4122,
+            vert_42,
             vert_322,
-            vert_3222,
+            vert_4222,
+            vert_image,
+            vert_gradient,
             frag_flat_round,
             frag_shaded_square,
             frag_shaded_round,
+            frag_image,
+            frag_gradient,
         })
     }
 }
@@ -0,0 +1,32 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+#version 450
+#extension GL_ARB_separate_shader_objects : enable
+
+precision mediump float;
+
+// Number of samples per gradient; must match gradient.rs
+const int RAMP_LEN = 256;
+
+layout(location = 0) in vec2 coord;
+// x: 1 for radial gradients, otherwise 0; y: ramp row
+layout(location = 1) flat in vec2 ramp;
+
+layout(location = 0) out vec4 outColor;
+
+layout(set = 1, binding = 0) uniform texture2D t_ramp;
+layout(set = 1, binding = 1) uniform sampler s_ramp;
+
+void main() {
+    float t = (ramp.x > 0.5) ? length(coord) : coord.x;
+    float x = clamp(t, 0.0, 1.0) * float(RAMP_LEN - 1);
+    int i = int(floor(x));
+    int j = min(i + 1, RAMP_LEN - 1);
+    int row = int(ramp.y);
+    vec4 c0 = texelFetch(sampler2D(t_ramp, s_ramp), ivec2(i, row), 0);
+    vec4 c1 = texelFetch(sampler2D(t_ramp, s_ramp), ivec2(j, row), 0);
+    outColor = mix(c0, c1, x - float(i));
+}
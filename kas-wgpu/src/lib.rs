@@ -127,6 +127,32 @@ where
         })
     }
 
+    /// Set the application's keyboard shortcuts
+    ///
+    /// User shortcuts (see [`Options::shortcuts`]) are applied on top of
+    /// these. This affects windows added subsequently.
+    pub fn set_shortcuts(&mut self, shortcuts: kas::event::Shortcuts) {
+        self.shared.set_shortcuts(shortcuts);
+    }
+
+    /// Set the store used to persist window and widget state
+    ///
+    /// State is loaded immediately and restored to windows added
+    /// subsequently which have a [`kas::Window::state_key`]; the state of
+    /// these windows is saved to the store on window closure. See
+    /// [`kas::persist`]. Example:
+    /// ```no_run
+    /// # use kas_wgpu::{Toolkit, theme::ShadedTheme};
+    /// use kas::persist::StateStore;
+    /// let mut toolkit = Toolkit::new(ShadedTheme::new()).unwrap();
+    /// if let Some(store) = StateStore::new("my-app") {
+    ///     toolkit.set_state_store(store);
+    /// }
+    /// ```
+    pub fn set_state_store(&mut self, store: kas::persist::StateStore) {
+        self.shared.set_state_store(store);
+    }
+
     /// Assume ownership of and display a window
     ///
     /// This is a convenience wrapper around [`Toolkit::add_boxed`].
@@ -6,7 +6,8 @@
 //! Options
 
 use log::warn;
-use std::env::var;
+use std::env::{var, var_os};
+use std::path::PathBuf;
 pub use wgpu::{BackendBit, PowerPreference};
 
 /// Toolkit options
@@ -16,6 +17,9 @@ pub struct Options {
     pub power_preference: PowerPreference,
     /// Adapter backend. Default value: PRIMARY (Vulkan/Metal/DX12).
     pub backends: BackendBit,
+    /// User keyboard shortcuts file, applied over the application's
+    /// shortcuts (see `kas::event::Shortcuts`). Default value: none.
+    pub shortcuts: Option<PathBuf>,
 }
 
 impl Default for Options {
@@ -23,6 +27,7 @@ impl Default for Options {
         Options {
             power_preference: PowerPreference::LowPower,
             backends: BackendBit::PRIMARY,
+            shortcuts: None,
         }
     }
 }
@@ -51,6 +56,11 @@ impl Options {
     /// -   `DX12`
     /// -   `PRIMARY`: any of Vulkan, Metal or DX12
     /// -   `SECONDARY`: any of GL or DX11
+    ///
+    /// ### Shortcuts
+    ///
+    /// The `KAS_SHORTCUTS` variable may be set to the path of a keyboard
+    /// shortcuts file (see `kas::event::Shortcuts` for the format).
     pub fn from_env() -> Self {
         let mut options = Options::default();
 
@@ -87,6 +97,10 @@ impl Options {
             }
         }
 
+        if let Some(path) = var_os("KAS_SHORTCUTS") {
+            options.shortcuts = Some(path.into());
+        }
+
         options
     }
 
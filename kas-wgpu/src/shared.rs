@@ -7,10 +7,13 @@
 
 use log::{info, warn};
 use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 use crate::draw::{CustomPipe, CustomPipeBuilder, DrawPipe, DrawWindow, ShaderManager};
 use crate::{Error, Options, WindowId};
-use kas::event::UpdateHandle;
+use kas::event::{Shortcuts, UpdateHandle};
+use kas::persist::{State, StateStore};
 use kas_theme::Theme;
 
 #[cfg(feature = "clipboard")]
@@ -29,6 +32,11 @@ pub struct SharedState<C: CustomPipe, T> {
     /// Newly created windows need to know the scale_factor *before* they are
     /// created. This is used to estimate ideal window size.
     pub scale_factor: f64,
+    pub shortcuts: Rc<Shortcuts>,
+    shortcuts_path: Option<PathBuf>,
+    state_store: Option<StateStore>,
+    /// Persistent state of windows, restored on window creation
+    pub state: State,
     window_id: u32,
 }
 
@@ -76,6 +84,9 @@ where
 
         theme.init(&mut draw);
 
+        let shortcuts_path = options.shortcuts;
+        let shortcuts = Rc::new(load_shortcuts(Shortcuts::default(), &shortcuts_path));
+
         Ok(SharedState {
             #[cfg(feature = "clipboard")]
             clipboard,
@@ -86,10 +97,47 @@ where
             theme,
             pending: vec![],
             scale_factor,
+            shortcuts,
+            shortcuts_path,
+            state_store: None,
+            state: State::new(),
             window_id: 0,
         })
     }
 
+    /// Set the application's shortcuts; user shortcuts are applied on top
+    pub fn set_shortcuts(&mut self, shortcuts: Shortcuts) {
+        self.shortcuts = Rc::new(load_shortcuts(shortcuts, &self.shortcuts_path));
+    }
+
+    /// Set the state store, loading state from it
+    pub fn set_state_store(&mut self, store: StateStore) {
+        self.state = match store.load() {
+            Ok(state) => {
+                info!("Loaded state from {}", store.path().display());
+                state
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to load state from {}: {}",
+                    store.path().display(),
+                    e
+                );
+                State::new()
+            }
+        };
+        self.state_store = Some(store);
+    }
+
+    /// Write state to the state store, if any
+    pub fn save_state(&self) {
+        if let Some(store) = self.state_store.as_ref() {
+            if let Err(e) = store.save(&self.state) {
+                warn!("Failed to save state to {}: {}", store.path().display(), e);
+            }
+        }
+    }
+
     pub fn next_window_id(&mut self) -> WindowId {
         self.window_id += 1;
         WindowId::new(NonZeroU32::new(self.window_id).unwrap())
@@ -147,3 +195,13 @@ pub enum PendingAction {
     RedrawAll,
     Update(UpdateHandle, u64),
 }
+
+fn load_shortcuts(mut shortcuts: Shortcuts, path: &Option<PathBuf>) -> Shortcuts {
+    if let Some(path) = path {
+        match shortcuts.load(path) {
+            Ok(()) => info!("Loaded shortcuts from {}", path.display()),
+            Err(e) => warn!("Failed to load shortcuts from {}: {}", path.display(), e),
+        }
+    }
+    shortcuts
+}
@@ -11,10 +11,10 @@ use std::time::Instant;
 use kas::draw::SizeHandle;
 use kas::event::{CursorIcon, ManagerState, UpdateHandle};
 use kas::geom::{Coord, Rect, Size};
-use kas::layout::SolveCache;
+use kas::layout::{LayoutDump, SolveCache};
 use kas::{ThemeAction, ThemeApi, TkAction, WindowId};
 use kas_theme::Theme;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::error::OsError;
 use winit::event::WindowEvent;
 use winit::event_loop::EventLoopWindowTarget;
@@ -37,6 +37,7 @@ pub(crate) struct Window<CW: CustomWindow, TW> {
     swap_chain: wgpu::SwapChain,
     draw: DrawWindow<CW>,
     theme_window: TW,
+    layout_dump: Option<LayoutDump>,
 }
 
 // Public functions, for use by the toolkit
@@ -67,7 +68,17 @@ where
         let ideal = solve_cache.ideal(true).max(Size(1, 1));
         drop(size_handle);
 
-        let mut builder = WindowBuilder::new().with_inner_size(ideal);
+        // Restore saved geometry, if any
+        let state_key = widget.state_key().map(|key| key.to_string());
+        let geometry = state_key
+            .as_ref()
+            .and_then(|key| shared.state.window_geometry(key));
+        let inner_size = match geometry {
+            Some((size, _)) => size,
+            None => ideal,
+        };
+
+        let mut builder = WindowBuilder::new().with_inner_size(inner_size);
         let restrict_dimensions = widget.restrict_dimensions();
         if restrict_dimensions.0 {
             builder = builder.with_min_inner_size(solve_cache.min(true));
@@ -76,6 +87,9 @@ where
             builder = builder.with_max_inner_size(ideal);
         }
         let window = builder.with_title(widget.title()).build(elwt)?;
+        if let Some((_, Some(pos))) = geometry {
+            window.set_outer_position(PhysicalPosition::new(pos.0, pos.1));
+        }
 
         let scale_factor = window.scale_factor();
         shared.scale_factor = scale_factor;
@@ -97,8 +111,15 @@ where
         let swap_chain = shared.device.create_swap_chain(&surface, &sc_desc);
 
         let mut mgr = ManagerState::new(scale_factor);
+        mgr.set_shortcuts(shared.shortcuts.clone());
         let mut tkw = TkWindow::new(shared, &window, &mut theme_window);
         mgr.configure(&mut tkw, &mut *widget);
+        if let Some(key) = state_key {
+            // Restored state is applied by the first layout (below); any
+            // further action (e.g. to re-solve layout) is handled on update.
+            let action = shared.state.restore_widgets(&key, widget.as_widget_mut());
+            mgr.send_action(action);
+        }
 
         let mut r = Window {
             widget,
@@ -111,8 +132,9 @@ where
             swap_chain,
             draw,
             theme_window,
+            layout_dump: None,
         };
-        r.apply_size();
+        r.apply_size(shared);
         Ok(r)
     }
 
@@ -128,10 +150,10 @@ where
         self.mgr.configure(&mut tkw, &mut *self.widget);
 
         self.solve_cache.invalidate_rule_cache();
-        self.apply_size();
+        self.apply_size(shared);
     }
 
-    pub fn theme_resize<C, T>(&mut self, shared: &SharedState<C, T>)
+    pub fn theme_resize<C, T>(&mut self, shared: &mut SharedState<C, T>)
     where
         C: CustomPipe<Window = CW>,
         T: Theme<DrawPipe<C>, Window = TW>,
@@ -142,7 +164,7 @@ where
             .theme
             .update_window(&mut self.theme_window, scale_factor);
         self.solve_cache.invalidate_rule_cache();
-        self.apply_size();
+        self.apply_size(shared);
     }
 
     /// Handle an event
@@ -184,35 +206,42 @@ where
         C: CustomPipe<Window = CW>,
         T: Theme<DrawPipe<C>, Window = TW>,
     {
-        let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
-        let action = self.mgr.update(&mut tkw, &mut *self.widget);
-
-        match action {
-            TkAction::None => (),
-            TkAction::Redraw => self.window.request_redraw(),
-            TkAction::RegionMoved => {
-                self.mgr.region_moved(&mut tkw, &mut *self.widget);
-                self.window.request_redraw();
+        let mut result = TkAction::None;
+        loop {
+            let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+            let action = self.mgr.update(&mut tkw, &mut *self.widget);
+            result += action;
+
+            match action {
+                TkAction::None => (),
+                TkAction::Redraw => self.window.request_redraw(),
+                TkAction::RegionMoved => {
+                    self.mgr.region_moved(&mut tkw, &mut *self.widget);
+                    self.window.request_redraw();
+                }
+                TkAction::Popup => {
+                    let mut size_handle = unsafe { self.theme_window.size_handle() };
+                    self.widget.resize_popups(&mut size_handle);
+                    drop(size_handle);
+
+                    let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+                    self.mgr.region_moved(&mut tkw, &mut *self.widget);
+                    self.window.request_redraw();
+                }
+                TkAction::ResetSize => self.apply_size(shared),
+                TkAction::Resize => {
+                    self.solve_cache.invalidate_rule_cache();
+                    self.apply_size(shared);
+                }
+                TkAction::Reconfigure => self.reconfigure(shared),
+                TkAction::Close | TkAction::CloseAll => (),
             }
-            TkAction::Popup => {
-                let mut size_handle = unsafe { self.theme_window.size_handle() };
-                self.widget.resize_popups(&mut size_handle);
-                drop(size_handle);
 
-                let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
-                self.mgr.region_moved(&mut tkw, &mut *self.widget);
-                self.window.request_redraw();
-            }
-            TkAction::ResetSize => self.apply_size(),
-            TkAction::Resize => {
-                self.solve_cache.invalidate_rule_cache();
-                self.apply_size();
+            // Widgets notified of a resize may require further action
+            if action < TkAction::ResetSize || action > TkAction::Reconfigure {
+                return (result, self.mgr.next_resume());
             }
-            TkAction::Reconfigure => self.reconfigure(shared),
-            TkAction::Close | TkAction::CloseAll => (),
         }
-
-        (action, self.mgr.next_resume())
     }
 
     pub fn handle_closure<C, T>(mut self, shared: &mut SharedState<C, T>) -> TkAction
@@ -225,7 +254,17 @@ where
         self.mgr.with(&mut tkw, |mut mgr| {
             widget.handle_closure(&mut mgr);
         });
-        self.mgr.update(&mut tkw, &mut *self.widget)
+        let action = self.mgr.update(&mut tkw, &mut *self.widget);
+
+        if let Some(key) = self.widget.state_key().map(|key| key.to_string()) {
+            let size = self.window.inner_size().into();
+            let pos = self.window.outer_position().ok().map(|p| Coord(p.x, p.y));
+            shared.state.set_window_geometry(&key, size, pos);
+            shared.state.save_widgets(&key, self.widget.as_widget());
+            shared.save_state();
+        }
+
+        action
     }
 
     pub fn update_timer<C, T>(&mut self, shared: &mut SharedState<C, T>) -> Option<Instant>
@@ -300,7 +339,11 @@ where
     CW: CustomWindow + 'static,
     TW: kas_theme::Window + 'static,
 {
-    fn apply_size(&mut self) {
+    fn apply_size<C, T>(&mut self, shared: &mut SharedState<C, T>)
+    where
+        C: CustomPipe<Window = CW>,
+        T: Theme<DrawPipe<C>, Window = TW>,
+    {
         let size = Size(self.sc_desc.width, self.sc_desc.height);
         let rect = Rect::new(Coord::ZERO, size);
         debug!("Resizing window to rect = {:?}", rect);
@@ -309,6 +352,14 @@ where
         self.solve_cache
             .apply_rect(self.widget.as_widget_mut(), &mut size_handle, rect, true);
         self.widget.resize_popups(&mut size_handle);
+        self.layout_dump = match self.mgr.layout_debug() {
+            true => Some(LayoutDump::new(
+                self.widget.as_widget_mut(),
+                &mut size_handle,
+            )),
+            false => None,
+        };
+        drop(size_handle);
 
         let restrict_dimensions = self.widget.restrict_dimensions();
         if restrict_dimensions.0 {
@@ -320,6 +371,12 @@ where
                 .set_max_inner_size(Some(self.solve_cache.ideal(true)));
         };
 
+        let mut tkw = TkWindow::new(shared, &self.window, &mut self.theme_window);
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.update_resize(widget);
+        });
+
         self.window.request_redraw();
     }
 
@@ -344,7 +401,7 @@ where
 
         // Note that on resize, width adjustments may affect height
         // requirements; we therefore refresh size restrictions.
-        self.apply_size();
+        self.apply_size(shared);
     }
 
     pub(crate) fn do_draw<C, T>(&mut self, shared: &mut SharedState<C, T>)
@@ -364,6 +421,9 @@ where
                 .draw_handle(&mut self.draw, &mut self.theme_window, rect)
         };
         self.widget.draw(&mut draw_handle, &self.mgr, false);
+        if let Some(dump) = self.layout_dump.as_ref() {
+            dump.draw(&mut draw_handle);
+        }
         drop(draw_handle);
 
         let frame = self.swap_chain.get_next_texture().unwrap();
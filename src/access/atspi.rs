@@ -0,0 +1,663 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! AT-SPI publication of accessibility trees
+//!
+//! [`AtspiServer`] serves an [`AccessNode`] tree over D-Bus, implementing the
+//! `org.a11y.atspi.Accessible` interface for each node,
+//! `org.a11y.atspi.Text` for nodes with a value (e.g. entry fields) and
+//! `org.a11y.atspi.Application` for the root.
+//!
+//! Screen readers find applications via the accessibility bus; use
+//! [`AtspiServer::connect`] to connect to this bus and register with the
+//! AT-SPI registry. For testing, [`AtspiServer::session`] serves on the
+//! session bus without registration; the tree may then be inspected with
+//! tools such as `busctl` or `dbus-send`.
+//!
+//! The toolkit should call [`AtspiServer::update`] when the tree changes and
+//! [`AtspiServer::handle_next`] to serve requests. The server is not `Sync`,
+//! thus both must be called from the thread owning it; since `handle_next`
+//! blocks until a message is received, it should only be called when the
+//! [connection](AtspiServer::connection) is readable (the connection
+//! implements `AsRawFd` for use with `poll` or an event loop).
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use zbus::{dbus_interface, Connection, ObjectServer};
+use zvariant::{ObjectPath, OwnedObjectPath};
+use zvariant_derive::Type;
+
+use super::{AccessNode, Role};
+
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+
+/// A reference to an accessible object: bus name and object path
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+struct ObjectRef {
+    name: String,
+    path: OwnedObjectPath,
+}
+
+impl ObjectRef {
+    fn null() -> Self {
+        ObjectRef {
+            name: String::new(),
+            path: object_path(NULL_PATH),
+        }
+    }
+}
+
+fn object_path(path: &str) -> OwnedObjectPath {
+    ObjectPath::try_from(path).unwrap().into()
+}
+
+const ROLE_APPLICATION: (u32, &str) = (75, "application");
+
+/// AT-SPI role (`AtspiRole`) and role name
+fn atspi_role(role: Role) -> (u32, &'static str) {
+    match role {
+        Role::Window => (69, "window"),
+        Role::Dialog => (16, "dialog"),
+        Role::Group => (39, "panel"),
+        Role::Filler => (20, "filler"),
+        Role::Separator => (50, "separator"),
+        Role::Label => (29, "label"),
+        Role::Image => (27, "image"),
+        Role::Button => (43, "push button"),
+        Role::CheckBox => (7, "check box"),
+        Role::RadioButton => (44, "radio button"),
+        Role::ComboBox => (11, "combo box"),
+        Role::Slider => (51, "slider"),
+        Role::ScrollBar => (48, "scroll bar"),
+        Role::ScrollRegion => (49, "scroll pane"),
+        Role::Splitter => (53, "split pane"),
+        Role::DragHandle => (67, "unknown"),
+        Role::TextEntry => (79, "entry"),
+        Role::TextArea => (61, "text"),
+        Role::List => (31, "list"),
+        Role::Table => (55, "table"),
+        Role::Tree => (65, "tree"),
+        Role::TreeItem => (91, "tree item"),
+        Role::MenuBar => (34, "menu bar"),
+        Role::Menu => (33, "menu"),
+        Role::PopupMenu => (41, "popup menu"),
+        Role::MenuItem => (35, "menu item"),
+        Role::CheckMenuItem => (8, "check menu item"),
+        Role::Unknown => (67, "unknown"),
+    }
+}
+
+// AtspiStateType values
+const STATE_CHECKED: u32 = 4;
+const STATE_EDITABLE: u32 = 7;
+const STATE_ENABLED: u32 = 8;
+const STATE_FOCUSABLE: u32 = 11;
+const STATE_FOCUSED: u32 = 12;
+const STATE_MULTI_LINE: u32 = 17;
+const STATE_SENSITIVE: u32 = 24;
+const STATE_SHOWING: u32 = 25;
+const STATE_SINGLE_LINE: u32 = 26;
+const STATE_VISIBLE: u32 = 30;
+const STATE_CHECKABLE: u32 = 41;
+
+/// A flattened node
+struct Node {
+    path: String,
+    parent: Option<usize>,
+    index_in_parent: i32,
+    children: Vec<usize>,
+    role: (u32, &'static str),
+    name: String,
+    value: Option<String>,
+    states: [u32; 2],
+}
+
+struct Tree {
+    bus_name: String,
+    parent: ObjectRef,
+    app_name: String,
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn object_ref(&self, index: usize) -> ObjectRef {
+        match self.nodes.get(index) {
+            Some(node) => ObjectRef {
+                name: self.bus_name.clone(),
+                path: object_path(&node.path),
+            },
+            None => ObjectRef::null(),
+        }
+    }
+
+    fn set_windows(&mut self, windows: &[AccessNode]) {
+        self.nodes.clear();
+        self.nodes.push(Node {
+            path: ROOT_PATH.to_string(),
+            parent: None,
+            index_in_parent: -1,
+            children: vec![],
+            role: ROLE_APPLICATION,
+            name: self.app_name.clone(),
+            value: None,
+            states: [0; 2],
+        });
+        for (i, window) in windows.iter().enumerate() {
+            let index = self.nodes.len();
+            self.nodes[0].children.push(index);
+            self.flatten(window, i, 0, i as i32);
+        }
+    }
+
+    fn flatten(&mut self, node: &AccessNode, window: usize, parent: usize, index_in_parent: i32) {
+        let index = self.nodes.len();
+        let path = format!(
+            "/org/a11y/atspi/accessible/w{}/{:016x}",
+            window,
+            u64::from(node.stable_id)
+        );
+
+        let mut states = [0u32; 2];
+        let mut set = |state: u32| states[(state / 32) as usize] |= 1 << (state % 32);
+        set(STATE_SHOWING);
+        set(STATE_VISIBLE);
+        if !node.state.disabled {
+            set(STATE_ENABLED);
+            set(STATE_SENSITIVE);
+        }
+        if node.state.focusable {
+            set(STATE_FOCUSABLE);
+        }
+        if node.state.focused {
+            set(STATE_FOCUSED);
+        }
+        if let Some(checked) = node.state.checked {
+            set(STATE_CHECKABLE);
+            if checked {
+                set(STATE_CHECKED);
+            }
+        }
+        if node.state.editable {
+            set(STATE_EDITABLE);
+        }
+        match node.role {
+            Role::TextEntry => set(STATE_SINGLE_LINE),
+            Role::TextArea => set(STATE_MULTI_LINE),
+            _ => (),
+        }
+
+        self.nodes.push(Node {
+            path,
+            parent: Some(parent),
+            index_in_parent,
+            children: vec![],
+            role: atspi_role(node.role),
+            name: node.name.clone().unwrap_or_default(),
+            value: node.value.clone(),
+            states,
+        });
+
+        for (i, child) in node.children.iter().enumerate() {
+            let child_index = self.nodes.len();
+            self.nodes[index].children.push(child_index);
+            self.flatten(child, window, index, i as i32);
+        }
+    }
+}
+
+/// Implementation of `org.a11y.atspi.Accessible` for one node
+struct Accessible {
+    tree: Arc<RwLock<Tree>>,
+    index: usize,
+}
+
+impl Accessible {
+    fn with_node<T: Default, F: FnOnce(&Tree, &Node) -> T>(&self, f: F) -> T {
+        let tree = self.tree.read().unwrap();
+        match tree.nodes.get(self.index) {
+            Some(node) => f(&tree, node),
+            None => T::default(),
+        }
+    }
+}
+
+#[dbus_interface(name = "org.a11y.atspi.Accessible")]
+impl Accessible {
+    fn get_child_at_index(&self, index: i32) -> ObjectRef {
+        let tree = self.tree.read().unwrap();
+        let child = tree
+            .nodes
+            .get(self.index)
+            .and_then(|node| node.children.get(index as usize).cloned());
+        tree.object_ref(child.unwrap_or(usize::MAX))
+    }
+
+    fn get_children(&self) -> Vec<ObjectRef> {
+        self.with_node(|tree, node| node.children.iter().map(|i| tree.object_ref(*i)).collect())
+    }
+
+    fn get_index_in_parent(&self) -> i32 {
+        self.with_node(|_, node| node.index_in_parent)
+    }
+
+    fn get_relation_set(&self) -> Vec<(u32, Vec<ObjectRef>)> {
+        vec![]
+    }
+
+    fn get_role(&self) -> u32 {
+        self.with_node(|_, node| node.role.0)
+    }
+
+    fn get_role_name(&self) -> String {
+        self.with_node(|_, node| node.role.1.to_string())
+    }
+
+    fn get_localized_role_name(&self) -> String {
+        self.get_role_name()
+    }
+
+    fn get_state(&self) -> Vec<u32> {
+        self.with_node(|_, node| node.states.to_vec())
+    }
+
+    fn get_attributes(&self) -> HashMap<String, String> {
+        let mut attrs = HashMap::new();
+        attrs.insert("toolkit".to_string(), "KAS".to_string());
+        attrs
+    }
+
+    fn get_application(&self) -> ObjectRef {
+        self.tree.read().unwrap().object_ref(0)
+    }
+
+    fn get_interfaces(&self) -> Vec<String> {
+        let mut ifaces = vec!["org.a11y.atspi.Accessible".to_string()];
+        if self.index == 0 {
+            ifaces.push("org.a11y.atspi.Application".to_string());
+        }
+        if self.with_node(|_, node| node.value.is_some()) {
+            ifaces.push("org.a11y.atspi.Text".to_string());
+        }
+        ifaces
+    }
+
+    #[dbus_interface(property)]
+    fn name(&self) -> String {
+        self.with_node(|_, node| node.name.clone())
+    }
+
+    #[dbus_interface(property)]
+    fn description(&self) -> String {
+        String::new()
+    }
+
+    #[dbus_interface(property)]
+    fn parent(&self) -> (String, OwnedObjectPath) {
+        let tree = self.tree.read().unwrap();
+        let parent = match tree.nodes.get(self.index).and_then(|node| node.parent) {
+            Some(parent) => tree.object_ref(parent),
+            None => tree.parent.clone(),
+        };
+        (parent.name, parent.path)
+    }
+
+    #[dbus_interface(property)]
+    fn child_count(&self) -> i32 {
+        self.with_node(|_, node| node.children.len() as i32)
+    }
+
+    #[dbus_interface(property)]
+    fn locale(&self) -> String {
+        String::new()
+    }
+}
+
+/// Implementation of `org.a11y.atspi.Text` for a node with a value
+///
+/// Offsets are in characters, as in AT-SPI.
+struct Text {
+    tree: Arc<RwLock<Tree>>,
+    index: usize,
+}
+
+impl Text {
+    fn with_value<T: Default, F: FnOnce(&str) -> T>(&self, f: F) -> T {
+        let tree = self.tree.read().unwrap();
+        match tree
+            .nodes
+            .get(self.index)
+            .and_then(|node| node.value.as_ref())
+        {
+            Some(value) => f(value),
+            None => T::default(),
+        }
+    }
+}
+
+#[dbus_interface(name = "org.a11y.atspi.Text")]
+impl Text {
+    // end_offset is -1 for the end of the text
+    fn get_text(&self, start_offset: i32, end_offset: i32) -> String {
+        self.with_value(|value| {
+            let start = start_offset.max(0) as usize;
+            let chars = value.chars().skip(start);
+            match end_offset {
+                end if end < 0 => chars.collect(),
+                end => chars.take((end as usize).saturating_sub(start)).collect(),
+            }
+        })
+    }
+
+    fn get_character_at_offset(&self, offset: i32) -> i32 {
+        self.with_value(|value| match offset {
+            offset if offset < 0 => 0,
+            offset => value
+                .chars()
+                .nth(offset as usize)
+                .map(|c| c as i32)
+                .unwrap_or(0),
+        })
+    }
+
+    #[dbus_interface(property)]
+    fn character_count(&self) -> i32 {
+        self.with_value(|value| value.chars().count() as i32)
+    }
+}
+
+/// Implementation of `org.a11y.atspi.Application` for the root node
+struct Application {
+    id: i32,
+}
+
+#[dbus_interface(name = "org.a11y.atspi.Application")]
+impl Application {
+    #[dbus_interface(property)]
+    fn toolkit_name(&self) -> String {
+        "KAS".to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn atspi_version(&self) -> String {
+        "2.1".to_string()
+    }
+
+    #[dbus_interface(property)]
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    #[dbus_interface(property)]
+    fn set_id(&mut self, id: i32) {
+        self.id = id;
+    }
+
+    fn get_locale(&self, _lctype: u32) -> String {
+        String::new()
+    }
+}
+
+/// Serves an accessibility tree over D-Bus
+pub struct AtspiServer {
+    connection: Connection,
+    server: ObjectServer,
+    tree: Arc<RwLock<Tree>>,
+    // Served paths, and whether the Text interface is served
+    paths: Vec<(String, bool)>,
+}
+
+impl AtspiServer {
+    /// Connect to the accessibility bus and register with the AT-SPI registry
+    ///
+    /// The accessibility bus address is found via the session bus.
+    pub fn connect(app_name: &str) -> zbus::Result<Self> {
+        let session = Connection::new_session()?;
+        let reply = session.call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.a11y.Bus"),
+            "GetAddress",
+            &(),
+        )?;
+        let address: String = reply.body()?;
+        let connection = Connection::new_for_address(&address, true)?;
+
+        let mut server = Self::new(connection, app_name)?;
+        let root = server.tree.read().unwrap().object_ref(0);
+        let reply = server.connection.call_method(
+            Some("org.a11y.atspi.Registry"),
+            ROOT_PATH,
+            Some("org.a11y.atspi.Socket"),
+            "Embed",
+            &root,
+        )?;
+        server.tree.write().unwrap().parent = reply.body()?;
+        Ok(server)
+    }
+
+    /// Serve on the session bus without registration
+    ///
+    /// This is intended for testing.
+    pub fn session(app_name: &str) -> zbus::Result<Self> {
+        Self::new(Connection::new_session()?, app_name)
+    }
+
+    /// Serve over an existing connection
+    ///
+    /// An empty tree is served until [`AtspiServer::update`] is called.
+    pub fn new(connection: Connection, app_name: &str) -> zbus::Result<Self> {
+        let bus_name = connection.unique_name().unwrap_or_default().to_string();
+        let tree = Arc::new(RwLock::new(Tree {
+            bus_name,
+            parent: ObjectRef::null(),
+            app_name: app_name.to_string(),
+            nodes: vec![],
+        }));
+        tree.write().unwrap().set_windows(&[]);
+        let mut server = ObjectServer::new(&connection);
+        let root = object_path(ROOT_PATH);
+        server.at(
+            &root,
+            Accessible {
+                tree: tree.clone(),
+                index: 0,
+            },
+        )?;
+        server.at(&root, Application { id: 0 })?;
+        Ok(AtspiServer {
+            connection,
+            server,
+            tree,
+            paths: vec![],
+        })
+    }
+
+    /// The unique bus name of the connection
+    pub fn bus_name(&self) -> String {
+        self.tree.read().unwrap().bus_name.clone()
+    }
+
+    /// Access the D-Bus connection
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Replace the served tree
+    ///
+    /// Each window is represented by an [`AccessNode`] tree; these are served
+    /// as children of the application's root node. Object paths are derived
+    /// from the window index and [`crate::StableId`].
+    pub fn update(&mut self, windows: &[AccessNode]) -> zbus::Result<()> {
+        for (path, text) in self.paths.drain(..) {
+            let path = object_path(&path);
+            self.server.remove::<Accessible, _>(&path)?;
+            if text {
+                self.server.remove::<Text, _>(&path)?;
+            }
+        }
+
+        let mut tree = self.tree.write().unwrap();
+        tree.set_windows(windows);
+        for (index, node) in tree.nodes.iter().enumerate().skip(1) {
+            let path = object_path(&node.path);
+            let tree = self.tree.clone();
+            self.server.at(&path, Accessible { tree, index })?;
+            let text = node.value.is_some();
+            if text {
+                let tree = self.tree.clone();
+                self.server.at(&path, Text { tree, index })?;
+            }
+            self.paths.push((node.path.clone(), text));
+        }
+        Ok(())
+    }
+
+    /// Wait for and handle the next D-Bus message
+    ///
+    /// This blocks until a message is received.
+    pub fn handle_next(&mut self) -> zbus::Result<()> {
+        self.server.try_handle_next()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::access::AccessState;
+    use crate::geom::Rect;
+    use crate::{StableId, WidgetId};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use zvariant::Value;
+
+    fn node(role: Role, index: usize, children: Vec<AccessNode>) -> AccessNode {
+        AccessNode {
+            role,
+            name: None,
+            value: None,
+            state: AccessState::default(),
+            id: WidgetId::FIRST,
+            stable_id: StableId::ROOT.child(index),
+            rect: Rect::default(),
+            children,
+        }
+    }
+
+    fn call<B>(
+        client: &Connection,
+        bus: &str,
+        path: &str,
+        iface: &str,
+        method: &str,
+        body: &B,
+    ) -> zbus::Message
+    where
+        B: Serialize + zvariant::Type,
+    {
+        client
+            .call_method(Some(bus), path, Some(iface), method, body)
+            .unwrap()
+    }
+
+    // Requires a session bus; skipped if DBUS_SESSION_BUS_ADDRESS is not set
+    #[test]
+    fn session_bus() {
+        if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+            eprintln!("atspi::test::session_bus: no session bus; skipping");
+            return;
+        }
+
+        let mut label = node(Role::Label, 1, vec![]);
+        label.name = Some("Name:".to_string());
+        let mut entry = node(Role::TextEntry, 2, vec![]);
+        entry.value = Some("Ferris 🦀".to_string());
+        let mut area = node(Role::TextArea, 3, vec![]);
+        area.value = Some("a\nb".to_string());
+        let mut window = node(Role::Window, 0, vec![label, entry, area]);
+        window.name = Some("Test".to_string());
+
+        let mut server = AtspiServer::session("kas-test").unwrap();
+        server.update(&[window]).unwrap();
+        let bus = server.bus_name();
+
+        // The server is not Send, thus serves from this thread
+        let done = Arc::new(AtomicBool::new(false));
+        let client = {
+            let done = done.clone();
+            std::thread::spawn(move || {
+                let client = Connection::new_session().unwrap();
+                let accessible = "org.a11y.atspi.Accessible";
+                let msg = call(&client, &bus, ROOT_PATH, accessible, "GetChildren", &());
+                let windows: Vec<ObjectRef> = msg.body().unwrap();
+                assert_eq!(windows.len(), 1);
+                assert_eq!(windows[0].name, bus);
+                let window = windows[0].path.as_str().to_string();
+
+                let msg = call(&client, &bus, &window, accessible, "GetRoleName", &());
+                assert_eq!(msg.body::<String>().unwrap(), "window");
+                let msg = call(&client, &bus, &window, accessible, "GetChildren", &());
+                let children: Vec<ObjectRef> = msg.body().unwrap();
+                assert_eq!(children.len(), 3);
+                let label = children[0].path.as_str().to_string();
+                let entry = children[1].path.as_str().to_string();
+                let area = children[2].path.as_str().to_string();
+
+                let props = "org.freedesktop.DBus.Properties";
+                let msg = call(&client, &bus, &label, props, "Get", &(accessible, "Name"));
+                match msg.body::<Value>().unwrap() {
+                    Value::Str(name) => assert_eq!(name.as_str(), "Name:"),
+                    value => panic!("unexpected value: {:?}", value),
+                }
+
+                // The entry's value is served via the Text interface
+                let msg = call(&client, &bus, &entry, accessible, "GetInterfaces", &());
+                let ifaces: Vec<String> = msg.body().unwrap();
+                assert!(ifaces.iter().any(|iface| iface == "org.a11y.atspi.Text"));
+                let msg = call(&client, &bus, &label, accessible, "GetInterfaces", &());
+                let ifaces: Vec<String> = msg.body().unwrap();
+                assert!(!ifaces.iter().any(|iface| iface == "org.a11y.atspi.Text"));
+                let text = "org.a11y.atspi.Text";
+                let msg = call(&client, &bus, &entry, text, "GetText", &(0i32, -1i32));
+                assert_eq!(msg.body::<String>().unwrap(), "Ferris 🦀");
+                let msg = call(&client, &bus, &entry, text, "GetText", &(2i32, 4i32));
+                assert_eq!(msg.body::<String>().unwrap(), "rr");
+                let msg = call(&client, &bus, &entry, text, "GetCharacterAtOffset", &7i32);
+                assert_eq!(msg.body::<i32>().unwrap(), '🦀' as i32);
+
+                // Entries are single-line; text areas are multi-line
+                let has_state = |path: &str, state: u32| {
+                    let msg = call(&client, &bus, path, accessible, "GetState", &());
+                    let states: Vec<u32> = msg.body().unwrap();
+                    states[(state / 32) as usize] & (1 << (state % 32)) != 0
+                };
+                assert!(has_state(&entry, STATE_SINGLE_LINE));
+                assert!(!has_state(&entry, STATE_MULTI_LINE));
+                assert!(has_state(&area, STATE_MULTI_LINE));
+                assert!(!has_state(&area, STATE_SINGLE_LINE));
+                let msg = call(&client, &bus, &area, accessible, "GetRoleName", &());
+                assert_eq!(msg.body::<String>().unwrap(), "text");
+
+                // Wake the server to observe done
+                done.store(true, Ordering::SeqCst);
+                let peer = "org.freedesktop.DBus.Peer";
+                call(&client, &bus, ROOT_PATH, peer, "Ping", &());
+            })
+        };
+
+        while !done.load(Ordering::SeqCst) {
+            server.handle_next().unwrap();
+        }
+        client.join().unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Accessibility
+//!
+//! Each widget reports a [`Role`] via [`WidgetConfig::access_role`]. An
+//! [`AccessNode`] tree may be constructed from a window's widget tree, naming
+//! widgets via [`WidgetConfig::as_clone_text`] and reporting state from
+//! [`WidgetConfig::as_has_bool`], the disabled state and navigation focus.
+//! Pop-ups are included only while open.
+//!
+//! With the `atspi` feature, the [`atspi`] module supports publishing this
+//! tree over the AT-SPI D-Bus protocol (used by screen readers on Linux).
+
+#[cfg(feature = "atspi")]
+pub mod atspi;
+
+use std::fmt;
+
+use crate::event::ManagerState;
+use crate::geom::Rect;
+use crate::{StableId, WidgetConfig, WidgetId};
+
+/// The role of a widget
+///
+/// This is a toolkit-neutral classification of widgets, as reported to
+/// accessibility tools.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// A top-level window
+    Window,
+    /// A dialog window
+    Dialog,
+    /// A container without special semantics (e.g. a row or frame)
+    Group,
+    /// Blank space
+    Filler,
+    /// A visual separator
+    Separator,
+    /// Static text
+    Label,
+    /// An image
+    Image,
+    /// A push button
+    Button,
+    /// A check box
+    CheckBox,
+    /// A radio button
+    RadioButton,
+    /// A combo box (drop-down selection)
+    ComboBox,
+    /// A slider
+    Slider,
+    /// A scroll bar
+    ScrollBar,
+    /// A scrollable region
+    ScrollRegion,
+    /// A region divided by draggable handles
+    Splitter,
+    /// A draggable handle
+    DragHandle,
+    /// A single-line text entry field
+    TextEntry,
+    /// A multi-line text entry field
+    TextArea,
+    /// A list of items (e.g. a list view)
+    List,
+    /// A table of items, with column headers
+    Table,
+    /// A hierarchical tree of items
+    Tree,
+    /// An item of a tree (including its expander)
+    TreeItem,
+    /// A menu bar
+    MenuBar,
+    /// A menu (which opens a pop-up on activation)
+    Menu,
+    /// A pop-up list of menu items
+    PopupMenu,
+    /// A menu item
+    MenuItem,
+    /// A menu item with a check box
+    CheckMenuItem,
+    /// Role is not known
+    Unknown,
+}
+
+impl Role {
+    /// Whether nodes with this role expose children
+    ///
+    /// Controls such as buttons are presented as a single node, even when
+    /// composed of multiple widgets (e.g. a check box and a label).
+    pub fn has_children(self) -> bool {
+        match self {
+            Role::Window
+            | Role::Dialog
+            | Role::Group
+            | Role::ScrollRegion
+            | Role::Splitter
+            | Role::List
+            | Role::Table
+            | Role::Tree
+            | Role::MenuBar
+            | Role::Menu
+            | Role::PopupMenu
+            | Role::Unknown => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the widget's text is its content (a value) rather than a name
+    pub fn text_is_value(self) -> bool {
+        match self {
+            Role::TextEntry | Role::TextArea => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::Group
+    }
+}
+
+/// Accessibility state of a widget
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccessState {
+    /// Disabled, directly or by inheritance
+    pub disabled: bool,
+    /// Supports keyboard navigation (see [`WidgetConfig::key_nav`])
+    pub focusable: bool,
+    /// Has navigation or character focus
+    pub focused: bool,
+    /// Check state, for widgets supporting [`crate::class::HasBool`]
+    pub checked: Option<bool>,
+    /// Text may be edited
+    pub editable: bool,
+}
+
+/// A node of an accessibility tree
+///
+/// Constructed via [`AccessNode::new`] from a widget (usually a window) and
+/// the window's event manager state. The tree is a snapshot; it must be
+/// reconstructed to observe changes.
+///
+/// `Display` formats the tree as indented text, for example:
+/// ```none
+/// Window "Hello"
+///   Group
+///     Label "Hello, world!"
+///     Button "OK" [focusable]
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    /// Role
+    pub role: Role,
+    /// Accessible name (e.g. a button's label)
+    pub name: Option<String>,
+    /// Value (e.g. an entry field's text)
+    pub value: Option<String>,
+    /// State
+    pub state: AccessState,
+    /// Widget identifier (valid until reconfigure)
+    pub id: WidgetId,
+    /// Stable widget identifier
+    pub stable_id: StableId,
+    /// Widget rect, in window coordinates
+    pub rect: Rect,
+    /// Child nodes
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode {
+    /// Construct a tree from `widget` and its descendants
+    pub fn new(widget: &dyn WidgetConfig, mgr: &ManagerState) -> Self {
+        Self::new_inherit(widget, mgr, false)
+    }
+
+    fn new_inherit(widget: &dyn WidgetConfig, mgr: &ManagerState, disabled: bool) -> Self {
+        let role = widget.access_role();
+        let disabled = disabled || widget.is_disabled();
+        let id = widget.id();
+
+        let text = if let Some(w) = widget.as_clone_text() {
+            Some(w.clone_string())
+        } else {
+            widget.as_has_string().map(|w| w.get_string())
+        };
+        let (name, value) = match role.text_is_value() {
+            false => (text, None),
+            true => (None, text),
+        };
+
+        let state = AccessState {
+            disabled,
+            focusable: widget.key_nav(),
+            focused: mgr.nav_focus(id) || mgr.char_focus(id),
+            checked: widget.as_has_bool().map(|w| w.get_bool()),
+            editable: role.text_is_value() && !disabled,
+        };
+
+        let mut children = vec![];
+        if role.has_children() {
+            for i in 0..widget.len() {
+                if let Some(child) = widget.get(i) {
+                    // Closed pop-ups (e.g. menus) are not shown
+                    if child.access_role() == Role::PopupMenu && !mgr.is_popup_open(child.id()) {
+                        continue;
+                    }
+                    children.push(Self::new_inherit(child, mgr, disabled));
+                }
+            }
+        }
+
+        AccessNode {
+            role,
+            name,
+            value,
+            state,
+            id,
+            stable_id: widget.stable_id(),
+            rect: widget.rect(),
+            children,
+        }
+    }
+
+    /// Find the node with navigation or character focus
+    pub fn focused(&self) -> Option<&AccessNode> {
+        if self.state.focused {
+            return Some(self);
+        }
+        self.children.iter().filter_map(|c| c.focused()).next()
+    }
+
+    /// Iterate over all nodes, in pre-order
+    pub fn walk<F: FnMut(&AccessNode)>(&self, mut f: F) {
+        self.walk_dyn(&mut f)
+    }
+
+    fn walk_dyn(&self, f: &mut dyn FnMut(&AccessNode)) {
+        f(self);
+        for child in &self.children {
+            child.walk_dyn(f);
+        }
+    }
+
+    fn fmt_indent(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:1$}{2:?}", "", 2 * depth, self.role)?;
+        if let Some(ref name) = self.name {
+            write!(f, " {:?}", name)?;
+        }
+        if let Some(ref value) = self.value {
+            write!(f, " value={:?}", value)?;
+        }
+
+        let mut flags = vec![];
+        if self.state.disabled {
+            flags.push("disabled");
+        }
+        if self.state.focusable {
+            flags.push("focusable");
+        }
+        if self.state.focused {
+            flags.push("focused");
+        }
+        match self.state.checked {
+            Some(true) => flags.push("checked"),
+            Some(false) => flags.push("unchecked"),
+            None => (),
+        }
+        if self.state.editable {
+            flags.push("editable");
+        }
+        if !flags.is_empty() {
+            write!(f, " [{}]", flags.join(", "))?;
+        }
+        writeln!(f)?;
+
+        for child in &self.children {
+            child.fmt_indent(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AccessNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indent(f, 0)
+    }
+}
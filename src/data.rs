@@ -5,8 +5,10 @@
 
 //! Data types
 
+use std::collections::hash_map::DefaultHasher;
 use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroU32;
 use std::u32;
 
@@ -23,7 +25,8 @@ use crate::geom::{Rect, Size};
 ///
 /// Identifiers are assigned when configured and when re-configured
 /// (via [`kas::TkAction::Reconfigure`]). Since user-code is not notified of a
-/// re-configure, user-code should not store a `WidgetId`.
+/// re-configure, user-code should not store a `WidgetId`; use [`StableId`]
+/// instead.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct WidgetId(NonZeroU32);
 
@@ -34,6 +37,10 @@ impl WidgetId {
     pub(crate) fn next(self) -> Self {
         WidgetId(NonZeroU32::new(self.0.get() + 1).unwrap())
     }
+
+    pub(crate) fn offset(self, n: u32) -> Self {
+        WidgetId(NonZeroU32::new(self.0.get().checked_add(n).unwrap()).unwrap())
+    }
 }
 
 impl TryFrom<u32> for WidgetId {
@@ -81,12 +88,73 @@ impl fmt::Display for WidgetId {
     }
 }
 
+/// Stable widget identifier
+///
+/// Unlike [`WidgetId`], which enumerates widgets and is reassigned on every
+/// re-configure, a `StableId` is derived from the widget's *path*: the sequence
+/// of child indices from the window's root widget. A widget with a key (see
+/// [`CoreData::key`]) contributes its key to the path in place of its index;
+/// thus keyed widgets keep the same identifier when siblings are inserted or
+/// removed.
+///
+/// Identifiers are assigned during configure and are available via
+/// [`kas::WidgetCore::stable_id`]. Since they are stable, they may be stored
+/// by user code across re-configures.
+///
+/// Internally this is a hash of the path; collisions are possible (though
+/// unlikely) and identifiers should not be persisted across program versions.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct StableId(u64);
+
+impl StableId {
+    /// The identifier of a window's root widget
+    pub const ROOT: StableId = StableId(0);
+
+    /// Derive the identifier of the child with the given `index`
+    pub fn child(self, index: usize) -> Self {
+        self.derive(0, index as u64)
+    }
+
+    /// Derive the identifier of a child with the given `key`
+    pub fn keyed(self, key: u64) -> Self {
+        self.derive(1, key)
+    }
+
+    fn derive(self, kind: u8, value: u64) -> Self {
+        let mut hasher = DefaultHasher::new();
+        (self.0, kind, value).hash(&mut hasher);
+        StableId(hasher.finish())
+    }
+}
+
+impl From<StableId> for u64 {
+    #[inline]
+    fn from(id: StableId) -> u64 {
+        id.0
+    }
+}
+
+impl fmt::Display for StableId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "@{:016x}", self.0)
+    }
+}
+
 #[test]
 fn size_of_option_widget_id() {
     use std::mem::size_of;
     assert_eq!(size_of::<WidgetId>(), size_of::<Option<WidgetId>>());
 }
 
+#[test]
+fn stable_id_derivation() {
+    let a = StableId::ROOT.child(1);
+    assert_eq!(a, StableId::ROOT.child(1));
+    assert_ne!(a, StableId::ROOT.child(2));
+    assert_ne!(a, StableId::ROOT.keyed(1));
+    assert_ne!(a.child(0), StableId::ROOT.child(0).child(1));
+}
+
 /// Common widget data
 ///
 /// All widgets should embed a `#[widget_core] core: CoreData` field.
@@ -95,6 +163,11 @@ pub struct CoreData {
     pub rect: Rect,
     pub id: WidgetId,
     pub disabled: bool,
+    /// Optional user-supplied key; see [`StableId`]
+    ///
+    /// Keys need only be unique among siblings.
+    pub key: Option<u64>,
+    pub stable_id: StableId,
 }
 
 /// Partial alignment information provided by the parent
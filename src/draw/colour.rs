@@ -20,10 +20,20 @@ impl Colour {
         Colour { r, g, b, a: 1.0 }
     }
 
+    /// Constructor with alpha (opacity)
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Colour { r, g, b, a }
+    }
+
     /// Construct from grey-scale
     pub const fn grey(s: f32) -> Self {
         Colour::new(s, s, s)
     }
+
+    /// Return this colour with alpha (opacity) `a`
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Colour { a, ..self }
+    }
 }
 
 impl From<Colour> for [f32; 4] {
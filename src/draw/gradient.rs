@@ -0,0 +1,201 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Gradient fills
+
+use super::Colour;
+use crate::geom::Vec2;
+
+/// Geometry of a [`Gradient`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientShape {
+    /// Colour varies along the line from `start` (offset 0) to `end`
+    /// (offset 1) and is constant perpendicular to this line
+    Linear { start: Vec2, end: Vec2 },
+    /// Colour varies with distance from `centre`, reaching offset 1 at
+    /// `radius` (which may be different on each axis)
+    Radial { centre: Vec2, radius: Vec2 },
+}
+
+/// A colour gradient
+///
+/// A gradient maps each point to an offset (see [`GradientShape`]), then to a
+/// colour by linear interpolation between colour stops. Offsets outside the
+/// range of the stops take the colour of the first or last stop.
+///
+/// Colours are interpolated in linear RGB space, including alpha.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    shape: GradientShape,
+    stops: Vec<(f32, Colour)>,
+}
+
+impl Gradient {
+    /// Construct a linear gradient from colour `from` at `start` to colour
+    /// `to` at `end`
+    pub fn linear(start: Vec2, end: Vec2, from: Colour, to: Colour) -> Self {
+        Gradient {
+            shape: GradientShape::Linear { start, end },
+            stops: vec![(0.0, from), (1.0, to)],
+        }
+    }
+
+    /// Construct a circular gradient from colour `inner` at `centre` to
+    /// colour `outer` at distance `radius`
+    pub fn radial(centre: Vec2, radius: f32, inner: Colour, outer: Colour) -> Self {
+        Gradient::elliptical(centre, Vec2::splat(radius), inner, outer)
+    }
+
+    /// Construct an elliptical gradient from colour `inner` at `centre` to
+    /// colour `outer` on the axis-aligned ellipse with the given `radii`
+    pub fn elliptical(centre: Vec2, radii: Vec2, inner: Colour, outer: Colour) -> Self {
+        Gradient {
+            shape: GradientShape::Radial {
+                centre,
+                radius: radii,
+            },
+            stops: vec![(0.0, inner), (1.0, outer)],
+        }
+    }
+
+    /// Add a colour stop at `offset` (chain style)
+    ///
+    /// Stops are kept sorted by offset; a stop added at the same offset as an
+    /// existing stop is placed after it, giving a hard transition.
+    pub fn with_stop(mut self, offset: f32, col: Colour) -> Self {
+        let i = self.stops.iter().take_while(|s| s.0 <= offset).count();
+        self.stops.insert(i, (offset, col));
+        self
+    }
+
+    /// Scale the opacity of all stops (chain style)
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        for stop in &mut self.stops {
+            stop.1.a *= opacity;
+        }
+        self
+    }
+
+    /// Move the gradient by `offset`
+    ///
+    /// This may be used to adjust for scroll offsets.
+    pub fn translate(&mut self, offset: Vec2) {
+        match &mut self.shape {
+            GradientShape::Linear { start, end } => {
+                *start = *start + offset;
+                *end = *end + offset;
+            }
+            GradientShape::Radial { centre, .. } => *centre = *centre + offset,
+        }
+    }
+
+    /// Gradient geometry
+    #[inline]
+    pub fn shape(&self) -> GradientShape {
+        self.shape
+    }
+
+    /// Colour stops, sorted by offset
+    #[inline]
+    pub fn stops(&self) -> &[(f32, Colour)] {
+        &self.stops
+    }
+
+    /// True if all stops are opaque
+    pub fn is_opaque(&self) -> bool {
+        self.stops.iter().all(|s| s.1.a >= 1.0)
+    }
+
+    /// Get the offset of point `p`
+    ///
+    /// This is not clamped to the range of the stops.
+    pub fn offset(&self, p: Vec2) -> f32 {
+        match self.shape {
+            GradientShape::Linear { start, end } => {
+                let d = end - start;
+                let len2 = d.sum_square();
+                if len2 > 0.0 {
+                    let v = p - start;
+                    (v.0 * d.0 + v.1 * d.1) / len2
+                } else {
+                    0.0
+                }
+            }
+            GradientShape::Radial { centre, radius } => {
+                if radius.0 > 0.0 && radius.1 > 0.0 {
+                    ((p - centre) / radius).sum_square().sqrt()
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Get the colour at offset `t`
+    pub fn colour_at_offset(&self, t: f32) -> Colour {
+        let stops = &self.stops;
+        let i = stops.iter().take_while(|s| s.0 <= t).count();
+        if i == 0 {
+            return stops.first().map(|s| s.1).unwrap_or_default();
+        } else if i == stops.len() {
+            return stops[i - 1].1;
+        }
+        let ((t0, c0), (t1, c1)) = (stops[i - 1], stops[i]);
+        let f = (t - t0) / (t1 - t0);
+        let mix = |a: f32, b: f32| a + (b - a) * f;
+        Colour {
+            r: mix(c0.r, c1.r),
+            g: mix(c0.g, c1.g),
+            b: mix(c0.b, c1.b),
+            a: mix(c0.a, c1.a),
+        }
+    }
+
+    /// Get the colour at point `p`
+    #[inline]
+    pub fn colour_at(&self, p: Vec2) -> Colour {
+        self.colour_at_offset(self.offset(p))
+    }
+
+    /// Sample colours at `n ≥ 2` evenly spaced offsets over `0 ≤ t ≤ 1`
+    ///
+    /// This may be used to construct a lookup texture. Since offsets beyond
+    /// the range `[0, 1]` are clamped by such a texture, stops outside this
+    /// range are only represented via interpolation.
+    pub fn ramp(&self, n: usize) -> Vec<Colour> {
+        let scale = 1.0 / (n.max(2) - 1) as f32;
+        (0..n)
+            .map(|i| self.colour_at_offset(i as f32 * scale))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gradient() {
+        let (black, white) = (Colour::grey(0.0), Colour::grey(1.0));
+        let g = Gradient::linear(Vec2(10.0, 0.0), Vec2(20.0, 0.0), black, white);
+        assert_eq!(g.offset(Vec2(15.0, 7.0)), 0.5);
+        assert_eq!(g.colour_at(Vec2(0.0, 0.0)), black);
+        assert_eq!(g.colour_at(Vec2(15.0, 0.0)), Colour::grey(0.5));
+        assert_eq!(g.colour_at(Vec2(30.0, 0.0)), white);
+
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let g = g.with_stop(0.5, red).with_stop(0.5, black);
+        assert_eq!(g.colour_at_offset(0.25), Colour::new(0.5, 0.0, 0.0));
+        assert_eq!(g.colour_at_offset(0.5), black);
+        assert_eq!(g.colour_at_offset(0.75), Colour::grey(0.5));
+
+        let g = Gradient::radial(Vec2(0.0, 0.0), 10.0, white, black).with_opacity(0.5);
+        assert_eq!(g.offset(Vec2(6.0, 8.0)), 1.0);
+        assert_eq!(g.colour_at(Vec2(0.0, 5.0)).a, 0.5);
+        assert!(!g.is_opaque());
+        let ramp = g.ramp(3);
+        assert_eq!(ramp[1].r, 0.5);
+    }
+}
@@ -7,7 +7,7 @@
 
 use std::ops::{Bound, Deref, DerefMut, Range, RangeBounds};
 
-use kas::draw::{Draw, Pass};
+use kas::draw::{Draw, Icon, ImageId, Pass};
 use kas::geom::{Coord, Rect, Size};
 use kas::layout::{AxisInfo, Margins, SizeRules};
 use kas::text::PreparedText;
@@ -17,6 +17,22 @@ use kas::Direction;
 pub enum ClipRegion {
     Popup,
     Scroll,
+    /// Content stacked over its siblings within the same window
+    Overlay,
+}
+
+/// Elevation of a surface above the content beneath it
+///
+/// Themes may draw a shadow beneath elevated surfaces; higher surfaces cast
+/// larger, softer shadows further offset from the surface.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum Elevation {
+    /// A surface level with its surroundings, casting no shadow
+    Flat,
+    /// Menus and other pop-ups
+    Menu,
+    /// Dialog boxes
+    Dialog,
 }
 
 /// Input and highlighting state of a widget
@@ -133,6 +149,15 @@ pub trait SizeHandle {
     /// The margin between UI elements, where desired
     fn outer_margins(&self) -> Margins;
 
+    /// Extent of the shadow cast by a surface at the given `elevation`
+    ///
+    /// Returns `(top_left, bottom_right)` dimensions as two `Size`s: the
+    /// distance the shadow drawn by [`DrawHandle::surface`] extends beyond
+    /// the surface's `rect` on each side. Shadows may be drawn over adjacent
+    /// content; widgets need only reserve this space where the shadow would
+    /// otherwise be clipped (e.g. at the edge of a window).
+    fn shadow(&self, elevation: Elevation) -> (Size, Size);
+
     /// The height of a line of text
     fn line_height(&self, class: TextClass) -> u32;
 
@@ -166,6 +191,8 @@ pub trait SizeHandle {
     fn edit_surround(&self) -> (Size, Size);
 
     /// Size of the element drawn by [`DrawHandle::checkbox`].
+    ///
+    /// This is also the size of [`DrawHandle::expander`].
     fn checkbox(&self) -> Size;
 
     /// Size of the element drawn by [`DrawHandle::radiobox`].
@@ -260,9 +287,29 @@ pub trait DrawHandle {
     /// The frame dimensions equal those of [`SizeHandle::frame`] on each side.
     fn menu_frame(&mut self, rect: Rect);
 
+    /// Draw a raised surface: frame, background and shadow
+    ///
+    /// The surface is drawn inside `rect` with frame dimensions equal to those
+    /// of [`SizeHandle::frame`]; the shadow extends beyond `rect` as given by
+    /// [`SizeHandle::shadow`]. [`DrawHandle::menu_frame`] is equivalent to
+    /// this method with [`Elevation::Menu`].
+    fn surface(&mut self, rect: Rect, elevation: Elevation);
+
     /// Draw a separator in the given `rect`
     fn separator(&mut self, rect: Rect);
 
+    /// Draw an image, scaled to fill `rect`
+    ///
+    /// The image is drawn as-is; it is not affected by the theme's colours.
+    fn image(&mut self, id: ImageId, rect: Rect);
+
+    /// Draw an icon, scaled to fill `rect`
+    ///
+    /// Vector icons are rasterised to the size of `rect` (in physical pixels).
+    /// Rasterised icons are cached by the theme until the scale factor changes
+    /// (see [`kas::draw::IconCache`]).
+    fn icon(&mut self, icon: &Icon, rect: Rect);
+
     /// Draw some text using the standard font
     ///
     /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
@@ -284,6 +331,12 @@ pub trait DrawHandle {
     /// Draw the background of a menu entry
     fn menu_entry(&mut self, rect: Rect, state: InputState);
 
+    /// Draw the background of a row in a view widget
+    ///
+    /// Selected rows are highlighted; `state` is used to highlight hover and
+    /// navigation focus.
+    fn view_row(&mut self, rect: Rect, selected: bool, state: InputState);
+
     /// Draw button sides, background and margin-area highlight
     fn button(&mut self, rect: Rect, state: InputState);
 
@@ -302,6 +355,12 @@ pub trait DrawHandle {
     /// This is similar in appearance to a checkbox.
     fn radiobox(&mut self, rect: Rect, checked: bool, state: InputState);
 
+    /// Draw UI element: expander
+    ///
+    /// This is a toggle similar in appearance to a checkbox, with a mark
+    /// pointing right (collapsed) or down (`expanded`), as used by tree views.
+    fn expander(&mut self, rect: Rect, expanded: bool, state: InputState);
+
     /// Draw UI element: scrollbar
     ///
     /// -   `rect`: area of whole widget (slider track)
@@ -384,6 +443,9 @@ impl<S: SizeHandle> SizeHandle for Box<S> {
     fn outer_margins(&self) -> Margins {
         self.deref().outer_margins()
     }
+    fn shadow(&self, elevation: Elevation) -> (Size, Size) {
+        self.deref().shadow(elevation)
+    }
 
     fn line_height(&self, class: TextClass) -> u32 {
         self.deref().line_height(class)
@@ -442,6 +504,9 @@ where
     fn outer_margins(&self) -> Margins {
         self.deref().outer_margins()
     }
+    fn shadow(&self, elevation: Elevation) -> (Size, Size) {
+        self.deref().shadow(elevation)
+    }
 
     fn line_height(&self, class: TextClass) -> u32 {
         self.deref().line_height(class)
@@ -504,9 +569,18 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     fn menu_frame(&mut self, rect: Rect) {
         self.deref_mut().menu_frame(rect);
     }
+    fn surface(&mut self, rect: Rect, elevation: Elevation) {
+        self.deref_mut().surface(rect, elevation);
+    }
     fn separator(&mut self, rect: Rect) {
         self.deref_mut().separator(rect);
     }
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        self.deref_mut().image(id, rect);
+    }
+    fn icon(&mut self, icon: &Icon, rect: Rect) {
+        self.deref_mut().icon(icon, rect);
+    }
     fn text(&mut self, pos: Coord, text: &PreparedText, class: TextClass) {
         self.deref_mut().text(pos, text, class)
     }
@@ -526,6 +600,9 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     fn menu_entry(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().menu_entry(rect, state)
     }
+    fn view_row(&mut self, rect: Rect, selected: bool, state: InputState) {
+        self.deref_mut().view_row(rect, selected, state)
+    }
     fn button(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().button(rect, state)
     }
@@ -538,6 +615,9 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     fn radiobox(&mut self, rect: Rect, checked: bool, state: InputState) {
         self.deref_mut().radiobox(rect, checked, state)
     }
+    fn expander(&mut self, rect: Rect, expanded: bool, state: InputState) {
+        self.deref_mut().expander(rect, expanded, state)
+    }
     fn scrollbar(&mut self, rect: Rect, h_rect: Rect, dir: Direction, state: InputState) {
         self.deref_mut().scrollbar(rect, h_rect, dir, state)
     }
@@ -575,9 +655,18 @@ where
     fn menu_frame(&mut self, rect: Rect) {
         self.deref_mut().menu_frame(rect);
     }
+    fn surface(&mut self, rect: Rect, elevation: Elevation) {
+        self.deref_mut().surface(rect, elevation);
+    }
     fn separator(&mut self, rect: Rect) {
         self.deref_mut().separator(rect);
     }
+    fn image(&mut self, id: ImageId, rect: Rect) {
+        self.deref_mut().image(id, rect);
+    }
+    fn icon(&mut self, icon: &Icon, rect: Rect) {
+        self.deref_mut().icon(icon, rect);
+    }
     fn text(&mut self, pos: Coord, text: &PreparedText, class: TextClass) {
         self.deref_mut().text(pos, text, class)
     }
@@ -597,6 +686,9 @@ where
     fn menu_entry(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().menu_entry(rect, state)
     }
+    fn view_row(&mut self, rect: Rect, selected: bool, state: InputState) {
+        self.deref_mut().view_row(rect, selected, state)
+    }
     fn button(&mut self, rect: Rect, state: InputState) {
         self.deref_mut().button(rect, state)
     }
@@ -609,6 +701,9 @@ where
     fn radiobox(&mut self, rect: Rect, checked: bool, state: InputState) {
         self.deref_mut().radiobox(rect, checked, state)
     }
+    fn expander(&mut self, rect: Rect, expanded: bool, state: InputState) {
+        self.deref_mut().expander(rect, expanded, state)
+    }
     fn scrollbar(&mut self, rect: Rect, h_rect: Rect, dir: Direction, state: InputState) {
         self.deref_mut().scrollbar(rect, h_rect, dir, state)
     }
@@ -0,0 +1,128 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Icons
+
+use super::{ImageId, Svg};
+use crate::geom::{Size, Vec2};
+
+/// An icon: either a raster image or a vector image
+///
+/// Icons are drawn via [`super::DrawHandle::icon`]. Vector icons are
+/// rasterised to the target size, hence remain sharp at any scale factor.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Icon {
+    /// A registered raster image
+    Image(ImageId),
+    /// A vector image
+    Svg(Svg),
+}
+
+impl Icon {
+    /// The natural size of the icon
+    ///
+    /// For raster images this is the size in pixels; for vector images it is
+    /// the intrinsic size in logical pixels. Returns `None` for unregistered
+    /// images.
+    pub fn size(&self) -> Option<Vec2> {
+        match self {
+            Icon::Image(id) => id.size().map(Vec2::from),
+            Icon::Svg(svg) => Some(svg.size()),
+        }
+    }
+}
+
+impl From<ImageId> for Icon {
+    fn from(id: ImageId) -> Self {
+        Icon::Image(id)
+    }
+}
+
+impl From<Svg> for Icon {
+    fn from(svg: Svg) -> Self {
+        Icon::Svg(svg)
+    }
+}
+
+/// A cache of rasterised vector images
+///
+/// This is a utility for themes: [`IconCache::get`] rasterises an [`Svg`] to
+/// the requested size (in physical pixels) on first use, registering the
+/// result as an image. A few sizes are kept per vector image; older sizes are
+/// released. The cache should be cleared when the scale factor changes.
+#[derive(Debug, Default)]
+pub struct IconCache {
+    entries: Vec<(Svg, Size, ImageId)>,
+}
+
+impl IconCache {
+    /// Maximum number of sizes cached for each vector image
+    const MAX_SIZES: usize = 4;
+
+    /// Construct an empty cache
+    pub fn new() -> Self {
+        IconCache::default()
+    }
+
+    /// Get an image of `svg` rasterised to `size`
+    pub fn get(&mut self, svg: &Svg, size: Size) -> ImageId {
+        let entry = self
+            .entries
+            .iter()
+            .find(|(s, sz, _)| s == svg && *sz == size);
+        if let Some((_, _, id)) = entry {
+            return *id;
+        }
+
+        let count = self.entries.iter().filter(|(s, _, _)| s == svg).count();
+        if count >= Self::MAX_SIZES {
+            let index = self.entries.iter().position(|(s, _, _)| s == svg).unwrap();
+            let (_, _, id) = self.entries.remove(index);
+            id.unregister();
+        }
+
+        let id = ImageId::register(svg.rasterise(size));
+        self.entries.push((svg.clone(), size, id));
+        id
+    }
+
+    /// Release all cached images
+    pub fn clear(&mut self) {
+        for (_, _, id) in self.entries.drain(..) {
+            id.unregister();
+        }
+    }
+}
+
+impl Drop for IconCache {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cache() {
+        let svg = Svg::parse(r#"<svg viewBox="0 0 4 4"><rect width="4" height="2"/></svg>"#);
+        let svg = svg.unwrap();
+        let mut cache = IconCache::new();
+        let a = cache.get(&svg, Size(8, 8));
+        assert_eq!(cache.get(&svg, Size(8, 8)), a);
+        assert_eq!(a.data().unwrap().pixel(0, 7)[3], 0);
+
+        for n in 1..=IconCache::MAX_SIZES as u32 {
+            cache.get(&svg, Size(n, n));
+        }
+        assert!(!a.is_registered());
+
+        let b = cache.get(&svg, Size(8, 8));
+        assert_eq!(b.data().unwrap().pixel(0, 0), [0, 0, 0, 255]);
+        cache.clear();
+        assert!(!b.is_registered());
+    }
+}
@@ -0,0 +1,408 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Freedesktop icon theme lookup
+//!
+//! See the [Icon Theme Specification](https://specifications.freedesktop.org/icon-theme-spec/icon-theme-spec-latest.html).
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Icon, Svg};
+#[cfg(feature = "image")]
+use super::{ImageData, ImageId};
+
+/// The kind of an icon theme directory
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DirKind {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+/// An icon theme sub-directory, as described by `index.theme`
+#[derive(Clone, Debug)]
+struct Directory {
+    path: String,
+    size: u32,
+    scale: u32,
+    kind: DirKind,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl Directory {
+    fn matches_size(&self, size: u32) -> bool {
+        if self.scale != 1 {
+            return false;
+        }
+        match self.kind {
+            DirKind::Fixed => self.size == size,
+            DirKind::Scalable => self.min_size <= size && size <= self.max_size,
+            DirKind::Threshold => {
+                self.size.saturating_sub(self.threshold) <= size
+                    && size <= self.size + self.threshold
+            }
+        }
+    }
+
+    fn size_distance(&self, size: u32) -> u32 {
+        let s = self.scale;
+        let (min, max) = match self.kind {
+            DirKind::Fixed => (self.size, self.size),
+            DirKind::Scalable => (self.min_size, self.max_size),
+            DirKind::Threshold => (
+                self.size.saturating_sub(self.threshold),
+                self.size + self.threshold,
+            ),
+        };
+        if size < min * s {
+            min * s - size
+        } else if size > max * s {
+            size - max * s
+        } else {
+            0
+        }
+    }
+}
+
+/// Parsed `index.theme`
+#[derive(Clone, Debug, Default)]
+struct ThemeIndex {
+    inherits: Vec<String>,
+    dirs: Vec<Directory>,
+}
+
+impl ThemeIndex {
+    fn parse(source: &str) -> ThemeIndex {
+        let mut sections: HashMap<&str, HashMap<&str, &str>> = HashMap::new();
+        let mut section = "";
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = &line[1..line.len() - 1];
+            } else if let Some(i) = line.find('=') {
+                let (key, value) = (line[..i].trim(), line[i + 1..].trim());
+                sections.entry(section).or_default().insert(key, value);
+            }
+        }
+
+        let list = |s: Option<&&str>| -> Vec<String> {
+            s.map(|s| {
+                s.split(',')
+                    .map(|s| s.trim())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+        };
+
+        let mut index = ThemeIndex::default();
+        let theme = match sections.get("Icon Theme") {
+            Some(theme) => theme,
+            None => return index,
+        };
+        index.inherits = list(theme.get("Inherits"));
+        let mut dirs = list(theme.get("Directories"));
+        dirs.extend(list(theme.get("ScaledDirectories")));
+
+        for path in dirs {
+            let keys = match sections.get(path.as_str()) {
+                Some(keys) => keys,
+                None => continue,
+            };
+            let num = |key: &str| keys.get(key).and_then(|v| v.parse::<u32>().ok());
+            let size = match num("Size") {
+                Some(size) => size,
+                None => continue,
+            };
+            let kind = match keys.get("Type").cloned() {
+                Some("Fixed") => DirKind::Fixed,
+                Some("Scalable") => DirKind::Scalable,
+                _ => DirKind::Threshold,
+            };
+            index.dirs.push(Directory {
+                path,
+                size,
+                scale: num("Scale").unwrap_or(1),
+                kind,
+                min_size: num("MinSize").unwrap_or(size),
+                max_size: num("MaxSize").unwrap_or(size),
+                threshold: num("Threshold").unwrap_or(2),
+            });
+        }
+        index
+    }
+}
+
+/// A freedesktop icon theme
+///
+/// Icons are looked up by name (as in the
+/// [Icon Naming Specification](https://specifications.freedesktop.org/icon-naming-spec/icon-naming-spec-latest.html))
+/// in the theme, then the themes it inherits from, then `hicolor`, then
+/// directly within the base directories.
+///
+/// SVG icons are always supported; PNG icons only with the `image` feature.
+#[derive(Clone, Debug)]
+pub struct IconTheme {
+    name: String,
+    base_dirs: Vec<PathBuf>,
+}
+
+impl IconTheme {
+    /// Construct for the theme `name`, using the default base directories
+    pub fn new<S: ToString>(name: S) -> Self {
+        IconTheme::with_base_dirs(name, IconTheme::default_base_dirs())
+    }
+
+    /// Construct for the theme `name`, using the given base directories
+    ///
+    /// Each theme is a sub-directory of one or more base directories.
+    pub fn with_base_dirs<S: ToString>(name: S, base_dirs: Vec<PathBuf>) -> Self {
+        IconTheme {
+            name: name.to_string(),
+            base_dirs,
+        }
+    }
+
+    /// Construct for the user's icon theme
+    ///
+    /// The theme name is taken from the `KAS_ICON_THEME` environment
+    /// variable if set, otherwise from `gtk-icon-theme-name` in the GTK 3
+    /// settings, otherwise `hicolor` is used.
+    pub fn system() -> Self {
+        let name = env::var("KAS_ICON_THEME")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .or_else(gtk_icon_theme_name)
+            .unwrap_or_else(|| "hicolor".to_string());
+        IconTheme::new(name)
+    }
+
+    /// The default base directories
+    ///
+    /// These are `$HOME/.icons`, `$XDG_DATA_HOME/icons`, `icons` within each
+    /// of `$XDG_DATA_DIRS` and `/usr/share/pixmaps`.
+    pub fn default_base_dirs() -> Vec<PathBuf> {
+        let mut dirs = vec![];
+        let home = env::var_os("HOME").map(PathBuf::from);
+        if let Some(ref home) = home {
+            dirs.push(home.join(".icons"));
+        }
+        match env::var_os("XDG_DATA_HOME") {
+            Some(data_home) if !data_home.is_empty() => {
+                dirs.push(PathBuf::from(data_home).join("icons"))
+            }
+            _ => {
+                if let Some(ref home) = home {
+                    dirs.push(home.join(".local/share/icons"));
+                }
+            }
+        }
+        let data_dirs = env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/usr/local/share/:/usr/share/".to_string());
+        for dir in data_dirs.split(':').filter(|dir| !dir.is_empty()) {
+            dirs.push(Path::new(dir).join("icons"));
+        }
+        dirs.push(PathBuf::from("/usr/share/pixmaps"));
+        dirs
+    }
+
+    /// The theme name
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Find the file for icon `icon` at the given nominal `size`
+    ///
+    /// An exact size match is preferred, otherwise the closest size is used.
+    pub fn lookup(&self, icon: &str, size: u32) -> Option<PathBuf> {
+        let mut visited = vec![];
+        self.lookup_in(&self.name, icon, size, &mut visited)
+            .or_else(|| self.lookup_in("hicolor", icon, size, &mut visited))
+            .or_else(|| {
+                for base in &self.base_dirs {
+                    for ext in EXTENSIONS {
+                        let path = base.join(format!("{}.{}", icon, ext));
+                        if path.is_file() {
+                            return Some(path);
+                        }
+                    }
+                }
+                None
+            })
+    }
+
+    /// Find and load icon `icon` at the given nominal `size`
+    ///
+    /// Returns `None` if the icon is not found or fails to load.
+    pub fn load(&self, icon: &str, size: u32) -> Option<Icon> {
+        let path = self.lookup(icon, size)?;
+        let result = load_icon(&path);
+        if result.is_none() {
+            log::warn!("IconTheme: failed to load {}", path.display());
+        }
+        result
+    }
+
+    fn index(&self, theme: &str) -> Option<ThemeIndex> {
+        self.base_dirs.iter().find_map(|base| {
+            let source = fs::read_to_string(base.join(theme).join("index.theme")).ok()?;
+            Some(ThemeIndex::parse(&source))
+        })
+    }
+
+    fn lookup_in(
+        &self,
+        theme: &str,
+        icon: &str,
+        size: u32,
+        visited: &mut Vec<String>,
+    ) -> Option<PathBuf> {
+        if visited.iter().any(|t| t == theme) {
+            return None;
+        }
+        visited.push(theme.to_string());
+        let index = self.index(theme)?;
+
+        let find = |dir: &Directory| {
+            for base in &self.base_dirs {
+                for ext in EXTENSIONS {
+                    let path = base
+                        .join(theme)
+                        .join(&dir.path)
+                        .join(format!("{}.{}", icon, ext));
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+            }
+            None
+        };
+
+        let exact = index
+            .dirs
+            .iter()
+            .filter(|dir| dir.matches_size(size))
+            .find_map(|dir| find(dir));
+        if exact.is_some() {
+            return exact;
+        }
+
+        let mut best: Option<(u32, PathBuf)> = None;
+        for dir in &index.dirs {
+            let dist = dir.size_distance(size);
+            if best.as_ref().map(|b| dist < b.0).unwrap_or(true) {
+                if let Some(path) = find(dir) {
+                    best = Some((dist, path));
+                }
+            }
+        }
+        if let Some((_, path)) = best {
+            return Some(path);
+        }
+
+        for parent in &index.inherits {
+            if let Some(path) = self.lookup_in(parent, icon, size, visited) {
+                return Some(path);
+            }
+        }
+        None
+    }
+}
+
+/// Supported file extensions, in order of preference
+#[cfg(feature = "image")]
+const EXTENSIONS: &[&str] = &["svg", "png"];
+#[cfg(not(feature = "image"))]
+const EXTENSIONS: &[&str] = &["svg"];
+
+fn load_icon(path: &Path) -> Option<Icon> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("svg") => Svg::load(path).ok().map(Icon::Svg),
+        #[cfg(feature = "image")]
+        Some("png") => {
+            let data = ImageData::load(path).ok()?;
+            Some(Icon::Image(ImageId::register(data)))
+        }
+        _ => None,
+    }
+}
+
+/// Read `gtk-icon-theme-name` from `$XDG_CONFIG_HOME/gtk-3.0/settings.ini`
+fn gtk_icon_theme_name() -> Option<String> {
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    let source = fs::read_to_string(config.join("gtk-3.0/settings.ini")).ok()?;
+    source.lines().find_map(|line| {
+        let i = line.find('=')?;
+        if line[..i].trim() == "gtk-icon-theme-name" {
+            Some(line[i + 1..].trim().trim_matches('"').to_string())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SVG: &str = r#"<svg viewBox="0 0 16 16"><rect width="16" height="16"/></svg>"#;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn lookup() {
+        let base = env::temp_dir().join(format!("kas-icon-theme-test-{}", std::process::id()));
+        write(
+            &base.join("parent/index.theme"),
+            "[Icon Theme]\nName=Parent\nDirectories=16x16/actions,scalable/actions\n\n\
+             [16x16/actions]\nSize=16\nType=Fixed\n\n\
+             [scalable/actions]\nSize=48\nMinSize=8\nMaxSize=512\nType=Scalable\n",
+        );
+        write(
+            &base.join("child/index.theme"),
+            "[Icon Theme]\nName=Child\nInherits=parent\nDirectories=24x24/actions\n\n\
+             [24x24/actions]\nSize=24\nType=Fixed\n",
+        );
+        let save = base.join("child/24x24/actions/document-save.svg");
+        let undo16 = base.join("parent/16x16/actions/edit-undo.svg");
+        let undo = base.join("parent/scalable/actions/edit-undo.svg");
+        for path in &[&save, &undo16, &undo] {
+            write(path, SVG);
+        }
+
+        let theme = IconTheme::with_base_dirs("child", vec![base.clone()]);
+        assert_eq!(theme.lookup("document-save", 24), Some(save.clone()));
+        // closest size in the child theme beats an exact match in the parent
+        assert_eq!(theme.lookup("document-save", 16), Some(save));
+        // inherited
+        assert_eq!(theme.lookup("edit-undo", 16), Some(undo16));
+        assert_eq!(theme.lookup("edit-undo", 32), Some(undo));
+        assert_eq!(theme.lookup("edit-redo", 16), None);
+        match theme.load("edit-undo", 32) {
+            Some(Icon::Svg(svg)) => assert_eq!(svg.size(), crate::geom::Vec2(16.0, 16.0)),
+            icon => panic!("unexpected: {:?}", icon),
+        }
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,197 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Images
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::geom::Size;
+
+#[allow(unused)]
+use super::DrawImage; // for doc-links
+
+/// Raster image data
+///
+/// Pixels are stored row-major as 8-bit RGBA in the sRGB colour space, with
+/// straight (not pre-multiplied) alpha.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageData {
+    size: Size,
+    data: Vec<u8>,
+}
+
+impl ImageData {
+    /// Construct from RGBA data
+    ///
+    /// Panics if `data.len() != 4 * size.0 * size.1`.
+    pub fn new(size: Size, data: Vec<u8>) -> Self {
+        assert_eq!(data.len(), 4 * size.0 as usize * size.1 as usize);
+        ImageData { size, data }
+    }
+
+    /// Decode an image (PNG or JPEG) from memory
+    #[cfg(feature = "image")]
+    pub fn decode(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.to_rgba();
+        let size = Size(image.width(), image.height());
+        Ok(ImageData::new(size, image.into_raw()))
+    }
+
+    /// Load and decode an image (PNG or JPEG) from a file
+    #[cfg(feature = "image")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgba();
+        let size = Size(image.width(), image.height());
+        Ok(ImageData::new(size, image.into_raw()))
+    }
+
+    /// Image size, in pixels
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Access pixel data
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get pixel `(x, y)` as RGBA
+    ///
+    /// Panics if out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        assert!(x < self.size.0 && y < self.size.1);
+        let i = 4 * (y * self.size.0 + x) as usize;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    next: u32,
+    images: HashMap<ImageId, Rc<ImageData>>,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// Handle to a registered image
+///
+/// Images are registered with a per-thread registry (usually that of the UI
+/// thread), from which draw back-ends fetch pixel data on first use, usually
+/// uploading it to a texture. Images are drawn via [`DrawImage::image`] or
+/// [`super::DrawHandle::image`].
+///
+/// Images remain registered until [`ImageId::unregister`] is called; handles
+/// may be freely copied. See also [`ImageHandle`], which unregisters the image
+/// automatically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImageId(u32);
+
+impl ImageId {
+    /// Register an image, returning a new handle
+    pub fn register(data: ImageData) -> ImageId {
+        REGISTRY.with(|r| {
+            let mut r = r.borrow_mut();
+            let id = ImageId(r.next);
+            r.next += 1;
+            r.images.insert(id, Rc::new(data));
+            id
+        })
+    }
+
+    /// Unregister an image
+    ///
+    /// Back-ends should release associated resources, though they may delay
+    /// doing so until the next frame is drawn.
+    pub fn unregister(self) {
+        // The registry may already be destroyed on thread exit
+        let _ = REGISTRY.try_with(|r| r.borrow_mut().images.remove(&self));
+    }
+
+    /// Check whether this image is registered
+    pub fn is_registered(self) -> bool {
+        REGISTRY.with(|r| r.borrow().images.contains_key(&self))
+    }
+
+    /// Get image data, if registered
+    pub fn data(self) -> Option<Rc<ImageData>> {
+        REGISTRY.with(|r| r.borrow().images.get(&self).cloned())
+    }
+
+    /// Get the image size, if registered
+    pub fn size(self) -> Option<Size> {
+        REGISTRY.with(|r| r.borrow().images.get(&self).map(|data| data.size))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct OwnedId(ImageId);
+
+impl Drop for OwnedId {
+    fn drop(&mut self) {
+        self.0.unregister();
+    }
+}
+
+/// Shared ownership of a registered image
+///
+/// The image is unregistered when the last clone of this handle is dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageHandle(Rc<OwnedId>);
+
+impl ImageHandle {
+    /// Register an image, returning a new handle
+    pub fn register(data: ImageData) -> Self {
+        ImageHandle(Rc::new(OwnedId(ImageId::register(data))))
+    }
+
+    /// Get the image identifier
+    #[inline]
+    pub fn id(&self) -> ImageId {
+        (self.0).0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn register() {
+        let data = ImageData::new(Size(2, 1), vec![255, 0, 0, 255, 0, 0, 255, 128]);
+        let id = ImageId::register(data.clone());
+        assert_eq!(id.size(), Some(Size(2, 1)));
+        assert_eq!(id.data().unwrap().pixel(1, 0), [0, 0, 255, 128]);
+
+        let id2 = ImageId::register(data);
+        assert!(id != id2);
+        id.unregister();
+        assert!(!id.is_registered());
+        assert!(id2.is_registered());
+    }
+
+    #[test]
+    fn handle() {
+        let data = ImageData::new(Size(1, 1), vec![0, 0, 0, 255]);
+        let handle = ImageHandle::register(data);
+        let id = handle.id();
+        let handle2 = handle.clone();
+        drop(handle);
+        assert!(id.is_registered());
+        assert_eq!(handle2.id(), id);
+        drop(handle2);
+        assert!(!id.is_registered());
+    }
+}
@@ -21,14 +21,32 @@
 //! an axis-aligned box or frame with several shading options.
 //!
 //! The [`Draw`] trait itself contains very little; extension traits
-//! [`DrawRounded`], [`DrawShaded`] and [`DrawText`] provide additional draw
-//! routines. Toolkits are required to implement only the base [`Draw`] trait,
-//! and may provide their own extension traits. For this reason, themes are
+//! [`DrawRounded`], [`DrawShaded`], [`DrawText`], [`DrawImage`],
+//! [`DrawPath`] and [`DrawGradient`] provide additional draw routines.
+//! Toolkits are required to implement only the base [`Draw`] trait, and may
+//! provide their own extension traits. For this reason, themes are
 //! parameterised over an object `D: Draw + ...` (with specified trait bounds).
 //!
-//! The medium-level API will be extended in the future to support texturing
-//! (not yet supported) and potentially a more comprehensive path-based API
-//! (e.g. Lyon).
+//! [`DrawPath`] supports arbitrary shapes built as a [`Path`] of lines,
+//! curves and arcs, which may be filled or stroked.
+//!
+//! All primitives respect the alpha component of [`Colour`], allowing
+//! translucent drawing; [`DrawGradient`] fills regions with a linear or
+//! radial [`Gradient`] and draws blurred shadows.
+//!
+//! ### Images
+//!
+//! Raster images are registered via [`ImageId::register`], returning a handle
+//! which may be drawn with [`DrawImage::image`] or [`DrawHandle::image`].
+//! An [`ImageHandle`] unregisters its image when the last clone is dropped.
+//!
+//! Vector images ([`Svg`]) are rasterised on the CPU, thus render identically
+//! with any back-end. An [`Icon`] may be either; [`DrawHandle::icon`] draws
+//! vector icons rasterised to the target size, cached by the theme (see
+//! [`IconCache`]).
+//!
+//! Common icons are identified by [`StandardIcon`], resolved from the user's
+//! freedesktop icon theme (see [`IconTheme`]) or a built-in fallback set.
 //!
 //! ### Low-level interface
 //!
@@ -38,7 +56,14 @@
 //! [`kas-wgpu::draw::CustomPipe`](https://docs.rs/kas-wgpu/*/kas_wgpu/draw/trait.CustomPipe.html).
 
 mod colour;
+mod gradient;
 mod handle;
+mod icon;
+mod icon_theme;
+mod image;
+mod path;
+mod standard_icon;
+mod svg;
 
 use std::any::Any;
 
@@ -46,14 +71,21 @@ use crate::geom::{Quad, Rect, Vec2};
 use crate::text::PreparedText;
 
 pub use colour::Colour;
+pub use gradient::{Gradient, GradientShape};
 pub use handle::*;
+pub use icon::{Icon, IconCache};
+pub use icon_theme::IconTheme;
+pub use image::{ImageData, ImageHandle, ImageId};
+pub use path::{Contour, CoverageMask, FillRule, LineCap, LineJoin, Path, Stroke, Tessellation};
+pub use standard_icon::StandardIcon;
+pub use svg::{Svg, SvgError};
 
 /// Pass identifier
 ///
 /// Users normally need only pass this value.
 ///
 /// Custom render pipes should extract the pass number and depth value.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Pass(u32, f32);
 
 impl Pass {
@@ -95,12 +127,26 @@ pub trait DrawShared {
 /// results are only loosely defined. Draw operations involving transparency
 /// should be ordered after those without transparency.
 ///
+/// Within a pass, primitives are batched by kind and drawn in the following
+/// order (within each batch, in order of issue): rects, frames and shaded
+/// square shapes; gradients and shadows; shaded round shapes; flat round
+/// shapes and paths; images. Text is drawn after all passes, using depth to
+/// hide text covered by a later pass. Thus, for example, a translucent
+/// gradient is blended over a rect from the same pass regardless of which was
+/// issued first, but a translucent rect is not blended over an image.
+///
 /// Draw operations take place over multiple render passes, identified by a
 /// handle of type [`Pass`]. In general the user only needs to pass this value
 /// into methods as required. [`Draw::add_clip_region`] creates a new [`Pass`].
 ///
 /// Each [`Pass`] has an associated depth value which may be used to determine
-/// the result of overlapping draw commands.
+/// the result of overlapping draw commands. Passes are composited in order of
+/// creation, thus translucent content of one pass is blended over content of
+/// previous passes. (Themes create passes for pop-ups after drawing the
+/// content beneath them.)
+///
+/// Colours may be translucent: the alpha component of [`Colour`] gives the
+/// opacity of each primitive.
 pub trait Draw: Any {
     /// Cast self to [`std::any::Any`] reference.
     ///
@@ -219,3 +265,109 @@ pub trait DrawText {
     /// Draw text
     fn text(&mut self, pass: Pass, pos: Vec2, col: Colour, text: &PreparedText);
 }
+
+/// Drawing commands for images
+///
+/// This trait is an extension over [`Draw`] providing textured rectangles.
+///
+/// Images are identified by an [`ImageId`]; implementations should fetch the
+/// image data via [`ImageId::data`] on first use (e.g. to upload a texture),
+/// and may release resources of images no longer registered.
+///
+/// Images may be partially transparent. If the implementation buffers draw
+/// commands, it should draw these primitives after solid primitives.
+pub trait DrawImage: Draw {
+    /// Draw an image, scaled to fill `rect`
+    ///
+    /// Images not registered are not drawn.
+    fn image(&mut self, pass: Pass, id: ImageId, rect: Quad);
+}
+
+/// Drawing commands for paths
+///
+/// This trait is an extension over [`Draw`] providing filled and stroked
+/// paths of arbitrary shape.
+///
+/// Implementations are expected to flatten paths ([`Path::flatten`]), then
+/// either tessellate them ([`Tessellation`]) or rasterise them
+/// ([`CoverageMask`]). Edges may be anti-aliased, hence these primitives are
+/// partially transparent. If the implementation buffers draw commands, it
+/// should draw these primitives after solid primitives.
+pub trait DrawPath: Draw {
+    /// Fill the region enclosed by `path` with uniform colour
+    ///
+    /// All sub-paths are implicitly closed.
+    fn fill_path(&mut self, pass: Pass, path: &Path, rule: FillRule, col: Colour);
+
+    /// Draw the outline of `path` with uniform colour
+    fn stroke_path(&mut self, pass: Pass, path: &Path, stroke: &Stroke, col: Colour);
+}
+
+/// Drawing commands for gradient fills
+///
+/// This trait is an extension over [`Draw`] providing shapes filled with a
+/// [`Gradient`]. Gradient coordinates use the same coordinate system as the
+/// shape (thus are not relative to the shape).
+///
+/// Gradients may be partially transparent. If the implementation buffers draw
+/// commands, it should draw these primitives after solid primitives.
+pub trait DrawGradient: Draw {
+    /// Draw a rectangle filled with a gradient
+    fn gradient_rect(&mut self, pass: Pass, rect: Quad, gradient: &Gradient);
+
+    /// Draw a frame filled with a gradient
+    ///
+    /// The frame is defined by the area inside `outer` and not inside `inner`.
+    fn gradient_frame(&mut self, pass: Pass, outer: Quad, inner: Quad, gradient: &Gradient);
+
+    /// Draw a blurred shadow
+    ///
+    /// The shadow covers the area inside `outer` and not inside `inner`. It
+    /// has colour `col` at the edge of `inner`, fading smoothly to transparent
+    /// at the edge of `outer`; corners are rounded. The inner quad is not
+    /// drawn, thus the surface casting the shadow may be drawn before or after.
+    ///
+    /// The default implementation composes the shadow from
+    /// [`DrawGradient::gradient_rect`] calls.
+    fn shadow(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour) {
+        // Smoothstep fall-off, a cheap approximation of a Gaussian blur
+        let fade = |g: Gradient| {
+            g.with_stop(0.25, col.with_alpha(col.a * 0.84375))
+                .with_stop(0.5, col.with_alpha(col.a * 0.5))
+                .with_stop(0.75, col.with_alpha(col.a * 0.15625))
+        };
+        let clear = col.with_alpha(0.0);
+        let linear = |start, end| fade(Gradient::linear(start, end, col, clear));
+        let corner = |centre, radii| fade(Gradient::elliptical(centre, radii, col, clear));
+
+        let (a, b) = (inner.a, inner.b);
+        let (oa, ob) = (outer.a, outer.b);
+        let mut piece = |rect: Quad, gradient: Gradient| {
+            if rect.a.lt(rect.b) {
+                self.gradient_rect(pass, rect, &gradient);
+            }
+        };
+
+        // Edges
+        let rect = Quad::with_coords(Vec2(a.0, oa.1), Vec2(b.0, a.1));
+        piece(rect, linear(Vec2(0.0, a.1), Vec2(0.0, oa.1)));
+        let rect = Quad::with_coords(Vec2(a.0, b.1), Vec2(b.0, ob.1));
+        piece(rect, linear(Vec2(0.0, b.1), Vec2(0.0, ob.1)));
+        let rect = Quad::with_coords(Vec2(oa.0, a.1), Vec2(a.0, b.1));
+        piece(rect, linear(Vec2(a.0, 0.0), Vec2(oa.0, 0.0)));
+        let rect = Quad::with_coords(Vec2(b.0, a.1), Vec2(ob.0, b.1));
+        piece(rect, linear(Vec2(b.0, 0.0), Vec2(ob.0, 0.0)));
+
+        // Corners
+        let rect = Quad::with_coords(oa, a);
+        piece(rect, corner(a, a - oa));
+        let c = Vec2(b.0, a.1);
+        let rect = Quad::with_coords(Vec2(b.0, oa.1), Vec2(ob.0, a.1));
+        piece(rect, corner(c, Vec2(ob.0 - b.0, a.1 - oa.1)));
+        let c = Vec2(a.0, b.1);
+        let rect = Quad::with_coords(Vec2(oa.0, b.1), Vec2(a.0, ob.1));
+        piece(rect, corner(c, Vec2(a.0 - oa.0, ob.1 - b.1)));
+        let rect = Quad::with_coords(b, ob);
+        piece(rect, corner(b, ob - b));
+    }
+}
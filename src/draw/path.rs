@@ -0,0 +1,882 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Paths
+//!
+//! A [`Path`] is built from move, line, curve and arc commands, then drawn via
+//! [`DrawPath`]. The remaining types in this module are utilities for
+//! implementations: paths are flattened to [`Contour`]s, strokes converted
+//! to polygons via [`Stroke::outline`], then polygons are either tessellated
+//! ([`Tessellation`]) or rasterised ([`CoverageMask`]), both on the CPU.
+
+use std::cmp::Ordering;
+use std::f32::consts::PI;
+
+use crate::geom::{Quad, Rect, Size, Vec2};
+
+#[allow(unused)]
+use super::DrawPath; // for doc-links
+
+/// Number of sub-scanlines sampled per pixel row by [`CoverageMask`]
+const SUB_SAMPLES: usize = 4;
+
+/// Rule determining which regions enclosed by a path are filled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillRule {
+    /// Fill regions with non-zero winding number
+    NonZero,
+    /// Fill regions with odd winding number
+    EvenOdd,
+}
+
+impl Default for FillRule {
+    fn default() -> Self {
+        FillRule::NonZero
+    }
+}
+
+impl FillRule {
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Shape used where stroke segments meet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineJoin {
+    /// Extend outer edges to meet at a point, subject to the miter limit
+    Miter,
+    /// Round corners
+    Round,
+    /// Cut corners
+    Bevel,
+}
+
+/// Shape used at the ends of open sub-paths
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineCap {
+    /// End exactly at the end-point
+    Butt,
+    /// Add a half-disc
+    Round,
+    /// Extend by half the stroke width
+    Square,
+}
+
+/// Stroke style
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stroke {
+    /// Line width, in pixels
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Maximum ratio of miter length to stroke width before a miter join is
+    /// replaced by a bevel
+    pub miter_limit: f32,
+}
+
+/// A flattened sub-path: a polyline, optionally closed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Segment {
+    Move(Vec2),
+    Line(Vec2),
+    Cubic(Vec2, Vec2, Vec2),
+    Close,
+}
+
+/// A path
+///
+/// Paths are composed of sub-paths, each started by [`Path::move_to`] (or
+/// implicitly by the first command) and optionally ended by [`Path::close`].
+/// Coordinates are as used by [`super::Draw`] (pixels, with y down).
+/// Quadratic curves and arcs are stored as cubic Bézier curves.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    pub(crate) segments: Vec<Segment>,
+    current: Vec2,
+    start: Vec2,
+}
+
+impl Path {
+    /// Construct an empty path
+    #[inline]
+    pub fn new() -> Self {
+        Path::default()
+    }
+
+    /// True if the path has no commands
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The current point (end of the last command)
+    #[inline]
+    pub fn current(&self) -> Vec2 {
+        self.current
+    }
+
+    /// Start a new sub-path at `p`
+    pub fn move_to(&mut self, p: Vec2) {
+        self.segments.push(Segment::Move(p));
+        self.current = p;
+        self.start = p;
+    }
+
+    /// Add a straight line to `p`
+    pub fn line_to(&mut self, p: Vec2) {
+        self.segments.push(Segment::Line(p));
+        self.current = p;
+    }
+
+    /// Add a quadratic Bézier curve with control point `c`, ending at `p`
+    pub fn quad_to(&mut self, c: Vec2, p: Vec2) {
+        let p0 = self.current;
+        self.cubic_to(p0 + (c - p0) * (2.0 / 3.0), p + (c - p) * (2.0 / 3.0), p);
+    }
+
+    /// Add a cubic Bézier curve with control points `c1`, `c2`, ending at `p`
+    pub fn cubic_to(&mut self, c1: Vec2, c2: Vec2, p: Vec2) {
+        self.segments.push(Segment::Cubic(c1, c2, p));
+        self.current = p;
+    }
+
+    /// Close the current sub-path with a line to its start
+    pub fn close(&mut self) {
+        self.segments.push(Segment::Close);
+        self.current = self.start;
+    }
+
+    /// Add an elliptical arc to `p`, as specified by SVG path data
+    ///
+    /// The ellipse has the given `radii`, with its x-axis rotated by
+    /// `x_rotation` degrees. Of the (up to) four matching arcs, `large_arc`
+    /// selects one spanning more than 180° and `sweep` selects one drawn
+    /// in the direction of increasing angle (clockwise on screen).
+    /// Radii are scaled up if too small to reach `p`.
+    pub fn arc_to(&mut self, radii: Vec2, x_rotation: f32, large_arc: bool, sweep: bool, p: Vec2) {
+        let p0 = self.current;
+        if p0 == p {
+            return;
+        }
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if rx == 0.0 || ry == 0.0 {
+            self.line_to(p);
+            return;
+        }
+
+        // Conversion to centre parameterisation (SVG spec, appendix F.6.5)
+        let (sin, cos) = x_rotation.to_radians().sin_cos();
+        let d = (p0 - p) * 0.5;
+        let x1 = cos * d.0 + sin * d.1;
+        let y1 = -sin * d.0 + cos * d.1;
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            let s = lambda.sqrt();
+            rx *= s;
+            ry *= s;
+        }
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = (num / den).max(0.0).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let mid = (p0 + p) * 0.5;
+        let centre = Vec2(cos * cx1 - sin * cy1 + mid.0, sin * cx1 + cos * cy1 + mid.1);
+
+        let angle_between = |u: Vec2, v: Vec2| (u.0 * v.1 - u.1 * v.0).atan2(u.0 * v.0 + u.1 * v.1);
+        let u = Vec2((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let v = Vec2((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let theta = angle_between(Vec2(1.0, 0.0), u);
+        let mut delta = angle_between(u, v);
+        if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        } else if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        }
+
+        self.arc_segments(centre, Vec2(rx, ry), (sin, cos), theta, delta, Some(p));
+    }
+
+    /// Add a circular or elliptical arc
+    ///
+    /// The arc is centred on `centre` with the given `radii`, starting at
+    /// angle `start` and spanning angle `sweep` (both in radians, measured
+    /// clockwise on screen from the positive x-axis). A line is added from
+    /// the current point to the start of the arc, unless the path is empty.
+    pub fn arc(&mut self, centre: Vec2, radii: Vec2, start: f32, sweep: f32) {
+        let (s, c) = start.sin_cos();
+        let p0 = centre + Vec2(c * radii.0, s * radii.1);
+        if self.segments.is_empty() {
+            self.move_to(p0);
+        } else if self.current != p0 {
+            self.line_to(p0);
+        }
+        if sweep != 0.0 {
+            self.arc_segments(centre, radii, (0.0, 1.0), start, sweep, None);
+        }
+    }
+
+    /// Add an ellipse as a closed sub-path
+    pub fn ellipse(&mut self, centre: Vec2, radii: Vec2) {
+        self.move_to(Vec2(centre.0 + radii.0, centre.1));
+        self.arc_segments(centre, radii, (0.0, 1.0), 0.0, 2.0 * PI, None);
+        self.close();
+    }
+
+    /// Add a rectangle as a closed sub-path
+    ///
+    /// Corners are rounded with the given `radii` (which may be zero).
+    pub fn rect(&mut self, rect: Quad, radii: Vec2) {
+        let (Vec2(x0, y0), Vec2(x1, y1)) = (rect.a, rect.b);
+        let r = Vec2(
+            radii.0.max(0.0).min(0.5 * (x1 - x0).abs()),
+            radii.1.max(0.0).min(0.5 * (y1 - y0).abs()),
+        );
+        if r.0 == 0.0 || r.1 == 0.0 {
+            self.move_to(Vec2(x0, y0));
+            self.line_to(Vec2(x1, y0));
+            self.line_to(Vec2(x1, y1));
+            self.line_to(Vec2(x0, y1));
+        } else {
+            self.move_to(Vec2(x0 + r.0, y0));
+            self.line_to(Vec2(x1 - r.0, y0));
+            self.arc_to(r, 0.0, false, true, Vec2(x1, y0 + r.1));
+            self.line_to(Vec2(x1, y1 - r.1));
+            self.arc_to(r, 0.0, false, true, Vec2(x1 - r.0, y1));
+            self.line_to(Vec2(x0 + r.0, y1));
+            self.arc_to(r, 0.0, false, true, Vec2(x0, y1 - r.1));
+            self.line_to(Vec2(x0, y0 + r.1));
+            self.arc_to(r, 0.0, false, true, Vec2(x0 + r.0, y0));
+        }
+        self.close();
+    }
+
+    // Approximate an arc with cubics, each spanning at most a quarter turn
+    fn arc_segments(
+        &mut self,
+        centre: Vec2,
+        r: Vec2,
+        (sin, cos): (f32, f32),
+        theta: f32,
+        delta: f32,
+        end: Option<Vec2>,
+    ) {
+        let n = (delta.abs() / (0.5 * PI)).ceil().max(1.0) as usize;
+        let step = delta / n as f32;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        let map = |u: Vec2| {
+            let (x, y) = (u.0 * r.0, u.1 * r.1);
+            Vec2(cos * x - sin * y + centre.0, sin * x + cos * y + centre.1)
+        };
+        for i in 0..n {
+            let t1 = theta + step * i as f32;
+            let t2 = t1 + step;
+            let (s1, c1) = t1.sin_cos();
+            let (s2, c2) = t2.sin_cos();
+            let ctrl1 = map(Vec2(c1 - k * s1, s1 + k * c1));
+            let ctrl2 = map(Vec2(c2 + k * s2, s2 - k * c2));
+            let p = match end {
+                Some(p) if i + 1 == n => p,
+                _ => map(Vec2(c2, s2)),
+            };
+            self.cubic_to(ctrl1, ctrl2, p);
+        }
+    }
+
+    /// Flatten to a list of polylines
+    pub fn flatten(&self) -> Vec<Contour> {
+        self.flatten_map(|p| p)
+    }
+
+    /// Flatten to a list of polylines, after transforming points with `map`
+    ///
+    /// `map` must be affine; output coordinates should be in pixels.
+    pub(crate) fn flatten_map<F: Fn(Vec2) -> Vec2>(&self, map: F) -> Vec<Contour> {
+        let mut contours = vec![];
+        let mut points: Vec<Vec2> = vec![];
+        let mut last = Vec2::ZERO;
+        for seg in &self.segments {
+            match *seg {
+                Segment::Move(p) => {
+                    if points.len() > 1 {
+                        contours.push(Contour {
+                            points: std::mem::take(&mut points),
+                            closed: false,
+                        });
+                    }
+                    points.clear();
+                    last = map(p);
+                    points.push(last);
+                }
+                Segment::Line(p) => {
+                    last = map(p);
+                    points.push(last);
+                }
+                Segment::Cubic(c1, c2, p) => {
+                    let (p0, c1, c2, p3) = (last, map(c1), map(c2), map(p));
+                    // Subdivide into segments of roughly 1.5 pixels
+                    let len = dist(p0, c1) + dist(c1, c2) + dist(c2, p3);
+                    let n = ((len / 1.5).ceil() as usize).max(1).min(100);
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let u = 1.0 - t;
+                        let p = p0 * (u * u * u)
+                            + c1 * (3.0 * u * u * t)
+                            + c2 * (3.0 * u * t * t)
+                            + p3 * (t * t * t);
+                        points.push(p);
+                    }
+                    last = p3;
+                }
+                Segment::Close => {
+                    if points.len() > 1 {
+                        let start = points[0];
+                        contours.push(Contour {
+                            points: std::mem::take(&mut points),
+                            closed: true,
+                        });
+                        points.push(start);
+                        last = start;
+                    }
+                }
+            }
+        }
+        if points.len() > 1 {
+            contours.push(Contour {
+                points,
+                closed: false,
+            });
+        }
+        contours
+    }
+}
+
+fn dist(a: Vec2, b: Vec2) -> f32 {
+    (b - a).sum_square().sqrt()
+}
+
+/// Signed area of a polygon (positive when clockwise on screen)
+fn signed_area(poly: &[Vec2]) -> f32 {
+    let mut sum = 0.0;
+    for i in 0..poly.len() {
+        let (a, b) = (poly[i], poly[(i + 1) % poly.len()]);
+        sum += a.0 * b.1 - b.0 * a.1;
+    }
+    0.5 * sum
+}
+
+impl Stroke {
+    /// Construct with the given `width`
+    ///
+    /// Other properties take the SVG defaults: miter joins with a limit of 4
+    /// and butt caps.
+    pub fn new(width: f32) -> Self {
+        Stroke {
+            width,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+
+    /// Set the line join (chain style)
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Set the line cap (chain style)
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Set the miter limit (chain style)
+    pub fn with_miter_limit(mut self, limit: f32) -> Self {
+        self.miter_limit = limit;
+        self
+    }
+
+    /// Convert polylines to polygons covering their stroke
+    ///
+    /// All polygons are wound in the same direction, hence their union may be
+    /// filled with [`FillRule::NonZero`].
+    pub fn outline(&self, contours: &[Contour]) -> Vec<Vec<Vec2>> {
+        let hw = 0.5 * self.width;
+        let mut out = vec![];
+        if !(hw > 0.0) {
+            return out;
+        }
+
+        let n = ((hw * 4.0).ceil() as usize).max(8).min(64);
+        let mut push = |mut poly: Vec<Vec2>| {
+            let area = signed_area(&poly);
+            if area < 0.0 {
+                poly.reverse();
+            }
+            if area != 0.0 {
+                out.push(poly);
+            }
+        };
+        let disc = |c: Vec2| -> Vec<Vec2> {
+            (0..n)
+                .map(|i| {
+                    let a = 2.0 * PI * i as f32 / n as f32;
+                    c + Vec2(a.cos(), a.sin()) * hw
+                })
+                .collect()
+        };
+        // left normal of unit direction d, scaled to hw
+        let normal = |d: Vec2| Vec2(-d.1, d.0) * hw;
+
+        for contour in contours {
+            let mut points: Vec<Vec2> = Vec::with_capacity(contour.points.len());
+            for p in &contour.points {
+                if points.last().map(|q| dist(*q, *p) > 1e-4).unwrap_or(true) {
+                    points.push(*p);
+                }
+            }
+            let closed = contour.closed;
+            if closed && points.len() > 1 && dist(points[0], points[points.len() - 1]) <= 1e-4 {
+                points.pop();
+            }
+
+            if points.len() == 1 {
+                let p = points[0];
+                match self.cap {
+                    LineCap::Butt => (),
+                    LineCap::Round => push(disc(p)),
+                    LineCap::Square => push(vec![
+                        p + Vec2(-hw, -hw),
+                        p + Vec2(hw, -hw),
+                        p + Vec2(hw, hw),
+                        p + Vec2(-hw, hw),
+                    ]),
+                }
+                continue;
+            } else if points.is_empty() {
+                continue;
+            }
+
+            let len = points.len();
+            let num_segs = if closed { len } else { len - 1 };
+            let dir = |i: usize| {
+                let (a, b) = (points[i % len], points[(i + 1) % len]);
+                (b - a) * (1.0 / dist(a, b))
+            };
+
+            for i in 0..num_segs {
+                let (a, b) = (points[i], points[(i + 1) % len]);
+                let n = normal(dir(i));
+                push(vec![a + n, b + n, b - n, a - n]);
+            }
+
+            let joins = if closed { 0..len } else { 1..len - 1 };
+            for i in joins {
+                let p = points[i];
+                let d0 = dir((i + num_segs - 1) % num_segs);
+                let d1 = dir(i % num_segs);
+                let cross = d0.0 * d1.1 - d0.1 * d1.0;
+                let dot = d0.0 * d1.0 + d0.1 * d1.1;
+                if cross.abs() < 1e-6 && dot > 0.0 {
+                    continue;
+                }
+                if self.join == LineJoin::Round {
+                    push(disc(p));
+                    continue;
+                }
+                // The outer side is opposite the direction of turn
+                let s = if cross > 0.0 { -1.0 } else { 1.0 };
+                let (n0, n1) = (normal(d0) * s, normal(d1) * s);
+                let ratio = 1.0 / (0.5 * (1.0 + dot)).max(0.0).sqrt();
+                if self.join == LineJoin::Miter && ratio <= self.miter_limit {
+                    let bisector = n0 + n1;
+                    let m = p + bisector * (hw * ratio / dist(Vec2::ZERO, bisector));
+                    push(vec![p, p + n0, m, p + n1]);
+                } else {
+                    push(vec![p, p + n0, p + n1]);
+                }
+            }
+
+            if !closed {
+                let ends = [(points[0], -dir(0)), (points[len - 1], dir(len - 2))];
+                for &(p, d) in &ends {
+                    match self.cap {
+                        LineCap::Butt => (),
+                        LineCap::Round => push(disc(p)),
+                        LineCap::Square => {
+                            let (n, e) = (normal(d), d * hw);
+                            push(vec![p + n, p + n + e, p - n + e, p - n]);
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Polygons tessellated to triangles
+///
+/// This is a utility for implementations of [`DrawPath`] on the GPU. The
+/// filled region is decomposed into horizontal trapezoids, each of which is
+/// split into two triangles. Polygons may be self-intersecting.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tessellation {
+    /// Triangles covering the filled region
+    pub triangles: Vec<[Vec2; 3]>,
+    /// Boundary edges of the filled region
+    ///
+    /// Each edge `(a, b)` has the filled region on its right-hand side (in
+    /// screen coordinates, where y points down). These may be used to draw an
+    /// anti-aliased fringe outside the filled region.
+    pub edges: Vec<(Vec2, Vec2)>,
+}
+
+struct Edge {
+    top: Vec2,
+    bottom: Vec2,
+    dir: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let (t, b) = (self.top, self.bottom);
+        t.0 + (y - t.1) * (b.0 - t.0) / (b.1 - t.1)
+    }
+}
+
+impl Tessellation {
+    /// Tessellate polygons (all implicitly closed) using the given fill rule
+    pub fn new(polygons: &[Vec<Vec2>], rule: FillRule) -> Self {
+        let mut edges = vec![];
+        let mut ys = vec![];
+        for poly in polygons {
+            for i in 0..poly.len() {
+                let (a, b) = (poly[i], poly[(i + 1) % poly.len()]);
+                if a.1 == b.1 || !(a.0.is_finite() && a.1.is_finite()) {
+                    continue;
+                }
+                let (top, bottom, dir) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+                edges.push(Edge { top, bottom, dir });
+                ys.push(a.1);
+            }
+        }
+
+        // Bands must not contain edge intersections
+        for (i, e) in edges.iter().enumerate() {
+            for f in &edges[i + 1..] {
+                let y0 = e.top.1.max(f.top.1);
+                let y1 = e.bottom.1.min(f.bottom.1);
+                if y0 >= y1 {
+                    continue;
+                }
+                let d0 = e.x_at(y0) - f.x_at(y0);
+                let d1 = e.x_at(y1) - f.x_at(y1);
+                if (d0 < 0.0 && d1 > 0.0) || (d0 > 0.0 && d1 < 0.0) {
+                    ys.push(y0 + (y1 - y0) * d0 / (d0 - d1));
+                }
+            }
+        }
+        ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        ys.dedup();
+
+        let mut result = Tessellation::default();
+        // Filled spans along the bottom of the previous band
+        let mut prev_spans: Vec<(f32, f32)> = vec![];
+        let mut active: Vec<(f32, f32, f32, i32)> = vec![];
+        for band in ys.windows(2) {
+            let (y0, y1) = (band[0], band[1]);
+            let ym = 0.5 * (y0 + y1);
+
+            active.clear();
+            for e in &edges {
+                if e.top.1 <= y0 && y1 <= e.bottom.1 {
+                    active.push((e.x_at(y0), e.x_at(y1), e.x_at(ym), e.dir));
+                }
+            }
+            active.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal));
+
+            let mut spans = vec![];
+            let mut winding = 0;
+            let mut left = None;
+            for &(x0, x1, _, dir) in &active {
+                let was_inside = rule.is_inside(winding);
+                winding += dir;
+                let inside = rule.is_inside(winding);
+                if !was_inside && inside {
+                    left = Some((x0, x1));
+                } else if was_inside && !inside {
+                    if let Some((l0, l1)) = left {
+                        spans.push(((l0, l1), (x0, x1)));
+                    }
+                }
+            }
+
+            let top: Vec<_> = spans.iter().map(|(l, r)| (l.0, r.0)).collect();
+            result.horizontal_edges(&prev_spans, &top, y0);
+            for &((l0, l1), (r0, r1)) in &spans {
+                let (a, b) = (Vec2(l0, y0), Vec2(r0, y0));
+                let (c, d) = (Vec2(r1, y1), Vec2(l1, y1));
+                if b.0 > a.0 {
+                    result.triangles.push([a, b, c]);
+                }
+                if c.0 > d.0 {
+                    result.triangles.push([a, c, d]);
+                }
+                result.edges.push((d, a));
+                result.edges.push((b, c));
+            }
+            prev_spans = spans.iter().map(|(l, r)| (l.1, r.1)).collect();
+        }
+        if let Some(y) = ys.last() {
+            result.horizontal_edges(&prev_spans, &[], *y);
+        }
+        result
+    }
+
+    // Add horizontal boundary edges at `y` between spans `above` and `below`
+    fn horizontal_edges(&mut self, above: &[(f32, f32)], below: &[(f32, f32)], y: f32) {
+        // Filled above only: the edge runs right-to-left
+        for (x0, x1) in subtract(above, below) {
+            self.edges.push((Vec2(x1, y), Vec2(x0, y)));
+        }
+        for (x0, x1) in subtract(below, above) {
+            self.edges.push((Vec2(x0, y), Vec2(x1, y)));
+        }
+    }
+}
+
+/// Subtract sorted, disjoint intervals `b` from `a`
+fn subtract(a: &[(f32, f32)], b: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let mut out = vec![];
+    for &(mut x0, x1) in a {
+        for &(y0, y1) in b {
+            if y1 <= x0 || y0 >= x1 {
+                continue;
+            }
+            if y0 > x0 {
+                out.push((x0, y0));
+            }
+            x0 = y1;
+        }
+        if x0 < x1 {
+            out.push((x0, x1));
+        }
+    }
+    out
+}
+
+/// A coverage mask
+///
+/// This is a utility for software implementations of [`DrawPath`]: polygons
+/// are rasterised to per-pixel coverage values (in the range `[0, 1]`), using
+/// exact horizontal coverage over several sub-scanlines per pixel row.
+#[derive(Clone, Debug)]
+pub struct CoverageMask {
+    rect: Rect,
+    coverage: Vec<f32>,
+}
+
+impl CoverageMask {
+    /// Construct an empty mask over the given pixel region
+    pub fn new(rect: Rect) -> Self {
+        let len = rect.size.0 as usize * rect.size.1 as usize;
+        CoverageMask {
+            rect,
+            coverage: vec![0.0; len],
+        }
+    }
+
+    /// The region covered by the mask
+    #[inline]
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Reset coverage to zero
+    pub fn clear(&mut self) {
+        for c in &mut self.coverage {
+            *c = 0.0;
+        }
+    }
+
+    /// Get coverage of pixel `(x, y)`
+    ///
+    /// Returns zero outside of the mask's region.
+    pub fn get(&self, x: i32, y: i32) -> f32 {
+        let (x, y) = (x - self.rect.pos.0, y - self.rect.pos.1);
+        let size = self.rect.size;
+        if x < 0 || y < 0 || x >= size.0 as i32 || y >= size.1 as i32 {
+            return 0.0;
+        }
+        self.coverage[y as usize * size.0 as usize + x as usize].min(1.0)
+    }
+
+    /// Accumulate coverage of polygons (all implicitly closed)
+    pub fn fill(&mut self, polygons: &[Vec<Vec2>], rule: FillRule) {
+        let origin = Vec2::from(self.rect.pos);
+        let mut edges = vec![];
+        for poly in polygons {
+            for i in 0..poly.len() {
+                let a = poly[i] - origin;
+                let b = poly[(i + 1) % poly.len()] - origin;
+                if a.1 != b.1 {
+                    edges.push((a, b));
+                }
+            }
+        }
+        if edges.is_empty() {
+            return;
+        }
+
+        let Size(w, h) = self.rect.size;
+        let (w, h) = (w as usize, h as usize);
+        let weight = 1.0 / SUB_SAMPLES as f32;
+        let mut crossings: Vec<(f32, i32)> = vec![];
+        for y in 0..h {
+            let row = &mut self.coverage[y * w..(y + 1) * w];
+            for s in 0..SUB_SAMPLES {
+                let sy = y as f32 + (s as f32 + 0.5) * weight;
+                crossings.clear();
+                for (a, b) in &edges {
+                    let (top, bottom, dir) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+                    if sy >= top.1 && sy < bottom.1 {
+                        let x = top.0 + (sy - top.1) * (bottom.0 - top.0) / (bottom.1 - top.1);
+                        crossings.push((x, dir));
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+                let mut winding = 0;
+                let mut start = 0.0;
+                for &(x, dir) in &crossings {
+                    let was_inside = rule.is_inside(winding);
+                    winding += dir;
+                    let inside = rule.is_inside(winding);
+                    if !was_inside && inside {
+                        start = x;
+                    } else if was_inside && !inside {
+                        add_span(row, start, x, weight);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Add coverage `weight` to `row` over the span `x0..x1`
+fn add_span(row: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let x0 = x0.max(0.0);
+    let x1 = x1.min(row.len() as f32);
+    if x0 >= x1 {
+        return;
+    }
+    let (i0, i1) = (x0 as usize, x1 as usize);
+    if i0 == i1 {
+        row[i0] += (x1 - x0) * weight;
+        return;
+    }
+    row[i0] += (i0 as f32 + 1.0 - x0) * weight;
+    for c in &mut row[i0 + 1..i1] {
+        *c += weight;
+    }
+    if i1 < row.len() {
+        row[i1] += (x1 - i1 as f32) * weight;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Coord;
+
+    fn area(tess: &Tessellation) -> f32 {
+        tess.triangles.iter().map(|t| signed_area(t).abs()).sum()
+    }
+
+    #[test]
+    fn tessellate() {
+        let square = |x: f32, s: f32| {
+            vec![
+                Vec2(x, x),
+                Vec2(x + s, x),
+                Vec2(x + s, x + s),
+                Vec2(x, x + s),
+            ]
+        };
+        let polys = vec![square(0.0, 4.0), square(1.0, 2.0)];
+        let tess = Tessellation::new(&polys, FillRule::NonZero);
+        assert!((area(&tess) - 16.0).abs() < 1e-4);
+        let tess = Tessellation::new(&polys, FillRule::EvenOdd);
+        assert!((area(&tess) - 12.0).abs() < 1e-4);
+        // outer and inner boundaries: 4 + 4 edges, split at y = 1 and y = 3
+        let len: f32 = tess.edges.iter().map(|(a, b)| dist(*a, *b)).sum();
+        assert!((len - 24.0).abs() < 1e-4);
+
+        // A bow-tie: self-intersecting
+        let bow = vec![vec![
+            Vec2(0.0, 0.0),
+            Vec2(2.0, 2.0),
+            Vec2(2.0, 0.0),
+            Vec2(0.0, 2.0),
+        ]];
+        let tess = Tessellation::new(&bow, FillRule::NonZero);
+        assert!((area(&tess) - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn stroke() {
+        let contour = Contour {
+            points: vec![Vec2(2.0, 2.0), Vec2(6.0, 2.0)],
+            closed: false,
+        };
+        let stroke = Stroke::new(2.0);
+        let mut mask = CoverageMask::new(Rect::new(Coord(0, 0), Size(8, 4)));
+        mask.fill(&stroke.outline(&[contour.clone()]), FillRule::NonZero);
+        assert_eq!(mask.get(2, 1), 1.0);
+        assert_eq!(mask.get(5, 2), 1.0);
+        assert_eq!(mask.get(1, 1), 0.0);
+        assert_eq!(mask.get(6, 1), 0.0);
+        assert_eq!(mask.get(4, 3), 0.0);
+
+        let stroke = stroke.with_cap(LineCap::Square);
+        mask.clear();
+        mask.fill(&stroke.outline(&[contour]), FillRule::NonZero);
+        assert_eq!(mask.get(1, 1), 1.0);
+        assert_eq!(mask.get(6, 2), 1.0);
+        assert_eq!(mask.get(0, 1), 0.0);
+    }
+
+    #[test]
+    fn path() {
+        let mut path = Path::new();
+        path.arc(Vec2(0.0, 0.0), Vec2(2.0, 2.0), 0.0, PI);
+        path.close();
+        let contours = path.flatten();
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].closed);
+        let last = *contours[0].points.last().unwrap();
+        assert!(dist(last, Vec2(-2.0, 0.0)) < 1e-4);
+        // clockwise on screen: passes through (0, 2)
+        assert!(contours[0]
+            .points
+            .iter()
+            .any(|p| dist(*p, Vec2(0.0, 2.0)) < 0.1));
+    }
+}
@@ -0,0 +1,262 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Standard icons
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::{Icon, IconTheme, Svg};
+
+/// Identifiers for common icons
+///
+/// These are resolved from the user's icon theme (see [`IconTheme`]) where
+/// possible, falling back to a small built-in set. Use [`StandardIcon::icon`]
+/// or `Icon::from` to resolve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StandardIcon {
+    New,
+    Open,
+    Save,
+    Close,
+    Undo,
+    Redo,
+    Cut,
+    Copy,
+    Paste,
+    Delete,
+    Find,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Information,
+    Warning,
+    Error,
+    Question,
+}
+
+/// Nominal size used for icon theme lookups
+///
+/// Icons are drawn at the line height, which is typically close to this.
+const LOOKUP_SIZE: u32 = 24;
+
+#[derive(Default)]
+struct Resolver {
+    theme: Option<Option<IconTheme>>,
+    cache: HashMap<StandardIcon, Icon>,
+}
+
+thread_local! {
+    static RESOLVER: RefCell<Resolver> = RefCell::new(Resolver::default());
+}
+
+impl StandardIcon {
+    /// The freedesktop icon name
+    pub fn name(self) -> &'static str {
+        use StandardIcon::*;
+        match self {
+            New => "document-new",
+            Open => "document-open",
+            Save => "document-save",
+            Close => "window-close",
+            Undo => "edit-undo",
+            Redo => "edit-redo",
+            Cut => "edit-cut",
+            Copy => "edit-copy",
+            Paste => "edit-paste",
+            Delete => "edit-delete",
+            Find => "edit-find",
+            ArrowUp => "go-up",
+            ArrowDown => "go-down",
+            ArrowLeft => "go-previous",
+            ArrowRight => "go-next",
+            Information => "dialog-information",
+            Warning => "dialog-warning",
+            Error => "dialog-error",
+            Question => "dialog-question",
+        }
+    }
+
+    /// The built-in version of this icon
+    pub fn builtin(self) -> Svg {
+        Svg::parse(builtin_source(self)).expect("built-in icon")
+    }
+
+    /// Resolve to an icon
+    ///
+    /// The icon is looked up in the current icon theme on first use (see
+    /// [`StandardIcon::set_theme`]), falling back to [`StandardIcon::builtin`].
+    /// The result is cached (per thread).
+    pub fn icon(self) -> Icon {
+        RESOLVER.with(|r| {
+            let mut r = r.borrow_mut();
+            if let Some(icon) = r.cache.get(&self) {
+                return icon.clone();
+            }
+            let theme = r.theme.get_or_insert_with(|| Some(IconTheme::system()));
+            let icon = theme
+                .as_ref()
+                .and_then(|theme| theme.load(self.name(), LOOKUP_SIZE))
+                .unwrap_or_else(|| Icon::Svg(self.builtin()));
+            r.cache.insert(self, icon.clone());
+            icon
+        })
+    }
+
+    /// Set the icon theme used to resolve standard icons
+    ///
+    /// By default, [`IconTheme::system`] is used. If `None` is passed, only
+    /// built-in icons are used. This affects subsequent calls to
+    /// [`StandardIcon::icon`] on the current thread; icons already in use by
+    /// widgets are not updated.
+    pub fn set_theme(theme: Option<IconTheme>) {
+        RESOLVER.with(|r| {
+            let mut r = r.borrow_mut();
+            r.theme = Some(theme);
+            r.cache.clear();
+        });
+    }
+}
+
+impl From<StandardIcon> for Icon {
+    fn from(icon: StandardIcon) -> Self {
+        icon.icon()
+    }
+}
+
+macro_rules! icon_svg {
+    ($($body:expr),*) => {
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" "#,
+            r#"fill="none" stroke="black" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">"#,
+            $($body,)*
+            "</svg>"
+        )
+    };
+}
+
+/// A dot (filled circle), used by several icons
+macro_rules! dot {
+    ($cx:expr, $cy:expr) => {
+        concat!(
+            r#"<circle cx=""#,
+            $cx,
+            r#"" cy=""#,
+            $cy,
+            r#"" r="1.25" fill="black" stroke="none"/>"#
+        )
+    };
+}
+
+fn builtin_source(icon: StandardIcon) -> &'static str {
+    use StandardIcon::*;
+    match icon {
+        New => icon_svg!(
+            r#"<path d="M6 3h8l4 4v14H6z"/>"#,
+            r#"<path d="M14 3v4h4"/>"#
+        ),
+        Open => icon_svg!(
+            r#"<path d="M3 5h6l2 2h10v12H3z"/>"#,
+            r#"<path d="M3 10h18"/>"#
+        ),
+        Save => icon_svg!(
+            r#"<path d="M4 4h13l3 3v13H4z"/>"#,
+            r#"<path d="M8 4v5h8V4"/>"#,
+            r#"<path d="M8 20v-6h8v6"/>"#
+        ),
+        Close => icon_svg!(r#"<path d="M6 6l12 12M18 6L6 18"/>"#),
+        Undo => icon_svg!(
+            r#"<path d="M9 14L4 9l5-5"/>"#,
+            r#"<path d="M4 9h10a6 6 0 0 1 0 12h-3"/>"#
+        ),
+        Redo => icon_svg!(
+            r#"<path d="M15 14l5-5-5-5"/>"#,
+            r#"<path d="M20 9H10a6 6 0 0 0 0 12h3"/>"#
+        ),
+        Cut => icon_svg!(
+            r#"<circle cx="6" cy="18" r="3"/>"#,
+            r#"<circle cx="18" cy="18" r="3"/>"#,
+            r#"<path d="M8 15.5L18 3M16 15.5L6 3"/>"#
+        ),
+        Copy => icon_svg!(
+            r#"<rect x="9" y="9" width="12" height="12" rx="1"/>"#,
+            r#"<path d="M5 15H3V3h12v2"/>"#
+        ),
+        Paste => icon_svg!(
+            r#"<path d="M8 4H5v17h14V4h-3"/>"#,
+            r#"<rect x="8" y="2" width="8" height="4" rx="1"/>"#
+        ),
+        Delete => icon_svg!(
+            r#"<path d="M4 6h16M9 6V3h6v3"/>"#,
+            r#"<path d="M6 6l1 15h10l1-15"/>"#
+        ),
+        Find => icon_svg!(
+            r#"<circle cx="10" cy="10" r="6"/>"#,
+            r#"<path d="M15 15l6 6"/>"#
+        ),
+        ArrowUp => icon_svg!(r#"<path d="M6 15l6-6 6 6"/>"#),
+        ArrowDown => icon_svg!(r#"<path d="M6 9l6 6 6-6"/>"#),
+        ArrowLeft => icon_svg!(r#"<path d="M15 6l-6 6 6 6"/>"#),
+        ArrowRight => icon_svg!(r#"<path d="M9 6l6 6-6 6"/>"#),
+        Information => icon_svg!(
+            r#"<circle cx="12" cy="12" r="10"/>"#,
+            r#"<path d="M12 11v6"/>"#,
+            dot!("12", "7.5")
+        ),
+        Warning => icon_svg!(
+            r#"<path d="M12 3l10 18H2z"/>"#,
+            r#"<path d="M12 10v5"/>"#,
+            dot!("12", "18")
+        ),
+        Error => icon_svg!(
+            r#"<circle cx="12" cy="12" r="10"/>"#,
+            r#"<path d="M8 8l8 8M16 8l-8 8"/>"#
+        ),
+        Question => icon_svg!(
+            r#"<circle cx="12" cy="12" r="10"/>"#,
+            r#"<path d="M9 9.5a3 3 0 1 1 3 3v1.5"/>"#,
+            dot!("12", "17.5")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Vec2;
+
+    #[test]
+    fn builtin() {
+        use StandardIcon::*;
+        for icon in &[
+            New,
+            Open,
+            Save,
+            Close,
+            Undo,
+            Redo,
+            Cut,
+            Copy,
+            Paste,
+            Delete,
+            Find,
+            ArrowUp,
+            ArrowDown,
+            ArrowLeft,
+            ArrowRight,
+            Information,
+            Warning,
+            Error,
+            Question,
+        ] {
+            let svg = icon.builtin();
+            assert_eq!(svg.size(), Vec2(24.0, 24.0));
+        }
+
+        StandardIcon::set_theme(None);
+        assert!(matches!(StandardIcon::Save.icon(), Icon::Svg(_)));
+    }
+}
@@ -0,0 +1,970 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Vector images (SVG subset)
+//!
+//! Parsing and CPU rasterisation of a subset of SVG sufficient for most icons:
+//!
+//! -   elements `svg`, `g`, `path`, `rect`, `circle`, `ellipse`, `line`,
+//!     `polyline` and `polygon`; other elements and their children are ignored
+//! -   attributes `fill`, `fill-rule`, `fill-opacity`, `stroke`,
+//!     `stroke-width`, `stroke-opacity`, `stroke-linejoin`, `stroke-linecap`,
+//!     `stroke-miterlimit`, `opacity` and `transform`, either as
+//!     presentation attributes or within `style`
+//! -   colours as `#rgb`, `#rrggbb`, `rgb(r, g, b)` or a few common names;
+//!     `currentColor` is treated as black
+//!
+//! Gradients, clipping, masks, text and CSS style-sheets are not supported.
+
+use std::cell::Cell;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+
+use super::{CoverageMask, FillRule, ImageData, LineCap, LineJoin, Path, Stroke};
+use crate::geom::{Coord, Quad, Rect, Size, Vec2};
+
+/// Error parsing an [`Svg`]
+#[derive(Debug)]
+pub enum SvgError {
+    /// Failure reading the file
+    Io(io::Error),
+    /// Malformed or unsupported document
+    Parse(String),
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SvgError::Io(e) => write!(f, "failed to read SVG: {}", e),
+            SvgError::Parse(msg) => write!(f, "SVG parse error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+impl From<io::Error> for SvgError {
+    fn from(e: io::Error) -> Self {
+        SvgError::Io(e)
+    }
+}
+
+/// A vector image
+///
+/// This is a parsed subset of SVG (see the module documentation), rasterised
+/// on the CPU by [`Svg::rasterise`]. Clones share the same data.
+///
+/// Usually vector images are drawn via [`super::DrawHandle::icon`], which
+/// rasterises at the target size (in physical pixels) and caches the result.
+#[derive(Clone)]
+pub struct Svg(Rc<SvgData>);
+
+struct SvgData {
+    id: u32,
+    size: Vec2,
+    view_box: (Vec2, Vec2),
+    preserve_aspect: bool,
+    shapes: Vec<Shape>,
+}
+
+thread_local! {
+    static NEXT_ID: Cell<u32> = Cell::new(0);
+}
+
+impl fmt::Debug for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Svg {{ id: {}, size: {:?}, shapes: {}, .. }}",
+            self.0.id,
+            self.0.size,
+            self.0.shapes.len()
+        )
+    }
+}
+
+impl PartialEq for Svg {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.0.id == rhs.0.id
+    }
+}
+
+impl Svg {
+    /// Parse from SVG source
+    pub fn parse(source: &str) -> Result<Self, SvgError> {
+        let mut data = parse_document(source)?;
+        data.id = NEXT_ID.with(|id| {
+            let n = id.get();
+            id.set(n + 1);
+            n
+        });
+        Ok(Svg(Rc::new(data)))
+    }
+
+    /// Load and parse an SVG file
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SvgError> {
+        let source = std::fs::read_to_string(path)?;
+        Svg::parse(&source)
+    }
+
+    /// A unique identifier
+    ///
+    /// Clones share the same identifier; separately parsed images do not.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.0.id
+    }
+
+    /// The intrinsic size, in logical pixels
+    ///
+    /// This is taken from the `width` and `height` attributes if present,
+    /// otherwise from the `viewBox`. Multiply by
+    /// [`super::SizeHandle::scale_factor`] to get the size in physical pixels.
+    #[inline]
+    pub fn size(&self) -> Vec2 {
+        self.0.size
+    }
+
+    /// Rasterise to an image of the given `size` (in pixels)
+    ///
+    /// The image is scaled to fit `size`, preserving its aspect ratio unless
+    /// `preserveAspectRatio="none"` was specified.
+    pub fn rasterise(&self, size: Size) -> ImageData {
+        let (w, h) = (size.0 as usize, size.1 as usize);
+        let mut pixels = vec![[0f32; 4]; w * h];
+
+        let (vb_pos, vb_size) = self.0.view_box;
+        if w > 0 && h > 0 && vb_size.0 > 0.0 && vb_size.1 > 0.0 {
+            let mut scale = Vec2::from(size) / vb_size;
+            let mut offset = Vec2::ZERO;
+            if self.0.preserve_aspect {
+                scale = Vec2::splat(scale.min_comp());
+                offset = (Vec2::from(size) - vb_size * scale) * 0.5;
+            }
+            let offset = offset - vb_pos * scale;
+            let view = Transform([scale.0, 0.0, 0.0, scale.1, offset.0, offset.1]);
+
+            let mut mask = CoverageMask::new(Rect::new(Coord::ZERO, size));
+            for shape in &self.0.shapes {
+                let transform = view.then(&shape.transform);
+                let contours = shape.path.flatten_map(|p| transform.apply(p));
+
+                if let Some(col) = shape.fill {
+                    let polys: Vec<_> = contours.iter().map(|c| c.points.clone()).collect();
+                    mask.clear();
+                    mask.fill(&polys, shape.fill_rule);
+                    composite(&mask, &mut pixels, col);
+                }
+                if let Some(col) = shape.stroke {
+                    let mut stroke = shape.stroke_style;
+                    stroke.width *= transform.scale();
+                    mask.clear();
+                    mask.fill(&stroke.outline(&contours), FillRule::NonZero);
+                    composite(&mask, &mut pixels, col);
+                }
+            }
+        }
+
+        let mut data = Vec::with_capacity(4 * w * h);
+        for p in pixels {
+            let a = p[3].min(1.0);
+            let f = if a > 0.0 { 255.0 / a } else { 0.0 };
+            let c = |x: f32| (x * f).round().max(0.0).min(255.0) as u8;
+            data.extend_from_slice(&[c(p[0]), c(p[1]), c(p[2]), (a * 255.0).round() as u8]);
+        }
+        ImageData::new(size, data)
+    }
+}
+
+/// An affine transform `[a, b, c, d, e, f]` mapping
+/// `(x, y)` to `(a*x + c*y + e, b*x + d*y + f)`
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Transform([f32; 6]);
+
+impl Transform {
+    const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn apply(&self, p: Vec2) -> Vec2 {
+        let m = &self.0;
+        Vec2(
+            m[0] * p.0 + m[2] * p.1 + m[4],
+            m[1] * p.0 + m[3] * p.1 + m[5],
+        )
+    }
+
+    /// The transform applying `rhs` first, then `self`
+    fn then(&self, rhs: &Transform) -> Transform {
+        let (l, r) = (&self.0, &rhs.0);
+        Transform([
+            l[0] * r[0] + l[2] * r[1],
+            l[1] * r[0] + l[3] * r[1],
+            l[0] * r[2] + l[2] * r[3],
+            l[1] * r[2] + l[3] * r[3],
+            l[0] * r[4] + l[2] * r[5] + l[4],
+            l[1] * r[4] + l[3] * r[5] + l[5],
+        ])
+    }
+
+    /// Approximate (geometric mean) scale factor
+    fn scale(&self) -> f32 {
+        let m = &self.0;
+        (m[0] * m[3] - m[1] * m[2]).abs().sqrt()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Shape {
+    path: Path,
+    transform: Transform,
+    /// Fill colour: sRGB with straight alpha
+    fill: Option<[f32; 4]>,
+    fill_rule: FillRule,
+    /// Stroke colour: sRGB with straight alpha
+    stroke: Option<[f32; 4]>,
+    /// Stroke style, in user units
+    stroke_style: Stroke,
+}
+
+/// Composite `col` (sRGB, straight alpha) onto pre-multiplied `pixels`
+fn composite(mask: &CoverageMask, pixels: &mut [[f32; 4]], col: [f32; 4]) {
+    let w = mask.rect().size.0 as usize;
+    for (i, p) in pixels.iter_mut().enumerate() {
+        let a = mask.get((i % w) as i32, (i / w) as i32) * col[3];
+        if a > 0.0 {
+            let f = 1.0 - a;
+            p[0] = col[0] * a + p[0] * f;
+            p[1] = col[1] * a + p[1] * f;
+            p[2] = col[2] * a + p[2] * f;
+            p[3] = a + p[3] * f;
+        }
+    }
+}
+
+/// Inherited style properties
+#[derive(Clone, Debug)]
+struct Style {
+    fill: Option<[f32; 3]>,
+    fill_rule: FillRule,
+    fill_opacity: f32,
+    stroke: Option<[f32; 3]>,
+    stroke_style: Stroke,
+    stroke_opacity: f32,
+    /// Product of `opacity` of this element and all ancestors
+    opacity: f32,
+    transform: Transform,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some([0.0; 3]),
+            fill_rule: FillRule::NonZero,
+            fill_opacity: 1.0,
+            stroke: None,
+            stroke_style: Stroke::new(1.0),
+            stroke_opacity: 1.0,
+            opacity: 1.0,
+            transform: Transform::IDENTITY,
+        }
+    }
+}
+
+impl Style {
+    fn set(&mut self, name: &str, value: &str) -> Result<(), SvgError> {
+        let value = value.trim();
+        match name {
+            "fill" => self.fill = parse_paint(value)?,
+            "fill-rule" => {
+                self.fill_rule = match value {
+                    "evenodd" => FillRule::EvenOdd,
+                    _ => FillRule::NonZero,
+                }
+            }
+            "fill-opacity" => self.fill_opacity = parse_number(value)?,
+            "stroke" => self.stroke = parse_paint(value)?,
+            "stroke-width" => self.stroke_style.width = parse_number(value)?,
+            "stroke-linejoin" => {
+                self.stroke_style.join = match value {
+                    "round" => LineJoin::Round,
+                    "bevel" => LineJoin::Bevel,
+                    _ => LineJoin::Miter,
+                }
+            }
+            "stroke-linecap" => {
+                self.stroke_style.cap = match value {
+                    "round" => LineCap::Round,
+                    "square" => LineCap::Square,
+                    _ => LineCap::Butt,
+                }
+            }
+            "stroke-miterlimit" => self.stroke_style.miter_limit = parse_number(value)?,
+            "stroke-opacity" => self.stroke_opacity = parse_number(value)?,
+            "opacity" => self.opacity *= parse_number(value)?,
+            "transform" => self.transform = self.transform.then(&parse_transform(value)?),
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Apply presentation attributes, then the `style` attribute
+    fn apply(&mut self, attrs: &[(&str, String)]) -> Result<(), SvgError> {
+        for (name, value) in attrs {
+            self.set(name, value)?;
+        }
+        if let Some(style) = get(attrs, "style") {
+            for decl in style.split(';') {
+                if let Some(i) = decl.find(':') {
+                    self.set(decl[..i].trim(), &decl[i + 1..])?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn shape(&self, path: Path) -> Shape {
+        let colour = |c: Option<[f32; 3]>, opacity: f32| {
+            c.map(|c| [c[0], c[1], c[2], (opacity * self.opacity).max(0.0).min(1.0)])
+        };
+        Shape {
+            path,
+            transform: self.transform,
+            fill: colour(self.fill, self.fill_opacity),
+            fill_rule: self.fill_rule,
+            stroke: colour(self.stroke, self.stroke_opacity),
+            stroke_style: self.stroke_style,
+        }
+    }
+}
+
+/// Elements whose content is ignored
+const SKIPPED: &[&str] = &[
+    "defs",
+    "clipPath",
+    "mask",
+    "symbol",
+    "pattern",
+    "marker",
+    "linearGradient",
+    "radialGradient",
+    "style",
+    "script",
+    "title",
+    "desc",
+    "metadata",
+    "text",
+];
+
+enum Tag<'a> {
+    Open(&'a str, Vec<(&'a str, String)>, bool),
+    Close,
+}
+
+fn get<'a>(attrs: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn get_number(attrs: &[(&str, String)], name: &str) -> Result<f32, SvgError> {
+    get(attrs, name).map(parse_number).unwrap_or(Ok(0.0))
+}
+
+fn parse_document(source: &str) -> Result<SvgData, SvgError> {
+    let mut data = SvgData {
+        id: 0,
+        size: Vec2::ZERO,
+        view_box: (Vec2::ZERO, Vec2::ZERO),
+        preserve_aspect: true,
+        shapes: vec![],
+    };
+    let mut found_root = false;
+    let mut stack: Vec<Style> = vec![];
+    let mut skip_depth = 0;
+
+    let mut rest = source;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let (tag, len) = next_tag(rest)?;
+        rest = &rest[len..];
+        let tag = match tag {
+            Some(tag) => tag,
+            None => continue,
+        };
+
+        match tag {
+            Tag::Close => {
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                } else {
+                    stack.pop();
+                }
+            }
+            Tag::Open(name, attrs, empty) => {
+                if skip_depth > 0 || SKIPPED.contains(&name) {
+                    if !empty {
+                        skip_depth += 1;
+                    }
+                    continue;
+                }
+
+                let mut style = stack.last().cloned().unwrap_or_default();
+                style.apply(&attrs)?;
+
+                if !found_root {
+                    if name != "svg" {
+                        return Err(SvgError::Parse(format!("unexpected root <{}>", name)));
+                    }
+                    found_root = true;
+                    parse_root(&mut data, &attrs)?;
+                } else if let Some(path) = parse_shape(name, &attrs)? {
+                    data.shapes.push(style.shape(path));
+                }
+
+                if !empty {
+                    stack.push(style);
+                }
+            }
+        }
+    }
+
+    if !found_root {
+        return Err(SvgError::Parse("no <svg> element".into()));
+    }
+    Ok(data)
+}
+
+fn parse_root(data: &mut SvgData, attrs: &[(&str, String)]) -> Result<(), SvgError> {
+    let width = get(attrs, "width").map(parse_number).transpose()?;
+    let height = get(attrs, "height").map(parse_number).transpose()?;
+    if let Some(vb) = get(attrs, "viewBox") {
+        let mut p = Parser::new(vb);
+        let pos = Vec2(p.number()?, p.number()?);
+        let size = Vec2(p.number()?, p.number()?);
+        data.view_box = (pos, size);
+    } else {
+        let size = Vec2(width.unwrap_or(0.0), height.unwrap_or(0.0));
+        data.view_box = (Vec2::ZERO, size);
+    }
+    let vb_size = data.view_box.1;
+    data.size = Vec2(width.unwrap_or(vb_size.0), height.unwrap_or(vb_size.1));
+    if let Some(par) = get(attrs, "preserveAspectRatio") {
+        data.preserve_aspect = par.trim() != "none";
+    }
+    Ok(())
+}
+
+fn parse_shape(name: &str, attrs: &[(&str, String)]) -> Result<Option<Path>, SvgError> {
+    let num = |attr| get_number(attrs, attr);
+    let mut path = Path::new();
+    match name {
+        "path" => {
+            if let Some(d) = get(attrs, "d") {
+                parse_path_data(&mut path, d)?;
+            }
+        }
+        "rect" => {
+            let pos = Vec2(num("x")?, num("y")?);
+            let size = Vec2(num("width")?, num("height")?);
+            let rx = get(attrs, "rx").map(parse_number).transpose()?;
+            let ry = get(attrs, "ry").map(parse_number).transpose()?;
+            let r = Vec2(rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0));
+            if size.0 > 0.0 && size.1 > 0.0 {
+                path.rect(
+                    Quad {
+                        a: pos,
+                        b: pos + size,
+                    },
+                    r,
+                );
+            }
+        }
+        "circle" => {
+            let r = num("r")?;
+            if r > 0.0 {
+                path.ellipse(Vec2(num("cx")?, num("cy")?), Vec2::splat(r));
+            }
+        }
+        "ellipse" => {
+            let r = Vec2(num("rx")?, num("ry")?);
+            if r.0 > 0.0 && r.1 > 0.0 {
+                path.ellipse(Vec2(num("cx")?, num("cy")?), r);
+            }
+        }
+        "line" => {
+            path.move_to(Vec2(num("x1")?, num("y1")?));
+            path.line_to(Vec2(num("x2")?, num("y2")?));
+        }
+        "polyline" | "polygon" => {
+            let mut p = Parser::new(get(attrs, "points").unwrap_or(""));
+            let mut first = true;
+            while !p.at_end() {
+                let v = Vec2(p.number()?, p.number()?);
+                if first {
+                    path.move_to(v);
+                    first = false;
+                } else {
+                    path.line_to(v);
+                }
+            }
+            if name == "polygon" && !first {
+                path.close();
+            }
+        }
+        _ => return Ok(None),
+    }
+    Ok(Some(path))
+}
+
+/// Parse the next tag, returning `(tag, bytes consumed)`
+///
+/// Comments, declarations and processing instructions yield `None`.
+fn next_tag(s: &str) -> Result<(Option<Tag<'_>>, usize), SvgError> {
+    let skip_to = |pat: &str| {
+        s.find(pat)
+            .map(|i| (None, i + pat.len()))
+            .ok_or_else(|| SvgError::Parse("unterminated markup".into()))
+    };
+    if s.starts_with("<!--") {
+        return skip_to("-->");
+    } else if s.starts_with("<![CDATA[") {
+        return skip_to("]]>");
+    } else if s.starts_with("<?") {
+        return skip_to("?>");
+    } else if s.starts_with("<!") {
+        return skip_to(">");
+    }
+
+    let bytes = s.as_bytes();
+    let mut i = 1;
+    let close = bytes.get(i) == Some(&b'/');
+    if close {
+        i += 1;
+    }
+    let name_start = i;
+    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"/>".contains(&bytes[i]) {
+        i += 1;
+    }
+    let name = &s[name_start..i];
+    if name.is_empty() {
+        return Err(SvgError::Parse("missing tag name".into()));
+    }
+
+    let mut attrs = vec![];
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => return Err(SvgError::Parse(format!("unterminated tag <{}>", name))),
+            Some(b'>') => {
+                let tag = match close {
+                    true => Tag::Close,
+                    false => Tag::Open(name, attrs, false),
+                };
+                return Ok((Some(tag), i + 1));
+            }
+            Some(b'/') if bytes.get(i + 1) == Some(&b'>') => {
+                return Ok((Some(Tag::Open(name, attrs, true)), i + 2));
+            }
+            Some(_) => (),
+        }
+
+        let attr_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"=/>".contains(&bytes[i]) {
+            i += 1;
+        }
+        let attr = &s[attr_start..i];
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if bytes.get(i) != Some(&b'=') {
+            // Attribute without value (not valid XML); ignore
+            if attr.is_empty() {
+                i += 1;
+            }
+            continue;
+        }
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let quote = match bytes.get(i) {
+            Some(q @ b'"') | Some(q @ b'\'') => *q,
+            _ => return Err(SvgError::Parse(format!("unquoted attribute {}", attr))),
+        };
+        i += 1;
+        let value_start = i;
+        while i < bytes.len() && bytes[i] != quote {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return Err(SvgError::Parse(format!("unterminated attribute {}", attr)));
+        }
+        attrs.push((attr, unescape(&s[value_start..i])));
+        i += 1;
+    }
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parse a number or length (any unit suffix is ignored)
+fn parse_number(s: &str) -> Result<f32, SvgError> {
+    Parser::new(s).number()
+}
+
+fn parse_paint(s: &str) -> Result<Option<[f32; 3]>, SvgError> {
+    let hex = |s: &str| u8::from_str_radix(s, 16).ok();
+    let rgb = |r: u8, g: u8, b: u8| Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]);
+    let invalid = || SvgError::Parse(format!("invalid colour: {}", s));
+
+    if s.starts_with('#') {
+        let s = &s[1..];
+        return match s.len() {
+            3 => {
+                let c = |i| hex(&s[i..i + 1]).map(|x| x * 17).ok_or_else(invalid);
+                Ok(rgb(c(0)?, c(1)?, c(2)?))
+            }
+            6 => {
+                let c = |i| hex(&s[i..i + 2]).ok_or_else(invalid);
+                Ok(rgb(c(0)?, c(2)?, c(4)?))
+            }
+            _ => Err(invalid()),
+        };
+    }
+    if s.starts_with("rgb(") && s.ends_with(')') {
+        let mut c = [0.0; 3];
+        for (i, part) in s[4..s.len() - 1].split(',').enumerate().take(3) {
+            let part = part.trim();
+            c[i] = if part.ends_with('%') {
+                parse_number(&part[..part.len() - 1])? / 100.0
+            } else {
+                parse_number(part)? / 255.0
+            };
+        }
+        return Ok(Some(c));
+    }
+
+    Ok(match s {
+        "none" | "transparent" => None,
+        "black" | "currentColor" => rgb(0, 0, 0),
+        "white" => rgb(255, 255, 255),
+        "red" => rgb(255, 0, 0),
+        "green" => rgb(0, 128, 0),
+        "lime" => rgb(0, 255, 0),
+        "blue" => rgb(0, 0, 255),
+        "yellow" => rgb(255, 255, 0),
+        "cyan" | "aqua" => rgb(0, 255, 255),
+        "magenta" | "fuchsia" => rgb(255, 0, 255),
+        "gray" | "grey" => rgb(128, 128, 128),
+        "silver" => rgb(192, 192, 192),
+        "maroon" => rgb(128, 0, 0),
+        "olive" => rgb(128, 128, 0),
+        "navy" => rgb(0, 0, 128),
+        "purple" => rgb(128, 0, 128),
+        "teal" => rgb(0, 128, 128),
+        "orange" => rgb(255, 165, 0),
+        // Unsupported paint (e.g. url(#gradient)): use black, like currentColor
+        _ if s.starts_with("url(") => rgb(0, 0, 0),
+        _ => return Err(invalid()),
+    })
+}
+
+fn parse_transform(s: &str) -> Result<Transform, SvgError> {
+    let mut transform = Transform::IDENTITY;
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let open = rest.find('(');
+        let close = rest.find(')');
+        let (open, close) = match (open, close) {
+            (Some(o), Some(c)) if o < c => (o, c),
+            _ => return Err(SvgError::Parse(format!("invalid transform: {}", s))),
+        };
+        let name = rest[..open].trim().trim_start_matches(',').trim();
+        let mut p = Parser::new(&rest[open + 1..close]);
+        let mut args = vec![];
+        while !p.at_end() {
+            args.push(p.number()?);
+        }
+        let arg = |i: usize| args.get(i).cloned();
+        let m = match (name, args.len()) {
+            ("matrix", 6) => [args[0], args[1], args[2], args[3], args[4], args[5]],
+            ("translate", 1) | ("translate", 2) => {
+                [1.0, 0.0, 0.0, 1.0, args[0], arg(1).unwrap_or(0.0)]
+            }
+            ("scale", 1) | ("scale", 2) => [args[0], 0.0, 0.0, arg(1).unwrap_or(args[0]), 0.0, 0.0],
+            ("rotate", 1) | ("rotate", 3) => {
+                let (sin, cos) = args[0].to_radians().sin_cos();
+                let (cx, cy) = (arg(1).unwrap_or(0.0), arg(2).unwrap_or(0.0));
+                [
+                    cos,
+                    sin,
+                    -sin,
+                    cos,
+                    cx - cos * cx + sin * cy,
+                    cy - sin * cx - cos * cy,
+                ]
+            }
+            ("skewX", 1) => [1.0, 0.0, args[0].to_radians().tan(), 1.0, 0.0, 0.0],
+            ("skewY", 1) => [1.0, args[0].to_radians().tan(), 0.0, 1.0, 0.0, 0.0],
+            _ => return Err(SvgError::Parse(format!("invalid transform: {}", s))),
+        };
+        transform = transform.then(&Transform(m));
+        rest = rest[close + 1..].trim_start();
+    }
+    Ok(transform)
+}
+
+/// Parser for numbers and flags in path data and similar lists
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Self {
+        let mut p = Parser {
+            s: s.as_bytes(),
+            pos: 0,
+        };
+        p.skip_separators();
+        p
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.s.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).cloned()
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() || c == b',' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn error(&self) -> SvgError {
+        let s = String::from_utf8_lossy(self.s);
+        SvgError::Parse(format!("invalid number at {} in \"{}\"", self.pos, s))
+    }
+
+    /// Whether the next token starts a number
+    fn at_number(&self) -> bool {
+        match self.peek() {
+            Some(c) => c.is_ascii_digit() || b"+-.".contains(&c),
+            None => false,
+        }
+    }
+
+    /// Read a number
+    fn number(&mut self) -> Result<f32, SvgError> {
+        let start = self.pos;
+        if let Some(b'+') | Some(b'-') = self.peek() {
+            self.pos += 1;
+        }
+        let mut digits = self.digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.digits();
+        }
+        if digits == 0 {
+            return Err(self.error());
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            // Take care not to consume units like "em" or "ex"
+            let mut i = self.pos + 1;
+            if let Some(b'+') | Some(b'-') = self.s.get(i) {
+                i += 1;
+            }
+            if self.s.get(i).map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                self.pos = i;
+                self.digits();
+            }
+        }
+        let text = std::str::from_utf8(&self.s[start..self.pos]).unwrap();
+        let value = text.parse().map_err(|_| self.error())?;
+        self.skip_separators();
+        Ok(value)
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Read an arc flag (a single `0` or `1`)
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error()),
+        };
+        self.pos += 1;
+        self.skip_separators();
+        Ok(flag)
+    }
+
+    fn point(&mut self) -> Result<Vec2, SvgError> {
+        Ok(Vec2(self.number()?, self.number()?))
+    }
+}
+
+fn parse_path_data(path: &mut Path, d: &str) -> Result<(), SvgError> {
+    let mut p = Parser::new(d);
+    let mut cmd = 0u8;
+    // Reflected control point for smooth curves, if the previous command was
+    // of the same type (cubic or quadratic)
+    let mut last_cubic: Option<Vec2> = None;
+    let mut last_quad: Option<Vec2> = None;
+
+    while let Some(c) = p.peek() {
+        if c.is_ascii_alphabetic() {
+            cmd = c;
+            p.pos += 1;
+            p.skip_separators();
+        } else if cmd == 0 || !p.at_number() {
+            return Err(SvgError::Parse(format!("invalid path data: {}", d)));
+        } else if cmd == b'M' {
+            // Implicit commands following a move are line-to
+            cmd = b'L';
+        } else if cmd == b'm' {
+            cmd = b'l';
+        }
+
+        let rel = cmd.is_ascii_lowercase();
+        let origin = if rel { path.current() } else { Vec2::ZERO };
+        let (mut next_cubic, mut next_quad) = (None, None);
+        match cmd.to_ascii_uppercase() {
+            b'M' => path.move_to(origin + p.point()?),
+            b'L' => path.line_to(origin + p.point()?),
+            b'H' => {
+                let x = p.number()? + if rel { path.current().0 } else { 0.0 };
+                path.line_to(Vec2(x, path.current().1));
+            }
+            b'V' => {
+                let y = p.number()? + if rel { path.current().1 } else { 0.0 };
+                path.line_to(Vec2(path.current().0, y));
+            }
+            b'C' => {
+                let c1 = origin + p.point()?;
+                let c2 = origin + p.point()?;
+                let end = origin + p.point()?;
+                path.cubic_to(c1, c2, end);
+                next_cubic = Some(c2);
+            }
+            b'S' => {
+                let c1 = match last_cubic {
+                    Some(c) => path.current() * 2.0 - c,
+                    None => path.current(),
+                };
+                let c2 = origin + p.point()?;
+                let end = origin + p.point()?;
+                path.cubic_to(c1, c2, end);
+                next_cubic = Some(c2);
+            }
+            b'Q' => {
+                let c = origin + p.point()?;
+                let end = origin + p.point()?;
+                path.quad_to(c, end);
+                next_quad = Some(c);
+            }
+            b'T' => {
+                let c = match last_quad {
+                    Some(c) => path.current() * 2.0 - c,
+                    None => path.current(),
+                };
+                let end = origin + p.point()?;
+                path.quad_to(c, end);
+                next_quad = Some(c);
+            }
+            b'A' => {
+                let r = p.point()?;
+                let angle = p.number()?;
+                let large = p.flag()?;
+                let sweep = p.flag()?;
+                let end = origin + p.point()?;
+                path.arc_to(r, angle, large, sweep, end);
+            }
+            b'Z' => path.close(),
+            _ => return Err(SvgError::Parse(format!("invalid path command in: {}", d))),
+        }
+        last_cubic = next_cubic;
+        last_quad = next_quad;
+
+        if (cmd == b'Z' || cmd == b'z') && p.at_number() {
+            return Err(SvgError::Parse(format!("invalid path data: {}", d)));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rasterise() {
+        let svg = Svg::parse(
+            r##"<?xml version="1.0"?>
+            <svg xmlns="http://www.w3.org/2000/svg" width="8" height="4" viewBox="0 0 16 8">
+              <!-- left half red, right half a half-transparent blue circle -->
+              <rect width="8" height="8" fill="#f00"/>
+              <g style="fill: blue; opacity: 0.5">
+                <circle cx="12" cy="4" r="4"/>
+              </g>
+            </svg>"##,
+        )
+        .unwrap();
+        assert_eq!(svg.size(), Vec2(8.0, 4.0));
+
+        let image = svg.rasterise(Size(8, 4));
+        assert_eq!(image.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(3, 3), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(6, 2), [0, 0, 255, 128]);
+        // corner pixels are only partially covered by the circle
+        assert!(image.pixel(7, 0)[3] < 64);
+
+        // Scaled (aspect ratio preserved and centred)
+        let image = svg.rasterise(Size(16, 16));
+        assert_eq!(image.pixel(4, 2)[3], 0);
+        assert_eq!(image.pixel(4, 8), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn path_data() {
+        let mut path = Path::new();
+        parse_path_data(&mut path, "M1,2l3-4h1.5.5v2zM0 0 1 1").unwrap();
+        use super::super::path::Segment::*;
+        assert_eq!(
+            path.segments,
+            vec![
+                Move(Vec2(1.0, 2.0)),
+                Line(Vec2(4.0, -2.0)),
+                Line(Vec2(5.5, -2.0)),
+                Line(Vec2(6.0, -2.0)),
+                Line(Vec2(6.0, 0.0)),
+                Close,
+                Move(Vec2(0.0, 0.0)),
+                Line(Vec2(1.0, 1.0)),
+            ]
+        );
+    }
+}
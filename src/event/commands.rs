@@ -0,0 +1,274 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Application commands
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+#[allow(unused)]
+use super::Event; // for doc-links
+use super::{Binding, Manager, ModifiersState, Shortcuts, UpdateHandle, VirtualKeyCode};
+use crate::string::AccelString;
+use crate::WidgetId;
+
+#[derive(Clone, Debug)]
+struct Command<M> {
+    label: AccelString,
+    shortcut: Option<(ModifiersState, VirtualKeyCode)>,
+    enabled: bool,
+    msg: M,
+}
+
+/// A shared registry of application commands
+///
+/// Each command has a name (its identifier), a label, an optional default
+/// shortcut, an enabled flag and a message. Clones of this type share the same
+/// data; widgets bound to a command (see [`CommandBinding`]) are notified of
+/// changes via an [`UpdateHandle`].
+///
+/// Widgets bound to a command emit its message on activation, or when a
+/// shortcut bound to [`Binding::Command`] is pressed (see
+/// [`Manager::register_command`]); this message propagates to the window's
+/// root widget like any other. Multiple widgets may be bound to the same
+/// command (e.g. a menu entry and a tool-bar button); only one emits the
+/// message for any shortcut.
+///
+/// If no widget is bound to a command, its shortcut sends [`Event::Command`]
+/// to the window's root widget (with [`kas::widget::Window`], the widget it
+/// contains), which may translate it via [`Commands::msg`].
+#[derive(Clone, Debug)]
+pub struct Commands<M: Clone + Debug + 'static> {
+    data: Rc<RefCell<HashMap<String, Command<M>>>>,
+    handle: UpdateHandle,
+}
+
+impl<M: Clone + Debug + 'static> Default for Commands<M> {
+    fn default() -> Self {
+        Commands::new()
+    }
+}
+
+impl<M: Clone + Debug + 'static> Commands<M> {
+    /// Construct an empty registry
+    pub fn new() -> Self {
+        Commands {
+            data: Default::default(),
+            handle: UpdateHandle::new(),
+        }
+    }
+
+    /// Add a command, replacing any previous command of the same `name`
+    ///
+    /// The command is enabled initially. The `label` may contain an
+    /// accelerator key prefixed with `&`.
+    ///
+    /// This should be called before binding widgets to the command.
+    pub fn add<S: ToString, L: Into<AccelString>>(&self, name: S, label: L, msg: M) {
+        let command = Command {
+            label: label.into(),
+            shortcut: None,
+            enabled: true,
+            msg,
+        };
+        self.data.borrow_mut().insert(name.to_string(), command);
+    }
+
+    /// Set the default shortcut of a command
+    ///
+    /// This has no effect until [`Commands::bind_shortcuts`] is called.
+    pub fn set_shortcut(&self, name: &str, modifiers: ModifiersState, vkey: VirtualKeyCode) {
+        if let Some(command) = self.data.borrow_mut().get_mut(name) {
+            command.shortcut = Some((modifiers, vkey));
+        }
+    }
+
+    /// Add default shortcuts of all commands to `shortcuts`
+    ///
+    /// Chords already bound in `shortcuts` are not replaced (only exact
+    /// matches are considered; see [`Shortcuts::get_exact`]). The resulting
+    /// table should be passed to the toolkit.
+    pub fn bind_shortcuts(&self, shortcuts: &mut Shortcuts) {
+        for (name, command) in self.data.borrow().iter() {
+            if let Some((modifiers, vkey)) = command.shortcut {
+                if shortcuts.get_exact(modifiers, vkey).is_none() {
+                    shortcuts.insert(modifiers, vkey, Binding::Command(name.clone()));
+                }
+            }
+        }
+    }
+
+    /// Get the update handle
+    ///
+    /// This is triggered whenever a command is enabled or disabled.
+    #[inline]
+    pub fn update_handle(&self) -> UpdateHandle {
+        self.handle
+    }
+
+    /// Check whether a command exists
+    pub fn contains(&self, name: &str) -> bool {
+        self.data.borrow().contains_key(name)
+    }
+
+    /// Get a command's label
+    pub fn label(&self, name: &str) -> Option<AccelString> {
+        self.data.borrow().get(name).map(|c| c.label.clone())
+    }
+
+    /// Get a command's default shortcut
+    pub fn shortcut(&self, name: &str) -> Option<(ModifiersState, VirtualKeyCode)> {
+        self.data.borrow().get(name).and_then(|c| c.shortcut)
+    }
+
+    /// Check whether a command is enabled
+    ///
+    /// Returns false if the command does not exist.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.data
+            .borrow()
+            .get(name)
+            .map(|c| c.enabled)
+            .unwrap_or(false)
+    }
+
+    /// Enable or disable a command, notifying bound widgets
+    pub fn set_enabled(&self, mgr: &mut Manager, name: &str, enabled: bool) {
+        let changed = match self.data.borrow_mut().get_mut(name) {
+            Some(command) if command.enabled != enabled => {
+                command.enabled = enabled;
+                true
+            }
+            _ => false,
+        };
+        if changed {
+            mgr.trigger_update(self.handle, 0);
+        }
+    }
+
+    /// Get a command's message
+    ///
+    /// This does not check whether the command is enabled.
+    pub fn msg(&self, name: &str) -> Option<M> {
+        self.data.borrow().get(name).map(|c| c.msg.clone())
+    }
+}
+
+/// The binding of a widget to a command of a [`Commands`] registry
+///
+/// This is a utility for widget implementations. The widget should:
+///
+/// -   call [`CommandBinding::configure`] from [`WidgetConfig::configure`]
+/// -   on [`Event::HandleUpdate`] call [`CommandBinding::update`], and redraw
+///     if this returns true
+/// -   on [`Event::Activate`] or [`Event::Command`] return
+///     [`CommandBinding::msg`]
+/// -   draw itself as disabled when [`CommandBinding::is_enabled`] is false
+///
+/// Note that the widget should not be disabled via
+/// [`WidgetCore::set_disabled`], since disabled widgets do not receive events.
+///
+/// [`WidgetConfig::configure`]: crate::WidgetConfig::configure
+/// [`WidgetCore::set_disabled`]: crate::WidgetCore::set_disabled
+#[derive(Clone, Debug)]
+pub struct CommandBinding<M: Clone + Debug + 'static> {
+    commands: Commands<M>,
+    name: String,
+    enabled: bool,
+}
+
+impl<M: Clone + Debug + 'static> CommandBinding<M> {
+    /// Construct a binding to the command `name`
+    pub fn new<S: ToString>(commands: &Commands<M>, name: S) -> Self {
+        let name = name.to_string();
+        let enabled = commands.is_enabled(&name);
+        CommandBinding {
+            commands: commands.clone(),
+            name,
+            enabled,
+        }
+    }
+
+    /// The command's name
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The command's label
+    ///
+    /// Returns an empty label if the command does not exist.
+    pub fn label(&self) -> AccelString {
+        self.commands.label(&self.name).unwrap_or_default()
+    }
+
+    /// Whether the command is enabled (as of the last update)
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Subscribe widget `id` to updates and register it as command handler
+    pub fn configure(&mut self, mgr: &mut Manager, id: WidgetId) {
+        mgr.update_on_handle(self.commands.update_handle(), id);
+        mgr.register_command(&self.name, id);
+        self.enabled = self.commands.is_enabled(&self.name);
+    }
+
+    /// Update the enabled state, returning true if changed
+    pub fn update(&mut self) -> bool {
+        let enabled = self.commands.is_enabled(&self.name);
+        let changed = enabled != self.enabled;
+        self.enabled = enabled;
+        changed
+    }
+
+    /// Get the command's message, if enabled
+    pub fn msg(&self) -> Option<M> {
+        if self.commands.is_enabled(&self.name) {
+            self.commands.msg(&self.name)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bind_shortcuts() {
+        let commands = Commands::new();
+        commands.add("save", "&Save", 1);
+        commands.add("open", "&Open", 2);
+        commands.set_shortcut("save", ModifiersState::CTRL, VirtualKeyCode::S);
+        commands.set_shortcut("open", ModifiersState::empty(), VirtualKeyCode::Home);
+        commands.add("top", "&Top", 3);
+        commands.set_shortcut("top", ModifiersState::CTRL, VirtualKeyCode::Home);
+
+        let mut shortcuts = Shortcuts::default();
+        commands.bind_shortcuts(&mut shortcuts);
+        let save = Binding::Command("save".to_string());
+        let open = Binding::Command("open".to_string());
+        assert_eq!(shortcuts.find(&save), commands.shortcut("save"));
+        // Home is already bound to ControlKey::Home
+        assert_eq!(shortcuts.find(&open), None);
+        // Ctrl+Home only falls back to ControlKey::Home, thus may be bound
+        let top = Binding::Command("top".to_string());
+        assert_eq!(shortcuts.find(&top), commands.shortcut("top"));
+        assert_eq!(
+            shortcuts.get(ModifiersState::CTRL, VirtualKeyCode::Home),
+            Some(&top)
+        );
+
+        let binding = CommandBinding::new(&commands, "save");
+        assert!(binding.is_enabled());
+        assert_eq!(binding.msg(), Some(1));
+        assert_eq!(binding.label(), AccelString::from("&Save"));
+    }
+}
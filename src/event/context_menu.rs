@@ -0,0 +1,149 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Context menus
+
+#[allow(unused)]
+use super::Manager; // for doc-links
+use super::{ControlKey, Event, ModifiersState};
+use crate::StableId;
+
+/// An entry of a [`ContextMenu`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContextEntry {
+    /// An action
+    ///
+    /// When activated, `event` is sent to the widget identified by `owner`.
+    Action {
+        /// Label; may contain an accelerator key (see `AccelString`)
+        label: String,
+        /// Whether the entry may be activated
+        enabled: bool,
+        /// The widget which contributed this entry
+        owner: StableId,
+        /// The event sent on activation
+        event: Event,
+    },
+    /// A separator
+    Separator,
+}
+
+/// Entries of a context menu
+///
+/// A context menu is assembled by the [`Manager`] on a secondary-button press
+/// or the Menu key, by calling [`WidgetConfig::context_menu`] on the target
+/// widget and then on each of its ancestors. Entries of each widget are
+/// separated from those of the widgets before it.
+///
+/// The assembled menu is sent to the window's root widget via
+/// [`Event::OpenContextMenu`]; [`Window`] displays it as a pop-up.
+///
+/// [`Window`]: crate::widget::Window
+/// [`WidgetConfig::context_menu`]: crate::WidgetConfig::context_menu
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContextMenu {
+    owner: StableId,
+    entries: Vec<ContextEntry>,
+}
+
+impl ContextMenu {
+    /// Construct an empty menu
+    #[inline]
+    pub fn new() -> Self {
+        ContextMenu::default()
+    }
+
+    /// Start a group of entries contributed by `owner`
+    pub(crate) fn begin_group(&mut self, owner: StableId) {
+        self.push_separator();
+        self.owner = owner;
+    }
+
+    /// Number of entries, including separators
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if the menu has no entries
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Access entries
+    #[inline]
+    pub fn entries(&self) -> &[ContextEntry] {
+        &self.entries
+    }
+
+    /// Get an entry
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&ContextEntry> {
+        self.entries.get(index)
+    }
+
+    /// Add an entry which sends `event` to the current widget when activated
+    ///
+    /// The `label` may contain an accelerator key prefixed with `&`.
+    pub fn push<S: ToString>(&mut self, label: S, event: Event, enabled: bool) {
+        self.entries.push(ContextEntry::Action {
+            label: label.to_string(),
+            enabled,
+            owner: self.owner,
+            event,
+        });
+    }
+
+    /// Add an entry which sends [`Event::Control`] to the current widget
+    ///
+    /// This is a convenience wrapper around [`ContextMenu::push`].
+    pub fn push_control<S: ToString>(&mut self, label: S, key: ControlKey, enabled: bool) {
+        let event = Event::Control(key, ModifiersState::empty());
+        self.push(label, event, enabled);
+    }
+
+    /// Add a separator
+    ///
+    /// Separators are not added at the start of a menu, nor directly after
+    /// another separator. A trailing separator is removed when the menu opens.
+    pub fn push_separator(&mut self) {
+        match self.entries.last() {
+            None | Some(ContextEntry::Separator) => (),
+            Some(_) => self.entries.push(ContextEntry::Separator),
+        }
+    }
+
+    /// Remove a trailing separator
+    pub(crate) fn finish(&mut self) {
+        if let Some(ContextEntry::Separator) = self.entries.last() {
+            self.entries.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn separators() {
+        let mut menu = ContextMenu::new();
+        menu.begin_group(StableId::default());
+        menu.push_separator();
+        assert!(menu.is_empty());
+        menu.push_control("&Copy", ControlKey::Copy, true);
+        menu.push_separator();
+        menu.push_separator();
+        assert_eq!(menu.len(), 2);
+
+        menu.begin_group(StableId::default());
+        menu.push("&Other", Event::Activate, false);
+        assert_eq!(menu.len(), 3);
+        menu.begin_group(StableId::default());
+        menu.finish();
+        assert_eq!(menu.len(), 3);
+    }
+}
@@ -5,11 +5,11 @@
 
 //! Event handling: events
 
+use super::{ContextMenu, ModifiersState, MouseButton, UpdateHandle, VirtualKeyCode};
 #[allow(unused)]
 use super::{GrabMode, Manager, Response}; // for doc-links
-use super::{ModifiersState, MouseButton, UpdateHandle, VirtualKeyCode};
 
-use crate::geom::{Coord, DVec2};
+use crate::geom::{Coord, DVec2, Rect};
 use crate::{WidgetId, WindowId};
 
 /// Events addressed to a widget
@@ -139,6 +139,13 @@ pub enum Event {
     /// A user-defined payload is passed. Interpretation of this payload is
     /// user-defined and unfortunately not type safe.
     HandleUpdate { handle: UpdateHandle, payload: u64 },
+    /// Notification that the window's layout has been applied
+    ///
+    /// This event is received after each resize (or other layout update) of
+    /// the window after registering via [`Manager::update_on_resize`]. It may
+    /// be used to add or remove children synchronously, e.g. via
+    /// [`Manager::configure_children`].
+    Resized,
     /// Notification that a new popup has been created
     ///
     /// This is sent to the parent of each open popup when a new popup is
@@ -151,6 +158,22 @@ pub enum Event {
     /// Since popups may be removed directly by the Manager, the parent should
     /// clean up any associated state here.
     PopupRemoved(WindowId),
+    /// Request to open a context menu
+    ///
+    /// This is sent by the [`Manager`] to the window's root widget after a
+    /// secondary-button press or the Menu key, when the target widget or its
+    /// ancestors contribute menu entries (see [`ContextMenu`]). The menu
+    /// should be placed next to `anchor`, a rect in window coordinates.
+    OpenContextMenu { menu: ContextMenu, anchor: Rect },
+    /// A named command
+    ///
+    /// This is sent to the widget registered via [`Manager::register_command`]
+    /// when a key chord bound to the command (see [`Shortcuts`]) is pressed,
+    /// regardless of focus. If no widget is registered, it is sent to the
+    /// window's root widget.
+    ///
+    /// [`Shortcuts`]: super::Shortcuts
+    Command(String),
     /// Sent when a widget receives keyboard navigation focus
     ///
     /// The widget should reply with [`Response::Focus`].
@@ -227,6 +250,8 @@ pub enum ControlKey {
     Undo,
     /// Redo the last undone action
     Redo,
+    /// Select all content
+    SelectAll,
 
     /// Navigate backwards one page/item
     Backward,
@@ -236,6 +261,9 @@ pub enum ControlKey {
 
 impl ControlKey {
     /// Try constructing from a [`VirtualKeyCode`]
+    ///
+    /// This is the default mapping of unmodified keys; key presses are
+    /// translated via [`Shortcuts`](super::Shortcuts).
     pub fn new(vkey: VirtualKeyCode) -> Option<Self> {
         use ControlKey as CK;
         use VirtualKeyCode::*;
@@ -297,6 +325,17 @@ impl PressSource {
         }
     }
 
+    /// Returns true if this represents the right mouse button
+    ///
+    /// Secondary presses open context menus when not handled.
+    #[inline]
+    pub fn is_secondary(self) -> bool {
+        match self {
+            PressSource::Mouse(button, _) => button == MouseButton::Right,
+            PressSource::Touch(_) => false,
+        }
+    }
+
     /// The `repetitions` value
     ///
     /// This is 1 for a single-click and all touch events, 2 for a double-click,
@@ -312,6 +351,7 @@ impl PressSource {
 
 /// Type used by [`Event::Scroll`]
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScrollDelta {
     /// Scroll a given number of lines
     LineDelta(f32, f32),
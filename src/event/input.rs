@@ -0,0 +1,98 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Raw input events, recording and replay
+
+use std::time::Duration;
+
+use super::{ElementState, ModifiersState, MouseButton, ScrollDelta, TouchPhase, VirtualKeyCode};
+use crate::geom::Coord;
+
+// doc imports
+#[allow(unused)]
+use super::{Manager, ManagerState};
+
+/// A raw input event, as received by a window
+///
+/// Each variant corresponds to one of the `handle_*` methods of [`Manager`];
+/// events may be delivered via [`Manager::handle_input`].
+///
+/// Coordinates are in physical pixels, relative to the window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputEvent {
+    /// The window was requested to close
+    CloseRequested,
+    /// A character was received
+    ReceivedCharacter(char),
+    /// A key was pressed or released
+    Key {
+        state: ElementState,
+        vkey: Option<VirtualKeyCode>,
+        scancode: u32,
+    },
+    /// Modifier state changed
+    ModifiersChanged(ModifiersState),
+    /// The mouse cursor moved
+    CursorMoved(Coord),
+    /// The mouse cursor left the window
+    CursorLeft,
+    /// Mouse wheel or touchpad scroll
+    Wheel(ScrollDelta),
+    /// A mouse button was pressed or released
+    MouseInput {
+        state: ElementState,
+        button: MouseButton,
+    },
+    /// A touch event
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        coord: Coord,
+    },
+}
+
+/// A recording of input to a window
+///
+/// Each event is stored along with the time it was received, relative to the
+/// start of recording. Time is taken from the window's clock, which may be
+/// virtual (see [`ManagerState::use_virtual_clock`]).
+///
+/// Recordings are started and stopped via [`ManagerState::start_recording`]
+/// and [`ManagerState::stop_recording`]. With the `serde` feature, recordings
+/// may be serialised and stored for later replay.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputRecording {
+    /// Events, each with time since the start of recording
+    ///
+    /// Times are non-decreasing.
+    pub events: Vec<(Duration, InputEvent)>,
+}
+
+impl InputRecording {
+    /// Construct an empty recording
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event
+    ///
+    /// If `time` is less than that of the last event, the last event's time is
+    /// used instead.
+    pub fn push(&mut self, time: Duration, event: InputEvent) {
+        let time = self.duration().max(time);
+        self.events.push((time, event));
+    }
+
+    /// The time of the last event, relative to the start of recording
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map(|(time, _)| *time)
+            .unwrap_or(Duration::from_secs(0))
+    }
+}
@@ -11,14 +11,14 @@
 use log::trace;
 use smallvec::SmallVec;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::Instant;
 use std::u16;
 
+use super::undo::UndoStack;
 use super::*;
-use crate::geom::Coord;
-#[allow(unused)]
-use crate::WidgetConfig; // for doc-links
-use crate::{TkAction, TkWindow, Widget, WidgetId, WindowId};
+use crate::geom::{Coord, Rect, Size};
+use crate::{StableId, TkAction, TkWindow, Widget, WidgetConfig, WidgetId, WindowId};
 
 mod mgr_pub;
 mod mgr_tk;
@@ -73,6 +73,7 @@ struct PanGrab {
 #[derive(Clone, Debug)]
 enum Pending {
     LostCharFocus(WidgetId),
+    Control(WidgetId, ControlKey),
 }
 
 /// Event manager state
@@ -92,6 +93,7 @@ enum Pending {
 #[derive(Debug)]
 pub struct ManagerState {
     end_id: WidgetId,
+    stable_ids: HashMap<StableId, WidgetId>,
     dpi_factor: f64,
     modifiers: ModifiersState,
     char_focus: Option<WidgetId>,
@@ -110,21 +112,37 @@ pub struct ManagerState {
     pan_grab: SmallVec<[PanGrab; 4]>,
     accel_stack: Vec<(bool, HashMap<VirtualKeyCode, WidgetId>)>,
     accel_layers: HashMap<WidgetId, (bool, HashMap<VirtualKeyCode, WidgetId>)>,
+    shortcuts: Rc<Shortcuts>,
+    commands: HashMap<String, WidgetId>,
+    // True while a key matching a shortcut is pressed
+    skip_char: bool,
     popups: SmallVec<[(WindowId, kas::Popup); 16]>,
     new_popups: SmallVec<[WidgetId; 16]>,
     popup_removed: SmallVec<[(WidgetId, WindowId); 16]>,
 
     time_start: Instant,
+    clock: Option<Instant>,
     time_updates: Vec<(Instant, WidgetId)>,
     // TODO(opt): consider other containers, e.g. C++ multimap
     // or sorted Vec with binary search yielding a range
     handle_updates: HashMap<UpdateHandle, Vec<WidgetId>>,
+    resize_updates: Vec<WidgetId>,
     pending: SmallVec<[Pending; 8]>,
+    undo: UndoStack,
+    recording: Option<(Instant, InputRecording)>,
+    layout_debug: bool,
     action: TkAction,
 }
 
 /// internals
 impl ManagerState {
+    fn record(&mut self, event: InputEvent) {
+        let now = self.now();
+        if let Some((start, recording)) = self.recording.as_mut() {
+            recording.push(now - *start, event);
+        }
+    }
+
     fn set_pan_on(
         &mut self,
         id: WidgetId,
@@ -251,10 +269,36 @@ impl<'a> Manager<'a> {
         W: Widget<Msg = VoidMsg> + ?Sized,
     {
         use VirtualKeyCode as VK;
+        self.mgr.skip_char = false;
+        if vkey == VK::Apps {
+            // The Menu key opens a context menu for the focussed widget
+            if let Some(id) = self.mgr.char_focus.or(self.mgr.nav_focus) {
+                self.open_context_menu(widget, id, None);
+            }
+            return;
+        }
+
+        let mut control = None;
+        match self.mgr.shortcuts.get(self.mgr.modifiers, vkey) {
+            Some(Binding::Control(key)) => control = Some(*key),
+            Some(Binding::Command(name)) => {
+                // Commands take precedence over focus. Without a registered
+                // handler, the command is sent to the root widget.
+                let id = self.mgr.commands.get(name).cloned();
+                let id = id.unwrap_or_else(|| widget.id());
+                let event = Event::Command(name.clone());
+                self.mgr.skip_char = true;
+                self.send_event(widget, id, event);
+                return;
+            }
+            Some(Binding::None) | None => (),
+        }
+        self.mgr.skip_char = control.is_some();
+
         if let Some(id) = self.mgr.char_focus {
             if vkey == VK::Escape {
                 self.set_char_focus(None);
-            } else if let Some(key) = ControlKey::new(vkey) {
+            } else if let Some(key) = control {
                 self.send_event(widget, id, Event::Control(key, self.mgr.modifiers));
             }
             return;
@@ -282,7 +326,7 @@ impl<'a> Manager<'a> {
                 if let Some(nav_id) = self.mgr.nav_focus {
                     if vkey == VK::Space || vkey == VK::Return || vkey == VK::NumpadEnter {
                         id_action = Some((nav_id, Event::Activate));
-                    } else if let Some(nav_key) = ControlKey::new(vkey) {
+                    } else if let Some(nav_key) = control {
                         id_action = Some((nav_id, Event::Control(nav_key, self.mgr.modifiers)));
                     }
                 }
@@ -290,12 +334,12 @@ impl<'a> Manager<'a> {
                 if id_action.is_none() {
                     // Next priority goes to pop-up widget
                     if let Some(popup) = self.mgr.popups.last() {
-                        if let Some(key) = ControlKey::new(vkey) {
+                        if let Some(key) = control {
                             let ev = Event::Control(key, self.mgr.modifiers);
                             id_action = Some((popup.1.parent, ev));
                         }
                     } else if let Some(id) = self.mgr.nav_fallback {
-                        if let Some(key) = ControlKey::new(vkey) {
+                        if let Some(key) = control {
                             id_action = Some((id, Event::Control(key, self.mgr.modifiers)));
                         }
                     }
@@ -352,6 +396,8 @@ impl<'a> Manager<'a> {
     }
 
     fn end_key_event(&mut self, scancode: u32) {
+        self.mgr.skip_char = false;
+
         // We must match scancode not vkey since the latter may have changed due to modifiers
 
         // TODO: it would be nice to replace key_depress with a set
@@ -440,39 +486,123 @@ impl<'a> Manager<'a> {
         let _ = widget.send(self, id, event);
     }
 
-    fn send_popup_first<W: Widget + ?Sized>(&mut self, widget: &mut W, id: WidgetId, event: Event) {
+    // Returns true if the event was handled
+    fn send_popup_first<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        id: WidgetId,
+        event: Event,
+    ) -> bool {
         while let Some((wid, parent)) = self.mgr.popups.last().map(|(wid, p)| (*wid, p.parent)) {
             trace!("Send to popup parent: {}: {:?}", parent, event);
             match widget.send(self, parent, event.clone()) {
                 Response::Unhandled(_) => (),
-                _ => return,
+                _ => return true,
             }
             self.close_window(wid);
         }
-        self.send_event(widget, id, event);
+        trace!("Send to {}: {:?}", id, event);
+        !widget.send(self, id, event).is_unhandled()
+    }
+
+    /// Assemble a context menu for `id` and send to the root widget
+    ///
+    /// The menu is placed at `coord` if given, otherwise next to widget `id`.
+    fn open_context_menu<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        id: WidgetId,
+        coord: Option<Coord>,
+    ) {
+        let mut menu = ContextMenu::new();
+        let rect = match collect_context_menu(widget.as_widget(), id, &*self.mgr, &mut menu) {
+            Some(rect) => rect,
+            None => return,
+        };
+        menu.finish();
+        if menu.is_empty() {
+            return;
+        }
+
+        let anchor = match coord {
+            Some(coord) => Rect::new(coord, Size::ZERO),
+            None => rect,
+        };
+        let root = widget.id();
+        self.send_event(widget, root, Event::OpenContextMenu { menu, anchor });
     }
 }
 
+// Collect context-menu entries of `id` and its ancestors, innermost first,
+// returning the rect of `id` in window coordinates.
+fn collect_context_menu(
+    widget: &dyn WidgetConfig,
+    id: WidgetId,
+    mgr: &ManagerState,
+    menu: &mut ContextMenu,
+) -> Option<Rect> {
+    let rect = if id == widget.id() {
+        widget.rect()
+    } else if id > widget.id() {
+        return None;
+    } else {
+        let mut rect = None;
+        for i in 0..widget.len() {
+            if let Some(w) = widget.get(i) {
+                if id > w.id() {
+                    continue;
+                }
+                rect = collect_context_menu(w, id, mgr, menu).map(|r| r - widget.translation(i));
+            }
+            break;
+        }
+        rect?
+    };
+
+    if !widget.is_disabled() {
+        menu.begin_group(widget.stable_id());
+        widget.context_menu(mgr, menu);
+    }
+    Some(rect)
+}
+
 /// Helper used during widget configuration
 pub struct ConfigureManager<'a: 'b, 'b> {
     id: &'b mut WidgetId,
     map: &'b mut HashMap<WidgetId, WidgetId>,
+    stable_ids: &'b mut HashMap<StableId, WidgetId>,
+    stable_id: StableId,
     mgr: &'b mut Manager<'a>,
 }
 
 impl<'a: 'b, 'b> ConfigureManager<'a, 'b> {
     /// Reborrow self to pass to a child
-    pub fn child<'c>(&'c mut self) -> ConfigureManager<'a, 'c>
+    ///
+    /// The child's `index` and `key` (`child.key()`) are used to derive its
+    /// [`StableId`].
+    pub fn child<'c>(&'c mut self, index: usize, key: Option<u64>) -> ConfigureManager<'a, 'c>
     where
         'b: 'c,
     {
+        let stable_id = match key {
+            Some(key) => self.stable_id.keyed(key),
+            None => self.stable_id.child(index),
+        };
         ConfigureManager {
             id: &mut *self.id,
             map: &mut *self.map,
+            stable_ids: &mut *self.stable_ids,
+            stable_id,
             mgr: &mut *self.mgr,
         }
     }
 
+    /// Get the [`StableId`] of the widget being configured
+    #[inline]
+    pub fn stable_id(&self) -> StableId {
+        self.stable_id
+    }
+
     /// Get a new [`WidgetId`] for the widget
     ///
     /// Pass the old ID (`self.id()`), even if not yet configured.
@@ -480,9 +610,22 @@ impl<'a: 'b, 'b> ConfigureManager<'a, 'b> {
         let id = *self.id;
         *self.id = id.next();
         self.map.insert(old_id, id);
+        self.stable_ids.insert(self.stable_id, id);
         id
     }
 
+    /// Reserve `n` identifiers, returning the first
+    ///
+    /// This may be called by [`WidgetConfig::configure_recurse`] before
+    /// configuring children. The reserved identifiers, together with those of
+    /// all children, may later be used by [`Manager::configure_children`] to
+    /// replace or add children without reconfiguring the whole window.
+    pub fn reserve(&mut self, n: u32) -> WidgetId {
+        let first = *self.id;
+        *self.id = first.offset(n);
+        first
+    }
+
     /// Get access to the wrapped [`Manager`]
     pub fn mgr(&mut self) -> &mut Manager<'a> {
         self.mgr
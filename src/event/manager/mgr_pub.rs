@@ -6,6 +6,7 @@
 //! Event manager — public API
 
 use log::{debug, trace, warn};
+use std::any::Any;
 use std::time::{Duration, Instant};
 use std::u16;
 
@@ -14,7 +15,7 @@ use crate::draw::SizeHandle;
 use crate::geom::Coord;
 #[allow(unused)]
 use crate::WidgetConfig; // for doc-links
-use crate::{ThemeAction, ThemeApi, TkAction, WidgetId, WindowId};
+use crate::{StableId, ThemeAction, ThemeApi, TkAction, WidgetId, WindowId};
 
 impl<'a> std::ops::AddAssign<TkAction> for Manager<'a> {
     #[inline]
@@ -35,6 +36,38 @@ impl ManagerState {
         self.modifiers.alt()
     }
 
+    /// Get the current state of keyboard modifiers
+    #[inline]
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    /// Get the [`WidgetId`] of the widget with the given [`StableId`]
+    ///
+    /// Returns `None` if no such widget was present at the last configure.
+    #[inline]
+    pub fn find_stable(&self, stable_id: StableId) -> Option<WidgetId> {
+        self.stable_ids.get(&stable_id).cloned()
+    }
+
+    /// Access the table of keyboard shortcuts
+    #[inline]
+    pub fn shortcuts(&self) -> &Shortcuts {
+        &self.shortcuts
+    }
+
+    /// Get whether widget `w_id` has undo history
+    #[inline]
+    pub fn can_undo(&self, w_id: WidgetId) -> bool {
+        self.undo.has_undo(w_id)
+    }
+
+    /// Get whether widget `w_id` has redo history
+    #[inline]
+    pub fn can_redo(&self, w_id: WidgetId) -> bool {
+        self.undo.has_redo(w_id)
+    }
+
     /// Get whether this widget has a grab on character input
     #[inline]
     pub fn char_focus(&self, w_id: WidgetId) -> bool {
@@ -47,6 +80,12 @@ impl ManagerState {
         self.nav_focus == Some(w_id)
     }
 
+    /// Get whether this widget is the root of an open pop-up
+    #[inline]
+    pub fn is_popup_open(&self, w_id: WidgetId) -> bool {
+        self.popups.iter().any(|(_, popup)| popup.id == w_id)
+    }
+
     /// Get whether the widget is under the mouse cursor
     #[inline]
     pub fn is_hovered(&self, w_id: WidgetId) -> bool {
@@ -71,10 +110,65 @@ impl ManagerState {
         }
         false
     }
+
+    /// Get the current time
+    ///
+    /// This is usually the system time, but may be virtual (e.g. when
+    /// replaying recorded input). Widgets measuring elapsed time should use
+    /// this instead of [`Instant::now`].
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.clock.unwrap_or_else(Instant::now)
+    }
+
+    /// True when the layout debugging overlay is enabled
+    ///
+    /// This is initially enabled when the `KAS_LAYOUT_DEBUG` environment
+    /// variable is set (to any value other than `0`), and may be toggled via
+    /// [`Manager::set_layout_debug`]. See [`kas::layout::LayoutDump`].
+    #[inline]
+    pub fn layout_debug(&self) -> bool {
+        self.layout_debug
+    }
 }
 
 /// Public API (around toolkit functionality)
 impl<'a> Manager<'a> {
+    /// Get the current time
+    ///
+    /// See [`ManagerState::now`].
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.mgr.now()
+    }
+
+    /// Get the [`WidgetId`] of the widget with the given [`StableId`]
+    ///
+    /// See [`ManagerState::find_stable`].
+    #[inline]
+    pub fn find_stable(&self, stable_id: StableId) -> Option<WidgetId> {
+        self.mgr.find_stable(stable_id)
+    }
+
+    /// Get the current state of keyboard modifiers
+    ///
+    /// See [`ManagerState::modifiers`].
+    #[inline]
+    pub fn modifiers(&self) -> ModifiersState {
+        self.mgr.modifiers
+    }
+
+    /// Enable or disable the layout debugging overlay
+    ///
+    /// See [`ManagerState::layout_debug`].
+    pub fn set_layout_debug(&mut self, enable: bool) {
+        if enable != self.mgr.layout_debug {
+            self.mgr.layout_debug = enable;
+            // Layout information is collected by the toolkit on resize
+            self.send_action(TkAction::ResetSize);
+        }
+    }
+
     /// Schedule an update
     ///
     /// Widgets requiring animation should schedule an update; as a result,
@@ -89,7 +183,7 @@ impl<'a> Manager<'a> {
     /// handler. Note that previously-scheduled updates are cleared when
     /// widgets are reconfigured.
     pub fn update_on_timer(&mut self, duration: Duration, w_id: WidgetId) {
-        let time = Instant::now() + duration;
+        let time = self.mgr.now() + duration;
         'outer: loop {
             for row in &mut self.mgr.time_updates {
                 if row.1 == w_id {
@@ -124,6 +218,168 @@ impl<'a> Manager<'a> {
             .push(w_id);
     }
 
+    /// Request notification after each resize
+    ///
+    /// The widget will be sent [`Event::Resized`] each time the window's
+    /// layout is applied (after [`crate::Layout::set_rect`] has been called on all
+    /// widgets).
+    ///
+    /// This should be called from [`WidgetConfig::configure`].
+    pub fn update_on_resize(&mut self, w_id: WidgetId) {
+        if !self.mgr.resize_updates.contains(&w_id) {
+            self.mgr.resize_updates.push(w_id);
+        }
+    }
+
+    /// Configure the children of `widget` in place
+    ///
+    /// This assigns new identifiers to all descendants of `widget` (calling
+    /// [`WidgetConfig::configure`] on each) without reconfiguring the whole
+    /// window; `widget` itself is not reconfigured. It may be used to open a
+    /// pop-up or grow a pool of widgets synchronously.
+    ///
+    /// Descendants are assigned identifiers from `first` up to (excluding)
+    /// `widget.id()`, where `first` was returned by
+    /// [`ConfigureManager::reserve`]. If this range is too small, `false` is
+    /// returned and a full reconfigure ([`TkAction::Reconfigure`]) is
+    /// required. Input state (e.g. navigation focus, grabs, timers and undo
+    /// history) of previous descendants is transferred by [`StableId`] where
+    /// possible and otherwise dropped.
+    ///
+    /// Accelerator keys are added to the current layer; the caller should use
+    /// [`Manager::push_accel_layer`] where required. Afterwards, the caller is
+    /// responsible for sizing new children.
+    pub fn configure_children(&mut self, first: WidgetId, widget: &mut dyn WidgetConfig) -> bool {
+        fn count(widget: &dyn WidgetConfig) -> u32 {
+            let children = (0..widget.len()).filter_map(|i| widget.get(i));
+            children.map(count).sum::<u32>() + 1
+        }
+        let end = widget.id();
+        let n: u32 = (0..widget.len())
+            .filter_map(|i| widget.get(i))
+            .map(count)
+            .sum();
+        if self.read_only || first > end || u32::from(end) - u32::from(first) < n {
+            return false;
+        }
+        trace!(
+            "Manager::configure_children of {} in {}..{}",
+            end,
+            first,
+            end
+        );
+
+        // Forget previous descendants, keeping a map for transfer of state:
+        let in_range = |id: WidgetId| first <= id && id < end;
+        let mut old_stable_ids = HashMap::new();
+        self.mgr.stable_ids.retain(|stable, id| {
+            if in_range(*id) {
+                old_stable_ids.insert(*id, *stable);
+                false
+            } else {
+                true
+            }
+        });
+        let (old_time_updates, time_updates): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.mgr.time_updates)
+                .into_iter()
+                .partition(|(_, id)| in_range(*id));
+        self.mgr.time_updates = time_updates;
+        for ids in self.mgr.handle_updates.values_mut() {
+            ids.retain(|id| !in_range(*id));
+        }
+        self.mgr.resize_updates.retain(|id| !in_range(*id));
+        self.mgr.commands.retain(|_, id| !in_range(*id));
+        // Layers of descendants are re-created by configure; others may
+        // reference descendants and are remapped below.
+        let mut old_accel_layers = std::mem::take(&mut self.mgr.accel_layers);
+        old_accel_layers.retain(|id, _| !in_range(*id));
+
+        let mut id = first;
+        let mut map = HashMap::new();
+        let mut stable_ids = HashMap::new();
+        let mut cmgr = ConfigureManager {
+            id: &mut id,
+            map: &mut map,
+            stable_ids: &mut stable_ids,
+            stable_id: widget.stable_id(),
+            mgr: self,
+        };
+        for i in 0..widget.len() {
+            if let Some(child) = widget.get_mut(i) {
+                let key = child.key();
+                child.configure_recurse(cmgr.child(i, key));
+            }
+        }
+        if id > end {
+            // Only possible if descendants reserve identifiers
+            warn!("Manager::configure_children: insufficient identifiers; reconfiguring");
+            self.send_action(TkAction::Reconfigure);
+            return false;
+        }
+
+        let remap = |id: WidgetId| -> Option<WidgetId> {
+            if !in_range(id) {
+                return Some(id);
+            }
+            let stable = old_stable_ids.get(&id)?;
+            stable_ids.get(stable).cloned()
+        };
+        let mgr = &mut *self.mgr;
+        mgr.char_focus = mgr.char_focus.and_then(remap);
+        mgr.nav_focus = mgr.nav_focus.and_then(remap);
+        mgr.hover = mgr.hover.and_then(remap);
+        mgr.undo.remap(|id| remap(*id));
+        mgr.mouse_grab = mgr.mouse_grab.take().and_then(|mut grab| {
+            grab.start_id = remap(grab.start_id)?;
+            grab.depress = grab.depress.and_then(remap);
+            Some(grab)
+        });
+        let mut i = 0;
+        while i < mgr.pan_grab.len() {
+            if let Some(id) = remap(mgr.pan_grab[i].id) {
+                mgr.pan_grab[i].id = id;
+                i += 1;
+            } else {
+                mgr.remove_pan(i);
+            }
+        }
+        mgr.touch_grab = std::mem::take(&mut mgr.touch_grab)
+            .into_iter()
+            .filter_map(|mut grab| {
+                grab.start_id = remap(grab.start_id)?;
+                grab.depress = grab.depress.and_then(remap);
+                grab.cur_id = grab.cur_id.and_then(remap);
+                Some(grab)
+            })
+            .collect();
+        mgr.key_depress = std::mem::take(&mut mgr.key_depress)
+            .into_iter()
+            .filter_map(|(code, id)| remap(id).map(|id| (code, id)))
+            .collect();
+        for (id, (alt_bypass, layer)) in old_accel_layers {
+            let layer = layer
+                .into_iter()
+                .filter_map(|(key, id)| remap(id).map(|id| (key, id)))
+                .collect();
+            mgr.accel_layers.insert(id, (alt_bypass, layer));
+        }
+        for (time, id) in old_time_updates {
+            if let Some(id) = remap(id) {
+                if let Some(row) = mgr.time_updates.iter_mut().find(|row| row.1 == id) {
+                    row.0 = row.0.min(time);
+                } else {
+                    mgr.time_updates.push((time, id));
+                }
+            }
+        }
+        mgr.time_updates.sort_by(|a, b| b.cmp(a)); // reverse sort
+        mgr.stable_ids.extend(stable_ids);
+
+        self.send_action(TkAction::Redraw);
+        true
+    }
+
     /// Notify that a widget must be redrawn
     ///
     /// Currently the entire window is redrawn on any redraw request and the
@@ -244,6 +500,93 @@ impl<'a> Manager<'a> {
         self.tkw.set_clipboard(content)
     }
 
+    /// Record an undoable edit
+    ///
+    /// Widget `id` should call this *before* applying an edit, where `state`
+    /// yields the state to be restored on undo. The undo history is shared by
+    /// all widgets of the window (including application-level entries pushed
+    /// under the [`WidgetId`] of some user-defined widget), and is unlimited.
+    ///
+    /// If the last entry was pushed by the same widget with the same `kind`
+    /// (other than [`EditKind::Other`]) then the edits are merged into a
+    /// single undo step and `state` is not called. Use
+    /// [`Manager::close_undo_group`] to prevent this.
+    ///
+    /// Pushing an entry clears all redo history.
+    pub fn push_undo<T: Any, F: FnOnce() -> T>(&mut self, id: WidgetId, kind: EditKind, state: F) {
+        self.mgr.undo.push(id, kind, state);
+    }
+
+    /// Stop merging edits of widget `id` into its last undo step
+    ///
+    /// This should be called e.g. when the edit cursor is moved.
+    #[inline]
+    pub fn close_undo_group(&mut self, id: WidgetId) {
+        self.mgr.undo.close(id);
+    }
+
+    /// Undo the last edit of widget `id`
+    ///
+    /// The last undo entry of widget `id` is removed and its state returned;
+    /// `current` is pushed to the redo history in its place. Returns `None`
+    /// (dropping `current`) if there is no such entry or its state does not
+    /// have type `T`.
+    ///
+    /// Widgets should call this on [`ControlKey::Undo`].
+    #[inline]
+    pub fn undo<T: Any>(&mut self, id: WidgetId, current: T) -> Option<T> {
+        self.mgr.undo.undo(id, current)
+    }
+
+    /// Redo the last undone edit of widget `id`
+    ///
+    /// This is the inverse of [`Manager::undo`]. Widgets should call this on
+    /// [`ControlKey::Redo`].
+    #[inline]
+    pub fn redo<T: Any>(&mut self, id: WidgetId, current: T) -> Option<T> {
+        self.mgr.undo.redo(id, current)
+    }
+
+    /// Undo the last edit of the window, whichever widget made it
+    ///
+    /// This sends [`ControlKey::Undo`] to the owner of the last undo entry
+    /// (after the current event has been handled). Returns false if there is
+    /// nothing to undo.
+    pub fn undo_last(&mut self) -> bool {
+        if let Some(id) = self.mgr.undo.last_undo() {
+            self.mgr
+                .pending
+                .push(Pending::Control(id, ControlKey::Undo));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Redo the last undone edit of the window, whichever widget made it
+    ///
+    /// This sends [`ControlKey::Redo`] to the owner of the last redo entry
+    /// (after the current event has been handled). Returns false if there is
+    /// nothing to redo.
+    pub fn redo_last(&mut self) -> bool {
+        if let Some(id) = self.mgr.undo.last_redo() {
+            self.mgr
+                .pending
+                .push(Pending::Control(id, ControlKey::Redo));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove all undo and redo history of widget `id`
+    ///
+    /// This may be called e.g. when the text of a widget is replaced.
+    #[inline]
+    pub fn clear_undo(&mut self, id: WidgetId) {
+        self.mgr.undo.clear(id);
+    }
+
     /// Adjust the theme
     #[inline]
     pub fn adjust_theme<F: FnMut(&mut dyn ThemeApi) -> ThemeAction>(&mut self, mut f: F) {
@@ -279,6 +622,21 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Register widget `id` as the handler of a named command
+    ///
+    /// When a key chord bound to this command (see [`Shortcuts`]) is pressed,
+    /// widget `id` receives [`Event::Command`], regardless of which widget has
+    /// focus. If several widgets register the same command, the last wins. If
+    /// no widget registers a command, the window's root widget receives it.
+    ///
+    /// Registrations are cleared when widgets are reconfigured, thus this
+    /// method should be called from [`WidgetConfig::configure`].
+    pub fn register_command<S: ToString>(&mut self, name: S, id: WidgetId) {
+        let name = name.to_string();
+        debug!("Manager: command {:?} => {}", name, id);
+        self.mgr.commands.insert(name, id);
+    }
+
     /// Add a new accelerator key layer and make it current
     ///
     /// This method affects the behaviour of [`Manager::add_accel_keys`] by
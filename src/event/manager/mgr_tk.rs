@@ -8,19 +8,27 @@
 use log::*;
 use smallvec::SmallVec;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 use super::*;
 use crate::geom::{Coord, DVec2};
 #[allow(unused)]
 use crate::WidgetConfig; // for doc-links
-use crate::{TkAction, TkWindow, Widget, WidgetId};
+use crate::{StableId, TkAction, TkWindow, Widget, WidgetId};
 
 // TODO: this should be configurable or derived from the system
 const DOUBLE_CLICK_TIMEOUT: Duration = Duration::from_secs(1);
 
 const FAKE_MOUSE_BUTTON: MouseButton = MouseButton::Other(0);
 
+fn layout_debug_from_env() -> bool {
+    match std::env::var_os("KAS_LAYOUT_DEBUG") {
+        Some(value) => !value.is_empty() && value != "0",
+        None => false,
+    }
+}
+
 /// Toolkit API
 #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
 impl ManagerState {
@@ -31,6 +39,7 @@ impl ManagerState {
     pub fn new(dpi_factor: f64) -> Self {
         ManagerState {
             end_id: Default::default(),
+            stable_ids: HashMap::new(),
             dpi_factor,
             modifiers: ModifiersState::empty(),
             char_focus: None,
@@ -49,18 +58,35 @@ impl ManagerState {
             pan_grab: SmallVec::new(),
             accel_stack: vec![],
             accel_layers: HashMap::new(),
+            shortcuts: Rc::new(Shortcuts::default()),
+            commands: HashMap::new(),
+            skip_char: false,
             popups: Default::default(),
             new_popups: Default::default(),
             popup_removed: Default::default(),
 
             time_start: Instant::now(),
+            clock: None,
             time_updates: vec![],
             handle_updates: HashMap::new(),
+            resize_updates: vec![],
             pending: SmallVec::new(),
+            undo: Default::default(),
+            recording: None,
+            layout_debug: layout_debug_from_env(),
             action: TkAction::None,
         }
     }
 
+    /// Set the table of keyboard shortcuts
+    ///
+    /// By default, [`Shortcuts::default`] is used. Toolkits usually share one
+    /// table between all windows.
+    #[inline]
+    pub fn set_shortcuts(&mut self, shortcuts: Rc<Shortcuts>) {
+        self.shortcuts = shortcuts;
+    }
+
     /// Configure event manager for a widget tree.
     ///
     /// This should be called by the toolkit on the widget tree when the window
@@ -73,18 +99,25 @@ impl ManagerState {
         self.action = TkAction::None;
 
         // Re-assigning WidgetIds might invalidate state; to avoid this we map
-        // existing ids to new ids
+        // existing ids to new ids. Where a widget was replaced, we fall back
+        // to matching by StableId.
         let mut map = HashMap::new();
+        let mut stable_ids = HashMap::new();
         let mut id = WidgetId::FIRST;
 
         // We re-set these instead of remapping:
         self.accel_stack.clear();
         self.accel_layers.clear();
-        self.time_updates.clear();
         self.handle_updates.clear();
+        self.resize_updates.clear();
+        self.commands.clear();
         self.pending.clear();
         self.nav_fallback = None;
 
+        // Timers scheduled during configure use new ids; only those scheduled
+        // before need remapping.
+        let old_time_updates = std::mem::take(&mut self.time_updates);
+
         // Enumerate and configure all widgets:
         let coord = self.last_mouse_coord;
         self.with(tkw, |mut mgr| {
@@ -92,13 +125,12 @@ impl ManagerState {
             widget.configure_recurse(ConfigureManager {
                 id: &mut id,
                 map: &mut map,
+                stable_ids: &mut stable_ids,
+                stable_id: StableId::ROOT,
                 mgr: &mut mgr,
             });
             mgr.pop_accel_layer(widget.id());
             debug_assert!(mgr.mgr.accel_stack.is_empty());
-
-            let hover = widget.find_id(coord);
-            mgr.set_hover(widget, hover);
         });
         if self.action == TkAction::Reconfigure {
             warn!("Detected TkAction::Reconfigure during configure. This may cause a reconfigure-loop.");
@@ -110,23 +142,50 @@ impl ManagerState {
 
         // The remaining code just updates all input states to new IDs via the map.
 
-        self.char_focus = self.char_focus.and_then(|id| map.get(&id).cloned());
-        self.nav_focus = self.nav_focus.and_then(|id| map.get(&id).cloned());
+        let old_stable_ids: HashMap<WidgetId, StableId> = self
+            .stable_ids
+            .drain()
+            .map(|(stable, id)| (id, stable))
+            .collect();
+        let remap = |id: &WidgetId| -> Option<WidgetId> {
+            map.get(id).cloned().or_else(|| {
+                old_stable_ids
+                    .get(id)
+                    .and_then(|stable| stable_ids.get(stable).cloned())
+            })
+        };
+
+        self.char_focus = self.char_focus.and_then(|id| remap(&id));
+        self.nav_focus = self.nav_focus.and_then(|id| remap(&id));
+        self.hover = self.hover.and_then(|id| remap(&id));
+        self.undo.remap(&remap);
         self.mouse_grab = self.mouse_grab.as_ref().and_then(|grab| {
-            map.get(&grab.start_id).map(|id| MouseGrab {
+            remap(&grab.start_id).map(|id| MouseGrab {
                 button: grab.button,
                 repetitions: grab.repetitions,
-                start_id: *id,
-                depress: grab.depress.and_then(|id| map.get(&id).cloned()),
+                start_id: id,
+                depress: grab.depress.and_then(|id| remap(&id)),
                 mode: grab.mode,
                 pan_grab: grab.pan_grab,
             })
         });
 
+        for (time, id) in old_time_updates {
+            if let Some(id) = remap(&id) {
+                // As in update_on_timer, keep only the earliest time per widget
+                if let Some(row) = self.time_updates.iter_mut().find(|row| row.1 == id) {
+                    row.0 = row.0.min(time);
+                } else {
+                    self.time_updates.push((time, id));
+                }
+            }
+        }
+        self.time_updates.sort_by(|a, b| b.cmp(a)); // reverse sort
+
         let mut i = 0;
         while i < self.pan_grab.len() {
-            if let Some(id) = map.get(&self.pan_grab[i].id) {
-                self.pan_grab[i].id = *id;
+            if let Some(id) = remap(&self.pan_grab[i].id) {
+                self.pan_grab[i].id = id;
                 i += 1;
             } else {
                 self.remove_pan(i);
@@ -152,19 +211,22 @@ impl ManagerState {
             };
         }
 
-        do_map!(self.touch_grab, |mut elt: TouchGrab| map
-            .get(&elt.start_id)
+        do_map!(self.touch_grab, |mut elt: TouchGrab| remap(&elt.start_id)
             .map(|id| {
-                elt.start_id = *id;
+                elt.start_id = id;
                 if let Some(cur_id) = elt.cur_id {
-                    elt.cur_id = map.get(&cur_id).cloned();
+                    elt.cur_id = remap(&cur_id);
                 }
                 elt
             }));
 
-        do_map!(self.key_depress, |elt: (u32, WidgetId)| map
-            .get(&elt.1)
-            .map(|id| (elt.0, *id)));
+        do_map!(self.key_depress, |elt: (u32, WidgetId)| remap(&elt.1)
+            .map(|id| (elt.0, id)));
+        self.stable_ids = stable_ids;
+
+        // Update hovered widget
+        let hover = widget.find_id(coord);
+        self.with(tkw, |mgr| mgr.set_hover(widget, hover));
     }
 
     /// Update the widgets under the cursor and touch events
@@ -200,6 +262,53 @@ impl ManagerState {
         self.time_updates.last().map(|time| time.0)
     }
 
+    /// Enable or disable the virtual clock
+    ///
+    /// By default, the system clock is used. When the virtual clock is
+    /// enabled, time (as reported by [`ManagerState::now`]) only passes when
+    /// advanced via [`ManagerState::advance_clock`]; this allows timer updates
+    /// (see [`Manager::update_on_timer`]) to be delivered deterministically.
+    ///
+    /// When enabled, the virtual clock starts at the current time.
+    pub fn use_virtual_clock(&mut self, enable: bool) {
+        self.clock = if enable {
+            Some(self.clock.unwrap_or_else(Instant::now))
+        } else {
+            None
+        };
+    }
+
+    /// Advance the virtual clock
+    ///
+    /// Does nothing if the virtual clock is not enabled. Timer updates which
+    /// become due are delivered on the next call to [`Manager::update_timer`].
+    pub fn advance_clock(&mut self, duration: Duration) {
+        if let Some(time) = self.clock.as_mut() {
+            *time += duration;
+        }
+    }
+
+    /// Start recording input
+    ///
+    /// All input delivered via the `handle_*` methods of [`Manager`] (including
+    /// [`Manager::handle_winit`]) is recorded until
+    /// [`ManagerState::stop_recording`] is called. Any existing recording is
+    /// discarded.
+    pub fn start_recording(&mut self) {
+        self.recording = Some((self.now(), InputRecording::new()));
+    }
+
+    /// Stop recording input, returning the recording (if any)
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recording.take().map(|(_, recording)| recording)
+    }
+
+    /// True if input is being recorded
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
     /// Set an action
     ///
     /// Since this is a commonly used operation, an operator overload is
@@ -304,12 +413,16 @@ impl ManagerState {
         // make mgr const, but merely pretend it is in the public API.
         mgr.read_only = true;
 
-        for item in mgr.mgr.pending.pop() {
+        for item in std::mem::take(&mut mgr.mgr.pending) {
             match item {
                 Pending::LostCharFocus(id) => {
                     let event = Event::LostCharFocus;
                     mgr.send_event(widget, id, event);
                 }
+                Pending::Control(id, key) => {
+                    let event = Event::Control(key, mgr.mgr.modifiers);
+                    mgr.send_event(widget, id, event);
+                }
             }
         }
 
@@ -325,7 +438,7 @@ impl ManagerState {
 impl<'a> Manager<'a> {
     /// Update widgets due to timer
     pub fn update_timer<W: Widget + ?Sized>(&mut self, widget: &mut W) {
-        let now = Instant::now();
+        let now = self.mgr.now();
 
         // assumption: time_updates are sorted in reverse order
         while !self.mgr.time_updates.is_empty() {
@@ -356,271 +469,406 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Update widgets after the window's layout is applied
+    ///
+    /// Toolkits should call this after each call to
+    /// [`crate::layout::SolveCache::apply_rect`] on the root widget.
+    pub fn update_resize<W: Widget + ?Sized>(&mut self, widget: &mut W) {
+        // NOTE: to avoid borrow conflict, we must clone values!
+        for w_id in self.mgr.resize_updates.clone() {
+            self.send_event(widget, w_id, Event::Resized);
+        }
+    }
+
     /// Handle a winit `WindowEvent`.
     ///
     /// Note that some event types are not *does not* handled, since for these
     /// events the toolkit must take direct action anyway:
     /// `Resized(size)`, `RedrawRequested`, `HiDpiFactorChanged(factor)`.
+    ///
+    /// This is a thin translation layer over the `handle_*` methods below.
     #[cfg(feature = "winit")]
     pub fn handle_winit<W>(&mut self, widget: &mut W, event: winit::event::WindowEvent)
     where
         W: Widget<Msg = VoidMsg> + ?Sized,
     {
-        use winit::event::{ElementState, MouseScrollDelta, TouchPhase, WindowEvent::*};
-
-        // Note: since <W as Handler>::Msg = VoidMsg, only two values of
-        // Response are possible: None and Unhandled. We don't have any use for
-        // Unhandled events here, so we can freely ignore all responses.
+        use winit::event::{MouseScrollDelta, WindowEvent::*};
 
         match event {
-            CloseRequested => self.send_action(TkAction::Close),
+            CloseRequested => self.handle_close_requested(),
             /* Not yet supported: see #98
             DroppedFile(path) => ,
             HoveredFile(path) => ,
             HoveredFileCancelled => ,
             */
-            ReceivedCharacter(c) => {
-                if let Some(id) = self.mgr.char_focus {
-                    // Filter out control codes (Unicode 5.11) which
-                    // should be sent via Event::Control
-                    if c < '\u{20}' || (c >= '\u{7f}' && c <= '\u{9f}') {
-                        // We ignore these when winit also sends a corresponding
-                        // VirtualKeyCode but match those codes it doesn't.
-                        // Possibly we should instead directly match Control+Letter
-                        // combos and handle localisation ourselves, but we
-                        // don't yet have localisation or customisation support.
-                        let key = match c {
-                            '\u{03}' => ControlKey::Copy,
-                            '\u{16}' => ControlKey::Paste,
-                            '\u{18}' => ControlKey::Cut,
-                            '\u{1A}' => ControlKey::Undo, // also redo; we can't differentiate
-                            _ => return,
-                        };
-                        let event = Event::Control(key, self.mgr.modifiers);
-                        self.send_event(widget, id, event);
-                    } else {
-                        let event = Event::ReceivedCharacter(c);
-                        self.send_event(widget, id, event);
-                    }
-                }
-            }
+            ReceivedCharacter(c) => self.handle_char(widget, c),
             KeyboardInput {
                 input,
                 is_synthetic,
                 ..
             } => {
-                if input.state == ElementState::Pressed && !is_synthetic {
-                    if let Some(vkey) = input.virtual_keycode {
-                        self.start_key_event(widget, vkey, input.scancode);
-                    }
-                } else if input.state == ElementState::Released {
-                    self.end_key_event(input.scancode);
+                if input.state == ElementState::Pressed && is_synthetic {
+                    return;
                 }
+                self.handle_key(widget, input.state, input.virtual_keycode, input.scancode);
+            }
+            ModifiersChanged(state) => self.handle_modifiers(state),
+            CursorMoved { position, .. } => self.handle_cursor_moved(widget, position.into()),
+            // CursorEntered { .. },
+            CursorLeft { .. } => self.handle_cursor_left(widget),
+            MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(x, y),
+                    MouseScrollDelta::PixelDelta(pos) => {
+                        ScrollDelta::PixelDelta(Coord::from_logical(pos, self.mgr.dpi_factor))
+                    }
+                };
+                self.handle_wheel(widget, delta);
+            }
+            MouseInput { state, button, .. } => self.handle_mouse_input(widget, state, button),
+            // TouchpadPressure { pressure: f32, stage: i64, },
+            // AxisMotion { axis: AxisId, value: f64, },
+            Touch(touch) => {
+                let coord = touch.location.into();
+                self.handle_touch(widget, touch.id, touch.phase, coord);
+            }
+            _ => (),
+        }
+    }
+
+    /// Handle a raw input event
+    ///
+    /// This dispatches to the `handle_*` method corresponding to the event;
+    /// it may be used to replay an [`InputRecording`]. Timer updates are not
+    /// delivered by this method; see [`ManagerState::advance_clock`].
+    pub fn handle_input<W>(&mut self, widget: &mut W, event: InputEvent)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        match event {
+            InputEvent::CloseRequested => self.handle_close_requested(),
+            InputEvent::ReceivedCharacter(c) => self.handle_char(widget, c),
+            InputEvent::Key {
+                state,
+                vkey,
+                scancode,
+            } => self.handle_key(widget, state, vkey, scancode),
+            InputEvent::ModifiersChanged(state) => self.handle_modifiers(state),
+            InputEvent::CursorMoved(coord) => self.handle_cursor_moved(widget, coord),
+            InputEvent::CursorLeft => self.handle_cursor_left(widget),
+            InputEvent::Wheel(delta) => self.handle_wheel(widget, delta),
+            InputEvent::MouseInput { state, button } => {
+                self.handle_mouse_input(widget, state, button)
             }
-            ModifiersChanged(state) => {
-                if state.alt() != self.mgr.modifiers.alt() {
-                    // This controls drawing of accelerator key indicators
-                    self.mgr.send_action(TkAction::Redraw);
+            InputEvent::Touch { id, phase, coord } => self.handle_touch(widget, id, phase, coord),
+        }
+    }
+
+    // Note: since <W as Handler>::Msg = VoidMsg, only two values of
+    // Response are possible: None and Unhandled. We don't have any use for
+    // Unhandled events in the methods below, so we can freely ignore all
+    // responses.
+    //
+    // Each of these methods records its input (see ManagerState::record).
+
+    /// Handle a request to close the window
+    pub fn handle_close_requested(&mut self) {
+        self.mgr.record(InputEvent::CloseRequested);
+        self.send_action(TkAction::Close);
+    }
+
+    /// Handle a received character
+    ///
+    /// Control characters are ignored: key presses are translated to
+    /// [`Event::Control`] via [`Shortcuts`] instead. Characters resulting
+    /// from a key press matching a shortcut are also ignored.
+    pub fn handle_char<W>(&mut self, widget: &mut W, c: char)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::ReceivedCharacter(c));
+        if std::mem::take(&mut self.mgr.skip_char) {
+            return;
+        }
+        if let Some(id) = self.mgr.char_focus {
+            // Filter out control codes (Unicode 5.11)
+            if c < '\u{20}' || (c >= '\u{7f}' && c <= '\u{9f}') {
+                return;
+            }
+            let event = Event::ReceivedCharacter(c);
+            self.send_event(widget, id, event);
+        }
+    }
+
+    /// Handle a key press or release
+    ///
+    /// The `scancode` is used to match key releases to key presses; it should
+    /// be unique to the physical key.
+    pub fn handle_key<W>(
+        &mut self,
+        widget: &mut W,
+        state: ElementState,
+        vkey: Option<VirtualKeyCode>,
+        scancode: u32,
+    ) where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::Key {
+            state,
+            vkey,
+            scancode,
+        });
+        match state {
+            ElementState::Pressed => {
+                if let Some(vkey) = vkey {
+                    self.start_key_event(widget, vkey, scancode);
                 }
-                self.mgr.modifiers = state;
             }
-            CursorMoved { position, .. } => {
-                self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
-                let coord = position.into();
+            ElementState::Released => self.end_key_event(scancode),
+        }
+    }
 
-                // Update hovered widget
-                let cur_id = widget.find_id(coord);
-                let delta = coord - self.mgr.last_mouse_coord;
-                self.set_hover(widget, cur_id);
+    /// Handle a change in modifier state
+    pub fn handle_modifiers(&mut self, state: ModifiersState) {
+        self.mgr.record(InputEvent::ModifiersChanged(state));
+        if state.alt() != self.mgr.modifiers.alt() {
+            // This controls drawing of accelerator key indicators
+            self.mgr.send_action(TkAction::Redraw);
+        }
+        self.mgr.modifiers = state;
+    }
 
-                if let Some(grab) = self.mouse_grab() {
-                    if grab.mode == GrabMode::Grab {
-                        let source = PressSource::Mouse(grab.button, grab.repetitions);
-                        let event = Event::PressMove {
-                            source,
-                            cur_id,
-                            coord,
-                            delta,
-                        };
-                        self.send_event(widget, grab.start_id, event);
-                    } else if let Some(pan) = self.mgr.pan_grab.get_mut(grab.pan_grab.0 as usize) {
-                        pan.coords[grab.pan_grab.1 as usize].1 = coord;
-                    }
-                } else if let Some(id) = self.mgr.popups.last().map(|(_, p)| p.parent) {
-                    let source = PressSource::Mouse(FAKE_MOUSE_BUTTON, 0);
-                    let event = Event::PressMove {
+    /// Handle motion of the mouse cursor to `coord`
+    pub fn handle_cursor_moved<W>(&mut self, widget: &mut W, coord: Coord)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::CursorMoved(coord));
+        self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
+
+        // Update hovered widget
+        let cur_id = widget.find_id(coord);
+        let delta = coord - self.mgr.last_mouse_coord;
+        self.set_hover(widget, cur_id);
+
+        if let Some(grab) = self.mouse_grab() {
+            if grab.mode == GrabMode::Grab {
+                let source = PressSource::Mouse(grab.button, grab.repetitions);
+                let event = Event::PressMove {
+                    source,
+                    cur_id,
+                    coord,
+                    delta,
+                };
+                self.send_event(widget, grab.start_id, event);
+            } else if let Some(pan) = self.mgr.pan_grab.get_mut(grab.pan_grab.0 as usize) {
+                pan.coords[grab.pan_grab.1 as usize].1 = coord;
+            }
+        } else if let Some(id) = self.mgr.popups.last().map(|(_, p)| p.parent) {
+            let source = PressSource::Mouse(FAKE_MOUSE_BUTTON, 0);
+            let event = Event::PressMove {
+                source,
+                cur_id,
+                coord,
+                delta,
+            };
+            self.send_event(widget, id, event);
+        } else {
+            // We don't forward move events without a grab
+        }
+
+        self.mgr.last_mouse_coord = coord;
+    }
+
+    /// Handle the mouse cursor leaving the window
+    pub fn handle_cursor_left<W>(&mut self, widget: &mut W)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::CursorLeft);
+        self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
+
+        if self.mouse_grab().is_none() {
+            // If there's a mouse grab, we will continue to receive
+            // coordinates; if not, set a fake coordinate off the window
+            self.mgr.last_mouse_coord = Coord(-1, -1);
+            self.set_hover(widget, None);
+        }
+    }
+
+    /// Handle mouse-wheel (or touchpad) scrolling
+    pub fn handle_wheel<W>(&mut self, widget: &mut W, delta: ScrollDelta)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::Wheel(delta));
+        self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
+
+        let event = Event::Scroll(delta);
+        if let Some(id) = self.mgr.hover {
+            self.send_event(widget, id, event);
+        }
+    }
+
+    /// Handle a mouse button press or release
+    ///
+    /// The press location is the last coordinate passed to
+    /// [`Manager::handle_cursor_moved`].
+    pub fn handle_mouse_input<W>(
+        &mut self,
+        widget: &mut W,
+        state: ElementState,
+        button: MouseButton,
+    ) where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::MouseInput { state, button });
+        let coord = self.mgr.last_mouse_coord;
+
+        if state == ElementState::Pressed {
+            let now = self.mgr.now();
+            if button != self.mgr.last_click_button || self.mgr.last_click_timeout < now {
+                self.mgr.last_click_button = button;
+                self.mgr.last_click_repetitions = 0;
+            }
+            self.mgr.last_click_repetitions += 1;
+            self.mgr.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
+        }
+
+        if let Some(grab) = self.mouse_grab() {
+            match grab.mode {
+                GrabMode::Grab => {
+                    // Mouse grab active: send events there
+                    debug_assert_eq!(state, ElementState::Released);
+                    let source = PressSource::Mouse(button, grab.repetitions);
+                    let event = Event::PressEnd {
                         source,
-                        cur_id,
+                        end_id: self.mgr.hover,
                         coord,
-                        delta,
                     };
-                    self.send_event(widget, id, event);
-                } else {
-                    // We don't forward move events without a grab
+                    self.send_event(widget, grab.start_id, event);
                 }
+                // Pan events do not receive Start/End notifications
+                _ => (),
+            };
 
-                self.mgr.last_mouse_coord = coord;
+            if state == ElementState::Released {
+                self.end_mouse_grab(button);
             }
-            // CursorEntered { .. },
-            CursorLeft { .. } => {
-                self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
-
-                if self.mouse_grab().is_none() {
-                    // If there's a mouse grab, we will continue to receive
-                    // coordinates; if not, set a fake coordinate off the window
-                    self.mgr.last_mouse_coord = Coord(-1, -1);
-                    self.set_hover(widget, None);
+        } else if let Some(start_id) = self.mgr.hover {
+            // No mouse grab but have a hover target
+            if state == ElementState::Pressed {
+                let source = PressSource::Mouse(button, self.mgr.last_click_repetitions);
+                let event = Event::PressStart {
+                    source,
+                    start_id,
+                    coord,
+                };
+                if !self.send_popup_first(widget, start_id, event) && source.is_secondary() {
+                    self.open_context_menu(widget, start_id, Some(coord));
                 }
             }
-            MouseWheel { delta, .. } => {
-                self.mgr.last_click_button = FAKE_MOUSE_BUTTON;
+        }
+    }
 
-                let event = Event::Scroll(match delta {
-                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(x, y),
-                    MouseScrollDelta::PixelDelta(pos) => {
-                        ScrollDelta::PixelDelta(Coord::from_logical(pos, self.mgr.dpi_factor))
-                    }
-                });
-                if let Some(id) = self.mgr.hover {
-                    self.send_event(widget, id, event);
+    /// Handle a touch event
+    pub fn handle_touch<W>(
+        &mut self,
+        widget: &mut W,
+        touch_id: u64,
+        phase: TouchPhase,
+        coord: Coord,
+    ) where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.mgr.record(InputEvent::Touch {
+            id: touch_id,
+            phase,
+            coord,
+        });
+        let source = PressSource::Touch(touch_id);
+        match phase {
+            TouchPhase::Started => {
+                if let Some(start_id) = widget.find_id(coord) {
+                    let event = Event::PressStart {
+                        source,
+                        start_id,
+                        coord,
+                    };
+                    self.send_popup_first(widget, start_id, event);
                 }
             }
-            MouseInput { state, button, .. } => {
-                let coord = self.mgr.last_mouse_coord;
-
-                if state == ElementState::Pressed {
-                    let now = Instant::now();
-                    if button != self.mgr.last_click_button || self.mgr.last_click_timeout < now {
-                        self.mgr.last_click_button = button;
-                        self.mgr.last_click_repetitions = 0;
-                    }
-                    self.mgr.last_click_repetitions += 1;
-                    self.mgr.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
-                }
-
-                if let Some(grab) = self.mouse_grab() {
-                    match grab.mode {
-                        GrabMode::Grab => {
-                            // Mouse grab active: send events there
-                            debug_assert_eq!(state, ElementState::Released);
-                            let source = PressSource::Mouse(button, grab.repetitions);
-                            let event = Event::PressEnd {
-                                source,
-                                end_id: self.mgr.hover,
-                                coord,
-                            };
-                            self.send_event(widget, grab.start_id, event);
-                        }
-                        // Pan events do not receive Start/End notifications
-                        _ => (),
-                    };
+            TouchPhase::Moved => {
+                let cur_id = widget.find_id(coord);
 
-                    if state == ElementState::Released {
-                        self.end_mouse_grab(button);
-                    }
-                } else if let Some(start_id) = self.mgr.hover {
-                    // No mouse grab but have a hover target
-                    if state == ElementState::Pressed {
-                        let source = PressSource::Mouse(button, self.mgr.last_click_repetitions);
-                        let event = Event::PressStart {
+                let mut r = None;
+                let mut pan_grab = None;
+                if let Some(grab) = self.get_touch(touch_id) {
+                    if grab.mode == GrabMode::Grab {
+                        let id = grab.start_id;
+                        let event = Event::PressMove {
                             source,
-                            start_id,
+                            cur_id,
                             coord,
+                            delta: coord - grab.coord,
                         };
-                        self.send_popup_first(widget, start_id, event);
+                        // Only when 'depressed' status changes:
+                        let redraw = grab.cur_id != cur_id
+                            && (grab.cur_id == Some(grab.start_id)
+                                || cur_id == Some(grab.start_id));
+
+                        grab.cur_id = cur_id;
+                        grab.coord = coord;
+
+                        r = Some((id, event, redraw));
+                    } else {
+                        pan_grab = Some(grab.pan_grab);
                     }
                 }
-            }
-            // TouchpadPressure { pressure: f32, stage: i64, },
-            // AxisMotion { axis: AxisId, value: f64, },
-            Touch(touch) => {
-                let source = PressSource::Touch(touch.id);
-                let coord = touch.location.into();
-                match touch.phase {
-                    TouchPhase::Started => {
-                        if let Some(start_id) = widget.find_id(coord) {
-                            let event = Event::PressStart {
-                                source,
-                                start_id,
-                                coord,
-                            };
-                            self.send_popup_first(widget, start_id, event);
-                        }
-                    }
-                    TouchPhase::Moved => {
-                        let cur_id = widget.find_id(coord);
-
-                        let mut r = None;
-                        let mut pan_grab = None;
-                        if let Some(grab) = self.get_touch(touch.id) {
-                            if grab.mode == GrabMode::Grab {
-                                let id = grab.start_id;
-                                let event = Event::PressMove {
-                                    source,
-                                    cur_id,
-                                    coord,
-                                    delta: coord - grab.coord,
-                                };
-                                // Only when 'depressed' status changes:
-                                let redraw = grab.cur_id != cur_id
-                                    && (grab.cur_id == Some(grab.start_id)
-                                        || cur_id == Some(grab.start_id));
-
-                                grab.cur_id = cur_id;
-                                grab.coord = coord;
-
-                                r = Some((id, event, redraw));
-                            } else {
-                                pan_grab = Some(grab.pan_grab);
-                            }
-                        }
 
-                        if let Some((id, event, redraw)) = r {
-                            if redraw {
-                                self.send_action(TkAction::Redraw);
-                            }
-                            self.send_event(widget, id, event);
-                        } else if let Some(pan_grab) = pan_grab {
-                            if (pan_grab.1 as usize) < MAX_PAN_GRABS {
-                                if let Some(pan) = self.mgr.pan_grab.get_mut(pan_grab.0 as usize) {
-                                    pan.coords[pan_grab.1 as usize].1 = coord;
-                                }
-                            }
-                        }
+                if let Some((id, event, redraw)) = r {
+                    if redraw {
+                        self.send_action(TkAction::Redraw);
                     }
-                    TouchPhase::Ended => {
-                        if let Some(grab) = self.remove_touch(touch.id) {
-                            if grab.mode == GrabMode::Grab {
-                                let event = Event::PressEnd {
-                                    source,
-                                    end_id: grab.cur_id,
-                                    coord,
-                                };
-                                if let Some(cur_id) = grab.cur_id {
-                                    self.redraw(cur_id);
-                                }
-                                self.send_event(widget, grab.start_id, event);
-                            } else {
-                                self.mgr.remove_pan_grab(grab.pan_grab);
-                            }
+                    self.send_event(widget, id, event);
+                } else if let Some(pan_grab) = pan_grab {
+                    if (pan_grab.1 as usize) < MAX_PAN_GRABS {
+                        if let Some(pan) = self.mgr.pan_grab.get_mut(pan_grab.0 as usize) {
+                            pan.coords[pan_grab.1 as usize].1 = coord;
                         }
                     }
-                    TouchPhase::Cancelled => {
-                        if let Some(grab) = self.remove_touch(touch.id) {
-                            let event = Event::PressEnd {
-                                source,
-                                end_id: None,
-                                coord,
-                            };
-                            if let Some(cur_id) = grab.cur_id {
-                                self.redraw(cur_id);
-                            }
-                            self.send_event(widget, grab.start_id, event);
+                }
+            }
+            TouchPhase::Ended => {
+                if let Some(grab) = self.remove_touch(touch_id) {
+                    if grab.mode == GrabMode::Grab {
+                        let event = Event::PressEnd {
+                            source,
+                            end_id: grab.cur_id,
+                            coord,
+                        };
+                        if let Some(cur_id) = grab.cur_id {
+                            self.redraw(cur_id);
                         }
+                        self.send_event(widget, grab.start_id, event);
+                    } else {
+                        self.mgr.remove_pan_grab(grab.pan_grab);
                     }
                 }
             }
-            _ => (),
+            TouchPhase::Cancelled => {
+                if let Some(grab) = self.remove_touch(touch_id) {
+                    let event = Event::PressEnd {
+                        source,
+                        end_id: None,
+                        coord,
+                    };
+                    if let Some(cur_id) = grab.cur_id {
+                        self.redraw(cur_id);
+                    }
+                    self.send_event(widget, grab.start_id, event);
+                }
+            }
         }
     }
 }
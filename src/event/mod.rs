@@ -68,12 +68,17 @@
 //! [`WidgetId`]: crate::WidgetId
 
 mod callback;
+mod commands;
+mod context_menu;
 #[cfg(not(feature = "winit"))]
 mod enums;
 mod events;
 mod handler;
+mod input;
 mod manager;
 mod response;
+mod shortcuts;
+mod undo;
 mod update;
 
 use smallvec::SmallVec;
@@ -84,17 +89,26 @@ use std::fmt::Debug;
 use kas::{draw::InputState, Layout, WidgetCore};
 
 #[cfg(feature = "winit")]
-pub use winit::event::{ModifiersState, MouseButton, VirtualKeyCode};
+pub use winit::event::{ElementState, ModifiersState, MouseButton, TouchPhase, VirtualKeyCode};
 #[cfg(feature = "winit")]
 pub use winit::window::CursorIcon;
 
 pub use callback::Callback;
+pub use commands::{CommandBinding, Commands};
+pub use context_menu::{ContextEntry, ContextMenu};
 #[cfg(not(feature = "winit"))]
-pub use enums::{CursorIcon, ModifiersState, MouseButton, VirtualKeyCode};
+pub use enums::{
+    CursorIcon, ElementState, ModifiersState, MouseButton, TouchPhase, VirtualKeyCode,
+};
 pub use events::*;
 pub use handler::{Handler, SendEvent};
+pub use input::{InputEvent, InputRecording};
 pub use manager::{ConfigureManager, GrabMode, Manager, ManagerState};
 pub use response::Response;
+pub use shortcuts::{format_chord, Binding, Shortcuts, ShortcutsError};
+pub use undo::EditKind;
+#[cfg(test)]
+pub(crate) use undo::UndoStack;
 pub use update::UpdateHandle;
 
 /// A type supporting a small number of key bindings
@@ -0,0 +1,396 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Keyboard shortcuts
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::{fmt, io};
+
+use super::{ControlKey, ModifiersState, VirtualKeyCode};
+#[allow(unused)]
+use super::{Event, Manager}; // for doc-links
+
+/// The modifier used by standard shortcuts (Ctrl, or Command on MacOS)
+#[cfg(target_os = "macos")]
+const PRIMARY: ModifiersState = ModifiersState::LOGO;
+#[cfg(not(target_os = "macos"))]
+const PRIMARY: ModifiersState = ModifiersState::CTRL;
+
+/// The action bound to a key chord
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    /// Send [`Event::Control`] (as if the key were pressed)
+    Control(ControlKey),
+    /// Send [`Event::Command`] to the command's handler
+    ///
+    /// See [`Manager::register_command`].
+    Command(String),
+    /// Do nothing
+    ///
+    /// Unlike a missing binding, this does not fall back to the binding of the
+    /// key without modifiers.
+    None,
+}
+
+/// Error loading a [`Shortcuts`] table
+#[derive(Debug)]
+pub enum ShortcutsError {
+    /// Failure reading the file
+    Io(io::Error),
+    /// Syntax error on a line (1-based)
+    Parse(usize, String),
+}
+
+impl fmt::Display for ShortcutsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShortcutsError::Io(e) => write!(f, "failed to read shortcuts: {}", e),
+            ShortcutsError::Parse(line, msg) => write!(f, "shortcuts line {}: {}", line, msg),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutsError {}
+
+impl From<io::Error> for ShortcutsError {
+    fn from(e: io::Error) -> Self {
+        ShortcutsError::Io(e)
+    }
+}
+
+/// A table of keyboard shortcuts
+///
+/// This maps key chords (a [`VirtualKeyCode`] plus [`ModifiersState`]) to a
+/// [`Binding`]. Each window's [`Manager`] consults this table on each key
+/// press; the toolkit usually shares one table between all windows.
+///
+/// The [`Default`] table maps navigation keys to the corresponding
+/// [`ControlKey`] and the usual clipboard, undo and select-all chords (using
+/// Ctrl, or Command on MacOS). When no binding matches the exact chord, a
+/// [`Binding::Control`] bound to the key without modifiers is used (with the
+/// modifiers passed in [`Event::Control`]); thus Shift+Left still reaches
+/// widgets as [`ControlKey::Left`].
+///
+/// ### Configuration files
+///
+/// Bindings may be loaded from a text file (see [`Shortcuts::load`]), each
+/// line taking the form `Chord = Binding`. A chord is a list of modifiers
+/// (`Ctrl`, `Shift`, `Alt`, `Super`) and a key name separated by `+`.
+/// Key names match [`VirtualKeyCode`] variants (case insensitive), with
+/// aliases `0`-`9`, `Enter`, `Esc`, `Backspace` and `Menu`. A binding is the
+/// name of a [`ControlKey`] variant, a quoted command name or `none` (to
+/// unbind the chord; see [`Binding::None`]). Lines starting with `#` are ignored. For example:
+///
+/// ```text
+/// # Emacs-style editing
+/// Ctrl+F = Right
+/// Ctrl+B = Left
+/// Ctrl+A = Home
+/// Ctrl+E = End
+/// Ctrl+W = Cut
+/// Alt+W = Copy
+/// Ctrl+Y = Paste
+/// Ctrl+Slash = Undo
+/// Ctrl+S = "save"
+/// Ctrl+Z = none
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shortcuts {
+    map: HashMap<(ModifiersState, VirtualKeyCode), Binding>,
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        use ControlKey as CK;
+        use VirtualKeyCode as VK;
+        let mut shortcuts = Shortcuts::new();
+
+        let none = ModifiersState::empty();
+        for (_, vkey) in KEYS {
+            if let Some(key) = ControlKey::new(*vkey) {
+                shortcuts.insert(none, *vkey, Binding::Control(key));
+            }
+        }
+
+        let shift = ModifiersState::SHIFT;
+        let list = [
+            (PRIMARY, VK::A, CK::SelectAll),
+            (PRIMARY, VK::C, CK::Copy),
+            (PRIMARY, VK::V, CK::Paste),
+            (PRIMARY, VK::X, CK::Cut),
+            (PRIMARY, VK::Z, CK::Undo),
+            (PRIMARY | shift, VK::Z, CK::Redo),
+            (PRIMARY, VK::Y, CK::Redo),
+        ];
+        for (modifiers, vkey, key) in list.iter() {
+            shortcuts.insert(*modifiers, *vkey, Binding::Control(*key));
+        }
+
+        shortcuts
+    }
+}
+
+impl Shortcuts {
+    /// Construct an empty table
+    ///
+    /// See also [`Shortcuts::default`].
+    #[inline]
+    pub fn new() -> Self {
+        Shortcuts {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Bind a chord, returning the previous binding (if any)
+    pub fn insert(
+        &mut self,
+        modifiers: ModifiersState,
+        vkey: VirtualKeyCode,
+        binding: Binding,
+    ) -> Option<Binding> {
+        self.map.insert((modifiers, vkey), binding)
+    }
+
+    /// Remove the binding of a chord
+    ///
+    /// Unlike binding [`Binding::None`], this restores the fallback to the
+    /// key without modifiers (see [`Shortcuts::get`]).
+    pub fn remove(&mut self, modifiers: ModifiersState, vkey: VirtualKeyCode) -> Option<Binding> {
+        self.map.remove(&(modifiers, vkey))
+    }
+
+    /// Look up the binding of a chord
+    ///
+    /// This returns the exact binding if any, otherwise a
+    /// [`Binding::Control`] bound to `vkey` without modifiers. Chords bound
+    /// to [`Binding::None`] return `None`.
+    pub fn get(&self, modifiers: ModifiersState, vkey: VirtualKeyCode) -> Option<&Binding> {
+        match self.map.get(&(modifiers, vkey)) {
+            Some(Binding::None) => return None,
+            Some(binding) => return Some(binding),
+            None => (),
+        }
+        match self.map.get(&(ModifiersState::empty(), vkey)) {
+            Some(binding @ Binding::Control(_)) => Some(binding),
+            _ => None,
+        }
+    }
+
+    /// Look up the binding of a chord, without fallback
+    ///
+    /// Unlike [`Shortcuts::get`], this only returns a binding of the exact
+    /// chord, including [`Binding::None`].
+    pub fn get_exact(&self, modifiers: ModifiersState, vkey: VirtualKeyCode) -> Option<&Binding> {
+        self.map.get(&(modifiers, vkey))
+    }
+
+    /// Find a chord bound to `binding`
+    ///
+    /// If several chords match, the first is returned when ordered by
+    /// modifiers (bit value), then by key code; e.g. Ctrl+Y before
+    /// Ctrl+Shift+Z.
+    pub fn find(&self, binding: &Binding) -> Option<(ModifiersState, VirtualKeyCode)> {
+        self.map
+            .iter()
+            .filter(|(_, b)| *b == binding)
+            .map(|(chord, _)| *chord)
+            .min_by_key(|(modifiers, vkey)| (modifiers.bits(), *vkey as u32))
+    }
+
+    /// Load bindings from a file, replacing existing bindings of the same chords
+    ///
+    /// See [configuration files](#configuration-files) for the format.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ShortcutsError> {
+        let text = std::fs::read_to_string(path)?;
+        self.parse(&text)
+    }
+
+    /// Parse bindings from a string, replacing existing bindings of the same chords
+    ///
+    /// See [configuration files](#configuration-files) for the format. On
+    /// error, bindings from lines before the erroneous line remain applied.
+    pub fn parse(&mut self, text: &str) -> Result<(), ShortcutsError> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |msg: String| ShortcutsError::Parse(index + 1, msg);
+
+            let mut parts = line.splitn(2, '=');
+            let chord = parts.next().unwrap().trim();
+            let binding = match parts.next() {
+                Some(binding) => binding.trim(),
+                None => return Err(err("expected `Chord = Binding`".into())),
+            };
+            let (modifiers, vkey) = parse_chord(chord).map_err(err)?;
+
+            if binding.eq_ignore_ascii_case("none") {
+                self.insert(modifiers, vkey, Binding::None);
+            } else if binding.len() >= 2 && binding.starts_with('"') && binding.ends_with('"') {
+                let name = binding[1..binding.len() - 1].to_string();
+                self.insert(modifiers, vkey, Binding::Command(name));
+            } else {
+                let key = lookup(CONTROL_KEYS, binding)
+                    .ok_or_else(|| err(format!("unknown control key: {}", binding)))?;
+                self.insert(modifiers, vkey, Binding::Control(key));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format a chord, e.g. "Ctrl+Shift+Z"
+///
+/// The result uses the syntax of shortcut configuration files (see
+/// [`Shortcuts`]) and is also suitable for display.
+pub fn format_chord(modifiers: ModifiersState, vkey: VirtualKeyCode) -> String {
+    let mut s = String::new();
+    for (name, m) in MODIFIERS {
+        if modifiers.contains(*m) {
+            s.push_str(name);
+            s.push('+');
+        }
+    }
+    match KEYS.iter().find(|(_, k)| *k == vkey) {
+        Some((name, _)) => s.push_str(name),
+        None => s.push_str(&format!("{:?}", vkey)),
+    }
+    s
+}
+
+fn parse_chord(chord: &str) -> Result<(ModifiersState, VirtualKeyCode), String> {
+    let mut modifiers = ModifiersState::empty();
+    let mut parts: Vec<&str> = chord.split('+').map(|s| s.trim()).collect();
+    let key = parts.pop().unwrap_or("");
+    for part in parts {
+        match lookup(MODIFIERS, part).or_else(|| lookup(MODIFIER_ALIASES, part)) {
+            Some(m) => modifiers |= m,
+            None => return Err(format!("unknown modifier: {}", part)),
+        }
+    }
+    match lookup(KEYS, key).or_else(|| lookup(KEY_ALIASES, key)) {
+        Some(vkey) => Ok((modifiers, vkey)),
+        None => Err(format!("unknown key: {}", key)),
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| *v)
+}
+
+const MODIFIERS: &[(&str, ModifiersState)] = &[
+    ("Ctrl", ModifiersState::CTRL),
+    ("Shift", ModifiersState::SHIFT),
+    ("Alt", ModifiersState::ALT),
+    ("Super", ModifiersState::LOGO),
+];
+
+const MODIFIER_ALIASES: &[(&str, ModifiersState)] = &[
+    ("Control", ModifiersState::CTRL),
+    ("Option", ModifiersState::ALT),
+    ("Logo", ModifiersState::LOGO),
+    ("Cmd", ModifiersState::LOGO),
+];
+
+macro_rules! table {
+    ($name:ident: $T:ident { $($v:ident),* $(,)? }) => {
+        const $name: &[(&str, $T)] = &[$((stringify!($v), $T::$v)),*];
+    };
+}
+
+table!(CONTROL_KEYS: ControlKey {
+    Return, Tab, Left, Right, Up, Down, Home, End, PageUp, PageDown,
+    Snapshot, ScrollLock, Pause, Insert, Delete, Backspace,
+    Cut, Copy, Paste, Undo, Redo, SelectAll, Backward, Forward,
+});
+
+table!(KEYS: VirtualKeyCode {
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    Escape, Snapshot, Scroll, Pause, Insert, Home, Delete, End, PageDown, PageUp,
+    Left, Up, Right, Down, Back, Return, Space, Tab, Apps, NumpadEnter,
+    Apostrophe, Backslash, Comma, Equals, Grave, LBracket, RBracket, Minus,
+    Period, Semicolon, Slash, Cut, Copy, Paste, NavigateForward, NavigateBackward,
+});
+
+const KEY_ALIASES: &[(&str, VirtualKeyCode)] = &[
+    ("0", VirtualKeyCode::Key0),
+    ("1", VirtualKeyCode::Key1),
+    ("2", VirtualKeyCode::Key2),
+    ("3", VirtualKeyCode::Key3),
+    ("4", VirtualKeyCode::Key4),
+    ("5", VirtualKeyCode::Key5),
+    ("6", VirtualKeyCode::Key6),
+    ("7", VirtualKeyCode::Key7),
+    ("8", VirtualKeyCode::Key8),
+    ("9", VirtualKeyCode::Key9),
+    ("Enter", VirtualKeyCode::Return),
+    ("Esc", VirtualKeyCode::Escape),
+    ("Backspace", VirtualKeyCode::Back),
+    ("Menu", VirtualKeyCode::Apps),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_lookup() {
+        let mut shortcuts = Shortcuts::default();
+        let ctrl = ModifiersState::CTRL;
+        let text = "# Emacs\nCtrl+F = Right\nAlt + w = copy\nCtrl+S = \"save\"\nCtrl+A = none\n";
+        shortcuts.parse(text).unwrap();
+
+        let right = Binding::Control(ControlKey::Right);
+        assert_eq!(shortcuts.get(ctrl, VirtualKeyCode::F), Some(&right));
+        let copy = Binding::Control(ControlKey::Copy);
+        assert_eq!(
+            shortcuts.get(ModifiersState::ALT, VirtualKeyCode::W),
+            Some(&copy)
+        );
+        let save = Binding::Command("save".into());
+        assert_eq!(shortcuts.get(ctrl, VirtualKeyCode::S), Some(&save));
+        assert_eq!(shortcuts.get(ctrl, VirtualKeyCode::A), None);
+        assert_eq!(shortcuts.find(&save), Some((ctrl, VirtualKeyCode::S)));
+
+        // Fall back to the unmodified key for control keys
+        let left = Binding::Control(ControlKey::Left);
+        assert_eq!(shortcuts.get(ctrl, VirtualKeyCode::Left), Some(&left));
+
+        // ... except where explicitly unbound
+        shortcuts.parse("Ctrl+Left = none").unwrap();
+        assert_eq!(shortcuts.get(ctrl, VirtualKeyCode::Left), None);
+        assert_eq!(
+            shortcuts.get_exact(ctrl, VirtualKeyCode::Left),
+            Some(&Binding::None)
+        );
+        let none = ModifiersState::empty();
+        assert_eq!(shortcuts.get(none, VirtualKeyCode::Left), Some(&left));
+        shortcuts.remove(ctrl, VirtualKeyCode::Left);
+        assert_eq!(shortcuts.get(ctrl, VirtualKeyCode::Left), Some(&left));
+
+        // Of several matching chords, the same is always found
+        let redo = Binding::Control(ControlKey::Redo);
+        assert_eq!(shortcuts.find(&redo), Some((PRIMARY, VirtualKeyCode::Y)));
+        shortcuts.insert(PRIMARY, VirtualKeyCode::R, redo.clone());
+        assert_eq!(shortcuts.find(&redo), Some((PRIMARY, VirtualKeyCode::R)));
+
+        match shortcuts.parse("Ctrl+S = save") {
+            Err(ShortcutsError::Parse(1, _)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert_eq!(
+            format_chord(ctrl | ModifiersState::SHIFT, VirtualKeyCode::Z),
+            "Ctrl+Shift+Z"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Undo history
+
+use std::any::Any;
+use std::fmt;
+
+use crate::WidgetId;
+
+/// Kind of an undoable edit
+///
+/// Consecutive edits of the same kind by the same widget are merged into a
+/// single undo step, except for [`EditKind::Other`] (see
+/// [`Manager::push_undo`]).
+///
+/// [`Manager::push_undo`]: super::Manager::push_undo
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EditKind {
+    /// Insertion of text (typing)
+    Insert,
+    /// Deletion of text
+    Delete,
+    /// Pasting of text
+    Paste,
+    /// Any other edit; these are never merged
+    Other,
+}
+
+struct Entry {
+    owner: WidgetId,
+    kind: EditKind,
+    // True while further edits of the same kind may be merged
+    open: bool,
+    state: Box<dyn Any>,
+}
+
+/// Undo and redo stacks
+///
+/// Entries are pairs of an owning widget and a type-erased state. The stacks
+/// are shared by all widgets of a window, thus edits of different widgets
+/// (and application-level edits) are interleaved in the order made.
+#[derive(Default)]
+pub(crate) struct UndoStack {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+impl fmt::Debug for UndoStack {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UndoStack {{ undo: {} entries, redo: {} entries }}",
+            self.undo.len(),
+            self.redo.len()
+        )
+    }
+}
+
+impl UndoStack {
+    /// Push an entry, unless it merges with the last
+    ///
+    /// Returns true if an entry was pushed (in which case `state` was called).
+    pub fn push<T: Any, F: FnOnce() -> T>(
+        &mut self,
+        owner: WidgetId,
+        kind: EditKind,
+        state: F,
+    ) -> bool {
+        if let Some(last) = self.undo.last() {
+            if last.open && last.owner == owner && last.kind == kind && kind != EditKind::Other {
+                return false;
+            }
+        }
+        self.close(owner);
+        self.redo.clear();
+        self.undo.push(Entry {
+            owner,
+            kind,
+            open: true,
+            state: Box::new(state()),
+        });
+        true
+    }
+
+    /// Stop merging edits into the last entry, if owned by `owner`
+    pub fn close(&mut self, owner: WidgetId) {
+        if let Some(last) = self.undo.last_mut() {
+            if last.owner == owner {
+                last.open = false;
+            }
+        }
+    }
+
+    /// Swap `current` with the last state of `owner` from `from`, pushing to `to`
+    fn swap<T: Any>(
+        from: &mut Vec<Entry>,
+        to: &mut Vec<Entry>,
+        owner: WidgetId,
+        current: T,
+    ) -> Option<T> {
+        let index = from.iter().rposition(|entry| entry.owner == owner)?;
+        if !from[index].state.is::<T>() {
+            return None;
+        }
+        let entry = from.remove(index);
+        to.push(Entry {
+            owner,
+            kind: entry.kind,
+            open: false,
+            state: Box::new(current),
+        });
+        entry.state.downcast().ok().map(|state| *state)
+    }
+
+    pub fn undo<T: Any>(&mut self, owner: WidgetId, current: T) -> Option<T> {
+        Self::swap(&mut self.undo, &mut self.redo, owner, current)
+    }
+
+    pub fn redo<T: Any>(&mut self, owner: WidgetId, current: T) -> Option<T> {
+        Self::swap(&mut self.redo, &mut self.undo, owner, current)
+    }
+
+    /// Owner of the last undo entry
+    pub fn last_undo(&self) -> Option<WidgetId> {
+        self.undo.last().map(|entry| entry.owner)
+    }
+
+    /// Owner of the last redo entry
+    pub fn last_redo(&self) -> Option<WidgetId> {
+        self.redo.last().map(|entry| entry.owner)
+    }
+
+    pub fn has_undo(&self, owner: WidgetId) -> bool {
+        self.undo.iter().any(|entry| entry.owner == owner)
+    }
+
+    pub fn has_redo(&self, owner: WidgetId) -> bool {
+        self.redo.iter().any(|entry| entry.owner == owner)
+    }
+
+    /// Remove all entries of `owner`
+    pub fn clear(&mut self, owner: WidgetId) {
+        self.undo.retain(|entry| entry.owner != owner);
+        self.redo.retain(|entry| entry.owner != owner);
+    }
+
+    /// Update owners to new identifiers, removing entries where `f` fails
+    pub fn remap<F: Fn(&WidgetId) -> Option<WidgetId>>(&mut self, f: F) {
+        for stack in [&mut self.undo, &mut self.redo].iter_mut() {
+            let entries = std::mem::take(&mut **stack);
+            **stack = entries
+                .into_iter()
+                .filter_map(|mut entry| {
+                    f(&entry.owner).map(|owner| {
+                        entry.owner = owner;
+                        entry
+                    })
+                })
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_and_swap() {
+        let a = WidgetId::FIRST;
+        let b = a.next();
+        let mut stack = UndoStack::default();
+
+        // Consecutive inserts by a merge; the delete does not
+        assert!(stack.push(a, EditKind::Insert, || "".to_string()));
+        assert!(!stack.push(a, EditKind::Insert, || "a".to_string()));
+        assert!(stack.push(b, EditKind::Other, || 0u32));
+        assert!(stack.push(a, EditKind::Insert, || "ab".to_string()));
+        assert!(stack.push(a, EditKind::Delete, || "abc".to_string()));
+
+        assert_eq!(stack.last_undo(), Some(a));
+        assert_eq!(stack.undo(a, "ab".to_string()), Some("abc".to_string()));
+        assert_eq!(stack.undo(a, "abc".to_string()), Some("ab".to_string()));
+        // Type mismatch: no change
+        assert_eq!(stack.undo(b, 0i64), None);
+        assert_eq!(stack.undo(b, 1u32), Some(0));
+        assert_eq!(stack.undo(a, "ab".to_string()), Some("".to_string()));
+        assert!(!stack.has_undo(a));
+
+        assert_eq!(stack.redo(a, "".to_string()), Some("ab".to_string()));
+        assert_eq!(stack.last_redo(), Some(b));
+
+        // A new edit clears the redo stack
+        assert!(stack.push(b, EditKind::Other, || 1u32));
+        assert!(!stack.has_redo(a));
+    }
+}
@@ -13,6 +13,7 @@ pub use vector::{DVec2, Quad, Vec2, Vec3};
 
 /// An `(x, y)` coordinate.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coord(pub i32, pub i32);
 
 impl Coord {
@@ -0,0 +1,193 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Layout debugging
+
+use std::fmt;
+
+use super::{AxisInfo, SizeRules, StretchPolicy};
+use crate::draw::{ClipRegion, Colour, DrawHandle, SizeHandle};
+use crate::geom::{Coord, Quad, Rect, Vec2};
+use crate::{WidgetConfig, WidgetId};
+
+/// Layout information on a single widget
+///
+/// See [`LayoutDump`].
+#[derive(Clone, Debug)]
+pub struct LayoutInfo {
+    /// Widget identifier
+    pub id: WidgetId,
+    /// Widget type name (see [`crate::WidgetCore::widget_name`])
+    pub name: &'static str,
+    /// Depth within the widget tree (zero for the root)
+    pub depth: usize,
+    /// The widget's rect, as last assigned by [`crate::Layout::set_rect`]
+    pub rect: Rect,
+    /// Horizontal size rules
+    pub horiz: SizeRules,
+    /// Vertical size rules, given the width of `rect`
+    pub vert: SizeRules,
+}
+
+/// Layout information on a widget tree
+///
+/// This records the [`SizeRules`] of each widget (on each axis) along with
+/// its final [`Rect`], in order to aid debugging of layout issues.
+///
+/// When formatted (via [`fmt::Display`]), each widget is written on its own
+/// line, indented by depth, e.g.:
+/// ```none
+/// Window #6 rect=(0, 0) 120x40; horiz: 100..120, m=(0, 0), Fixed; vert: ...
+///   TextButton #5 rect=(4, 4) 112x32; horiz: 60..80, m=(4, 4), Fixed; vert: ...
+/// ```
+/// Maximum sizes, where present, are written as a third value
+/// (`min..ideal..max`).
+///
+/// When layout debugging is enabled (see
+/// [`crate::event::Manager::set_layout_debug`]), toolkits collect this
+/// information after each resize and draw it over the window (see
+/// [`LayoutDump::draw`]).
+#[derive(Clone, Debug, Default)]
+pub struct LayoutDump {
+    entries: Vec<LayoutInfo>,
+}
+
+impl LayoutDump {
+    /// Collect layout information on `widget` and its descendants
+    ///
+    /// This calls [`crate::Layout::size_rules`] on every widget (thus has cost
+    /// quadratic in the depth of the tree), using the width of each widget's
+    /// current `rect` when solving the vertical axis. Layout should have been
+    /// solved already.
+    pub fn new(widget: &mut dyn WidgetConfig, size_handle: &mut dyn SizeHandle) -> Self {
+        let mut dump = LayoutDump::default();
+        dump.collect(widget, size_handle, 0);
+        dump
+    }
+
+    fn collect(
+        &mut self,
+        widget: &mut dyn WidgetConfig,
+        size_handle: &mut dyn SizeHandle,
+        depth: usize,
+    ) {
+        let rect = widget.rect();
+        let horiz = widget.size_rules(size_handle, AxisInfo::new(false, None));
+        let vert = widget.size_rules(size_handle, AxisInfo::new(true, Some(rect.size.0)));
+        self.entries.push(LayoutInfo {
+            id: widget.id(),
+            name: widget.widget_name(),
+            depth,
+            rect,
+            horiz,
+            vert,
+        });
+
+        for i in 0..widget.len() {
+            if let Some(child) = widget.get_mut(i) {
+                self.collect(child, size_handle, depth + 1);
+            }
+        }
+    }
+
+    /// Access entries, in pre-order (parents before children)
+    #[inline]
+    pub fn entries(&self) -> &[LayoutInfo] {
+        &self.entries
+    }
+
+    /// Find the entry for widget `id`
+    pub fn get(&self, id: WidgetId) -> Option<&LayoutInfo> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Draw a debugging overlay
+    ///
+    /// For each widget, margins are shaded and the widget's rect is outlined.
+    /// The colour of the top and bottom edges indicates the horizontal
+    /// [`StretchPolicy`] and that of the left and right edges the vertical
+    /// policy: grey (`Fixed`), blue (`Filler`), green (`LowUtility`),
+    /// yellow (`HighUtility`) or red (`Maximise`).
+    ///
+    /// Scroll offsets are not accounted for.
+    pub fn draw(&self, draw_handle: &mut dyn DrawHandle) {
+        let rect = draw_handle.target_rect();
+        draw_handle.clip_region(rect, Coord::ZERO, ClipRegion::Overlay, &mut |draw_handle| {
+            let (pass, offset, draw) = draw_handle.draw_device();
+            let margin_col = Colour::rgba(1.0, 0.5, 0.0, 0.25);
+            for entry in &self.entries {
+                if entry.rect.size.0 < 2 || entry.rect.size.1 < 2 {
+                    continue;
+                }
+                let quad = Quad::from(entry.rect + offset);
+                let (h, v) = (entry.horiz.margins(), entry.vert.margins());
+                let outer = Quad::with_coords(
+                    quad.a - Vec2(h.0 as f32, v.0 as f32),
+                    quad.b + Vec2(h.1 as f32, v.1 as f32),
+                );
+                if outer != quad {
+                    draw.frame(pass, outer, quad, margin_col);
+                }
+
+                let (a, b) = (quad.a, quad.b);
+                let h_col = stretch_colour(entry.horiz.stretch());
+                let v_col = stretch_colour(entry.vert.stretch());
+                let top = Quad::with_coords(a, Vec2(b.0, a.1 + 1.0));
+                let bottom = Quad::with_coords(Vec2(a.0, b.1 - 1.0), b);
+                let left = Quad::with_coords(Vec2(a.0, a.1 + 1.0), Vec2(a.0 + 1.0, b.1 - 1.0));
+                let right = Quad::with_coords(Vec2(b.0 - 1.0, a.1 + 1.0), Vec2(b.0, b.1 - 1.0));
+                draw.rect(pass, top, h_col);
+                draw.rect(pass, bottom, h_col);
+                draw.rect(pass, left, v_col);
+                draw.rect(pass, right, v_col);
+            }
+        });
+    }
+}
+
+fn stretch_colour(stretch: StretchPolicy) -> Colour {
+    match stretch {
+        StretchPolicy::Fixed => Colour::grey(0.5),
+        StretchPolicy::Filler => Colour::new(0.2, 0.4, 1.0),
+        StretchPolicy::LowUtility => Colour::new(0.0, 0.8, 0.0),
+        StretchPolicy::HighUtility => Colour::new(0.9, 0.8, 0.0),
+        StretchPolicy::Maximise => Colour::new(1.0, 0.0, 0.0),
+    }
+}
+
+struct DisplayRules(SizeRules);
+impl fmt::Display for DisplayRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rules = self.0;
+        write!(f, "{}..{}", rules.min_size(), rules.ideal_size())?;
+        if let Some(max) = rules.max_size() {
+            write!(f, "..{}", max)?;
+        }
+        let m = rules.margins();
+        write!(f, ", m=({}, {}), {:?}", m.0, m.1, rules.stretch())
+    }
+}
+
+impl fmt::Display for LayoutDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.entries {
+            let (pos, size) = (entry.rect.pos, entry.rect.size);
+            writeln!(
+                f,
+                "{}{} {} rect=({}, {}) {}x{}; horiz: {}; vert: {}",
+                "  ".repeat(entry.depth),
+                entry.name,
+                entry.id,
+                pos.0,
+                pos.1,
+                size.0,
+                size.1,
+                DisplayRules(entry.horiz),
+                DisplayRules(entry.vert),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,337 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Flow solver
+
+use std::ops::Range;
+
+use super::{AxisInfo, RulesSetter, RulesSolver, SizeRules, Storage};
+use crate::geom::{Coord, Rect, Size};
+use crate::{Align, AlignHints};
+
+/// Storage for [`FlowSolver`] and [`FlowSetter`]
+///
+/// Children are placed left-to-right, wrapping onto a new line when the
+/// available width is exhausted. Line breaks depend on the width, thus are
+/// recalculated whenever this changes.
+#[derive(Clone, Debug, Default)]
+pub struct FlowStorage {
+    width_rules: Vec<SizeRules>,
+    height_rules: Vec<SizeRules>,
+    widths: Vec<u32>,
+    // Index of the first child of each line
+    lines: Vec<usize>,
+    line_rules: Vec<SizeRules>,
+    // Width for which lines were calculated
+    width: Option<u32>,
+}
+
+impl Storage for FlowStorage {}
+
+impl FlowStorage {
+    fn set_dim(&mut self, len: usize) {
+        if self.widths.len() != len {
+            self.width_rules.resize(len, SizeRules::EMPTY);
+            self.height_rules.resize(len, SizeRules::EMPTY);
+            self.widths.resize(len, 0);
+            self.width = None;
+        }
+    }
+
+    /// Number of lines
+    ///
+    /// This is valid after the vertical axis has been solved.
+    pub fn num_lines(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Range of child indices on line `index`
+    ///
+    /// Panics if `index >= self.num_lines()`.
+    pub fn line_range(&self, index: usize) -> Range<usize> {
+        let end = self
+            .lines
+            .get(index + 1)
+            .cloned()
+            .unwrap_or(self.widths.len());
+        self.lines[index]..end
+    }
+
+    fn line_of(&self, child: usize) -> usize {
+        match self.lines.binary_search(&child) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        }
+    }
+
+    // Break children into lines within `width`, assigning each child its
+    // ideal width (reduced if a single child does not fit)
+    fn break_lines(&mut self, width: u32) {
+        self.lines.clear();
+        self.width = Some(width);
+
+        let mut start = 0;
+        let mut line_width = 0;
+        for i in 0..self.widths.len() {
+            let rules = self.width_rules[i];
+            if i > start {
+                let gap = self.width_rules[i - 1].margins().1.max(rules.margins().0) as u32;
+                if line_width + gap + rules.ideal_size() <= width {
+                    line_width += gap + rules.ideal_size();
+                    continue;
+                }
+                self.solve_line(start..i, width);
+                start = i;
+            }
+            line_width = rules.ideal_size();
+        }
+        if start < self.widths.len() {
+            self.solve_line(start..self.widths.len(), width);
+        }
+    }
+
+    fn solve_line(&mut self, range: Range<usize>, width: u32) {
+        self.lines.push(range.start);
+        let rules = &self.width_rules[range.clone()];
+        let target = width.min(SizeRules::sum(rules).ideal_size());
+        let widths = &mut self.widths[range];
+        for w in widths.iter_mut() {
+            *w = 0;
+        }
+        SizeRules::solve_seq(widths, rules, target);
+    }
+
+    fn update_line_rules(&mut self) {
+        self.line_rules.clear();
+        for line in 0..self.lines.len() {
+            let range = self.line_range(line);
+            let rules = self.height_rules[range]
+                .iter()
+                .fold(SizeRules::EMPTY, |a, b| a.max(*b));
+            self.line_rules.push(rules);
+        }
+    }
+}
+
+/// A [`RulesSolver`] for flow layouts
+///
+/// Children are placed in lines, left-to-right, wrapping when the width
+/// is exhausted. The minimum width is that of the widest child, while the
+/// ideal width places all children on a single line. The height depends on
+/// the width, which should be provided via [`AxisInfo::other`] when solving
+/// the vertical axis; if not, children are placed on a single line.
+///
+/// The horizontal axis must be solved before the vertical axis.
+pub struct FlowSolver {
+    axis: AxisInfo,
+    rules: Option<SizeRules>,
+    min: u32,
+}
+
+impl FlowSolver {
+    /// Construct.
+    ///
+    /// Argument order is consistent with other [`RulesSolver`]s.
+    ///
+    /// - `axis`: `AxisInfo` instance passed into `size_rules`
+    /// - `len`: number of items
+    /// - `storage`: reference to persistent storage
+    pub fn new(axis: AxisInfo, len: usize, storage: &mut FlowStorage) -> Self {
+        storage.set_dim(len);
+
+        if axis.is_vertical() {
+            storage.break_lines(axis.other().unwrap_or(std::u32::MAX));
+        }
+
+        FlowSolver {
+            axis,
+            rules: None,
+            min: 0,
+        }
+    }
+}
+
+impl RulesSolver for FlowSolver {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        child_info: Self::ChildInfo,
+        child_rules: CR,
+    ) {
+        if self.axis.is_horizontal() {
+            let rules = child_rules(self.axis);
+            storage.width_rules[child_info] = rules;
+            self.min = self.min.max(rules.min_size());
+            self.rules = Some(match self.rules {
+                Some(r) => r.appended(rules),
+                None => rules,
+            });
+        } else {
+            let width = storage.widths[child_info];
+            let rules = child_rules(AxisInfo::new(true, Some(width)));
+            storage.height_rules[child_info] = rules;
+        }
+    }
+
+    fn finish(self, storage: &mut Self::Storage) -> SizeRules {
+        if self.axis.is_horizontal() {
+            // Any child may start or end a line, thus margins of all apply
+            let margins = storage.width_rules.iter().fold((0, 0), |m, r| {
+                let n = r.margins();
+                (m.0.max(n.0), m.1.max(n.1))
+            });
+            let mut rules = self.rules.unwrap_or(SizeRules::EMPTY);
+            rules.reduce_min_to(self.min);
+            rules.include_margins(margins);
+            rules
+        } else {
+            storage.update_line_rules();
+            SizeRules::sum(&storage.line_rules)
+        }
+    }
+}
+
+/// A [`RulesSetter`] for flow layouts
+///
+/// See [`FlowSolver`]. Lines are aligned horizontally according to the
+/// alignment hints (default: left); with [`Align::Stretch`], children are
+/// stretched to fill each line.
+pub struct FlowSetter {
+    rect: Rect,
+    rects: Vec<Rect>,
+    // (position, height) of each line
+    lines: Vec<(i32, u32)>,
+}
+
+impl FlowSetter {
+    /// Construct
+    ///
+    /// Argument order is consistent with other [`RulesSetter`]s.
+    ///
+    /// -   `rect`: the [`Rect`] within which to position children
+    /// -   `len`: number of items
+    /// -   `align`: alignment hints
+    /// -   `storage`: access to the solver's storage
+    pub fn new(rect: Rect, len: usize, align: AlignHints, storage: &mut FlowStorage) -> Self {
+        storage.set_dim(len);
+        if storage.width != Some(rect.size.0) {
+            // Normally the vertical axis was solved for this width already
+            storage.break_lines(rect.size.0);
+            storage.update_line_rules();
+        }
+
+        let num_lines = storage.num_lines();
+        let mut heights = vec![0; num_lines];
+        SizeRules::solve_seq(&mut heights, &storage.line_rules, rect.size.1);
+
+        let align = align.horiz.unwrap_or(Align::Default);
+        let mut rects = vec![Rect::default(); len];
+        let mut lines = Vec::with_capacity(num_lines);
+        let mut y = rect.pos.1;
+        for line in 0..num_lines {
+            if line > 0 {
+                let m0 = storage.line_rules[line - 1].margins().1;
+                let m1 = storage.line_rules[line].margins().0;
+                y += heights[line - 1] as i32 + m0.max(m1) as i32;
+            }
+            lines.push((y, heights[line]));
+
+            let range = storage.line_range(line);
+            let rules = &storage.width_rules[range.clone()];
+            let line_width = SizeRules::sum(rules).ideal_size().min(rect.size.0);
+            if align == Align::Stretch {
+                SizeRules::solve_seq(&mut storage.widths[range.clone()], rules, rect.size.0);
+            }
+            let extra = rect.size.0.saturating_sub(line_width);
+            let mut x = rect.pos.0
+                + match align {
+                    Align::Default | Align::TL | Align::Stretch => 0,
+                    Align::Centre => extra / 2,
+                    Align::BR => extra,
+                } as i32;
+
+            for i in range.clone() {
+                if i > range.start {
+                    let gap = rules[i - range.start - 1]
+                        .margins()
+                        .1
+                        .max(rules[i - range.start].margins().0);
+                    x += gap as i32;
+                }
+                let size = Size(storage.widths[i], heights[line]);
+                rects[i] = Rect::new(Coord(x, y), size);
+                x += size.0 as i32;
+            }
+        }
+
+        FlowSetter { rect, rects, lines }
+    }
+}
+
+impl RulesSetter for FlowSetter {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn child_rect(&mut self, _: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
+        self.rects[index]
+    }
+
+    fn maximal_rect_of(&mut self, storage: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
+        let (y, height) = self.lines[storage.line_of(index)];
+        Rect::new(Coord(self.rect.pos.0, y), Size(self.rect.size.0, height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::StretchPolicy;
+
+    #[test]
+    fn flow() {
+        let mut storage = FlowStorage::default();
+        let widths = [40, 30, 50, 20];
+        let rule = |w| SizeRules::new(w / 2, w, (2, 2), StretchPolicy::LowUtility);
+
+        let axis = AxisInfo::new(false, None);
+        let mut solver = FlowSolver::new(axis, widths.len(), &mut storage);
+        for (i, w) in widths.iter().enumerate() {
+            solver.for_child(&mut storage, i, |_| rule(*w));
+        }
+        let rules = solver.finish(&mut storage);
+        assert_eq!(rules.min_size(), 25);
+        assert_eq!(rules.ideal_size(), 146);
+
+        let axis = AxisInfo::new(true, Some(100));
+        let mut solver = FlowSolver::new(axis, widths.len(), &mut storage);
+        for i in 0..widths.len() {
+            solver.for_child(&mut storage, i, |axis| {
+                assert_eq!(axis.other(), Some(widths[i]));
+                SizeRules::fixed(10, (2, 2))
+            });
+        }
+        let rules = solver.finish(&mut storage);
+        assert_eq!(storage.num_lines(), 2);
+        assert_eq!(storage.line_range(1), 2..4);
+        assert_eq!(rules.ideal_size(), 22);
+
+        let rect = Rect::new(Coord(0, 0), Size(100, 22));
+        let mut setter = FlowSetter::new(rect, widths.len(), AlignHints::NONE, &mut storage);
+        let r = setter.child_rect(&mut storage, 1);
+        assert_eq!(r, Rect::new(Coord(42, 0), Size(30, 10)));
+        let r = setter.child_rect(&mut storage, 3);
+        assert_eq!(r, Rect::new(Coord(52, 12), Size(20, 10)));
+
+        // A narrower rect forces re-calculation of lines
+        let rect = Rect::new(Coord(0, 0), Size(45, 44));
+        let mut setter = FlowSetter::new(rect, widths.len(), AlignHints::NONE, &mut storage);
+        assert_eq!(storage.num_lines(), 4);
+        let r = setter.child_rect(&mut storage, 2);
+        assert_eq!(r.size.0, 45);
+    }
+}
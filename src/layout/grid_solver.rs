@@ -231,11 +231,17 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
         if cols > 0 {
             let align = align.horiz.unwrap_or(Align::Stretch);
             let (rules, widths) = storage.rules_and_widths();
-            let ideal = rules[cols].ideal_size();
+            let mut target = rect.size.0;
+            if align != Align::Stretch {
+                target = target.min(rules[cols].ideal_size());
+            }
+            if let Some(max) = rules[cols].max_size() {
+                target = target.min(max);
+            }
 
             w_offsets.as_mut()[0] = 0;
-            if align != Align::Stretch && rect.size.0 > ideal {
-                let extra = rect.size.0 - ideal;
+            if rect.size.0 > target {
+                let extra = rect.size.0 - target;
                 w_offsets.as_mut()[0] = match align {
                     Align::Default | Align::TL | Align::Stretch => 0,
                     Align::Centre => extra / 2,
@@ -243,7 +249,7 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
                 };
             }
 
-            SizeRules::solve_seq_total(widths, rules, rect.size.0);
+            SizeRules::solve_seq_total(widths, rules, target);
             for i in 1..w_offsets.as_mut().len() {
                 let i1 = i - 1;
                 let m1 = storage.width_rules()[i1].margins().1;
@@ -256,11 +262,17 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
         if rows > 0 {
             let align = align.vert.unwrap_or(Align::Stretch);
             let (rules, heights) = storage.rules_and_heights();
-            let ideal = rules[rows].ideal_size();
+            let mut target = rect.size.1;
+            if align != Align::Stretch {
+                target = target.min(rules[rows].ideal_size());
+            }
+            if let Some(max) = rules[rows].max_size() {
+                target = target.min(max);
+            }
 
             h_offsets.as_mut()[0] = 0;
-            if align != Align::Stretch && rect.size.1 > ideal {
-                let extra = rect.size.1 - ideal;
+            if rect.size.1 > target {
+                let extra = rect.size.1 - target;
                 h_offsets.as_mut()[0] = match align {
                     Align::Default | Align::TL | Align::Stretch => 0,
                     Align::Centre => extra / 2,
@@ -268,7 +280,7 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
                 };
             }
 
-            SizeRules::solve_seq_total(heights, rules, rect.size.1);
+            SizeRules::solve_seq_total(heights, rules, target);
             for i in 1..h_offsets.as_mut().len() {
                 let i1 = i - 1;
                 let m1 = storage.height_rules()[i1].margins().1;
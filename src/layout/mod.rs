@@ -22,6 +22,8 @@
 //!
 //! -   [`SingleSolver`] and [`SingleSetter`] are trivial implementations for
 //!     single-child parents
+//! -   [`OverlaySolver`] and [`OverlaySetter`] place several children in the
+//!     same space, each positioned according to an [`Anchor`]
 //! -   [`RowSolver`] and [`RowSetter`] set out a row or column of children.
 //!     These are parametrised over `S: RowStorage` allowing both efficient
 //!     operation on a small fixed number of children with [`FixedRowStorage`]
@@ -29,10 +31,21 @@
 //! -   [`GridSolver`] and [`GridSetter`] set out children assigned to grid
 //!     cells with optional cell-spans. This is the most powerful and flexible
 //!     layout engine.
+//! -   [`FlowSolver`] and [`FlowSetter`] place children in lines,
+//!     left-to-right, wrapping onto a new line when the width is exhausted.
+//!     The height thus depends on the width (see [`AxisInfo::other`]).
 //!
 //! [`RowPositionSolver`] may be used with widgets set out by [`RowSetter`]
 //! to quickly locate children from a `coord` or `rect`.
+//!
+//! ## Debugging
+//!
+//! [`LayoutDump`] records the size rules and final rect of each widget in a
+//! tree. It may be printed as text or drawn over the window; see also
+//! [`crate::event::Manager::set_layout_debug`].
 
+mod dump;
+mod flow_solver;
 mod grid_solver;
 mod row_solver;
 mod single_solver;
@@ -42,9 +55,13 @@ mod storage;
 
 use crate::geom::Size;
 
+pub use dump::{LayoutDump, LayoutInfo};
+pub use flow_solver::{FlowSetter, FlowSolver, FlowStorage};
 pub use grid_solver::{GridChildInfo, GridSetter, GridSolver};
 pub use row_solver::{RowPositionSolver, RowSetter, RowSolver};
-pub use single_solver::{SingleSetter, SingleSolver};
+pub use single_solver::{
+    Anchor, OverlaySetter, OverlaySolver, OverlayStorage, SingleSetter, SingleSolver, SingleStorage,
+};
 pub use size_rules::{Margins, SizeRules, StretchPolicy};
 pub use sizer::{RulesSetter, RulesSolver, SolveCache};
 pub use storage::{
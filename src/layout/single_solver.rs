@@ -5,9 +5,17 @@
 
 //! Miscellaneous solvers
 
-use super::{AxisInfo, RulesSetter, RulesSolver, SizeRules};
-use crate::geom::Rect;
-use kas::AlignHints;
+use super::{AxisInfo, RulesSetter, RulesSolver, SizeRules, Storage};
+use crate::geom::{Coord, Rect, Size, Vec2};
+use kas::{Align, AlignHints};
+
+/// Storage for [`SingleSolver`] and [`SingleSetter`]
+#[derive(Clone, Debug, Default)]
+pub struct SingleStorage {
+    max: (Option<u32>, Option<u32>),
+}
+
+impl Storage for SingleStorage {}
 
 /// [`RulesSolver`] implementation for a fixed single-child layout
 pub struct SingleSolver {
@@ -22,8 +30,8 @@ impl SingleSolver {
     ///
     /// - `axis`: `AxisInfo` instance passed into `size_rules`
     /// - `_dim`: unused
-    /// - `_storage`: unused
-    pub fn new(axis: AxisInfo, _dim: (), _storage: &mut ()) -> Self {
+    /// - `_storage`: reference to persistent storage
+    pub fn new(axis: AxisInfo, _dim: (), _storage: &mut SingleStorage) -> Self {
         SingleSolver {
             axis,
             rules: SizeRules::EMPTY,
@@ -32,16 +40,20 @@ impl SingleSolver {
 }
 
 impl RulesSolver for SingleSolver {
-    type Storage = ();
+    type Storage = SingleStorage;
     type ChildInfo = ();
 
     fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
         &mut self,
-        _storage: &mut Self::Storage,
+        storage: &mut Self::Storage,
         _child_info: Self::ChildInfo,
         child_rules: CR,
     ) {
         self.rules = child_rules(self.axis);
+        match self.axis.is_vertical() {
+            false => storage.max.0 = self.rules.max_size(),
+            true => storage.max.1 = self.rules.max_size(),
+        }
     }
 
     fn finish(self, _storage: &mut Self::Storage) -> SizeRules {
@@ -61,18 +73,32 @@ impl SingleSetter {
     ///
     /// -   `rect`: the [`Rect`] within which to position children
     /// -   `_dim`: unused
-    /// -   `_align`: unused
-    /// -   `_storage`: unused
-    pub fn new(rect: Rect, _dim: (), _align: AlignHints, _storage: &mut ()) -> Self {
-        // NOTE: possibly we should apply alignment here, but we can't without
-        // storing the ideal size for each dimension in the storage.
+    /// -   `align`: alignment hints, used only where the child's maximum size
+    ///     is less than the size of `rect`
+    /// -   `storage`: access to the solver's storage
+    pub fn new(rect: Rect, _dim: (), align: AlignHints, storage: &mut SingleStorage) -> Self {
+        // NOTE: possibly we should apply alignment below the ideal size too,
+        // but we can't without storing the ideal size for each dimension.
         // If we do, we should do the same for the other axis of RowSetter.
+        fn clamp(align: Option<Align>, avail: u32, max: Option<u32>) -> (i32, u32) {
+            let size = max.map(|max| max.min(avail)).unwrap_or(avail);
+            let offset = match align.unwrap_or(Align::Stretch) {
+                Align::Default | Align::TL | Align::Stretch => 0,
+                Align::Centre => (avail - size) / 2,
+                Align::BR => avail - size,
+            };
+            (offset as i32, size)
+        }
+
+        let (x, w) = clamp(align.horiz, rect.size.0, storage.max.0);
+        let (y, h) = clamp(align.vert, rect.size.1, storage.max.1);
+        let rect = Rect::new(rect.pos + Coord(x, y), Size(w, h));
         SingleSetter { rect }
     }
 }
 
 impl RulesSetter for SingleSetter {
-    type Storage = ();
+    type Storage = SingleStorage;
     type ChildInfo = ();
 
     fn child_rect(&mut self, _: &mut Self::Storage, _: Self::ChildInfo) -> Rect {
@@ -83,3 +109,277 @@ impl RulesSetter for SingleSetter {
         self.rect
     }
 }
+
+/// Placement of a child within an overlay layout
+///
+/// Each axis is aligned to the start, centre or end of the available space
+/// (at the child's ideal size) or stretched to fill it; the result is then
+/// moved by `offset`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anchor {
+    /// Horizontal alignment
+    pub horiz: Align,
+    /// Vertical alignment
+    pub vert: Align,
+    /// Offset from the aligned position
+    ///
+    /// This is specified in logical pixels, thus is multiplied by the scale
+    /// factor. Positive values move right / down.
+    pub offset: Vec2,
+}
+
+impl Anchor {
+    /// Fill all available space
+    pub const FILL: Anchor = Anchor::new(Align::Stretch, Align::Stretch);
+
+    /// Construct with the given alignment and no offset
+    #[inline]
+    pub const fn new(horiz: Align, vert: Align) -> Self {
+        Anchor {
+            horiz,
+            vert,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// Set the offset (chain style)
+    #[inline]
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Anchor::FILL
+    }
+}
+
+/// Storage for [`OverlaySolver`] and [`OverlaySetter`]
+#[derive(Clone, Debug, Default)]
+pub struct OverlayStorage {
+    ideal: Vec<Size>,
+    max: Vec<(Option<u32>, Option<u32>)>,
+    scale_factor: f32,
+}
+
+impl Storage for OverlayStorage {}
+
+impl OverlayStorage {
+    fn set_dim(&mut self, len: usize) {
+        self.ideal.resize(len, Size::ZERO);
+        self.max.resize(len, (None, None));
+    }
+}
+
+/// [`RulesSolver`] implementation for overlays
+///
+/// All children occupy the same space, each placed according to an
+/// [`Anchor`]. Size requirements are the maximum of those of all children;
+/// offsets are not included, thus children may overhang the parent.
+///
+/// Children are stacked in order, the last being topmost. Parents should draw
+/// children in this order and search them in reverse order in `find_id`.
+pub struct OverlaySolver {
+    axis: AxisInfo,
+    rules: SizeRules,
+}
+
+impl OverlaySolver {
+    /// Construct.
+    ///
+    /// Argument order is consistent with other [`RulesSolver`]s.
+    ///
+    /// - `axis`: `AxisInfo` instance passed into `size_rules`
+    /// - `(len, scale_factor)`: number of items and the scale factor (see
+    ///   [`kas::draw::SizeHandle::scale_factor`])
+    /// - `storage`: reference to persistent storage
+    pub fn new(
+        axis: AxisInfo,
+        (len, scale_factor): (usize, f32),
+        storage: &mut OverlayStorage,
+    ) -> Self {
+        storage.set_dim(len);
+        storage.scale_factor = scale_factor;
+        OverlaySolver {
+            axis,
+            rules: SizeRules::EMPTY,
+        }
+    }
+}
+
+impl RulesSolver for OverlaySolver {
+    type Storage = OverlayStorage;
+    type ChildInfo = (usize, Anchor);
+
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        (index, anchor): Self::ChildInfo,
+        child_rules: CR,
+    ) {
+        let mut axis = self.axis;
+        if let Some(other) = axis.other() {
+            // Children not stretched on the other axis use their ideal size
+            let (align, ideal) = match axis.is_vertical() {
+                false => (anchor.vert, storage.ideal[index].1),
+                true => (anchor.horiz, storage.ideal[index].0),
+            };
+            if align != Align::Stretch {
+                axis = AxisInfo::new(axis.is_vertical(), Some(other.min(ideal)));
+            }
+        }
+
+        let rules = child_rules(axis);
+        match axis.is_vertical() {
+            false => {
+                storage.ideal[index].0 = rules.ideal_size();
+                storage.max[index].0 = rules.max_size();
+            }
+            true => {
+                storage.ideal[index].1 = rules.ideal_size();
+                storage.max[index].1 = rules.max_size();
+            }
+        }
+        self.rules = self.rules.max(rules);
+    }
+
+    fn finish(self, _storage: &mut Self::Storage) -> SizeRules {
+        self.rules
+    }
+}
+
+/// [`RulesSetter`] implementation for overlays
+///
+/// See [`OverlaySolver`].
+pub struct OverlaySetter {
+    rect: Rect,
+}
+
+impl OverlaySetter {
+    /// Construct
+    ///
+    /// Argument order is consistent with other [`RulesSetter`]s.
+    ///
+    /// -   `rect`: the [`Rect`] within which to position children
+    /// -   `len`: number of items
+    /// -   `_align`: unused
+    /// -   `storage`: access to the solver's storage
+    pub fn new(rect: Rect, len: usize, _align: AlignHints, storage: &mut OverlayStorage) -> Self {
+        storage.set_dim(len);
+        OverlaySetter { rect }
+    }
+}
+
+impl RulesSetter for OverlaySetter {
+    type Storage = OverlayStorage;
+    type ChildInfo = (usize, Anchor);
+
+    fn child_rect(
+        &mut self,
+        storage: &mut Self::Storage,
+        (index, anchor): Self::ChildInfo,
+    ) -> Rect {
+        fn place(align: Align, avail: u32, ideal: u32, max: Option<u32>) -> (i32, u32) {
+            let mut size = match align {
+                Align::Stretch => avail,
+                _ => ideal.min(avail),
+            };
+            if let Some(max) = max {
+                size = size.min(max);
+            }
+            let offset = match align {
+                Align::Default | Align::TL | Align::Stretch => 0,
+                Align::Centre => (avail - size) / 2,
+                Align::BR => avail - size,
+            };
+            (offset as i32, size)
+        }
+
+        let (ideal, max) = (storage.ideal[index], storage.max[index]);
+        let (x, w) = place(anchor.horiz, self.rect.size.0, ideal.0, max.0);
+        let (y, h) = place(anchor.vert, self.rect.size.1, ideal.1, max.1);
+        let offset = Coord::from(anchor.offset * storage.scale_factor);
+        Rect::new(self.rect.pos + Coord(x, y) + offset, Size(w, h))
+    }
+
+    fn maximal_rect_of(&mut self, _: &mut Self::Storage, _: Self::ChildInfo) -> Rect {
+        self.rect
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::StretchPolicy;
+
+    #[test]
+    fn overlay() {
+        let mut storage = OverlayStorage::default();
+        let children = [
+            (
+                Anchor::FILL,
+                SizeRules::new(50, 100, (0, 0), StretchPolicy::Filler),
+            ),
+            (
+                Anchor::new(Align::BR, Align::TL).with_offset(Vec2(-2.0, 2.0)),
+                SizeRules::fixed(10, (0, 0)),
+            ),
+        ];
+
+        for vertical in [false, true].iter() {
+            let axis = AxisInfo::new(*vertical, None);
+            let mut solver = OverlaySolver::new(axis, (children.len(), 2.0), &mut storage);
+            for (i, (anchor, rules)) in children.iter().enumerate() {
+                solver.for_child(&mut storage, (i, *anchor), |_| *rules);
+            }
+            let rules = solver.finish(&mut storage);
+            assert_eq!(rules.min_size(), 50);
+            assert_eq!(rules.ideal_size(), 100);
+        }
+
+        let rect = Rect::new(Coord(10, 10), Size(80, 60));
+        let mut setter = OverlaySetter::new(rect, children.len(), AlignHints::NONE, &mut storage);
+        let r = setter.child_rect(&mut storage, (0, children[0].0));
+        assert_eq!(r, rect);
+        let r = setter.child_rect(&mut storage, (1, children[1].0));
+        assert_eq!(r, Rect::new(Coord(76, 14), Size(10, 10)));
+    }
+
+    #[test]
+    fn max_size() {
+        let rules = SizeRules::new(20, 40, (0, 0), StretchPolicy::Maximise).with_max(50);
+
+        let mut storage = SingleStorage::default();
+        for vertical in [false, true].iter() {
+            let axis = AxisInfo::new(*vertical, None);
+            let mut solver = SingleSolver::new(axis, (), &mut storage);
+            solver.for_child(&mut storage, (), |_| rules);
+            assert_eq!(solver.finish(&mut storage), rules);
+        }
+
+        let rect = Rect::new(Coord(10, 10), Size(80, 30));
+        let mut setter = SingleSetter::new(rect, (), AlignHints::NONE, &mut storage);
+        let r = setter.child_rect(&mut storage, ());
+        assert_eq!(r, Rect::new(Coord(10, 10), Size(50, 30)));
+        let align = AlignHints::new(Some(Align::Centre), None);
+        let mut setter = SingleSetter::new(rect, (), align, &mut storage);
+        let r = setter.child_rect(&mut storage, ());
+        assert_eq!(r, Rect::new(Coord(25, 10), Size(50, 30)));
+
+        let mut storage = OverlayStorage::default();
+        let anchor = Anchor::new(Align::Stretch, Align::BR);
+        for vertical in [false, true].iter() {
+            let axis = AxisInfo::new(*vertical, None);
+            let mut solver = OverlaySolver::new(axis, (1, 1.0), &mut storage);
+            solver.for_child(&mut storage, (0, anchor), |_| rules);
+            solver.finish(&mut storage);
+        }
+
+        let rect = Rect::new(Coord(0, 0), Size(80, 60));
+        let mut setter = OverlaySetter::new(rect, 1, AlignHints::NONE, &mut storage);
+        let r = setter.child_rect(&mut storage, (0, anchor));
+        assert_eq!(r, Rect::new(Coord(0, 20), Size(50, 40)));
+    }
+}
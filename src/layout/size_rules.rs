@@ -9,6 +9,7 @@ use smallvec::SmallVec;
 use std::fmt;
 use std::iter::Sum;
 
+use super::AxisInfo;
 use crate::geom::Size;
 
 // for doc use
@@ -99,6 +100,7 @@ impl Default for StretchPolicy {
 ///
 /// - the minimum size required for correct operation
 /// - the preferred / ideal size
+/// - an optional maximum size
 /// - a [`StretchPolicy`]
 ///
 /// Available space is distributed between widgets depending on whether the
@@ -109,6 +111,18 @@ impl Default for StretchPolicy {
 /// [`StretchPolicy::Fixed`]. When expanding a row/column, the highest stretch
 /// policy of all contents will be used.
 ///
+/// A maximum size (see [`SizeRules::with_max`]) is a hard limit: space is
+/// never allocated beyond it, instead passing to widgets with lower stretch
+/// policy or, if all widgets are at their maximum size, being left unused.
+/// By default, sizes are unbounded.
+///
+/// ### Aspect ratio
+///
+/// Since `SizeRules` concern only a single axis, an aspect ratio cannot be
+/// expressed directly. Instead, the size of one axis may be made dependent on
+/// the size of the other (see [`AxisInfo::other`]); this is usually the height
+/// given the width. Use [`SizeRules::with_aspect_ratio`] for this.
+///
 /// ### Margins
 ///
 /// Required margin sizes are handled separately for each side of a widget.
@@ -142,23 +156,35 @@ impl Default for StretchPolicy {
 /// [`kas::Layout::set_rect`] and [`kas::AlignHints`].
 ///
 /// [`Rect`]: kas::geom::Rect
-#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct SizeRules {
     // minimum good size
     a: u32,
     // ideal size; b >= a
     b: u32,
+    // maximum size; c >= b (u32::MAX if unbounded)
+    c: u32,
     // (pre, post) margins
     m: (u16, u16),
     stretch: StretchPolicy,
 }
 
+impl Default for SizeRules {
+    fn default() -> Self {
+        SizeRules::EMPTY
+    }
+}
+
 impl fmt::Debug for SizeRules {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SizeRules {{ a: {}, b: {}, ", self.a, self.b)?;
+        if self.c != u32::MAX {
+            write!(f, "c: {}, ", self.c)?;
+        }
         write!(
             f,
-            "SizeRules {{ a: {}, b: {}, m: ({}, {}), stretch: {:?} }}",
-            self.a, self.b, self.m.0, self.m.1, self.stretch
+            "m: ({}, {}), stretch: {:?} }}",
+            self.m.0, self.m.1, self.stretch
         )
     }
 }
@@ -180,6 +206,7 @@ impl SizeRules {
         SizeRules {
             a: 0,
             b: 0,
+            c: u32::MAX,
             m: (0, 0),
             stretch,
         }
@@ -191,6 +218,7 @@ impl SizeRules {
         SizeRules {
             a: size,
             b: size,
+            c: u32::MAX,
             m: margins,
             stretch: StretchPolicy::Fixed,
         }
@@ -203,6 +231,7 @@ impl SizeRules {
             SizeRules {
                 a: size.0,
                 b: size.0,
+                c: u32::MAX,
                 m: margin.horiz,
                 stretch: StretchPolicy::Fixed,
             }
@@ -210,6 +239,7 @@ impl SizeRules {
             SizeRules {
                 a: size.1,
                 b: size.1,
+                c: u32::MAX,
                 m: margin.vert,
                 stretch: StretchPolicy::Fixed,
             }
@@ -227,6 +257,7 @@ impl SizeRules {
         SizeRules {
             a: min,
             b: ideal.max(min),
+            c: u32::MAX,
             m: margins,
             stretch,
         }
@@ -244,12 +275,63 @@ impl SizeRules {
         self.b
     }
 
+    /// Get the maximum size, if bounded
+    #[inline]
+    pub fn max_size(self) -> Option<u32> {
+        if self.c != u32::MAX {
+            Some(self.c)
+        } else {
+            None
+        }
+    }
+
+    /// Set the maximum size (chain style)
+    ///
+    /// If `max` is less than the minimum size, the minimum size is used
+    /// instead. The ideal size is reduced to `max` if necessary.
+    #[inline]
+    pub fn with_max(mut self, max: u32) -> Self {
+        self.c = max.max(self.a);
+        self.b = self.b.min(self.c);
+        self
+    }
+
+    /// Constrain size to an aspect ratio with the other axis (chain style)
+    ///
+    /// The `ratio` is given as `(width, height)`. If the size of the other
+    /// axis is known (see [`AxisInfo::other`]), then the ideal and maximum
+    /// sizes on this axis are set to match the ratio (within the bounds of
+    /// the current minimum and maximum sizes); otherwise rules are returned
+    /// unchanged. The minimum size is not affected, thus the widget may still
+    /// be given less space than the ratio requires.
+    ///
+    /// Usually the height is constrained given the width, since layouts solve
+    /// the horizontal axis first.
+    pub fn with_aspect_ratio(mut self, axis: AxisInfo, ratio: (u32, u32)) -> Self {
+        let (num, den) = match axis.is_vertical() {
+            false => (ratio.0, ratio.1),
+            true => (ratio.1, ratio.0),
+        };
+        if let (Some(other), true) = (axis.other(), den > 0) {
+            let size = (other as u64 * num as u64 / den as u64).min(self.c as u64) as u32;
+            self.b = size.max(self.a);
+            self.c = self.b;
+        }
+        self
+    }
+
     /// Get the `(pre, post)` margin sizes
     #[inline]
     pub fn margins(self) -> (u16, u16) {
         self.m
     }
 
+    /// Get the stretch policy
+    #[inline]
+    pub fn stretch(self) -> StretchPolicy {
+        self.stretch
+    }
+
     /// Set margins to max of own margins and given margins
     pub fn include_margins(&mut self, margins: (u16, u16)) {
         self.m.0 = self.m.0.max(margins.0);
@@ -257,11 +339,17 @@ impl SizeRules {
     }
 
     /// Use the maximum size of `self` and `rhs`.
+    ///
+    /// This is used where several widgets share the same space. The result's
+    /// maximum size is the lesser of the two maxima (but not less than the
+    /// resulting ideal size).
     #[inline]
     pub fn max(self, rhs: Self) -> SizeRules {
+        let b = self.b.max(rhs.b);
         SizeRules {
             a: self.a.max(rhs.a),
-            b: self.b.max(rhs.b),
+            b,
+            c: self.c.min(rhs.c).max(b),
             m: (self.m.0.max(rhs.m.0), self.m.1.max(rhs.m.1)),
             stretch: self.stretch.max(rhs.stretch),
         }
@@ -284,6 +372,7 @@ impl SizeRules {
         let c = self.m.1.max(rhs.m.0) as u32;
         self.a += rhs.a + c;
         self.b += rhs.b + c;
+        self.c = self.c.saturating_add(rhs.c.saturating_add(c));
         self.m.1 = rhs.m.1;
         self.stretch = self.stretch.max(rhs.stretch);
     }
@@ -302,6 +391,7 @@ impl SizeRules {
         SizeRules {
             a: self.a + rhs.a + c,
             b: self.b + rhs.b + c,
+            c: self.c.saturating_add(rhs.c.saturating_add(c)),
             m: (self.m.0, rhs.m.1),
             stretch: self.stretch.max(rhs.stretch),
         }
@@ -321,6 +411,7 @@ impl SizeRules {
         SizeRules {
             a: self.a + frame.a + c,
             b: self.b + frame.b + c,
+            c: self.c.saturating_add(frame.b + c),
             m,
             stretch: self.stretch.max(frame.stretch),
         }
@@ -353,8 +444,14 @@ impl SizeRules {
     /// This is a specialised operation to join two spans, subtracing the
     /// common overlap (`x`), thus margins are `self.m.0` and `y.m.1`.
     pub fn sub_add(&mut self, x: Self, y: Self) {
-        self.a = (self.a + y.a).saturating_sub(x.a);
-        self.b = (self.b + y.b).saturating_sub(x.b);
+        self.a = self.a.saturating_add(y.a).saturating_sub(x.a);
+        self.b = self.b.saturating_add(y.b).saturating_sub(x.b);
+        // Saturation implies an unbounded maximum (also if either is unbounded)
+        let c = self.c.saturating_add(y.c);
+        self.c = match c == u32::MAX {
+            true => u32::MAX,
+            false => c.saturating_sub(x.c).max(self.b),
+        };
         self.m.1 = y.m.1;
         self.stretch = self.stretch.max(y.stretch);
     }
@@ -396,6 +493,9 @@ impl SizeRules {
     ///     met without decreasing any widths
     /// -   Excess space is divided evenly among members with the highest
     ///     stretch policy
+    /// -   No width exceeds its maximum size, nor its ideal size if its policy
+    ///     is [`StretchPolicy::Fixed`]; if all members reach this limit, the
+    ///     sum of widths may be less than `target`
     ///
     /// Input requirements: `rules.len() == out.len()`.
     ///
@@ -425,13 +525,13 @@ impl SizeRules {
 
         if target > total.a {
             // All minimum sizes can be met.
-            out[0] = out[0].max(rules[0].a);
+            out[0] = out[0].max(rules[0].a).min(rules[0].c);
             let mut margin_sum = 0;
             let mut sum = out[0];
             let mut dist_under_b = rules[0].b.saturating_sub(out[0]);
             let mut dist_over_b = out[0].saturating_sub(rules[0].b);
             for i in 1..N {
-                out[i] = out[i].max(rules[i].a);
+                out[i] = out[i].max(rules[i].a).min(rules[i].c);
                 margin_sum += (rules[i - 1].m.1).max(rules[i].m.0) as u32;
                 sum += out[i];
                 dist_under_b += rules[i].b.saturating_sub(out[i]);
@@ -442,31 +542,57 @@ impl SizeRules {
             if sum == target {
                 return;
             } else if sum < target {
-                fn increase_targets<F: Fn(usize) -> u32>(
+                // Returns the amount of space which could not be allocated
+                // due to maximum size limits (`cap`).
+                fn increase_targets<F: Fn(usize) -> u32, G: Fn(usize) -> u32>(
                     out: &mut [u32],
                     targets: &mut Targets,
                     base: F,
+                    cap: G,
                     mut avail: u32,
-                ) {
-                    // Calculate ceiling above which sizes will not be increased
-                    let mut any_removed = true;
-                    while any_removed {
-                        any_removed = false;
-                        let count = targets.len() as u32;
-                        let ceil = (avail + count - 1) / count; // round up
+                ) -> u32 {
+                    let mut any_capped = true;
+                    while any_capped {
+                        // Calculate ceiling above which sizes will not be increased
+                        let mut any_removed = true;
+                        while any_removed {
+                            any_removed = false;
+                            let count = targets.len() as u32;
+                            let ceil = (avail + count - 1) / count; // round up
+                            let mut t = 0;
+                            while t < targets.len() {
+                                let i = targets[t] as usize;
+                                if out[i] >= base(i) + ceil {
+                                    avail -= out[i] - base(i);
+                                    targets.remove(t);
+                                    any_removed = true;
+                                    continue;
+                                }
+                                t += 1;
+                            }
+                            if targets.is_empty() {
+                                return avail;
+                            }
+                        }
+
+                        // Targets which would exceed their maximum size are
+                        // set to this maximum and removed.
+                        any_capped = false;
+                        let floor = avail / targets.len() as u32;
                         let mut t = 0;
                         while t < targets.len() {
                             let i = targets[t] as usize;
-                            if out[i] >= base(i) + ceil {
+                            if cap(i) <= base(i) + floor {
+                                out[i] = cap(i);
                                 avail -= out[i] - base(i);
                                 targets.remove(t);
-                                any_removed = true;
+                                any_capped = true;
                                 continue;
                             }
                             t += 1;
                         }
                         if targets.is_empty() {
-                            return;
+                            return avail;
                         }
                     }
 
@@ -485,28 +611,42 @@ impl SizeRules {
                         let i = targets[t] as usize;
                         out[i] = base(i) + per_elt;
                     }
+                    0
                 }
 
                 if target - sum >= dist_under_b {
                     // We can increase all sizes to their ideal. Since this may
                     // not be enough, we also count the number with highest
                     // stretch factor and how far these are over their ideal.
+                    // Should all of these reach their maximum size, the
+                    // remainder goes to those with the next highest stretch
+                    // factor; if none remain the remainder is left unused.
+                    // Sizes with policy Fixed are never increased over ideal.
                     sum = 0;
-                    let highest_stretch = total.stretch;
-                    let mut targets = Targets::new();
-                    let mut over = 0;
                     for i in 0..N {
                         out[i] = out[i].max(rules[i].b);
                         sum += out[i];
-                        if rules[i].stretch == highest_stretch {
-                            over += out[i] - rules[i].b;
-                            targets.push(i as u32);
-                        }
                     }
 
-                    let avail = target - sum + over;
-                    increase_targets(out, &mut targets, |i| rules[i].b, avail);
-                    debug_assert_eq!(target, (0..N).fold(0, |x, i| x + out[i]));
+                    let mut avail = target - sum;
+                    let mut stretch = total.stretch as usize + 1;
+                    while avail > 0 && stretch > StretchPolicy::Fixed as usize + 1 {
+                        stretch -= 1;
+                        let mut targets = Targets::new();
+                        let mut over = 0;
+                        for i in 0..N {
+                            if rules[i].stretch as usize == stretch && out[i] < rules[i].c {
+                                over += out[i] - rules[i].b;
+                                targets.push(i as u32);
+                            }
+                        }
+
+                        if !targets.is_empty() {
+                            let (base, cap) = (|i: usize| rules[i].b, |i: usize| rules[i].c);
+                            avail = increase_targets(out, &mut targets, base, cap, avail + over);
+                        }
+                    }
+                    debug_assert_eq!(target, (0..N).fold(avail, |x, i| x + out[i]));
                 } else {
                     // We cannot increase sizes as far as their ideal: instead
                     // increase over minimum size and under ideal
@@ -520,7 +660,8 @@ impl SizeRules {
                     }
 
                     let avail = target - sum + over;
-                    increase_targets(out, &mut targets, |i| rules[i].a, avail);
+                    let (base, cap) = (|i: usize| rules[i].a, |i: usize| rules[i].c);
+                    increase_targets(out, &mut targets, base, cap, avail);
                     debug_assert_eq!(target, (0..N).fold(0, |x, i| x + out[i]));
                 }
             } else {
@@ -716,6 +857,7 @@ impl SizeRules {
                     rules[i].b += 1;
                     extra_b -= 1;
                 }
+                rules[i].c = rules[i].c.max(rules[i].b);
                 if highest_stretch < self.stretch {
                     rules[i].stretch = self.stretch;
                 }
@@ -749,3 +891,69 @@ impl<'a> Sum<&'a Self> for SizeRules {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn max_size() {
+        let solve = |rules: &[SizeRules], target| {
+            let mut out = vec![0; rules.len()];
+            SizeRules::solve_seq(&mut out, rules, target);
+            out
+        };
+
+        let max = SizeRules::new(10, 20, (0, 0), StretchPolicy::Maximise).with_max(50);
+        let low = SizeRules::new(10, 20, (0, 0), StretchPolicy::LowUtility);
+        assert_eq!(solve(&[max, low], 60), vec![40, 20]);
+        assert_eq!(solve(&[max, low], 100), vec![50, 50]);
+        assert_eq!(solve(&[max, low.with_max(30)], 100), vec![50, 30]);
+        assert_eq!(SizeRules::sum(&[max, low]).max_size(), None);
+        assert_eq!(
+            SizeRules::sum(&[max, low.with_max(30)]).max_size(),
+            Some(80)
+        );
+        assert_eq!(max.max(low).max_size(), Some(50));
+
+        // Fixed sizes are not stretched, even if nothing else may be
+        let fixed = SizeRules::new(10, 20, (0, 0), StretchPolicy::Fixed);
+        assert_eq!(solve(&[fixed, low], 100), vec![20, 80]);
+        assert_eq!(solve(&[fixed, low.with_max(30)], 100), vec![20, 30]);
+        assert_eq!(solve(&[fixed, fixed], 100), vec![20, 20]);
+
+        let axis = AxisInfo::new(true, Some(160));
+        let rules = low.with_aspect_ratio(axis, (16, 9));
+        assert_eq!(rules.min_size(), 10);
+        assert_eq!(rules.ideal_size(), 90);
+        assert_eq!(rules.max_size(), Some(90));
+    }
+
+    #[test]
+    fn sub_add() {
+        let fixed = |size| SizeRules::fixed(size, (0, 0));
+        let bounded = SizeRules::new(10, 20, (0, 0), StretchPolicy::Filler).with_max(50);
+        let unbounded = SizeRules::new(10, 20, (0, 0), StretchPolicy::Filler);
+
+        let mut rules = bounded;
+        rules.sub_add(fixed(5), bounded);
+        assert_eq!(rules.min_size(), 15);
+        assert_eq!(rules.ideal_size(), 35);
+        assert_eq!(rules.max_size(), Some(95));
+
+        // An unbounded maximum remains unbounded, in either position
+        let mut rules = bounded;
+        rules.sub_add(fixed(5), unbounded);
+        assert_eq!(rules.max_size(), None);
+        let mut rules = unbounded;
+        rules.sub_add(fixed(5), bounded);
+        assert_eq!(rules.max_size(), None);
+        assert_eq!(rules.ideal_size(), 35);
+
+        // Large bounded maxima saturate instead of overflowing
+        let large = SizeRules::new(10, 20, (0, 0), StretchPolicy::Filler).with_max(u32::MAX - 1);
+        let mut rules = large;
+        rules.sub_add(fixed(5), large);
+        assert_eq!(rules.max_size(), None);
+    }
+}
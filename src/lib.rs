@@ -35,11 +35,13 @@ mod toolkit;
 mod traits;
 
 // public implementations:
+pub mod access;
 pub mod class;
 pub mod draw;
 pub mod event;
 pub mod geom;
 pub mod layout;
+pub mod persist;
 pub mod prelude;
 pub mod string;
 pub mod text;
@@ -105,6 +105,10 @@
 //! -   `left` — reversed row
 //! -   `grid` — child widgets are arranged in a grid; position is specified
 //!     via parameters to the `#[widget]` attribute on child fields
+//! -   `flow` — child widgets are arranged left-to-right, wrapping onto new
+//!     lines as required; the height depends on the available width
+//! -   `overlay` — child widgets are stacked in the same space, the last being
+//!     topmost; each is anchored via its alignment parameters (see below)
 //!
 //! Optionally, a second parameter of form `area=FIELD` is allowed (e.g.
 //! `#[layout(row, area=checkbox)]`). `FIELD` must identify a child widget.
@@ -133,10 +137,20 @@
 //! -   `halign = ...` — one of `begin`, `centre`, `end`, `stretch`
 //! -   `valign = ...` — one of `begin`, `centre`, `end`, `stretch`
 //!
+//! For the `overlay` layout, alignment parameters specify the child's
+//! [`Anchor`] (default: `stretch`). Additionally, an offset may be given in
+//! logical pixels (positive values move right / down):
+//!
+//! -   `offset = (x, y)` — where `x` and `y` are `f32` values
+//!
+//! For example, a badge may be drawn over the top-right corner of an icon
+//! with `#[widget(halign = end, valign = begin, offset = (4.0, -4.0))]`.
+//!
 //! **Layout data storage**
 //!
 //! When deriving [`Layout`], data storage is required (exception: layout
-//! `single` requires no storage, but defining it anyway is harmless).
+//! `single` may omit storage, in which case the child's maximum size is left
+//! to the parent to enforce).
 //! The [`LayoutData`] trait is also derived and used to specify the required
 //! data type. The `#[layout_data]` attribute is required to identify this
 //! storage, resulting in a field like the following:
@@ -163,6 +177,8 @@
 //!     keyboard navigation (see [`WidgetConfig::key_nav`])
 //!  -   `cursor_icon = kas::event::CursorIcon::Default`: the cursor icon to use
 //!     when the mouse hovers over this widget (see [`WidgetConfig::cursor_icon`])
+//!  -   `role = kas::access::Role::Group`: the widget's role, as reported to
+//!     accessibility tools (see [`WidgetConfig::access_role`])
 //!
 //! ### Handler and SendEvent
 //!
@@ -417,6 +433,7 @@
 #[allow(unused)]
 use crate::{
     event::{Handler, SendEvent},
+    layout::Anchor,
     AlignHints, CoreData, Layout, LayoutData, Widget, WidgetChildren, WidgetConfig, WidgetCore,
     WidgetId,
 };
@@ -0,0 +1,591 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Persistent state
+//!
+//! Applications may save UI state such as window geometry, splitter positions
+//! and scroll offsets on window closure, restoring this on the next start.
+//!
+//! State is opt-in: windows with a [`Window::state_key`] have their geometry
+//! saved, and within these windows widgets implementing [`Persist`] (via
+//! [`WidgetConfig::as_persist`]) and with a [`Persist::state_key`] have their
+//! state saved. Keys are strings and should be unique within a window; unlike
+//! [`crate::StableId`] they do not depend on the widget tree and may safely be
+//! used across program versions.
+//!
+//! All state is collected in a [`State`] map. Toolkits usually read and write
+//! this via a [`StateStore`], which uses a [`StateFormat`] to serialise it
+//! to a file (by default, in [`TextFormat`] under `$XDG_STATE_HOME`).
+//!
+//! [`Window::state_key`]: crate::Window::state_key
+
+use std::collections::{btree_map, BTreeMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::{env, error, fmt, fs, io};
+
+use crate::geom::{Coord, Size};
+use crate::{TkAction, WidgetConfig};
+
+/// A persistent value
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StateValue {
+    /// A boolean
+    Bool(bool),
+    /// An integer
+    Int(i64),
+    /// A floating-point number
+    Float(f64),
+    /// A string
+    Text(String),
+    /// A list of integers (e.g. a position or a list of sizes)
+    Ints(Vec<i64>),
+}
+
+/// Widgets with persistent state
+///
+/// Widgets implementing this trait should also implement
+/// [`WidgetConfig::as_persist`] and [`WidgetConfig::as_persist_mut`].
+pub trait Persist {
+    /// The key under which state is saved
+    ///
+    /// If `None`, the widget's state is not saved.
+    fn state_key(&self) -> Option<&str>;
+
+    /// Get the state to save, if any
+    fn save_state(&self) -> Option<StateValue>;
+
+    /// Restore from a saved value
+    ///
+    /// This is usually called after configuration but before the widget is
+    /// first sized. Widgets should ignore values not matching the expected
+    /// type or shape, since these may have been saved by another version.
+    fn restore_state(&mut self, value: &StateValue) -> TkAction;
+}
+
+/// A map of persistent state
+///
+/// Values are stored by string key. Window geometry is stored under the
+/// window's key; widget state under `window/widget`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct State {
+    values: BTreeMap<String, StateValue>,
+}
+
+impl State {
+    /// Construct an empty map
+    #[inline]
+    pub fn new() -> Self {
+        State::default()
+    }
+
+    /// True if the map is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get a value
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&StateValue> {
+        self.values.get(key)
+    }
+
+    /// Insert a value, returning the previous value (if any)
+    #[inline]
+    pub fn insert(&mut self, key: String, value: StateValue) -> Option<StateValue> {
+        self.values.insert(key, value)
+    }
+
+    /// Remove a value
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<StateValue> {
+        self.values.remove(key)
+    }
+
+    /// Iterate over entries, ordered by key
+    #[inline]
+    pub fn iter(&self) -> btree_map::Iter<String, StateValue> {
+        self.values.iter()
+    }
+
+    /// Get the saved geometry of `window`
+    ///
+    /// Returns the inner size and, if known, the outer position.
+    pub fn window_geometry(&self, window: &str) -> Option<(Size, Option<Coord>)> {
+        let v = match self.values.get(window) {
+            Some(StateValue::Ints(v)) => v,
+            _ => return None,
+        };
+        if (v.len() != 2 && v.len() != 4) || v[0] <= 0 || v[1] <= 0 {
+            return None;
+        }
+        let size = Size(v[0] as u32, v[1] as u32);
+        let pos = match v.len() {
+            4 => Some(Coord(v[2] as i32, v[3] as i32)),
+            _ => None,
+        };
+        Some((size, pos))
+    }
+
+    /// Set the geometry of `window`
+    ///
+    /// See [`State::window_geometry`].
+    pub fn set_window_geometry(&mut self, window: &str, size: Size, pos: Option<Coord>) {
+        let mut v = vec![size.0 as i64, size.1 as i64];
+        if let Some(pos) = pos {
+            v.extend_from_slice(&[pos.0 as i64, pos.1 as i64]);
+        }
+        self.values.insert(window.to_string(), StateValue::Ints(v));
+    }
+
+    /// Save state of `widget` and its descendants, within `window`
+    pub fn save_widgets(&mut self, window: &str, widget: &dyn WidgetConfig) {
+        widget.walk_dyn(&mut |w| {
+            if let Some(w) = w.as_persist() {
+                if let Some(key) = w.state_key() {
+                    if let Some(value) = w.save_state() {
+                        self.values.insert(format!("{}/{}", window, key), value);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Restore state of `widget` and its descendants, within `window`
+    pub fn restore_widgets(&self, window: &str, widget: &mut dyn WidgetConfig) -> TkAction {
+        let mut action = TkAction::None;
+        widget.walk_mut_dyn(&mut |w| {
+            if let Some(w) = w.as_persist_mut() {
+                let key = w.state_key().map(|key| format!("{}/{}", window, key));
+                if let Some(value) = key.and_then(|key| self.values.get(&key)) {
+                    action += w.restore_state(value);
+                }
+            }
+        });
+        action
+    }
+}
+
+/// Error reading or writing [`State`]
+#[derive(Debug)]
+pub enum StateError {
+    /// Failure reading or writing the file
+    Io(io::Error),
+    /// Syntax error on a line (1-based)
+    Parse(usize, String),
+    /// Other error (e.g. from a custom [`StateFormat`])
+    Other(Box<dyn error::Error + Send + Sync>),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateError::Io(e) => write!(f, "failed to access state: {}", e),
+            StateError::Parse(line, msg) => write!(f, "state line {}: {}", line, msg),
+            StateError::Other(e) => write!(f, "state error: {}", e),
+        }
+    }
+}
+
+impl error::Error for StateError {}
+
+impl From<io::Error> for StateError {
+    fn from(e: io::Error) -> Self {
+        StateError::Io(e)
+    }
+}
+
+/// A serialisation format for [`State`]
+///
+/// [`TextFormat`] is provided. With the `serde` feature, [`State`]
+/// implements `Serialize` and `Deserialize`, thus other formats are easily
+/// supported.
+pub trait StateFormat {
+    /// File extension used by this format (without `.`)
+    fn extension(&self) -> &str;
+
+    /// Serialise
+    fn write(&self, state: &State) -> Result<Vec<u8>, StateError>;
+
+    /// Deserialise
+    fn read(&self, data: &[u8]) -> Result<State, StateError>;
+}
+
+/// A simple text format
+///
+/// Each entry is written on its own line as `key = value`. Values are
+/// `true` or `false`, integers, floats (always including a `.` or exponent),
+/// quoted strings (with escapes `\\`, `\"` and `\n`) or integer lists like
+/// `[1, 2, 3]`. Lines starting with `#` are ignored. For example:
+///
+/// ```text
+/// main = [800, 600, 40, 40]
+/// main/sidebar = [200, 596]
+/// main/tabs = 1
+/// ```
+///
+/// Within keys, backslashes, `=`, line breaks, tabs, a leading `#` and
+/// leading or trailing spaces are escaped with `\` (using `\n`, `\r`, `\t`
+/// and `\s` for line feeds, carriage returns, tabs and spaces), thus all keys
+/// round-trip.
+#[derive(Clone, Debug, Default)]
+pub struct TextFormat;
+
+impl StateFormat for TextFormat {
+    fn extension(&self) -> &str {
+        "txt"
+    }
+
+    fn write(&self, state: &State) -> Result<Vec<u8>, StateError> {
+        let mut text = String::new();
+        for (key, value) in state.iter() {
+            write_key(&mut text, key);
+            text.push_str(" = ");
+            match value {
+                StateValue::Bool(b) => text.push_str(if *b { "true" } else { "false" }),
+                StateValue::Int(x) => text.push_str(&x.to_string()),
+                StateValue::Float(x) => text.push_str(&format!("{:?}", x)),
+                StateValue::Text(s) => {
+                    text.push('"');
+                    for c in s.chars() {
+                        match c {
+                            '\\' => text.push_str("\\\\"),
+                            '"' => text.push_str("\\\""),
+                            '\n' => text.push_str("\\n"),
+                            c => text.push(c),
+                        }
+                    }
+                    text.push('"');
+                }
+                StateValue::Ints(v) => {
+                    let v: Vec<String> = v.iter().map(|x| x.to_string()).collect();
+                    text.push('[');
+                    text.push_str(&v.join(", "));
+                    text.push(']');
+                }
+            }
+            text.push('\n');
+        }
+        Ok(text.into_bytes())
+    }
+
+    fn read(&self, data: &[u8]) -> Result<State, StateError> {
+        let text = std::str::from_utf8(data).map_err(|e| StateError::Other(Box::new(e)))?;
+        let mut state = State::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |msg: String| StateError::Parse(index + 1, msg);
+
+            let (key, value) = match key_end(line) {
+                Some(end) => (&line[..end], &line[end + 1..]),
+                None => return Err(err("expected `key = value`".into())),
+            };
+            let key = parse_key(key.trim()).map_err(err)?;
+            let value = parse_value(value.trim()).map_err(err)?;
+            state.insert(key, value);
+        }
+        Ok(state)
+    }
+}
+
+fn write_key(text: &mut String, key: &str) {
+    let last = key.chars().count().wrapping_sub(1);
+    for (i, c) in key.chars().enumerate() {
+        match c {
+            '\\' => text.push_str("\\\\"),
+            '=' => text.push_str("\\="),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            '#' if i == 0 => text.push_str("\\#"),
+            ' ' if i == 0 || i == last => text.push_str("\\s"),
+            c => text.push(c),
+        }
+    }
+}
+
+/// Find the byte index of the first unescaped `=` in `line`
+fn key_end(line: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' => return Some(i),
+            _ => (),
+        }
+    }
+    None
+}
+
+fn parse_key(s: &str) -> Result<String, String> {
+    let mut key = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            key.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => key.push('\n'),
+            Some('r') => key.push('\r'),
+            Some('t') => key.push('\t'),
+            Some('s') => key.push(' '),
+            Some(c @ '\\') | Some(c @ '=') | Some(c @ '#') => key.push(c),
+            _ => return Err(format!("invalid escape sequence in key {}", s)),
+        }
+    }
+    Ok(key)
+}
+
+fn parse_value(s: &str) -> Result<StateValue, String> {
+    if s == "true" || s == "false" {
+        Ok(StateValue::Bool(s == "true"))
+    } else if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+        let mut text = String::new();
+        let mut chars = s[1..s.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                text.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('\\') => text.push('\\'),
+                Some('"') => text.push('"'),
+                Some('n') => text.push('\n'),
+                _ => return Err(format!("invalid escape sequence in {}", s)),
+            }
+        }
+        Ok(StateValue::Text(text))
+    } else if s.len() >= 2 && s.starts_with('[') && s.ends_with(']') {
+        let inner = s[1..s.len() - 1].trim();
+        let mut v = vec![];
+        if !inner.is_empty() {
+            for x in inner.split(',') {
+                let x = x.trim();
+                v.push(x.parse().map_err(|_| format!("invalid integer: {}", x))?);
+            }
+        }
+        Ok(StateValue::Ints(v))
+    } else if let Ok(x) = s.parse() {
+        Ok(StateValue::Int(x))
+    } else if let Ok(x) = s.parse() {
+        Ok(StateValue::Float(x))
+    } else {
+        Err(format!("invalid value: {}", s))
+    }
+}
+
+/// File storage for [`State`]
+pub struct StateStore {
+    path: PathBuf,
+    format: Box<dyn StateFormat>,
+}
+
+impl StateStore {
+    /// Construct for application `app`
+    ///
+    /// State is stored in `state.txt` (using [`TextFormat`]) within
+    /// `<app>` in the directory given by [`StateStore::state_home`]. Returns
+    /// `None` if that directory is unknown, in which case state should not be
+    /// persisted (or a path chosen via [`StateStore::with_path`]).
+    pub fn new(app: &str) -> Option<Self> {
+        let dir = Self::state_home()?.join(app);
+        Some(StateStore::with_path(dir.join("state.txt")))
+    }
+
+    /// Construct for a specific file path
+    ///
+    /// This uses [`TextFormat`] (see [`StateStore::with_format`]).
+    pub fn with_path<P: Into<PathBuf>>(path: P) -> Self {
+        StateStore {
+            path: path.into(),
+            format: Box::new(TextFormat),
+        }
+    }
+
+    /// Set the format (chaining)
+    ///
+    /// The path's extension is replaced by [`StateFormat::extension`].
+    pub fn with_format<F: StateFormat + 'static>(mut self, format: F) -> Self {
+        self.path.set_extension(format.extension());
+        self.format = Box::new(format);
+        self
+    }
+
+    /// The user's state directory
+    ///
+    /// This is `$XDG_STATE_HOME` if set, otherwise `$HOME/.local/state`.
+    /// Relative paths are ignored; if neither variable gives an absolute path
+    /// this returns `None`.
+    pub fn state_home() -> Option<PathBuf> {
+        let absolute = |var: &str| {
+            env::var_os(var)
+                .map(PathBuf::from)
+                .filter(|p| p.is_absolute())
+        };
+        absolute("XDG_STATE_HOME").or_else(|| Some(absolute("HOME")?.join(".local/state")))
+    }
+
+    /// The file path
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load state
+    ///
+    /// If the file does not exist, an empty [`State`] is returned.
+    pub fn load(&self) -> Result<State, StateError> {
+        match fs::read(&self.path) {
+            Ok(data) => self.format.read(&data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(State::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save state
+    ///
+    /// The parent directory is created if necessary. State is written to a
+    /// temporary file in the same directory which then replaces the old file,
+    /// thus an interrupted save does not lose previously saved state.
+    pub fn save(&self, state: &State) -> Result<(), StateError> {
+        let data = self.format.write(state)?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let result = fs::File::create(&tmp)
+            .and_then(|mut file| {
+                file.write_all(&data)?;
+                file.sync_all()
+            })
+            .and_then(|()| fs::rename(&tmp, &self.path));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result.map_err(StateError::Io)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_format() {
+        let mut state = State::new();
+        state.set_window_geometry("main", Size(800, 600), Some(Coord(-5, 40)));
+        state.insert("main/sidebar".into(), StateValue::Ints(vec![200, 596]));
+        state.insert("main/tabs".into(), StateValue::Int(1));
+        state.insert("main/zoom".into(), StateValue::Float(2.0));
+        state.insert("main/wrap".into(), StateValue::Bool(true));
+        let text = "a \"quoted\" \\ string\nwith = sign";
+        state.insert("main/note".into(), StateValue::Text(text.into()));
+
+        let data = TextFormat.write(&state).unwrap();
+        let s = std::str::from_utf8(&data).unwrap();
+        assert!(s.contains("main = [800, 600, -5, 40]\n"));
+        assert!(s.contains("main/zoom = 2.0\n"));
+        assert_eq!(TextFormat.read(&data).unwrap(), state);
+        assert_eq!(
+            state.window_geometry("main"),
+            Some((Size(800, 600), Some(Coord(-5, 40))))
+        );
+
+        match TextFormat.read(b"# comment\n\nx = 1\ny") {
+            Err(StateError::Parse(4, _)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn text_format_keys() {
+        let mut state = State::new();
+        let keys = [
+            "a=b",
+            "line\nbreak",
+            "cr\r\tlf",
+            "#hash",
+            "not#comment",
+            " padded ",
+            "back\\slash\\",
+            "",
+        ];
+        for (i, key) in keys.iter().enumerate() {
+            state.insert(key.to_string(), StateValue::Int(i as i64));
+        }
+
+        let data = TextFormat.write(&state).unwrap();
+        let s = std::str::from_utf8(&data).unwrap();
+        assert_eq!(s.lines().count(), keys.len());
+        assert!(s.contains("a\\=b = 0\n"));
+        assert!(s.contains("\\#hash = 3\n"));
+        assert!(s.contains("\\spadded\\s = 5\n"));
+        assert_eq!(TextFormat.read(&data).unwrap(), state);
+
+        match TextFormat.read(b"x\\y = 1") {
+            Err(StateError::Parse(1, _)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn store() {
+        let dir = env::temp_dir().join(format!("kas-persist-{}", std::process::id()));
+        let store = StateStore::with_path(dir.join("state.txt"));
+        let mut state = State::new();
+        assert_eq!(store.load().unwrap(), state);
+
+        state.insert("key".into(), StateValue::Int(1));
+        store.save(&state).unwrap();
+        state.insert("key".into(), StateValue::Int(2));
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap(), state);
+
+        // Only the state file remains
+        let files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec![std::ffi::OsString::from("state.txt")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn state_home() {
+        let vars = ["XDG_STATE_HOME", "HOME"];
+        let saved: Vec<_> = vars.iter().map(env::var_os).collect();
+
+        env::set_var("XDG_STATE_HOME", "/state");
+        env::set_var("HOME", "/home/user");
+        let store = StateStore::new("app").unwrap();
+        assert_eq!(store.path(), Path::new("/state/app/state.txt"));
+
+        env::set_var("XDG_STATE_HOME", "state");
+        let path = PathBuf::from("/home/user/.local/state");
+        assert_eq!(StateStore::state_home(), Some(path));
+
+        // Never fall back to the working directory
+        env::remove_var("HOME");
+        assert_eq!(StateStore::state_home(), None);
+        assert!(StateStore::new("app").is_none());
+
+        for (var, value) in vars.iter().zip(saved) {
+            match value {
+                Some(value) => env::set_var(var, value),
+                None => env::remove_var(var),
+            }
+        }
+    }
+}
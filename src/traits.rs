@@ -90,6 +90,17 @@ pub trait Window: Widget<Msg = event::VoidMsg> {
     /// windows.
     fn restrict_dimensions(&self) -> (bool, bool);
 
+    /// Key under which to persist window state
+    ///
+    /// If set, toolkits save the window's geometry and the state of its
+    /// widgets on closure, restoring this when next opened (see
+    /// [`crate::persist`]). Keys should be unique within the application.
+    ///
+    /// Defaults to `None` (no persistence).
+    fn state_key(&self) -> Option<&str> {
+        None
+    }
+
     /// Add a pop-up as a layer in the current window
     ///
     /// Each [`Popup`] is assigned a [`WindowId`]; both are passed.
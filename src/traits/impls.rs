@@ -6,10 +6,13 @@
 //! Trait impls
 
 use super::*;
+use crate::access::Role;
+use crate::class::{CloneText, HasBool, HasString};
 use crate::draw::{DrawHandle, SizeHandle};
 use crate::event::{self, Event, Manager, Response};
 use crate::geom::{Coord, Rect};
 use crate::layout::{AxisInfo, SizeRules};
+use crate::persist::Persist;
 use crate::{AlignHints, CoreData, WidgetId};
 
 impl<M: 'static> WidgetCore for Box<dyn Widget<Msg = M>> {
@@ -76,6 +79,29 @@ impl<M: 'static> WidgetConfig for Box<dyn Widget<Msg = M>> {
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+
+    fn access_role(&self) -> Role {
+        self.as_ref().access_role()
+    }
+    fn context_menu(&self, mgr: &event::ManagerState, menu: &mut event::ContextMenu) {
+        self.as_ref().context_menu(mgr, menu);
+    }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        self.as_ref().as_has_bool()
+    }
+    fn as_has_string(&self) -> Option<&dyn HasString> {
+        self.as_ref().as_has_string()
+    }
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        self.as_ref().as_clone_text()
+    }
+    fn as_persist(&self) -> Option<&dyn Persist> {
+        self.as_ref().as_persist()
+    }
+    fn as_persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        self.as_mut().as_persist_mut()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Widget<Msg = M>> {
@@ -9,11 +9,14 @@ use std::any::Any;
 use std::fmt;
 
 use super::Boxed;
+use crate::access::Role;
+use crate::class::{CloneText, HasBool, HasString};
 use crate::draw::{DrawHandle, InputState, SizeHandle};
 use crate::event::{self, ConfigureManager, Manager, ManagerState};
 use crate::geom::{Coord, Rect};
 use crate::layout::{AxisInfo, SizeRules};
-use crate::{AlignHints, CoreData, TkAction, WidgetId};
+use crate::persist::Persist;
+use crate::{AlignHints, CoreData, StableId, TkAction, WidgetId};
 
 impl dyn WidgetCore {
     /// Forwards to the method defined on the type `Any`.
@@ -66,6 +69,38 @@ pub trait WidgetCore: Any + fmt::Debug {
         self.core_data().id
     }
 
+    /// Get the widget's stable identifier
+    ///
+    /// This is assigned during configure; see [`StableId`].
+    #[inline]
+    fn stable_id(&self) -> StableId {
+        self.core_data().stable_id
+    }
+
+    /// Get the widget's key, if any
+    #[inline]
+    fn key(&self) -> Option<u64> {
+        self.core_data().key
+    }
+
+    /// Set the widget's key (chaining)
+    ///
+    /// Keyed widgets have a [`StableId`] which does not depend on their index,
+    /// thus survives insertion and removal of siblings. Keys need only be
+    /// unique among siblings. Example:
+    /// ```
+    /// use kas::{WidgetCore, widget::TextButton};
+    /// let button = TextButton::new("Save", ()).with_key(1);
+    /// ```
+    #[inline]
+    fn with_key(mut self, key: u64) -> Self
+    where
+        Self: Sized,
+    {
+        self.core_data_mut().key = Some(key);
+        self
+    }
+
     /// Get whether the widget is disabled
     #[inline]
     fn is_disabled(&self) -> bool {
@@ -311,9 +346,11 @@ pub trait WidgetConfig: Layout {
     fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
         for i in 0..self.len() {
             if let Some(w) = self.get_mut(i) {
-                w.configure_recurse(cmgr.child());
+                let key = w.key();
+                w.configure_recurse(cmgr.child(i, key));
             }
         }
+        self.core_data_mut().stable_id = cmgr.stable_id();
         self.core_data_mut().id = cmgr.next_id(self.id());
         self.configure(cmgr.mgr());
     }
@@ -331,6 +368,65 @@ pub trait WidgetConfig: Layout {
     fn cursor_icon(&self) -> event::CursorIcon {
         event::CursorIcon::Default
     }
+
+    /// The widget's role, as reported to accessibility tools
+    ///
+    /// See [`crate::access`]. Defaults to [`Role::Group`].
+    fn access_role(&self) -> Role {
+        Role::Group
+    }
+
+    /// Contribute entries to a context menu
+    ///
+    /// This is called on the target of a context-menu request (secondary-button
+    /// press or Menu key) and on each of its ancestors, innermost first.
+    /// Widgets may add entries to `menu`; each entry sends an event back to
+    /// this widget when activated. See [`event::ContextMenu`].
+    ///
+    /// The default implementation adds nothing.
+    fn context_menu(&self, _: &ManagerState, _: &mut event::ContextMenu) {}
+
+    /// Get self as [`HasBool`], if implemented
+    ///
+    /// This allows type-erased access to widget state, e.g. for testing.
+    /// Widgets implementing [`HasBool`] should implement this method.
+    ///
+    /// Defaults to `None`.
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        None
+    }
+
+    /// Get self as [`HasString`], if implemented
+    ///
+    /// See [`WidgetConfig::as_has_bool`].
+    fn as_has_string(&self) -> Option<&dyn HasString> {
+        None
+    }
+
+    /// Get self as [`CloneText`], if implemented
+    ///
+    /// See [`WidgetConfig::as_has_bool`].
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        None
+    }
+
+    /// Get self as [`Persist`], if implemented
+    ///
+    /// This allows saving of widget state (see [`crate::persist`]). Widgets
+    /// implementing [`Persist`] should implement this method and
+    /// [`WidgetConfig::as_persist_mut`].
+    ///
+    /// Defaults to `None`.
+    fn as_persist(&self) -> Option<&dyn Persist> {
+        None
+    }
+
+    /// Get self as [`Persist`], if implemented (mutable)
+    ///
+    /// See [`WidgetConfig::as_persist`].
+    fn as_persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        None
+    }
 }
 
 /// Positioning and drawing routines for widgets
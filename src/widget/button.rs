@@ -7,9 +7,11 @@
 
 use std::fmt::Debug;
 
+use super::label::LabelIcon;
+use kas::access::Role;
 use kas::class::{CloneText, SetAccel};
-use kas::draw::TextClass;
-use kas::event::{VirtualKeyCode, VirtualKeyCodes};
+use kas::draw::{Icon, TextClass};
+use kas::event::{CommandBinding, Commands, VirtualKeyCode, VirtualKeyCodes};
 use kas::prelude::*;
 
 /// A push-button with a text label
@@ -23,17 +25,32 @@ pub struct TextButton<M: Clone + Debug + 'static> {
     keys2: VirtualKeyCodes,
     // label_rect: Rect,
     label: PreparedText,
+    icon: LabelIcon,
+    icon_off: i32,
     msg: M,
+    command: Option<CommandBinding<M>>,
 }
 
 impl<M: Clone + Debug + 'static> WidgetConfig for TextButton<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.id(), &self.keys1);
         mgr.add_accel_keys(self.id(), &self.keys2);
+        let id = self.id();
+        if let Some(ref mut command) = self.command {
+            command.configure(mgr, id);
+        }
     }
 
     fn key_nav(&self) -> bool {
-        true
+        self.command_enabled()
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::Button
     }
 }
 
@@ -43,7 +60,11 @@ impl<M: Clone + Debug + 'static> Layout for TextButton<M> {
         let margins = size_handle.outer_margins();
         let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), sides.0 + sides.1, margins);
 
-        let content_rules = size_handle.text_bound(&mut self.label, TextClass::Button, axis);
+        let text_rules = size_handle.text_bound(&mut self.label, TextClass::Button, axis);
+        let content_rules = self
+            .icon
+            .size_rules(size_handle, axis, TextClass::Button, text_rules);
+        self.icon_off = (sides.0).0 as i32;
         content_rules.surrounded_by(frame_rules, true)
     }
 
@@ -54,7 +75,8 @@ impl<M: Clone + Debug + 'static> Layout for TextButton<M> {
         // In practice, it sometimes overflows a tiny bit, and looks better if
         // we let it overflow. Since the text is centred this is okay.
         // self.label_rect = ...
-        self.label.set_size(rect.size);
+        let width = rect.size.0.saturating_sub(self.icon.label_offset() as u32);
+        self.label.set_size(Size(width, rect.size.1));
         self.label.set_alignment(
             align.horiz.unwrap_or(Align::Centre),
             align.vert.unwrap_or(Align::Centre),
@@ -62,9 +84,14 @@ impl<M: Clone + Debug + 'static> Layout for TextButton<M> {
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || !self.command_enabled();
         draw_handle.button(self.core.rect, self.input_state(mgr, disabled));
         // TODO: mgr.show_accel_labels();
-        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Button);
+        let mut pos = self.core.rect.pos;
+        let icon_pos = pos + Coord(self.icon_off, 0);
+        self.icon.draw(draw_handle, icon_pos, self.core.rect.size.1);
+        pos.0 += self.icon.label_offset();
+        draw_handle.text(pos, &self.label, TextClass::Button);
     }
 }
 
@@ -85,10 +112,30 @@ impl<M: Clone + Debug + 'static> TextButton<M> {
             keys2,
             // label_rect: Default::default(),
             label: text,
+            icon: Default::default(),
+            icon_off: 0,
             msg,
+            command: None,
         }
     }
 
+    /// Construct a button bound to the command `name` of `commands`
+    ///
+    /// The button takes its label and message from the command, and is drawn
+    /// disabled (ignoring activation) while the command is disabled. It also
+    /// handles [`Event::Command`] for shortcuts bound to the command.
+    ///
+    /// Panics if `commands` has no command `name`.
+    pub fn new_cmd(commands: &Commands<M>, name: &str) -> Self {
+        let msg = commands
+            .msg(name)
+            .expect("TextButton::new_cmd: unknown command");
+        let command = CommandBinding::new(commands, name);
+        let mut button = TextButton::new(command.label(), msg);
+        button.command = Some(command);
+        button
+    }
+
     /// Add accelerator keys (chain style)
     ///
     /// These keys are added to those inferred from the label via `&` marks.
@@ -98,10 +145,32 @@ impl<M: Clone + Debug + 'static> TextButton<M> {
         self
     }
 
+    /// Show an icon beside the label (chain style)
+    ///
+    /// The icon is drawn to the left of the label, scaled to the height of a
+    /// line of text. Vector icons are rasterised at the window's scale factor.
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        self.icon.set(Some(icon.into()));
+        self
+    }
+
+    /// Set or remove the icon
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> TkAction {
+        self.icon.set(icon);
+        TkAction::Resize
+    }
+
     /// Replace the message value
     pub fn set_msg(&mut self, msg: M) {
         self.msg = msg;
     }
+
+    fn command_enabled(&self) -> bool {
+        self.command
+            .as_ref()
+            .map(|c| c.is_enabled())
+            .unwrap_or(true)
+    }
 }
 
 impl<M: Clone + Debug + 'static> CloneText for TextButton<M> {
@@ -126,10 +195,19 @@ impl<M: Clone + Debug + 'static> event::Handler for TextButton<M> {
         true
     }
 
-    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<M> {
-        match event {
-            Event::Activate => self.msg.clone().into(),
-            event => Response::Unhandled(event),
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+        match (event, &mut self.command) {
+            (Event::Activate, None) => self.msg.clone().into(),
+            (Event::Activate, Some(command)) | (Event::Command(_), Some(command)) => {
+                command.msg().into()
+            }
+            (Event::HandleUpdate { .. }, Some(command)) => {
+                if command.update() {
+                    mgr.redraw(self.core.id);
+                }
+                Response::None
+            }
+            (event, _) => Response::Unhandled(event),
         }
     }
 }
@@ -9,11 +9,13 @@ use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 use super::AccelLabel;
-use kas::class::HasBool;
+use kas::access::Role;
+use kas::class::{CloneText, HasBool};
+use kas::draw::Icon;
 use kas::prelude::*;
 
 /// A bare checkbox (no label)
-#[widget(config(key_nav = true))]
+#[widget(config=noauto)]
 #[handler(handle=noauto)]
 #[derive(Clone, Default, Widget)]
 pub struct CheckBoxBare<M: 'static> {
@@ -107,6 +109,20 @@ impl<M: 'static> CheckBoxBare<M> {
     }
 }
 
+impl<M: 'static> WidgetConfig for CheckBoxBare<M> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::CheckBox
+    }
+}
+
 impl<M: 'static> HasBool for CheckBoxBare<M> {
     fn get_bool(&self) -> bool {
         self.state
@@ -233,12 +249,39 @@ impl<M: 'static> CheckBox<M> {
         self.checkbox = self.checkbox.state(state);
         self
     }
+
+    /// Show an icon before the label (chain style)
+    ///
+    /// The icon is scaled to the height of a line of text.
+    #[inline]
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        self.label = self.label.with_icon(icon);
+        self
+    }
+
+    /// Set or remove the icon
+    #[inline]
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> TkAction {
+        self.label.set_icon(icon)
+    }
 }
 
 impl<M: 'static> WidgetConfig for CheckBox<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.checkbox.id(), self.label.keys());
     }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        Some(self)
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::CheckBox
+    }
 }
 
 impl<M: 'static> HasBool for CheckBox<M> {
@@ -252,3 +295,9 @@ impl<M: 'static> HasBool for CheckBox<M> {
         self.checkbox.set_bool(state)
     }
 }
+
+impl<M: 'static> CloneText for CheckBox<M> {
+    fn clone_text(&self) -> kas::text::RichText {
+        self.label.clone_text()
+    }
+}
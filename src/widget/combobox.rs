@@ -9,20 +9,24 @@ use std::fmt::Debug;
 use std::iter::FromIterator;
 
 use super::{Column, MenuEntry, MenuFrame};
+use kas::access::Role;
 use kas::class::{CloneText, SetAccel};
-use kas::draw::TextClass;
+use kas::draw::{Icon, StandardIcon, TextClass};
 use kas::event::{ControlKey, GrabMode};
 use kas::prelude::*;
 use kas::WindowId;
 
 /// A pop-up multiple choice menu
-#[widget(config(key_nav = true))]
+#[widget(config(key_nav = true, role = Role::ComboBox))]
 #[handler(noauto)]
 #[derive(Clone, Debug, Widget)]
 pub struct ComboBox<M: Clone + Debug + 'static> {
     #[widget_core]
     core: CoreData,
     label: PreparedText,
+    arrow: Icon,
+    arrow_size: Size,
+    arrow_off: u32,
     #[widget]
     popup: ComboPopup,
     messages: Vec<M>, // TODO: is this a useless lookup step?
@@ -38,12 +42,29 @@ impl<M: Clone + Debug + 'static> kas::Layout for ComboBox<M> {
         let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), sides.0 + sides.1, margins);
 
         let content_rules = size_handle.text_bound(&mut self.label, TextClass::Button, axis);
+
+        // The drop-down arrow is drawn right of the label at line height
+        let h = size_handle.line_height(TextClass::Button);
+        let content_rules = if axis.is_horizontal() {
+            let aspect = match self.arrow.size() {
+                Some(size) if size.1 > 0.0 => size.0 / size.1,
+                _ => 1.0,
+            };
+            let w = (h as f32 * aspect).round() as u32;
+            let gap = size_handle.inner_margin().0;
+            self.arrow_size = Size(w, h);
+            self.arrow_off = w + (sides.1).0;
+            content_rules.appended(SizeRules::fixed(gap + w, (0, 0)))
+        } else {
+            content_rules.max(SizeRules::fixed(h, (0, 0)))
+        };
         content_rules.surrounded_by(frame_rules, true)
     }
 
     fn set_rect(&mut self, rect: Rect, align: kas::AlignHints) {
         self.core.rect = rect;
-        self.label.set_size(rect.size);
+        let size = Size(rect.size.0.saturating_sub(self.arrow_off), rect.size.1);
+        self.label.set_size(size);
         self.label.set_alignment(
             align.horiz.unwrap_or(Align::Centre),
             align.vert.unwrap_or(Align::Centre),
@@ -62,6 +83,12 @@ impl<M: Clone + Debug + 'static> kas::Layout for ComboBox<M> {
         }
         draw_handle.button(self.core.rect, state);
         draw_handle.text(self.core.rect.pos, &self.label, TextClass::Button);
+
+        let rect = self.core.rect;
+        let x = rect.size.0 as i32 - self.arrow_off as i32;
+        let y = (rect.size.1 as i32 - self.arrow_size.1 as i32) / 2;
+        let arrow = Rect::new(rect.pos + Coord(x, y), self.arrow_size);
+        draw_handle.icon(&self.arrow, arrow);
     }
 }
 
@@ -93,6 +120,9 @@ impl<M: Clone + Debug + 'static> ComboBox<M> {
         ComboBox {
             core: Default::default(),
             label,
+            arrow: StandardIcon::ArrowDown.icon(),
+            arrow_size: Size::ZERO,
+            arrow_off: 0,
             popup: ComboPopup {
                 core: Default::default(),
                 inner: MenuFrame::new(Column::new(column)),
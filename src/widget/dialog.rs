@@ -8,6 +8,8 @@
 //! KAS dialog boxes are pre-configured windows, usually allowing some
 //! customisation.
 
+use kas::access::Role;
+use kas::draw::{Elevation, Icon, StandardIcon};
 use kas::event::VirtualKeyCode;
 use kas::prelude::*;
 use kas::widget::{Label, TextButton};
@@ -18,42 +20,125 @@ enum DialogButton {
     Close,
 }
 
-/// A simple message box.
 #[layout(column)]
-#[widget(config=noauto)]
 #[derive(Clone, Debug, Widget)]
-pub struct MessageBox {
+struct MessageBody {
     #[widget_core]
     core: CoreData,
     #[layout_data]
     layout_data: <Self as kas::LayoutData>::Data,
-    title: String,
     #[widget]
     label: Label,
     #[widget(handler = handle_button)]
     button: TextButton<DialogButton>,
 }
 
+impl MessageBody {
+    fn handle_button(&mut self, mgr: &mut Manager, msg: DialogButton) -> Response<VoidMsg> {
+        match msg {
+            DialogButton::Close => mgr.send_action(TkAction::Close),
+        };
+        Response::None
+    }
+}
+
+/// A simple message box.
+///
+/// The message is drawn on a raised surface ([`Elevation::Dialog`]) casting a
+/// shadow over the window's background.
+#[widget(config=noauto)]
+#[derive(Clone, Debug, Widget)]
+pub struct MessageBox {
+    #[widget_core]
+    core: CoreData,
+    title: String,
+    #[widget]
+    body: MessageBody,
+    shadow: (Size, Size),
+    surface: Rect,
+    m0: Size,
+    m1: Size,
+}
+
 impl MessageBox {
+    /// Construct a message box
+    ///
+    /// The message is shown beside [`StandardIcon::Information`].
     pub fn new<T: ToString, M: Into<LabelString>>(title: T, message: M) -> Self {
         MessageBox {
             core: Default::default(),
-            layout_data: Default::default(),
             title: title.to_string(),
-            label: Label::new(message),
-            button: TextButton::new("Ok", DialogButton::Close).with_keys(&[
-                VirtualKeyCode::Return,
-                VirtualKeyCode::Space,
-                VirtualKeyCode::NumpadEnter,
-            ]),
+            body: MessageBody {
+                core: Default::default(),
+                layout_data: Default::default(),
+                label: Label::new(message).with_icon(StandardIcon::Information),
+                button: TextButton::new("Ok", DialogButton::Close).with_keys(&[
+                    VirtualKeyCode::Return,
+                    VirtualKeyCode::Space,
+                    VirtualKeyCode::NumpadEnter,
+                ]),
+            },
+            shadow: Default::default(),
+            surface: Default::default(),
+            m0: Size::ZERO,
+            m1: Size::ZERO,
         }
     }
 
-    fn handle_button(&mut self, mgr: &mut Manager, msg: DialogButton) -> Response<VoidMsg> {
-        match msg {
-            DialogButton::Close => mgr.send_action(TkAction::Close),
-        };
-        Response::None
+    /// Replace the icon (chain style)
+    ///
+    /// For example, use [`StandardIcon::Warning`] for a warning message.
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        let _ = self.body.label.set_icon(Some(icon.into()));
+        self
+    }
+}
+
+impl Layout for MessageBox {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        // Reserve space for the shadow, which would otherwise be clipped by
+        // the window's edge
+        let (s0, s1) = size_handle.shadow(Elevation::Dialog);
+        self.shadow = (s0, s1);
+        let frame = size_handle.frame();
+        let surround = s0 + s1 + frame + frame;
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), surround, Margins::ZERO);
+
+        let child_rules = self.body.size_rules(size_handle, axis);
+        let m = child_rules.margins();
+
+        if axis.is_horizontal() {
+            self.m0.0 = s0.0 + frame.0 + m.0 as u32;
+            self.m1.0 = s1.0 + frame.0 + m.1 as u32;
+        } else {
+            self.m0.1 = s0.1 + frame.1 + m.0 as u32;
+            self.m1.1 = s1.1 + frame.1 + m.1 as u32;
+        }
+
+        child_rules.surrounded_by(frame_rules, true)
+    }
+
+    fn set_rect(&mut self, mut rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let (s0, s1) = self.shadow;
+        self.surface = Rect::new(rect.pos + s0, rect.size - s0 - s1);
+        rect.pos += self.m0;
+        rect.size -= self.m0 + self.m1;
+        self.body.set_rect(rect, align);
+    }
+
+    #[inline]
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        self.body.find_id(coord).or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        draw_handle.surface(self.surface, Elevation::Dialog);
+        let disabled = disabled || self.is_disabled();
+        self.body.draw(draw_handle, mgr, disabled);
     }
 }
 
@@ -61,6 +146,10 @@ impl kas::WidgetConfig for MessageBox {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.enable_alt_bypass(true);
     }
+
+    fn access_role(&self) -> Role {
+        Role::Dialog
+    }
 }
 
 impl kas::Window for MessageBox {
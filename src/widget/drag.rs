@@ -7,6 +7,7 @@
 
 use std::fmt::Debug;
 
+use kas::access::Role;
 use kas::event::PressSource;
 use kas::prelude::*;
 
@@ -26,6 +27,7 @@ use kas::prelude::*;
 /// 4.  Optionally, this widget can handle clicks on the track area via
 ///     [`DragHandle::handle_press_on_track`].
 #[handler(handle=noauto)]
+#[widget(config(role = Role::DragHandle))]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct DragHandle {
     #[widget_core]
@@ -9,25 +9,12 @@ use std::fmt::{self, Debug};
 use std::ops::Range;
 use unicode_segmentation::GraphemeCursor;
 
-use kas::class::HasString;
+use kas::access::Role;
+use kas::class::{CloneText, HasString};
 use kas::draw::{DrawHandleExt, TextClass};
-use kas::event::{ControlKey, GrabMode, ModifiersState};
+use kas::event::{ContextMenu, ControlKey, EditKind, GrabMode, ModifiersState};
 use kas::prelude::*;
 
-#[derive(Clone, Debug, PartialEq)]
-enum LastEdit {
-    None,
-    Insert,
-    Delete,
-    Paste,
-}
-
-impl Default for LastEdit {
-    fn default() -> Self {
-        LastEdit::None
-    }
-}
-
 enum EditAction {
     None,
     Activate,
@@ -128,9 +115,11 @@ impl<F: Fn(&str) -> Option<M>, M> EditGuard for EditEdit<F, M> {
 ///
 /// Currently, this widget has a [`EditBox::multi_line`] mode, with some
 /// limitations (incorrect positioning of the edit cursor at line end,
-/// non-functional up/down keys, lack of scrolling). Later this will be replaced
-/// by a dedicated multi-line widget, probably using the `ropey` crate.
-#[widget(config(key_nav = true, cursor_icon = event::CursorIcon::Text))]
+/// non-functional up/down keys, lack of scrolling). For editing longer texts,
+/// use [`TextEdit`] instead.
+///
+/// [`TextEdit`]: super::TextEdit
+#[widget(config=noauto)]
 #[handler(handle=noauto, generics = <> where G: EditGuard)]
 #[derive(Clone, Default, Widget)]
 pub struct EditBox<G: 'static> {
@@ -146,8 +135,6 @@ pub struct EditBox<G: 'static> {
     prepared: PreparedText,
     edit_pos: usize,
     sel_pos: usize,
-    old_state: Option<(String, usize, usize)>,
-    last_edit: LastEdit,
     error_state: bool,
     /// The associated [`EditGuard`] implementation
     pub guard: G,
@@ -163,6 +150,46 @@ impl<G> Debug for EditBox<G> {
     }
 }
 
+impl<G: 'static> WidgetConfig for EditBox<G> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn cursor_icon(&self) -> event::CursorIcon {
+        event::CursorIcon::Text
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::TextEntry
+    }
+
+    fn context_menu(&self, mgr: &ManagerState, menu: &mut ContextMenu) {
+        let editable = self.editable;
+        let have_sel = self.edit_pos != self.sel_pos;
+        menu.push_control("Cu&t", ControlKey::Cut, editable && have_sel);
+        menu.push_control("&Copy", ControlKey::Copy, editable && have_sel);
+        menu.push_control("&Paste", ControlKey::Paste, editable);
+        menu.push_separator();
+        menu.push_control(
+            "&Undo",
+            ControlKey::Undo,
+            editable && mgr.can_undo(self.id()),
+        );
+        menu.push_control(
+            "&Redo",
+            ControlKey::Redo,
+            editable && mgr.can_redo(self.id()),
+        );
+        menu.push_separator();
+        let any = !self.text.is_empty();
+        menu.push_control("Select &All", ControlKey::SelectAll, editable && any);
+    }
+}
+
 impl<G: 'static> Layout for EditBox<G> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let frame_sides = size_handle.edit_surround();
@@ -245,8 +272,6 @@ impl EditBox<EditVoid> {
             prepared: PreparedText::new(text.into(), false),
             edit_pos,
             sel_pos: edit_pos,
-            old_state: None,
-            last_edit: LastEdit::None,
             error_state: false,
             guard: EditVoid,
         }
@@ -271,8 +296,6 @@ impl EditBox<EditVoid> {
             prepared: self.prepared,
             edit_pos: self.edit_pos,
             sel_pos: self.sel_pos,
-            old_state: self.old_state,
-            last_edit: self.last_edit,
             error_state: self.error_state,
             guard,
         };
@@ -373,10 +396,10 @@ impl<G> EditBox<G> {
         let pos = self.edit_pos;
         let selection = self.selection();
         let have_sel = selection.start < selection.end;
-        if self.last_edit != LastEdit::Insert || have_sel {
-            self.old_state = Some((self.text.clone(), pos, self.sel_pos));
-            self.last_edit = LastEdit::Insert;
+        if have_sel {
+            mgr.close_undo_group(self.id());
         }
+        self.push_undo(mgr, EditKind::Insert);
         if have_sel {
             let mut buf = [0u8; 4];
             let s = c.encode_utf8(&mut buf);
@@ -414,15 +437,16 @@ impl<G> EditBox<G> {
             None,
             Activate,
             Edit,
-            Insert(&'a str, LastEdit),
+            Insert(&'a str, EditKind),
             Delete(Range<usize>),
             Move(usize),
+            Select(usize, usize),
         }
 
         let action = match key {
             ControlKey::Return if shift || !self.multi_line => Action::Activate,
             ControlKey::Return if self.multi_line => {
-                Action::Insert('\n'.encode_utf8(&mut buf), LastEdit::Insert)
+                Action::Insert('\n'.encode_utf8(&mut buf), EditKind::Insert)
             }
             ControlKey::Left => {
                 let mut cursor = GraphemeCursor::new(pos, self.text.len(), true);
@@ -490,23 +514,27 @@ impl<G> EditBox<G> {
                     }
 
                     string = content;
-                    Action::Insert(&string[0..end], LastEdit::Paste)
+                    Action::Insert(&string[0..end], EditKind::Paste)
                 } else {
                     Action::None
                 }
             }
+            ControlKey::SelectAll => Action::Select(0, self.text.len()),
             ControlKey::Undo | ControlKey::Redo => {
-                // TODO: maintain full edit history (externally?)
-                // NOTE: undo *and* redo shortcuts map to this control char
-                if let Some((state, pos2, sel_pos)) = self.old_state.as_mut() {
-                    std::mem::swap(state, &mut self.text);
-                    self.edit_pos = *pos2;
-                    *pos2 = pos;
-                    std::mem::swap(sel_pos, &mut self.sel_pos);
+                let current = (self.text.clone(), pos, self.sel_pos);
+                let state = match key {
+                    ControlKey::Undo => mgr.undo(self.id(), current),
+                    _ => mgr.redo(self.id(), current),
+                };
+                if let Some((text, edit_pos, sel_pos)) = state {
+                    self.text = text;
+                    self.edit_pos = edit_pos;
+                    self.sel_pos = sel_pos;
                     mgr_action += self.prepared.set_text(self.text.clone());
-                    self.last_edit = LastEdit::None;
+                    Action::Edit
+                } else {
+                    Action::None
                 }
-                Action::Edit
             }
             _ => Action::None,
         };
@@ -515,20 +543,16 @@ impl<G> EditBox<G> {
             Action::None => EditAction::None,
             Action::Activate => EditAction::Activate,
             Action::Edit => EditAction::Edit,
-            Action::Insert(s, edit) => {
+            Action::Insert(s, kind) => {
                 let mut pos = pos;
                 if have_sel {
-                    self.old_state = Some((self.text.clone(), pos, self.sel_pos));
-                    self.last_edit = edit;
-
+                    mgr.close_undo_group(self.id());
+                }
+                self.push_undo(mgr, kind);
+                if have_sel {
                     self.text.replace_range(selection.clone(), s);
                     pos = selection.start;
                 } else {
-                    if self.last_edit != edit {
-                        self.old_state = Some((self.text.clone(), pos, self.sel_pos));
-                        self.last_edit = edit;
-                    }
-
                     self.text.insert_str(pos, s);
                 }
                 self.edit_pos = pos + s.len();
@@ -537,10 +561,10 @@ impl<G> EditBox<G> {
                 EditAction::Edit
             }
             Action::Delete(sel) => {
-                if self.last_edit != LastEdit::Delete {
-                    self.old_state = Some((self.text.clone(), pos, self.sel_pos));
-                    self.last_edit = LastEdit::Delete;
+                if have_sel {
+                    mgr.close_undo_group(self.id());
                 }
+                self.push_undo(mgr, EditKind::Delete);
 
                 self.text.replace_range(sel.clone(), "");
                 self.edit_pos = sel.start;
@@ -549,6 +573,7 @@ impl<G> EditBox<G> {
                 EditAction::Edit
             }
             Action::Move(pos) => {
+                mgr.close_undo_group(self.id());
                 self.edit_pos = pos;
                 if !shift {
                     self.sel_pos = self.edit_pos;
@@ -556,18 +581,44 @@ impl<G> EditBox<G> {
                 mgr_action += TkAction::Redraw;
                 EditAction::None
             }
+            Action::Select(sel_pos, edit_pos) => {
+                mgr.close_undo_group(self.id());
+                self.sel_pos = sel_pos;
+                self.edit_pos = edit_pos;
+                mgr_action += TkAction::Redraw;
+                EditAction::None
+            }
         };
 
         *mgr += mgr_action;
         result
     }
 
+    // Record the current state for undo (see Manager::push_undo)
+    fn push_undo(&self, mgr: &mut Manager, kind: EditKind) {
+        let state = || (self.text.clone(), self.edit_pos, self.sel_pos);
+        mgr.push_undo(self.id(), kind, state);
+    }
+
     fn set_edit_pos_from_coord(&mut self, mgr: &mut Manager, coord: Coord) {
+        mgr.close_undo_group(self.id());
         self.edit_pos = self.prepared.text_index_nearest(self.text_pos, coord);
         mgr.redraw(self.id());
     }
 }
 
+// Note: HasString requires G: EditGuard, hence cannot be used by
+// WidgetConfig::as_has_string; we implement CloneText for that purpose.
+impl<G> CloneText for EditBox<G> {
+    fn clone_string(&self) -> String {
+        self.text.clone()
+    }
+
+    fn clone_text(&self) -> kas::text::RichText {
+        self.text.clone().into()
+    }
+}
+
 impl<G: EditGuard> HasString for EditBox<G> {
     fn get_str(&self) -> &str {
         &self.text
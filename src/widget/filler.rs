@@ -5,12 +5,14 @@
 
 //! Filler widget
 
+use kas::access::Role;
 use kas::prelude::*;
 
 /// A space filler
 ///
 /// This widget has zero minimum size but can expand according to the given
 /// stretch policy.
+#[widget(config(role = Role::Filler))]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct Filler {
     #[widget_core]
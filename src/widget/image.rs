@@ -0,0 +1,164 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image widget
+
+use kas::access::Role;
+use kas::draw::{ImageData, ImageHandle, ImageId};
+use kas::prelude::*;
+
+/// An image
+///
+/// The image is displayed at its native pixel size when the stretch policy
+/// is [`StretchPolicy::Fixed`] (the default). Otherwise, the image may be
+/// enlarged to fill the space available, preserving its aspect ratio; its
+/// height is then requested according to the width available (see
+/// [`SizeRules::with_aspect_ratio`]).
+///
+/// The image is identified by an [`ImageId`]. Images constructed from data
+/// (or an [`ImageHandle`]) are owned by the widget and unregistered when the
+/// widget (and all clones of the handle) are dropped or the image is
+/// replaced; images constructed via [`Image::new`] are not.
+#[widget(config(role = Role::Image))]
+#[derive(Clone, Debug, Widget)]
+pub struct Image {
+    #[widget_core]
+    core: CoreData,
+    id: ImageId,
+    handle: Option<ImageHandle>,
+    size: Size,
+    stretch: StretchPolicy,
+}
+
+impl Layout for Image {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let margins = size_handle.outer_margins();
+        let (size, margins) = match axis.is_vertical() {
+            false => (self.size.0, margins.horiz),
+            true => (self.size.1, margins.vert),
+        };
+        let rules = SizeRules::new(size, size, margins, self.stretch);
+        match self.stretch {
+            StretchPolicy::Fixed => rules,
+            _ => rules.with_aspect_ratio(axis, (self.size.0, self.size.1)),
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        let rect = match self.stretch {
+            StretchPolicy::Fixed => align
+                .complete(Align::Centre, Align::Centre, self.size)
+                .apply(rect),
+            _ => rect,
+        };
+        self.core.rect = rect;
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::ManagerState, _: bool) {
+        draw_handle.image(self.id, self.image_rect());
+    }
+}
+
+impl Image {
+    /// Construct from a registered image
+    ///
+    /// If `id` is not registered, the widget has zero size. The image is not
+    /// unregistered by the widget.
+    pub fn new(id: ImageId) -> Self {
+        Image {
+            core: Default::default(),
+            id,
+            handle: None,
+            size: id.size().unwrap_or(Size::ZERO),
+            stretch: StretchPolicy::Fixed,
+        }
+    }
+
+    /// Construct from an image handle
+    pub fn from_handle(handle: ImageHandle) -> Self {
+        let mut image = Image::new(handle.id());
+        image.handle = Some(handle);
+        image
+    }
+
+    /// Construct from image data, registering the image
+    pub fn from_data(data: ImageData) -> Self {
+        Image::from_handle(ImageHandle::register(data))
+    }
+
+    /// Construct by loading an image (PNG or JPEG) from a file
+    #[cfg(feature = "image")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, image::ImageError> {
+        Ok(Image::from_data(ImageData::load(path)?))
+    }
+
+    /// Set the stretch policy (chaining)
+    #[inline]
+    pub fn with_stretch(mut self, stretch: StretchPolicy) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Get the image identifier
+    #[inline]
+    pub fn image_id(&self) -> ImageId {
+        self.id
+    }
+
+    /// Replace the image
+    ///
+    /// If the old image is owned by the widget, its handle is dropped (thus
+    /// the image is unregistered unless other clones of the handle exist).
+    /// The new image is not owned; see also [`Image::set_image_handle`].
+    pub fn set_image(&mut self, id: ImageId) -> TkAction {
+        if self.handle.as_ref().map(|h| h.id()) != Some(id) {
+            self.handle = None;
+        }
+        self.id = id;
+        let size = id.size().unwrap_or(Size::ZERO);
+        if size != self.size {
+            self.size = size;
+            TkAction::Resize
+        } else {
+            TkAction::Redraw
+        }
+    }
+
+    /// Replace the image with one owned via `handle`
+    ///
+    /// As with [`Image::set_image`], an old owned image is released.
+    pub fn set_image_handle(&mut self, handle: ImageHandle) -> TkAction {
+        let action = self.set_image(handle.id());
+        self.handle = Some(handle);
+        action
+    }
+
+    /// Set image data, registering the image
+    ///
+    /// As with [`Image::set_image`], an old owned image is released.
+    pub fn set_image_data(&mut self, data: ImageData) -> TkAction {
+        self.set_image_handle(ImageHandle::register(data))
+    }
+
+    /// The area to draw the image, preserving its aspect ratio
+    fn image_rect(&self) -> Rect {
+        let rect = self.core.rect;
+        if self.size.0 == 0 || self.size.1 == 0 {
+            return rect;
+        }
+        let scale_x = rect.size.0 as f32 / self.size.0 as f32;
+        let scale_y = rect.size.1 as f32 / self.size.1 as f32;
+        let scale = scale_x.min(scale_y);
+        let size = Size(
+            (self.size.0 as f32 * scale).round() as u32,
+            (self.size.1 as f32 * scale).round() as u32,
+        );
+        let offset = Coord(
+            (rect.size.0 - size.0.min(rect.size.0)) as i32 / 2,
+            (rect.size.1 - size.1.min(rect.size.1)) as i32 / 2,
+        );
+        Rect::new(rect.pos + offset, size)
+    }
+}
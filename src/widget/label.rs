@@ -5,19 +5,96 @@
 
 //! Text widgets
 
+use kas::access::Role;
 use kas::class::{CloneText, SetAccel, SetText};
-use kas::draw::TextClass;
+use kas::draw::{Icon, TextClass};
 use kas::event::VirtualKeyCodes;
 use kas::prelude::*;
 use std::ops::Deref;
 
+/// An optional icon preceding a text label
+///
+/// This is a layout utility for widgets with a label. The icon is scaled to
+/// the height of a line of text, preserving its aspect ratio, and separated
+/// from the label by the inner margin.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LabelIcon {
+    icon: Option<Icon>,
+    size: Size,
+    label_off: i32,
+}
+
+impl LabelIcon {
+    /// Set or remove the icon
+    pub fn set(&mut self, icon: Option<Icon>) {
+        self.icon = icon;
+    }
+
+    /// Combine the label's `text_rules` with those of the icon
+    pub fn size_rules(
+        &mut self,
+        size_handle: &mut dyn SizeHandle,
+        axis: AxisInfo,
+        class: TextClass,
+        text_rules: SizeRules,
+    ) -> SizeRules {
+        self.label_off = 0;
+        let size = match self.icon.as_ref().and_then(|icon| icon.size()) {
+            Some(size) if size.1 > 0.0 => size,
+            _ => return text_rules,
+        };
+
+        let h = size_handle.line_height(class);
+        let w = (h as f32 * size.0 / size.1).round() as u32;
+        self.size = Size(w, h);
+        let icon_rules = SizeRules::extract_fixed(axis.is_vertical(), self.size, Margins::ZERO);
+        if axis.is_vertical() {
+            text_rules.max(icon_rules)
+        } else {
+            let gap = size_handle.inner_margin().0;
+            self.label_off = (w + gap) as i32;
+            icon_rules
+                .appended(SizeRules::fixed(gap, (0, 0)))
+                .appended(text_rules)
+        }
+    }
+
+    /// Horizontal offset of the label from the start of the icon
+    ///
+    /// This is zero when there is no icon.
+    pub fn label_offset(&self) -> i32 {
+        self.label_off
+    }
+
+    /// Draw the icon at horizontal position `pos.0`, centred vertically
+    /// within `height` below `pos.1`
+    pub fn draw(&self, draw_handle: &mut dyn DrawHandle, pos: Coord, height: u32) {
+        if let Some(ref icon) = self.icon {
+            let y = (height as i32 - self.size.1 as i32) / 2;
+            draw_handle.icon(icon, Rect::new(pos + Coord(0, y), self.size));
+        }
+    }
+}
+
 /// A simple text label
+#[widget(config=noauto)]
 #[derive(Clone, Default, Debug, Widget)]
 pub struct Label {
     #[widget_core]
     core: CoreData,
     reserve: Option<&'static str>,
     label: PreparedText,
+    icon: LabelIcon,
+}
+
+impl WidgetConfig for Label {
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::Label
+    }
 }
 
 impl Layout for Label {
@@ -30,6 +107,9 @@ impl Layout for Label {
             &mut self.label
         };
         let rules = size_handle.text_bound(text, TextClass::Label, axis);
+        let rules = self
+            .icon
+            .size_rules(size_handle, axis, TextClass::Label, rules);
         if axis.is_horizontal() {
             self.core.rect.size.0 = rules.ideal_size();
         } else {
@@ -40,7 +120,9 @@ impl Layout for Label {
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
         self.core.rect = rect;
-        self.label.set_size(rect.size);
+        let off = self.icon.label_offset() as u32;
+        let size = Size(rect.size.0.saturating_sub(off), rect.size.1);
+        self.label.set_size(size);
         self.label.set_alignment(
             align.horiz.unwrap_or(Align::Default),
             align.vert.unwrap_or(Align::Centre),
@@ -48,7 +130,10 @@ impl Layout for Label {
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Label);
+        let mut pos = self.core.rect.pos;
+        self.icon.draw(draw_handle, pos, self.core.rect.size.1);
+        pos.0 += self.icon.label_offset();
+        draw_handle.text(pos, &self.label, TextClass::Label);
     }
 }
 
@@ -59,9 +144,24 @@ impl Label {
             core: Default::default(),
             reserve: None,
             label: PreparedText::new(label.into().deref().into(), true),
+            icon: Default::default(),
         }
     }
 
+    /// Show an icon before the label (chain style)
+    ///
+    /// The icon is scaled to the height of a line of text.
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        self.icon.set(Some(icon.into()));
+        self
+    }
+
+    /// Set or remove the icon
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> TkAction {
+        self.icon.set(icon);
+        TkAction::Resize
+    }
+
     /// Reserve sufficient room for the given text
     ///
     /// If this option is used, the label will be sized to fit this text, not
@@ -89,17 +189,32 @@ impl SetText for Label {
 ///
 /// Accelerator keys are not useful on plain labels, but this widget may be
 /// embedded within a parent (e.g. `CheckBox` uses this).
+#[widget(config=noauto)]
 #[derive(Clone, Default, Debug, Widget)]
 pub struct AccelLabel {
     #[widget_core]
     core: CoreData,
     keys: VirtualKeyCodes,
     label: PreparedText,
+    icon: LabelIcon,
+}
+
+impl WidgetConfig for AccelLabel {
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::Label
+    }
 }
 
 impl Layout for AccelLabel {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let rules = size_handle.text_bound(&mut self.label, TextClass::Label, axis);
+        let rules = self
+            .icon
+            .size_rules(size_handle, axis, TextClass::Label, rules);
         if axis.is_horizontal() {
             self.core.rect.size.0 = rules.ideal_size();
         } else {
@@ -110,7 +225,9 @@ impl Layout for AccelLabel {
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
         self.core.rect = rect;
-        self.label.set_size(rect.size);
+        let off = self.icon.label_offset() as u32;
+        let size = Size(rect.size.0.saturating_sub(off), rect.size.1);
+        self.label.set_size(size);
         self.label.set_alignment(
             align.horiz.unwrap_or(Align::Default),
             align.vert.unwrap_or(Align::Centre),
@@ -119,7 +236,10 @@ impl Layout for AccelLabel {
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, _mgr: &ManagerState, _: bool) {
         // TODO: mgr.show_accel_labels();
-        draw_handle.text(self.core.rect.pos, &self.label, TextClass::Label);
+        let mut pos = self.core.rect.pos;
+        self.icon.draw(draw_handle, pos, self.core.rect.size.1);
+        pos.0 += self.icon.label_offset();
+        draw_handle.text(pos, &self.label, TextClass::Label);
     }
 }
 
@@ -133,9 +253,24 @@ impl AccelLabel {
             core: Default::default(),
             keys,
             label: text,
+            icon: Default::default(),
         }
     }
 
+    /// Show an icon before the label (chain style)
+    ///
+    /// The icon is scaled to the height of a line of text.
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        self.icon.set(Some(icon.into()));
+        self
+    }
+
+    /// Set or remove the icon
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> TkAction {
+        self.icon.set(icon);
+        TkAction::Resize
+    }
+
     /// Get the accelerator keys
     pub fn keys(&self) -> &[event::VirtualKeyCode] {
         &self.keys
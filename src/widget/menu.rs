@@ -17,6 +17,9 @@ pub use menu_frame::MenuFrame;
 pub use menubar::MenuBar;
 pub use submenu::SubMenu;
 
+use kas::access::Role;
+use kas::class::{CloneText, HasBool, HasString};
+use kas::persist::Persist;
 use kas::prelude::*;
 
 /// Trait governing menus, sub-menus and menu-entries
@@ -102,6 +105,29 @@ impl<M: 'static> WidgetConfig for Box<dyn Menu<Msg = M>> {
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+
+    fn access_role(&self) -> Role {
+        self.as_ref().access_role()
+    }
+    fn context_menu(&self, mgr: &event::ManagerState, menu: &mut event::ContextMenu) {
+        self.as_ref().context_menu(mgr, menu);
+    }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        self.as_ref().as_has_bool()
+    }
+    fn as_has_string(&self) -> Option<&dyn HasString> {
+        self.as_ref().as_has_string()
+    }
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        self.as_ref().as_clone_text()
+    }
+    fn as_persist(&self) -> Option<&dyn Persist> {
+        self.as_ref().as_persist()
+    }
+    fn as_persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        self.as_mut().as_persist_mut()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Menu<Msg = M>> {
@@ -8,11 +8,13 @@
 use std::fmt::{self, Debug};
 
 use super::Menu;
+use kas::access::Role;
 use kas::class::{CloneText, HasBool, SetAccel};
-use kas::draw::TextClass;
-use kas::event::VirtualKeyCodes;
+use kas::draw::{Icon, TextClass};
+use kas::event::{CommandBinding, Commands, VirtualKeyCodes};
 use kas::layout::{RulesSetter, RulesSolver};
 use kas::prelude::*;
+use kas::widget::label::LabelIcon;
 use kas::widget::{AccelLabel, CheckBoxBare};
 
 /// A standard menu entry
@@ -25,16 +27,30 @@ pub struct MenuEntry<M: Clone + Debug + 'static> {
     keys: VirtualKeyCodes,
     label: PreparedText,
     label_off: Coord,
+    icon: LabelIcon,
     msg: M,
+    command: Option<CommandBinding<M>>,
 }
 
 impl<M: Clone + Debug + 'static> WidgetConfig for MenuEntry<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.id(), &self.keys);
+        let id = self.id();
+        if let Some(ref mut command) = self.command {
+            command.configure(mgr, id);
+        }
     }
 
     fn key_nav(&self) -> bool {
-        true
+        self.command_enabled()
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::MenuItem
     }
 }
 
@@ -44,12 +60,17 @@ impl<M: Clone + Debug + 'static> Layout for MenuEntry<M> {
         self.label_off = size.into();
         let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), size + size, Margins::ZERO);
         let text_rules = size_handle.text_bound(&mut self.label, TextClass::Label, axis);
-        text_rules.surrounded_by(frame_rules, true)
+        let content_rules = self
+            .icon
+            .size_rules(size_handle, axis, TextClass::Label, text_rules);
+        content_rules.surrounded_by(frame_rules, true)
     }
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
         self.core.rect = rect;
-        self.label.set_size(rect.size);
+        let off = self.icon.label_offset() as u32;
+        self.label
+            .set_size(Size(rect.size.0.saturating_sub(off), rect.size.1));
         self.label.set_alignment(
             align.horiz.unwrap_or(Align::Default),
             align.vert.unwrap_or(Align::Centre),
@@ -57,9 +78,13 @@ impl<M: Clone + Debug + 'static> Layout for MenuEntry<M> {
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || !self.command_enabled();
         draw_handle.menu_entry(self.core.rect, self.input_state(mgr, disabled));
         // TODO: mgr.show_accel_labels();
-        let pos = self.core.rect.pos + self.label_off;
+        let mut pos = self.core.rect.pos + self.label_off;
+        let height = (self.core.rect.size.1).saturating_sub(2 * self.label_off.1 as u32);
+        self.icon.draw(draw_handle, pos, height);
+        pos.0 += self.icon.label_offset();
         draw_handle.text(pos, &self.label, TextClass::Label);
     }
 }
@@ -79,14 +104,54 @@ impl<M: Clone + Debug + 'static> MenuEntry<M> {
             keys,
             label: text,
             label_off: Coord::ZERO,
+            icon: Default::default(),
             msg,
+            command: None,
         }
     }
 
+    /// Construct a menu item bound to the command `name` of `commands`
+    ///
+    /// The entry takes its label and message from the command, and is drawn
+    /// disabled (ignoring activation) while the command is disabled. It also
+    /// handles [`Event::Command`] for shortcuts bound to the command.
+    ///
+    /// Panics if `commands` has no command `name`.
+    pub fn new_cmd(commands: &Commands<M>, name: &str) -> Self {
+        let msg = commands
+            .msg(name)
+            .expect("MenuEntry::new_cmd: unknown command");
+        let command = CommandBinding::new(commands, name);
+        let mut entry = MenuEntry::new(command.label(), msg);
+        entry.command = Some(command);
+        entry
+    }
+
+    /// Show an icon before the label (chain style)
+    ///
+    /// The icon is scaled to the height of a line of text.
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        self.icon.set(Some(icon.into()));
+        self
+    }
+
+    /// Set or remove the icon
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> TkAction {
+        self.icon.set(icon);
+        TkAction::Resize
+    }
+
     /// Replace the message value
     pub fn set_msg(&mut self, msg: M) {
         self.msg = msg;
     }
+
+    fn command_enabled(&self) -> bool {
+        self.command
+            .as_ref()
+            .map(|c| c.is_enabled())
+            .unwrap_or(true)
+    }
 }
 
 impl<M: Clone + Debug + 'static> CloneText for MenuEntry<M> {
@@ -106,10 +171,19 @@ impl<M: Clone + Debug + 'static> SetAccel for MenuEntry<M> {
 impl<M: Clone + Debug + 'static> event::Handler for MenuEntry<M> {
     type Msg = M;
 
-    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<M> {
-        match event {
-            Event::Activate => self.msg.clone().into(),
-            event => Response::Unhandled(event),
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+        match (event, &mut self.command) {
+            (Event::Activate, None) => self.msg.clone().into(),
+            (Event::Activate, Some(command)) | (Event::Command(_), Some(command)) => {
+                command.msg().into()
+            }
+            (Event::HandleUpdate { .. }, Some(command)) => {
+                if command.update() {
+                    mgr.redraw(self.core.id);
+                }
+                Response::None
+            }
+            (event, _) => Response::Unhandled(event),
         }
     }
 }
@@ -165,6 +239,15 @@ impl<M: 'static> MenuToggle<M> {
         self.checkbox = self.checkbox.state(state);
         self
     }
+
+    /// Show an icon before the label (chain style)
+    ///
+    /// The icon is scaled to the height of a line of text.
+    #[inline]
+    pub fn with_icon<I: Into<Icon>>(mut self, icon: I) -> Self {
+        self.label = self.label.with_icon(icon);
+        self
+    }
 }
 
 impl MenuToggle<VoidMsg> {
@@ -201,6 +284,18 @@ impl<M: 'static> WidgetConfig for MenuToggle<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.checkbox.id(), self.label.keys());
     }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        Some(self)
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::CheckMenuItem
+    }
 }
 
 impl<M: 'static> Layout for MenuToggle<M> {
@@ -268,3 +363,9 @@ impl<M: 'static> HasBool for MenuToggle<M> {
         self.checkbox.set_bool(state)
     }
 }
+
+impl<M: 'static> CloneText for MenuToggle<M> {
+    fn clone_text(&self) -> kas::text::RichText {
+        self.label.clone_text()
+    }
+}
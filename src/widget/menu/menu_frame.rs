@@ -5,11 +5,13 @@
 
 //! Menus
 
+use kas::access::Role;
 use kas::class::*;
 use kas::prelude::*;
 
 /// A frame around content, plus background
 #[handler(msg = <W as Handler>::Msg)]
+#[widget(config(role = Role::PopupMenu))]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct MenuFrame<W: Widget> {
     #[widget_core]
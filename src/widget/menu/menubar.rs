@@ -8,6 +8,7 @@
 use std::time::Duration;
 
 use super::{Menu, SubMenu};
+use kas::access::Role;
 use kas::event::{ControlKey, GrabMode};
 use kas::prelude::*;
 use kas::widget::List;
@@ -19,6 +20,7 @@ const DELAY: Duration = Duration::from_millis(200);
 /// This widget houses a sequence of menu buttons, allowing input actions across
 /// menus.
 #[handler(noauto)]
+#[widget(config(role = Role::MenuBar))]
 #[derive(Clone, Debug, Widget)]
 pub struct MenuBar<D: Directional, W: Menu> {
     #[widget_core]
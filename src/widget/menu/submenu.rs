@@ -6,6 +6,7 @@
 //! Sub-menu
 
 use super::{Menu, MenuFrame};
+use kas::access::Role;
 use kas::class::{CloneText, SetAccel};
 use kas::draw::TextClass;
 use kas::event::{ConfigureManager, ControlKey, VirtualKeyCodes};
@@ -95,7 +96,9 @@ impl<D: Directional, W: Menu> SubMenu<D, W> {
 impl<D: Directional, W: Menu> WidgetConfig for SubMenu<D, W> {
     fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
         cmgr.mgr().push_accel_layer(true);
-        self.list.configure_recurse(cmgr.child());
+        let key = self.list.key();
+        self.list.configure_recurse(cmgr.child(0, key));
+        self.core_data_mut().stable_id = cmgr.stable_id();
         self.core_data_mut().id = cmgr.next_id(self.id());
         let mgr = cmgr.mgr();
         mgr.pop_accel_layer(self.id());
@@ -105,6 +108,14 @@ impl<D: Directional, W: Menu> WidgetConfig for SubMenu<D, W> {
     fn key_nav(&self) -> bool {
         true
     }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::Menu
+    }
 }
 
 impl<D: Directional, W: Menu> kas::Layout for SubMenu<D, W> {
@@ -19,10 +19,20 @@
 //! -   [`ScrollRegion`]: may be larger on the inside than the outside
 //! -   [`Stack`]: a stack of widgets in the same rect (TODO: `TabbedStack`)
 //! -   [`List`]: a dynamic row / column of children
+//! -   [`FlowList`]: a dynamic list of children, wrapping onto new lines
 //! -   [`Splitter`]: similar to [`List`] but with resizing handles
 //! -   [`Window`] is usually the root widget and has special handling for
 //!     pop-ups and callbacks
 //!
+//! ## View widgets
+//!
+//! View widgets display data from an [`Accessor`], constructing widgets only
+//! for visible items.
+//!
+//! -   [`ListView`]: a scrollable row / column of items
+//! -   [`Table`]: a scrollable table with resizable, sortable columns
+//! -   [`TreeView`]: a hierarchy of expandable nodes, loaded on demand
+//!
 //! ## Menus
 //!
 //! -   [`ComboBox`]: a simple pop-up selector
@@ -36,6 +46,7 @@
 //! -   [`CheckBox`]: a checkable box
 //! -   [`RadioBox`]: a checkable box bound to a group
 //! -   [`EditBox`]: a text-editing box
+//! -   [`TextEdit`]: a multi-line text editor
 //! -   [`ScrollBar`]: a scrollbar
 //! -   [`Slider`]: a slider
 //!
@@ -44,6 +55,7 @@
 //! -   [`Filler`]: an empty widget, sometimes used to fill space
 //! -   [`Separator`]: a visible bar to separate things
 //! -   [`Label`]: a simple text label
+//! -   [`Image`]: a raster image
 //!
 //! ## Components
 //!
@@ -59,7 +71,9 @@ mod dialog;
 mod drag;
 mod editbox;
 mod filler;
+mod flow_list;
 mod frame;
+mod image;
 mod label;
 mod list;
 mod menu;
@@ -70,6 +84,8 @@ mod separator;
 mod slider;
 mod splitter;
 mod stack;
+mod text_edit;
+mod view;
 mod window;
 
 pub use button::TextButton;
@@ -79,7 +95,9 @@ pub use dialog::MessageBox;
 pub use drag::DragHandle;
 pub use editbox::{EditBox, EditBoxVoid, EditGuard};
 pub use filler::Filler;
+pub use flow_list::{BoxFlowList, FlowList};
 pub use frame::Frame;
+pub use image::Image;
 pub use label::{AccelLabel, Label};
 pub use list::*;
 pub use menu::*;
@@ -90,4 +108,6 @@ pub use separator::Separator;
 pub use slider::{Slider, SliderType};
 pub use splitter::*;
 pub use stack::{BoxStack, RefStack, Stack};
+pub use text_edit::TextEdit;
+pub use view::*;
 pub use window::Window;
@@ -10,7 +10,8 @@ use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 use super::AccelLabel;
-use kas::class::HasBool;
+use kas::access::Role;
+use kas::class::{CloneText, HasBool};
 use kas::event::UpdateHandle;
 use kas::prelude::*;
 
@@ -44,6 +45,14 @@ impl<M: 'static> WidgetConfig for RadioBoxBare<M> {
     fn key_nav(&self) -> bool {
         true
     }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::RadioButton
+    }
 }
 
 impl<M: 'static> event::Handler for RadioBoxBare<M> {
@@ -277,6 +286,18 @@ impl<M: 'static> WidgetConfig for RadioBox<M> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.add_accel_keys(self.radiobox.id(), self.label.keys());
     }
+
+    fn as_has_bool(&self) -> Option<&dyn HasBool> {
+        Some(self)
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::RadioButton
+    }
 }
 
 impl<M: 'static> HasBool for RadioBox<M> {
@@ -290,3 +311,9 @@ impl<M: 'static> HasBool for RadioBox<M> {
         self.radiobox.set_bool(state)
     }
 }
+
+impl<M: 'static> CloneText for RadioBox<M> {
+    fn clone_text(&self) -> kas::text::RichText {
+        self.label.clone_text()
+    }
+}
@@ -8,9 +8,11 @@
 use std::fmt::Debug;
 
 use super::ScrollBar;
+use kas::access::Role;
 use kas::draw::{ClipRegion, TextClass};
 use kas::event::ControlKey;
 use kas::event::ScrollDelta::{LineDelta, PixelDelta};
+use kas::persist::{Persist, StateValue};
 use kas::prelude::*;
 
 /// A scrollable region
@@ -21,7 +23,8 @@ use kas::prelude::*;
 ///
 /// Scroll regions translate their contents by an `offset`, which has a
 /// minimum value of [`Coord::ZERO`] and a maximum value of
-/// [`ScrollRegion::max_offset`].
+/// [`ScrollRegion::max_offset`]. The offset may be persisted; see
+/// [`ScrollRegion::with_state_key`].
 #[widget(config=noauto)]
 #[handler(send=noauto, msg = <W as event::Handler>::Msg)]
 #[derive(Clone, Debug, Default, Widget)]
@@ -36,6 +39,7 @@ pub struct ScrollRegion<W: Widget> {
     bar_width: u32,
     auto_bars: bool,
     show_bars: (bool, bool),
+    state_key: Option<String>,
     #[widget]
     horiz_bar: ScrollBar<kas::Right>,
     #[widget]
@@ -58,6 +62,7 @@ impl<W: Widget> ScrollRegion<W> {
             bar_width: 0,
             auto_bars: false,
             show_bars: (false, false),
+            state_key: None,
             horiz_bar: ScrollBar::new(),
             vert_bar: ScrollBar::new(),
             inner,
@@ -84,6 +89,14 @@ impl<W: Widget> ScrollRegion<W> {
         self
     }
 
+    /// Set the key under which the scroll offset is persisted (chaining)
+    ///
+    /// See [`kas::persist`].
+    pub fn with_state_key<S: ToString>(mut self, key: S) -> Self {
+        self.state_key = Some(key.to_string());
+        self
+    }
+
     /// Set which scroll bars are visible
     #[inline]
     pub fn show_bars(&mut self, horiz: bool, vert: bool) {
@@ -134,6 +147,39 @@ impl<W: Widget> WidgetConfig for ScrollRegion<W> {
     fn configure(&mut self, mgr: &mut Manager) {
         mgr.register_nav_fallback(self.id());
     }
+
+    fn access_role(&self) -> Role {
+        Role::ScrollRegion
+    }
+
+    fn as_persist(&self) -> Option<&dyn Persist> {
+        Some(self)
+    }
+    fn as_persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        Some(self)
+    }
+}
+
+impl<W: Widget> Persist for ScrollRegion<W> {
+    fn state_key(&self) -> Option<&str> {
+        self.state_key.as_deref()
+    }
+
+    fn save_state(&self) -> Option<StateValue> {
+        let v = vec![self.offset.0 as i64, self.offset.1 as i64];
+        Some(StateValue::Ints(v))
+    }
+
+    fn restore_state(&mut self, value: &StateValue) -> TkAction {
+        match value {
+            StateValue::Ints(v) if v.len() == 2 => {
+                // The offset is clamped by set_rect
+                self.offset = Coord(v[0] as i32, v[1] as i32);
+                TkAction::ResetSize
+            }
+            _ => TkAction::None,
+        }
+    }
 }
 
 impl<W: Widget> Layout for ScrollRegion<W> {
@@ -189,7 +235,8 @@ impl<W: Widget> Layout for ScrollRegion<W> {
                 .set_rect(Rect { pos, size }, AlignHints::NONE);
             let _ = self
                 .horiz_bar
-                .set_limits(self.max_offset.0 as u32, rect.size.0);
+                .set_limits(self.max_offset.0 as u32, rect.size.0)
+                + self.horiz_bar.set_value(self.offset.0 as u32);
         }
         if self.show_bars.1 {
             let pos = Coord(pos.0 + self.inner_size.0 as i32, pos.1);
@@ -197,7 +244,8 @@ impl<W: Widget> Layout for ScrollRegion<W> {
             self.vert_bar.set_rect(Rect { pos, size }, AlignHints::NONE);
             let _ = self
                 .vert_bar
-                .set_limits(self.max_offset.1 as u32, rect.size.1);
+                .set_limits(self.max_offset.1 as u32, rect.size.1)
+                + self.vert_bar.set_value(self.offset.1 as u32);
         }
     }
 
@@ -8,13 +8,19 @@
 use std::fmt::Debug;
 
 use super::DragHandle;
+use kas::access::Role;
+use kas::draw::{Icon, StandardIcon};
 use kas::prelude::*;
 
 /// A scroll bar
 ///
 /// Scroll bars allow user-input of a value between 0 and a defined maximum,
 /// and allow the size of the handle to be specified.
+///
+/// Optionally, arrow buttons may be shown at each end (see
+/// [`ScrollBar::with_arrows`]).
 #[handler(send=noauto, msg = u32)]
+#[widget(config(role = Role::ScrollBar))]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct ScrollBar<D: Directional> {
     #[widget_core]
@@ -26,6 +32,8 @@ pub struct ScrollBar<D: Directional> {
     handle_value: u32, // contract: > 0
     max_value: u32,
     value: u32,
+    arrows: Option<(Icon, Icon)>,
+    track: Rect,
     #[widget]
     handle: DragHandle,
 }
@@ -53,6 +61,8 @@ impl<D: Directional> ScrollBar<D> {
             handle_value: 1,
             max_value: 0,
             value: 0,
+            arrows: None,
+            track: Rect::default(),
             handle: DragHandle::new(),
         }
     }
@@ -73,6 +83,22 @@ impl<D: Directional> ScrollBar<D> {
         self
     }
 
+    /// Show arrow buttons at each end
+    ///
+    /// Pressing an arrow steps the value by a tenth of the handle value (see
+    /// [`ScrollBar::set_limits`]), or at least 1.
+    pub fn with_arrows(mut self, arrows: bool) -> Self {
+        self.arrows = match (arrows, self.direction.is_vertical()) {
+            (false, _) => None,
+            (true, false) => Some((
+                StandardIcon::ArrowLeft.icon(),
+                StandardIcon::ArrowRight.icon(),
+            )),
+            (true, true) => Some((StandardIcon::ArrowUp.icon(), StandardIcon::ArrowDown.icon())),
+        };
+        self
+    }
+
     /// Set the page limits
     ///
     /// The `max_value` parameter specifies the maximum possible value.
@@ -118,17 +144,48 @@ impl<D: Directional> ScrollBar<D> {
     #[inline]
     fn len(&self) -> u32 {
         match self.direction.is_vertical() {
-            false => self.core.rect.size.0,
-            true => self.core.rect.size.1,
+            false => self.track.size.0,
+            true => self.track.size.1,
         }
     }
 
+    // rects of the arrow buttons, at the start and end of the bar
+    fn arrow_rects(&self) -> Option<(Rect, Rect)> {
+        self.arrows.as_ref()?;
+        let rect = self.core.rect;
+        Some(match self.direction.is_vertical() {
+            false => {
+                let size = Size(rect.size.1, rect.size.1);
+                let end = Coord(rect.size.0 as i32 - size.0 as i32, 0);
+                (Rect::new(rect.pos, size), Rect::new(rect.pos + end, size))
+            }
+            true => {
+                let size = Size(rect.size.0, rect.size.0);
+                let end = Coord(0, rect.size.1 as i32 - size.1 as i32);
+                (Rect::new(rect.pos, size), Rect::new(rect.pos + end, size))
+            }
+        })
+    }
+
+    // step the value by an arrow press; returns true if changed
+    fn step(&mut self, mgr: &mut Manager, towards_start: bool) -> bool {
+        let step = (self.handle_value / 10).max(1);
+        let value = if towards_start != self.direction.is_reversed() {
+            self.value.saturating_sub(step)
+        } else {
+            self.value.saturating_add(step)
+        };
+        let old = self.value;
+        *mgr += self.set_value(value);
+        self.value != old
+    }
+
     fn update_handle(&mut self) -> TkAction {
         let len = self.len();
         let total = self.max_value as u64 + self.handle_value as u64;
         let handle_len = self.handle_value as u64 * len as u64 / total;
         self.handle_len = (handle_len as u32).max(self.min_handle_len).min(len);
-        let mut size = self.core.rect.size;
+        let mut size = self.track.size;
         if self.direction.is_horizontal() {
             size.0 = self.handle_len;
         } else {
@@ -189,6 +246,10 @@ impl<D: Directional> Layout for ScrollBar<D> {
         self.min_handle_len = size.0;
         let margins = (0, 0);
         if self.direction.is_vertical() == axis.is_vertical() {
+            let min_len = match self.arrows {
+                Some(_) => min_len + 2 * size.1,
+                None => min_len,
+            };
             SizeRules::new(min_len, min_len, margins, StretchPolicy::HighUtility)
         } else {
             SizeRules::fixed(size.1, margins)
@@ -197,7 +258,17 @@ impl<D: Directional> Layout for ScrollBar<D> {
 
     fn set_rect(&mut self, rect: Rect, align: AlignHints) {
         self.core.rect = rect;
-        self.handle.set_rect(rect, align);
+        self.track = rect;
+        if let Some((start, end)) = self.arrow_rects() {
+            if self.direction.is_vertical() {
+                self.track.pos.1 += start.size.1 as i32;
+                self.track.size.1 = rect.size.1.saturating_sub(start.size.1 + end.size.1);
+            } else {
+                self.track.pos.0 += start.size.0 as i32;
+                self.track.size.0 = rect.size.0.saturating_sub(start.size.0 + end.size.0);
+            }
+        }
+        self.handle.set_rect(self.track, align);
         let _ = self.update_handle();
     }
 
@@ -211,7 +282,11 @@ impl<D: Directional> Layout for ScrollBar<D> {
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
         let dir = self.direction.as_direction();
         let state = self.handle.input_state(mgr, disabled);
-        draw_handle.scrollbar(self.core.rect, self.handle.rect(), dir, state);
+        draw_handle.scrollbar(self.track, self.handle.rect(), dir, state);
+        if let (Some((a, b)), Some((ra, rb))) = (self.arrows.as_ref(), self.arrow_rects()) {
+            draw_handle.icon(a, ra);
+            draw_handle.icon(b, rb);
+        }
     }
 }
 
@@ -229,6 +304,14 @@ impl<D: Directional> event::SendEvent for ScrollBar<D> {
         } else {
             match event {
                 Event::PressStart { source, coord, .. } => {
+                    if let Some((start, end)) = self.arrow_rects() {
+                        if start.contains(coord) || end.contains(coord) {
+                            return match self.step(mgr, start.contains(coord)) {
+                                true => Response::Msg(self.value),
+                                false => Response::None,
+                            };
+                        }
+                    }
                     self.handle.handle_press_on_track(mgr, source, coord)
                 }
                 ev @ _ => return Response::Unhandled(ev),
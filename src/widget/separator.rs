@@ -8,6 +8,7 @@
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
+use kas::access::Role;
 use kas::prelude::*;
 use kas::widget::Menu;
 
@@ -16,6 +17,7 @@ use kas::widget::Menu;
 /// This widget draws a bar when in a list. It may expand larger than expected
 /// if no other widget will fill spare space.
 #[handler(msg=M)]
+#[widget(config(role = Role::Separator))]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct Separator<M: Debug + 'static> {
     #[widget_core]
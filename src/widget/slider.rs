@@ -10,6 +10,7 @@ use std::ops::{Add, Sub};
 use std::time::Duration;
 
 use super::DragHandle;
+use kas::access::Role;
 use kas::event::ControlKey;
 use kas::prelude::*;
 
@@ -74,7 +75,7 @@ impl SliderType for Duration {
 ///
 /// Sliders allow user input of a value from a fixed range.
 #[handler(send=noauto, msg = T)]
-#[widget(config(key_nav = true))]
+#[widget(config(key_nav = true, role = Role::Slider))]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct Slider<T: SliderType, D: Directional> {
     #[widget_core]
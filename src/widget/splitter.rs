@@ -9,7 +9,9 @@ use log::warn;
 use std::ops::{Index, IndexMut};
 
 use super::DragHandle;
-use kas::layout::{RulesSetter, RulesSolver};
+use kas::access::Role;
+use kas::layout::{DynRowStorage, RowSetter, RulesSetter, RulesSolver};
+use kas::persist::{Persist, StateValue};
 use kas::prelude::*;
 
 /// A generic row widget
@@ -67,9 +69,12 @@ pub type RefSplitter<'a, D, M> = Splitter<D, &'a mut dyn Widget<Msg = M>>;
 /// A resizable row/column widget
 ///
 /// Similar to [`kas::widget::List`] but with draggable handles between items.
+///
+/// Sizes of children (thus handle positions) may be persisted; see
+/// [`Splitter::with_state_key`].
 // TODO: better doc
 #[handler(send=noauto, msg=<W as event::Handler>::Msg)]
-#[widget(children=noauto)]
+#[widget(config=noauto, children=noauto)]
 #[derive(Clone, Default, Debug, Widget)]
 pub struct Splitter<D: Directional, W: Widget> {
     #[widget_core]
@@ -79,6 +84,12 @@ pub struct Splitter<D: Directional, W: Widget> {
     handle_size: Size,
     data: layout::DynRowStorage,
     direction: D,
+    state_key: Option<String>,
+    // Sizes restored from saved state, applied by set_rect. Since layout may
+    // be repeated (e.g. on TkAction::ResetSize), these are kept until the rect
+    // first used (restore_rect) changes or a handle is dragged.
+    restore: Option<Vec<u32>>,
+    restore_rect: Option<Rect>,
 }
 
 impl<D: Directional, W: Widget> WidgetChildren for Splitter<D, W> {
@@ -104,6 +115,45 @@ impl<D: Directional, W: Widget> WidgetChildren for Splitter<D, W> {
     }
 }
 
+impl<D: Directional, W: Widget> WidgetConfig for Splitter<D, W> {
+    fn access_role(&self) -> Role {
+        Role::Splitter
+    }
+
+    fn as_persist(&self) -> Option<&dyn Persist> {
+        Some(self)
+    }
+    fn as_persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        Some(self)
+    }
+}
+
+impl<D: Directional, W: Widget> Persist for Splitter<D, W> {
+    fn state_key(&self) -> Option<&str> {
+        self.state_key.as_deref()
+    }
+
+    fn save_state(&self) -> Option<StateValue> {
+        let is_horiz = self.direction.is_horizontal();
+        let sizes = self.widgets.iter().map(|w| {
+            let size = w.rect().size;
+            (if is_horiz { size.0 } else { size.1 }) as i64
+        });
+        Some(StateValue::Ints(sizes.collect()))
+    }
+
+    fn restore_state(&mut self, value: &StateValue) -> TkAction {
+        match value {
+            StateValue::Ints(v) if v.len() == self.widgets.len() && v.iter().all(|x| *x >= 0) => {
+                self.restore = Some(v.iter().map(|x| *x as u32).collect());
+                self.restore_rect = None;
+                TkAction::ResetSize
+            }
+            _ => TkAction::None,
+        }
+    }
+}
+
 impl<D: Directional, W: Widget> Layout for Splitter<D, W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         if self.widgets.len() == 0 {
@@ -176,6 +226,14 @@ impl<D: Directional, W: Widget> Layout for Splitter<D, W> {
 
             n += 1;
         }
+
+        if let Some(sizes) = self.restore.take() {
+            if self.restore_rect.map(|r| r == rect).unwrap_or(true) {
+                self.apply_sizes(&sizes);
+                self.restore = Some(sizes);
+                self.restore_rect = Some(rect);
+            }
+        }
     }
 
     fn find_id(&self, coord: Coord) -> Option<WidgetId> {
@@ -273,12 +331,24 @@ impl<D: Directional, W: Widget> Splitter<D, W> {
             handle_size: Size::ZERO,
             data: Default::default(),
             direction,
+            state_key: None,
+            restore: None,
+            restore_rect: None,
         }
     }
 
+    /// Set the key under which sizes of children are persisted (chaining)
+    ///
+    /// See [`kas::persist`].
+    pub fn with_state_key<S: ToString>(mut self, key: S) -> Self {
+        self.state_key = Some(key.to_string());
+        self
+    }
+
     fn adjust_size(&mut self, n: usize) {
         assert!(n < self.handles.len());
         assert_eq!(self.widgets.len(), self.handles.len() + 1);
+        self.restore = None;
         let index = 2 * n + 1;
 
         let is_horiz = self.direction.is_horizontal();
@@ -294,7 +364,65 @@ impl<D: Directional, W: Widget> Splitter<D, W> {
         setter.solve_range(&mut self.data, 0..index, width1);
         setter.solve_range(&mut self.data, (index + 1)..dim.1, width2);
         setter.update_offsets(&mut self.data);
+        self.set_child_rects(setter);
+    }
+
+    // Apply sizes of children (along the axis), with the last child taking
+    // the remaining space. Sizes are ignored if they do not fit.
+    fn apply_sizes(&mut self, sizes: &[u32]) {
+        if sizes.len() != self.widgets.len() || self.widgets.is_empty() {
+            return;
+        }
+
+        let is_horiz = self.direction.is_horizontal();
+        let extract = |r: Rect| match is_horiz {
+            true => (r.pos.0, r.size.0),
+            false => (r.pos.1, r.size.1),
+        };
+        let (pos, size) = extract(self.core.rect);
+        let handle_size = if is_horiz {
+            self.handle_size.0
+        } else {
+            self.handle_size.1
+        };
+
+        let dim = (self.direction, WidgetChildren::len(self));
+        let mut setter =
+            RowSetter::<D, Vec<u32>, _>::new_unsolved(self.core.rect, dim, &mut self.data);
+        for (n, width) in sizes.iter().enumerate() {
+            let index = n << 1;
+            setter.solve_range(&mut self.data, index..(index + 1), *width);
+            if index + 1 < dim.1 {
+                setter.solve_range(&mut self.data, (index + 1)..(index + 2), handle_size);
+            }
+        }
+        setter.update_offsets(&mut self.data);
+
+        // Adjust the last child to fill the remaining space
+        type Setter<D> = RowSetter<D, Vec<u32>, DynRowStorage>;
+        let end = |setter: &mut Setter<D>, data: &mut DynRowStorage| {
+            (0..dim.1)
+                .map(|i| extract(setter.child_rect(data, i)))
+                .map(|(p, s)| p + s as i32)
+                .max()
+                .unwrap()
+        };
+        let last = dim.1 - 1;
+        let excess = end(&mut setter, &mut self.data) - (pos + size as i32);
+        let last_size = extract(setter.child_rect(&mut self.data, last)).1 as i32 - excess;
+        if last_size < 0 {
+            return;
+        }
+        setter.solve_range(&mut self.data, last..dim.1, last_size as u32);
+        setter.update_offsets(&mut self.data);
+        if end(&mut setter, &mut self.data) != pos + size as i32 {
+            return;
+        }
+
+        self.set_child_rects(setter);
+    }
 
+    fn set_child_rects(&mut self, mut setter: RowSetter<D, Vec<u32>, DynRowStorage>) {
         let mut n = 0;
         loop {
             assert!(n < self.widgets.len());
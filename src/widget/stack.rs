@@ -8,6 +8,7 @@
 use std::fmt::Debug;
 use std::ops::{Index, IndexMut};
 
+use kas::persist::{Persist, StateValue};
 use kas::prelude::*;
 
 /// A stack of boxed widgets
@@ -30,14 +31,17 @@ pub type RefStack<'a, M> = Stack<&'a mut dyn Widget<Msg = M>>;
 ///
 /// Configuring and resizing elements is O(n) in the number of children.
 /// Drawing and event handling is O(1).
+///
+/// The active page may be persisted; see [`Stack::with_state_key`].
 #[handler(send=noauto, msg=<W as event::Handler>::Msg)]
-#[widget(children=noauto)]
+#[widget(config=noauto, children=noauto)]
 #[derive(Clone, Default, Debug, Widget)]
 pub struct Stack<W: Widget> {
     #[widget_core]
     core: CoreData,
     widgets: Vec<W>,
     active: usize,
+    state_key: Option<String>,
 }
 
 impl<W: Widget> WidgetChildren for Stack<W> {
@@ -55,6 +59,34 @@ impl<W: Widget> WidgetChildren for Stack<W> {
     }
 }
 
+impl<W: Widget> WidgetConfig for Stack<W> {
+    fn as_persist(&self) -> Option<&dyn Persist> {
+        Some(self)
+    }
+    fn as_persist_mut(&mut self) -> Option<&mut dyn Persist> {
+        Some(self)
+    }
+}
+
+impl<W: Widget> Persist for Stack<W> {
+    fn state_key(&self) -> Option<&str> {
+        self.state_key.as_deref()
+    }
+
+    fn save_state(&self) -> Option<StateValue> {
+        Some(StateValue::Int(self.active as i64))
+    }
+
+    fn restore_state(&mut self, value: &StateValue) -> TkAction {
+        match value {
+            StateValue::Int(x) if *x >= 0 && (*x as usize) < self.widgets.len() => {
+                self.set_active(*x as usize)
+            }
+            _ => TkAction::None,
+        }
+    }
+}
+
 impl<W: Widget> Layout for Stack<W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let mut rules = SizeRules::EMPTY;
@@ -116,9 +148,18 @@ impl<W: Widget> Stack<W> {
             core: Default::default(),
             widgets,
             active,
+            state_key: None,
         }
     }
 
+    /// Set the key under which the active index is persisted (chaining)
+    ///
+    /// See [`kas::persist`].
+    pub fn with_state_key<S: ToString>(mut self, key: S) -> Self {
+        self.state_key = Some(key.to_string());
+        self
+    }
+
     /// Get the index of the active widget
     pub fn active_index(&self) -> usize {
         self.active
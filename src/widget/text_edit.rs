@@ -0,0 +1,1029 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Multi-line text editor
+
+use ropey::Rope;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+use std::rc::Rc;
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+
+use kas::access::Role;
+use kas::class::CloneText;
+use kas::draw::{ClipRegion, DrawHandleExt, TextClass};
+use kas::event::{ContextMenu, ControlKey, EditKind, GrabMode, ModifiersState, ScrollDelta};
+use kas::geom::Vec2;
+use kas::prelude::*;
+
+/// A multi-line text editor
+///
+/// Text is stored in a [rope](https://docs.rs/ropey/), thus edits have
+/// `O(log n)` cost. Each line is laid out separately, thus an edit only
+/// re-lays-out the lines it touches.
+///
+/// Lines are not wrapped. The widget shows a fixed number of rows (see
+/// [`TextEdit::with_rows`]) and scrolls its content to keep the edit cursor
+/// visible. Cursor movement also yields [`Response::Focus`], thus a parent
+/// [`ScrollRegion`] will scroll to follow the cursor.
+///
+/// Navigation: Up/Down and PageUp/PageDown move by line, retaining the
+/// horizontal cursor position where possible; Home/End move to the start or
+/// end of the line, or, with Ctrl, of the text. Holding Shift extends the
+/// selection, as does dragging with the mouse.
+///
+/// Edits are recorded in the window's undo history (see [`Manager::undo`]).
+///
+/// [`ScrollRegion`]: super::ScrollRegion
+#[widget(config=noauto)]
+#[handler(handle=noauto)]
+#[derive(Clone, Widget)]
+pub struct TextEdit<M: 'static> {
+    #[widget_core]
+    core: CoreData,
+    frame_offset: Coord,
+    frame_size: Size,
+    rows: u32,
+    line_height: u32,
+    view: Rect,
+    offset: Coord,
+    max_offset: Coord,
+    editable: bool,
+    model: EditModel,
+    // One entry per line of the model
+    lines: Vec<PreparedText>,
+    on_edit: Option<Rc<dyn Fn(&str) -> Option<M>>>,
+}
+
+impl<M: 'static> Debug for TextEdit<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TextEdit {{ core: {:?}, editable: {:?}, len: {}, ... }}",
+            self.core,
+            self.editable,
+            self.model.len()
+        )
+    }
+}
+
+impl<M: 'static> WidgetConfig for TextEdit<M> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn cursor_icon(&self) -> event::CursorIcon {
+        event::CursorIcon::Text
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        Some(self)
+    }
+
+    fn access_role(&self) -> Role {
+        Role::TextArea
+    }
+
+    fn context_menu(&self, mgr: &ManagerState, menu: &mut ContextMenu) {
+        let editable = self.editable;
+        let have_sel = self.model.has_selection();
+        menu.push_control("Cu&t", ControlKey::Cut, editable && have_sel);
+        menu.push_control("&Copy", ControlKey::Copy, have_sel);
+        menu.push_control("&Paste", ControlKey::Paste, editable);
+        menu.push_separator();
+        menu.push_control(
+            "&Undo",
+            ControlKey::Undo,
+            editable && mgr.can_undo(self.id()),
+        );
+        menu.push_control(
+            "&Redo",
+            ControlKey::Redo,
+            editable && mgr.can_redo(self.id()),
+        );
+        menu.push_separator();
+        menu.push_control("Select &All", ControlKey::SelectAll, !self.is_empty());
+    }
+}
+
+impl<M: 'static> Layout for TextEdit<M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let frame_sides = size_handle.edit_surround();
+        let inner = size_handle.inner_margin();
+        let frame_offset = frame_sides.0 + inner;
+        let frame_size = frame_offset + frame_sides.1 + inner;
+
+        let margins = size_handle.outer_margins();
+        let frame_rules = SizeRules::extract_fixed(axis.is_vertical(), frame_size, margins);
+
+        self.line_height = size_handle.line_height(TextClass::EditMulti);
+        let content_rules = if axis.is_horizontal() {
+            // Our width does not depend on the text
+            size_handle.text_bound(&mut self.lines[0], TextClass::EditMulti, axis)
+        } else {
+            // Nor our height
+            for line in &mut self.lines {
+                size_handle.prepare(line, TextClass::EditMulti);
+            }
+            let height = self.line_height * self.rows;
+            SizeRules::new(height, height, (0, 0), StretchPolicy::HighUtility)
+        };
+        let m = content_rules.margins();
+
+        let rules = content_rules.surrounded_by(frame_rules, true);
+        if axis.is_horizontal() {
+            self.frame_offset.0 = frame_offset.0 as i32 + m.0 as i32;
+            self.frame_size.0 = frame_size.0 + (m.0 + m.1) as u32;
+        } else {
+            self.frame_offset.1 = frame_offset.1 as i32 + m.0 as i32;
+            self.frame_size.1 = frame_size.1 + (m.0 + m.1) as u32;
+        }
+        rules
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        self.view = Rect {
+            pos: rect.pos + self.frame_offset,
+            size: rect.size - self.frame_size,
+        };
+        self.update_bounds();
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let class = TextClass::EditMulti;
+        let input_state = self.input_state(mgr, disabled);
+        draw_handle.edit_box(self.core.rect, input_state);
+        let selection = self.model.selection();
+        let edit_line = self.model.edit_line();
+        draw_handle.clip_region(self.view, self.offset, ClipRegion::Scroll, &mut |handle| {
+            for index in self.visible_lines() {
+                let (pos, line) = (self.line_pos(self.view.pos, index), &self.lines[index]);
+                let range = self.model.line_range(index);
+                let start = selection.start.max(range.start);
+                let end = selection.end.min(range.end);
+                if start < end {
+                    let selected = (start - range.start)..(end - range.start);
+                    handle.text_selected(pos, line, selected, class);
+                } else {
+                    handle.text(pos, line, class);
+                }
+                if input_state.char_focus && index == edit_line {
+                    let edit_pos = self.model.edit_pos - range.start;
+                    handle.edit_marker(pos, line, class, edit_pos);
+                }
+            }
+        });
+    }
+}
+
+impl TextEdit<VoidMsg> {
+    /// Construct a `TextEdit` with the given inital `text`
+    pub fn new<S: ToString>(text: S) -> Self {
+        let model = EditModel::new(&text.to_string());
+        TextEdit {
+            core: Default::default(),
+            frame_offset: Default::default(),
+            frame_size: Default::default(),
+            rows: 5,
+            line_height: 0,
+            view: Default::default(),
+            offset: Coord::ZERO,
+            max_offset: Coord::ZERO,
+            editable: true,
+            lines: new_lines(&model, 0..model.text.len_lines()),
+            model,
+            on_edit: None,
+        }
+    }
+
+    /// Set a function to be called on edit
+    ///
+    /// The closure `f` is called with the new contents when the text is edited
+    /// by the user. Its result, if not `None`, is the event handler's response.
+    pub fn on_edit<M, F>(self, f: F) -> TextEdit<M>
+    where
+        F: Fn(&str) -> Option<M> + 'static,
+    {
+        TextEdit {
+            core: self.core,
+            frame_offset: self.frame_offset,
+            frame_size: self.frame_size,
+            rows: self.rows,
+            line_height: self.line_height,
+            view: self.view,
+            offset: self.offset,
+            max_offset: self.max_offset,
+            editable: self.editable,
+            model: self.model,
+            lines: self.lines,
+            on_edit: Some(Rc::new(f)),
+        }
+    }
+}
+
+impl<M: 'static> TextEdit<M> {
+    /// Set the number of text rows shown (default: 5)
+    pub fn with_rows(mut self, rows: u32) -> Self {
+        self.rows = rows.max(1);
+        self
+    }
+
+    /// Set whether this `TextEdit` is editable (inline)
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Get whether this `TextEdit` is editable
+    pub fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    /// Set whether this `TextEdit` is editable
+    pub fn set_editable(&mut self, editable: bool) {
+        self.editable = editable;
+    }
+
+    /// Length of the text, in bytes
+    pub fn len(&self) -> usize {
+        self.model.len()
+    }
+
+    /// True if the text is empty
+    pub fn is_empty(&self) -> bool {
+        self.model.len() == 0
+    }
+
+    /// Number of lines of text
+    ///
+    /// This is at least one (even when the text is empty) and counts the
+    /// (empty) line following a trailing line break.
+    pub fn num_lines(&self) -> usize {
+        self.model.text.len_lines()
+    }
+
+    /// Get the text of line `index`, excluding any line break
+    pub fn line(&self, index: usize) -> String {
+        self.model.slice(self.model.line_range(index))
+    }
+
+    /// Replace the text
+    ///
+    /// The edit cursor is moved to the end of the text. Unlike user edits,
+    /// this does not call the `on_edit` function.
+    pub fn set_string(&mut self, text: String) -> TkAction {
+        self.model = EditModel::new(&text);
+        self.lines = new_lines(&self.model, 0..self.num_lines());
+        TkAction::Resize
+    }
+
+    /// Get the edit cursor position, as a byte index
+    pub fn edit_pos(&self) -> usize {
+        self.model.edit_pos
+    }
+
+    /// Get the selected range (bytes), which may be empty
+    pub fn selection(&self) -> Range<usize> {
+        self.model.selection()
+    }
+
+    /// Get the selected text
+    pub fn selected_text(&self) -> String {
+        self.model.slice(self.model.selection())
+    }
+
+    /// Set the cursor position and selection
+    ///
+    /// Text from `sel_pos` to `edit_pos` is selected; the edit cursor is at
+    /// `edit_pos`. Both are byte indices and are clamped to the text length.
+    pub fn set_cursor(&mut self, edit_pos: usize, sel_pos: usize) -> TkAction {
+        self.model.set_cursor(edit_pos, sel_pos);
+        TkAction::Redraw
+    }
+
+    // Position of line `index`, given the text origin `pos`
+    fn line_pos(&self, pos: Coord, index: usize) -> Coord {
+        pos + Coord(0, (index as u32 * self.line_height) as i32)
+    }
+
+    // Range of lines at least partially visible
+    fn visible_lines(&self) -> Range<usize> {
+        let line_height = self.line_height.max(1);
+        let first = self.offset.1.max(0) as u32 / line_height;
+        let last = (self.offset.1.max(0) as u32 + self.view.size.1 + line_height - 1) / line_height;
+        (first as usize)..(last as usize).min(self.lines.len())
+    }
+
+    // Position of the glyph at `pos`, given the text origin `origin`
+    fn glyph_pos(&self, origin: Coord, pos: usize) -> Vec2 {
+        let index = self.model.text.byte_to_line(pos);
+        let range = self.model.line_range(index);
+        let origin = self.line_pos(origin, index);
+        let pos = pos.min(range.end) - range.start;
+        self.lines[index].text_glyph_pos(origin, pos)
+    }
+
+    // Find the index `lines` lines above (negative) or below the cursor
+    fn vertical_target(&mut self, lines: i64) -> usize {
+        let text = self.model.text.clone();
+        let (prepared, line_height) = (&self.lines, self.line_height);
+        let x_of = |pos: usize| {
+            let index = text.byte_to_line(pos);
+            let line_pos = pos - text.line_to_byte(index);
+            prepared[index].text_glyph_pos(Coord::ZERO, line_pos).0
+        };
+        let nearest = |range: Range<usize>, x| {
+            let index = text.byte_to_line(range.start);
+            nearest_index(&prepared[index], line_height, range, x)
+        };
+        self.model.vertical_target(lines, x_of, nearest)
+    }
+
+    fn set_edit_pos_from_coord(&mut self, mgr: &mut Manager, coord: Coord) {
+        mgr.close_undo_group(self.id());
+        let rel = coord + self.offset - self.view.pos;
+        let line = (rel.1.max(0) as u32 / self.line_height.max(1)) as usize;
+        let line = line.min(self.num_lines() - 1);
+        let range = self.model.line_range(line);
+        let prepared = &self.lines[line];
+        let pos = nearest_index(prepared, self.line_height, range, rel.0 as f32);
+        self.model.move_to(pos, true);
+        mgr.redraw(self.id());
+    }
+
+    fn page_lines(&self) -> i64 {
+        (self.view.size.1 / self.line_height.max(1)).max(1) as i64
+    }
+
+    // Update scroll limits
+    fn update_bounds(&mut self) {
+        let width = (self.lines.iter())
+            .map(|line| line.required_size().0)
+            .fold(0.0, f32::max);
+        let height = self.lines.len() as u32 * self.line_height;
+        let content = Size(width.ceil() as u32, height).max(self.view.size);
+        self.max_offset = Coord::from(content) - Coord::from(self.view.size);
+        self.offset = self.offset.clamp(Coord::ZERO, self.max_offset);
+    }
+
+    // Re-prepare lines after an edit
+    //
+    // Our size does not depend on the text, thus we prepare lines directly
+    // instead of requesting a resize. Only lines touched by the edit are
+    // laid out again.
+    fn prepare_lines(&mut self, mgr: &mut Manager, edit: LineEdit) {
+        let range = edit.first..(edit.first + edit.new);
+        let lines = new_lines(&self.model, range.clone());
+        self.lines
+            .splice(edit.first..(edit.first + edit.old), lines);
+        let lines = &mut self.lines[range];
+        mgr.size_handle(|size_handle| {
+            for line in lines {
+                size_handle.prepare(line, TextClass::EditMulti);
+            }
+        });
+        self.update_bounds();
+        mgr.redraw(self.id());
+    }
+
+    // Scroll such that the edit cursor is visible, returning its rect
+    fn scroll_to_cursor(&mut self, mgr: &mut Manager) -> Rect {
+        let pos = self.glyph_pos(self.view.pos, self.model.edit_pos);
+        let rect = Rect::new(Coord::from(pos), Size(1, self.line_height));
+        let mut offset = self.offset;
+        offset = offset.max(rect.pos_end() - self.view.pos_end());
+        offset = offset.min(rect.pos - self.view.pos);
+        offset = offset.clamp(Coord::ZERO, self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            mgr.redraw(self.id());
+        }
+        rect - self.offset
+    }
+
+    fn scroll(&mut self, mgr: &mut Manager, delta: ScrollDelta) -> bool {
+        let d = match delta {
+            ScrollDelta::LineDelta(x, y) => {
+                let rate = 3.0 * self.line_height as f32;
+                Coord((-rate * x) as i32, (rate * y) as i32)
+            }
+            ScrollDelta::PixelDelta(d) => d,
+        };
+        let offset = (self.offset - d).clamp(Coord::ZERO, self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            mgr.redraw(self.id());
+            true
+        } else {
+            false
+        }
+    }
+
+    // Record the current state for undo (see Manager::push_undo)
+    fn push_undo(&self, mgr: &mut Manager, kind: EditKind) {
+        if self.model.has_selection() {
+            // Replacing a selection is always a new undo step
+            mgr.close_undo_group(self.id());
+        }
+        mgr.push_undo(self.id(), kind, || self.model.undo_state());
+    }
+
+    // Returns Some(true) on edit, Some(false) on cursor movement
+    fn control_key(
+        &mut self,
+        mgr: &mut Manager,
+        key: ControlKey,
+        modifiers: ModifiersState,
+    ) -> Option<bool> {
+        let model = &self.model;
+        let pos = model.edit_pos;
+        let selection = model.selection();
+        let have_sel = model.has_selection();
+        let editable = self.editable;
+        let len = model.len();
+
+        enum Action {
+            Insert(String, EditKind),
+            Delete(Range<usize>),
+            Move(usize),
+            // Vertical movement retains the horizontal position
+            MoveV(usize),
+        }
+
+        let action = match key {
+            ControlKey::Return if editable => Action::Insert("\n".into(), EditKind::Insert),
+            ControlKey::Left => Action::Move(model.prev_grapheme(pos)),
+            ControlKey::Right => Action::Move(model.next_grapheme(pos)),
+            ControlKey::Up => Action::MoveV(self.vertical_target(-1)),
+            ControlKey::Down => Action::MoveV(self.vertical_target(1)),
+            ControlKey::PageUp => Action::MoveV(self.vertical_target(-self.page_lines())),
+            ControlKey::PageDown => Action::MoveV(self.vertical_target(self.page_lines())),
+            ControlKey::Home if modifiers.ctrl() => Action::Move(0),
+            ControlKey::End if modifiers.ctrl() => Action::Move(len),
+            ControlKey::Home => Action::Move(model.line_range(model.edit_line()).start),
+            ControlKey::End => Action::Move(model.line_range(model.edit_line()).end),
+            ControlKey::Delete | ControlKey::Backspace if editable && have_sel => {
+                Action::Delete(selection)
+            }
+            ControlKey::Delete if editable && pos < len => {
+                Action::Delete(pos..model.next_grapheme(pos))
+            }
+            ControlKey::Backspace if editable && pos > 0 => {
+                Action::Delete(model.prev_grapheme(pos)..pos)
+            }
+            ControlKey::Cut if editable && have_sel => {
+                mgr.set_clipboard(model.slice(selection.clone()).into());
+                Action::Delete(selection)
+            }
+            ControlKey::Copy if have_sel => {
+                mgr.set_clipboard(model.slice(selection).into());
+                return None;
+            }
+            ControlKey::Paste if editable => {
+                let content = mgr.get_clipboard()?;
+                Action::Insert(clean_paste(&content), EditKind::Paste)
+            }
+            ControlKey::Undo | ControlKey::Redo if editable => {
+                let current = model.undo_state();
+                let state = match key {
+                    ControlKey::Undo => mgr.undo(self.id(), current),
+                    _ => mgr.redo(self.id(), current),
+                };
+                let edit = self.model.restore(state?);
+                self.prepare_lines(mgr, edit);
+                return Some(true);
+            }
+            ControlKey::SelectAll => {
+                mgr.close_undo_group(self.id());
+                self.model.select_all();
+                mgr.redraw(self.id());
+                return Some(false);
+            }
+            _ => return None,
+        };
+
+        let shift = modifiers.shift();
+        match action {
+            Action::Insert(s, kind) => {
+                self.push_undo(mgr, kind);
+                let edit = self.model.insert(&s);
+                self.prepare_lines(mgr, edit);
+                Some(true)
+            }
+            Action::Delete(range) => {
+                self.push_undo(mgr, EditKind::Delete);
+                let edit = self.model.delete(range);
+                self.prepare_lines(mgr, edit);
+                Some(true)
+            }
+            Action::Move(pos) => {
+                mgr.close_undo_group(self.id());
+                self.model.move_to(pos, shift);
+                mgr.redraw(self.id());
+                Some(false)
+            }
+            Action::MoveV(pos) => {
+                mgr.close_undo_group(self.id());
+                self.model.move_vertical(pos, shift);
+                mgr.redraw(self.id());
+                Some(false)
+            }
+        }
+    }
+
+    fn received_char(&mut self, mgr: &mut Manager, c: char) -> bool {
+        if !self.editable {
+            return false;
+        }
+        let mut buf = [0u8; 4];
+        self.push_undo(mgr, EditKind::Insert);
+        let edit = self.model.insert(c.encode_utf8(&mut buf));
+        self.prepare_lines(mgr, edit);
+        true
+    }
+
+    // Lines have already been prepared; the contents are passed to on_edit
+    fn edited(&mut self, mgr: &mut Manager) -> Response<M> {
+        let rect = self.scroll_to_cursor(mgr);
+        if let Some(f) = self.on_edit.as_ref() {
+            if let Some(msg) = f(&self.model.text.to_string()) {
+                return Response::Msg(msg);
+            }
+        }
+        Response::Focus(rect)
+    }
+}
+
+impl<M: 'static> CloneText for TextEdit<M> {
+    fn clone_string(&self) -> String {
+        self.model.text.to_string()
+    }
+
+    fn clone_text(&self) -> kas::text::RichText {
+        self.model.text.to_string().into()
+    }
+}
+
+impl<M: 'static> event::Handler for TextEdit<M> {
+    type Msg = M;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+        match event {
+            Event::Activate => {
+                mgr.request_char_focus(self.id());
+                Response::None
+            }
+            Event::Control(key, modifiers) => match self.control_key(mgr, key, modifiers) {
+                None => Response::None,
+                Some(true) => self.edited(mgr),
+                Some(false) => Response::Focus(self.scroll_to_cursor(mgr)),
+            },
+            Event::ReceivedCharacter(c) => match self.received_char(mgr, c) {
+                false => Response::None,
+                true => self.edited(mgr),
+            },
+            Event::Scroll(delta) => match self.scroll(mgr, delta) {
+                true => Response::None,
+                false => Response::Unhandled(Event::Scroll(delta)),
+            },
+            Event::PressStart { source, coord, .. } if source.is_primary() => {
+                self.set_edit_pos_from_coord(mgr, coord);
+                if !mgr.modifiers().shift() {
+                    self.model.sel_pos = self.model.edit_pos;
+                }
+                mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                mgr.request_char_focus(self.id());
+                Response::None
+            }
+            Event::PressMove { coord, .. } => {
+                // Selection may extend beyond the visible region
+                self.set_edit_pos_from_coord(mgr, coord);
+                let _ = self.scroll_to_cursor(mgr);
+                Response::None
+            }
+            Event::PressEnd { .. } => Response::None,
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+// Find the index within `range` (a line, laid out by `line`) nearest
+// horizontal position `x` (relative to the text origin)
+fn nearest_index(line: &PreparedText, line_height: u32, range: Range<usize>, x: f32) -> usize {
+    let coord = Coord(x.round() as i32, (line_height / 2) as i32);
+    let index = line.text_index_nearest(Coord::ZERO, coord);
+    (range.start + index).min(range.end)
+}
+
+// Construct (unprepared) text objects for lines `range` of `model`
+fn new_lines(model: &EditModel, range: Range<usize>) -> Vec<PreparedText> {
+    range
+        .map(|index| PreparedText::new(model.slice(model.line_range(index)).into(), false))
+        .collect()
+}
+
+// Normalise line breaks of pasted text and drop other control characters
+fn clean_paste(content: &str) -> String {
+    let content = content.replace("\r\n", "\n");
+    content
+        .chars()
+        .filter(|c| *c == '\n' || *c == '\t' || !c.is_control())
+        .collect()
+}
+
+// Lines replaced by an edit: index of the first, and count before and after
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct LineEdit {
+    first: usize,
+    old: usize,
+    new: usize,
+}
+
+// State recorded for undo: text, edit position and selection position
+//
+// Rope clones are cheap (the data is shared).
+type UndoState = (Rope, usize, usize);
+
+// The editing model of a TextEdit: text, cursor and selection
+//
+// Positions are byte indices on char boundaries. The model is independent of
+// text layout; vertical movement is passed functions mapping between
+// positions and horizontal coordinates.
+#[derive(Clone, Debug, Default)]
+struct EditModel {
+    text: Rope,
+    edit_pos: usize,
+    sel_pos: usize,
+    // Horizontal position retained across vertical moves
+    edit_x: Option<f32>,
+}
+
+impl EditModel {
+    // Construct with the cursor at the end of text
+    fn new(text: &str) -> Self {
+        EditModel {
+            text: Rope::from_str(text),
+            edit_pos: text.len(),
+            sel_pos: text.len(),
+            edit_x: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.len_bytes()
+    }
+
+    fn slice(&self, range: Range<usize>) -> String {
+        let start = self.text.byte_to_char(range.start);
+        let end = self.text.byte_to_char(range.end);
+        self.text.slice(start..end).to_string()
+    }
+
+    fn selection(&self) -> Range<usize> {
+        let mut range = self.edit_pos..self.sel_pos;
+        if range.start > range.end {
+            std::mem::swap(&mut range.start, &mut range.end);
+        }
+        range
+    }
+
+    fn has_selection(&self) -> bool {
+        self.edit_pos != self.sel_pos
+    }
+
+    // Set positions, clamped to the text and snapped to char boundaries
+    fn set_cursor(&mut self, edit_pos: usize, sel_pos: usize) {
+        let len = self.text.len_bytes();
+        let snap = |text: &Rope, pos: usize| text.char_to_byte(text.byte_to_char(pos.min(len)));
+        self.edit_pos = snap(&self.text, edit_pos);
+        self.sel_pos = snap(&self.text, sel_pos);
+        self.edit_x = None;
+    }
+
+    // Line of the edit cursor
+    fn edit_line(&self) -> usize {
+        self.text.byte_to_line(self.edit_pos)
+    }
+
+    // Range of line `index`, excluding any line break
+    fn line_range(&self, index: usize) -> Range<usize> {
+        let lines = self.text.len_lines();
+        let index = index.min(lines - 1);
+        let start = self.text.line_to_byte(index);
+        let mut end = if index + 1 < lines {
+            self.text.line_to_byte(index + 1)
+        } else {
+            self.text.len_bytes()
+        };
+        if end > start && self.text.byte(end - 1) == b'\n' {
+            end -= 1;
+            if end > start && self.text.byte(end - 1) == b'\r' {
+                end -= 1;
+            }
+        }
+        start..end
+    }
+
+    fn prev_grapheme(&self, pos: usize) -> usize {
+        let (mut chunk, mut chunk_start, _, _) = self.text.chunk_at_byte(pos);
+        let mut cursor = GraphemeCursor::new(pos, self.text.len_bytes(), true);
+        loop {
+            match cursor.prev_boundary(chunk, chunk_start) {
+                Ok(None) => return 0,
+                Ok(Some(n)) => return n,
+                Err(GraphemeIncomplete::PrevChunk) => {
+                    let (c, start, _, _) = self.text.chunk_at_byte(chunk_start - 1);
+                    chunk = c;
+                    chunk_start = start;
+                }
+                Err(GraphemeIncomplete::PreContext(n)) => {
+                    let context = self.text.chunk_at_byte(n - 1).0;
+                    cursor.provide_context(context, n - context.len());
+                }
+                Err(_) => unreachable!(),
+            }
+        }
+    }
+
+    fn next_grapheme(&self, pos: usize) -> usize {
+        let (mut chunk, mut chunk_start, _, _) = self.text.chunk_at_byte(pos);
+        let mut cursor = GraphemeCursor::new(pos, self.text.len_bytes(), true);
+        loop {
+            match cursor.next_boundary(chunk, chunk_start) {
+                Ok(None) => return self.text.len_bytes(),
+                Ok(Some(n)) => return n,
+                Err(GraphemeIncomplete::NextChunk) => {
+                    chunk_start += chunk.len();
+                    chunk = self.text.chunk_at_byte(chunk_start).0;
+                }
+                Err(GraphemeIncomplete::PreContext(n)) => {
+                    let context = self.text.chunk_at_byte(n - 1).0;
+                    cursor.provide_context(context, n - context.len());
+                }
+                Err(_) => unreachable!(),
+            }
+        }
+    }
+
+    // Find the index `lines` lines above (negative) or below the cursor
+    //
+    // `x_of` yields the horizontal position of an index; `nearest` yields the
+    // index within a line's range nearest a horizontal position. The
+    // position of the cursor is retained until the next non-vertical move.
+    fn vertical_target<X, N>(&mut self, lines: i64, x_of: X, nearest: N) -> usize
+    where
+        X: FnOnce(usize) -> f32,
+        N: FnOnce(Range<usize>, f32) -> usize,
+    {
+        let line = self.edit_line() as i64 + lines;
+        if line < 0 {
+            return 0;
+        } else if line >= self.text.len_lines() as i64 {
+            return self.text.len_bytes();
+        }
+        let edit_pos = self.edit_pos;
+        let x = *self.edit_x.get_or_insert_with(|| x_of(edit_pos));
+        nearest(self.line_range(line as usize), x)
+    }
+
+    // Move the cursor, extending the selection if `extend`
+    fn move_to(&mut self, pos: usize, extend: bool) {
+        self.edit_x = None;
+        self.move_vertical(pos, extend);
+    }
+
+    // As `move_to`, but retain the horizontal position
+    fn move_vertical(&mut self, pos: usize, extend: bool) {
+        self.edit_pos = pos;
+        if !extend {
+            self.sel_pos = pos;
+        }
+    }
+
+    fn select_all(&mut self) {
+        self.sel_pos = 0;
+        self.edit_pos = self.text.len_bytes();
+        self.edit_x = None;
+    }
+
+    // Replace the selection with `s`
+    fn insert(&mut self, s: &str) -> LineEdit {
+        let selection = self.selection();
+        let (first, old_end) = self.line_span(selection.clone());
+        let start = self.text.byte_to_char(selection.start);
+        let end = self.text.byte_to_char(selection.end);
+        self.text.remove(start..end);
+        self.text.insert(start, s);
+        self.edit_pos = selection.start + s.len();
+        self.sel_pos = self.edit_pos;
+        self.edit_x = None;
+        let new_end = self.line_span(selection.start..self.edit_pos).1;
+        LineEdit {
+            first,
+            old: old_end - first,
+            new: new_end - first,
+        }
+    }
+
+    fn delete(&mut self, range: Range<usize>) -> LineEdit {
+        let (first, old_end) = self.line_span(range.clone());
+        let start = self.text.byte_to_char(range.start);
+        let end = self.text.byte_to_char(range.end);
+        self.text.remove(start..end);
+        self.edit_pos = range.start;
+        self.sel_pos = range.start;
+        self.edit_x = None;
+        LineEdit {
+            first,
+            old: old_end - first,
+            new: 1,
+        }
+    }
+
+    // Lines containing (any part of) `range`, as `first..end`
+    fn line_span(&self, range: Range<usize>) -> (usize, usize) {
+        let first = self.text.byte_to_line(range.start);
+        (first, self.text.byte_to_line(range.end) + 1)
+    }
+
+    fn undo_state(&self) -> UndoState {
+        (self.text.clone(), self.edit_pos, self.sel_pos)
+    }
+
+    // Restore a state recorded for undo, which replaces all lines
+    fn restore(&mut self, state: UndoState) -> LineEdit {
+        let (text, edit_pos, sel_pos) = state;
+        let old = self.text.len_lines();
+        self.text = text;
+        self.edit_pos = edit_pos;
+        self.sel_pos = sel_pos;
+        self.edit_x = None;
+        LineEdit {
+            first: 0,
+            old,
+            new: self.text.len_lines(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::event::UndoStack;
+
+    // Horizontal position of `pos` in a monospace layout of `text`
+    fn column(text: &str, pos: usize) -> f32 {
+        let start = text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        (pos - start) as f32
+    }
+
+    // Index within `range` nearest column `x` in a monospace layout
+    fn nearest(range: Range<usize>, x: f32) -> usize {
+        (range.start + x as usize).min(range.end)
+    }
+
+    #[test]
+    fn lines_and_graphemes() {
+        let model = EditModel::new("ab\r\ncd\ne\u{301}");
+        assert_eq!(model.text.len_lines(), 3);
+        assert_eq!(model.line_range(0), 0..2);
+        assert_eq!(model.line_range(1), 4..6);
+        assert_eq!(model.line_range(2), 7..10);
+        assert_eq!(model.slice(model.line_range(1)), "cd");
+
+        // CRLF and combining sequences are single graphemes
+        assert_eq!(model.next_grapheme(2), 4);
+        assert_eq!(model.prev_grapheme(4), 2);
+        assert_eq!(model.next_grapheme(7), 10);
+        assert_eq!(model.prev_grapheme(10), 7);
+        assert_eq!(model.prev_grapheme(0), 0);
+        assert_eq!(model.next_grapheme(10), 10);
+
+        // A trailing line break starts an empty line
+        let model = EditModel::new("ab\n");
+        assert_eq!(model.text.len_lines(), 2);
+        assert_eq!(model.line_range(1), 3..3);
+        assert_eq!(model.edit_line(), 1);
+    }
+
+    #[test]
+    fn vertical_movement() {
+        let text = "hello\nab\nworld";
+        let mut model = EditModel::new(text);
+        let down = |model: &mut EditModel, lines| {
+            let pos = model.vertical_target(lines, |pos| column(text, pos), nearest);
+            model.move_vertical(pos, false);
+            model.edit_pos
+        };
+
+        // The horizontal position is retained across shorter lines
+        model.move_to(4, false);
+        assert_eq!(down(&mut model, 1), 8);
+        assert_eq!(down(&mut model, 1), 13);
+        assert_eq!(down(&mut model, -2), 4);
+
+        // Moving beyond the first or last line moves to the start or end
+        assert_eq!(down(&mut model, -1), 0);
+        model.move_to(11, false);
+        assert_eq!(down(&mut model, 1), text.len());
+
+        // Horizontal movement resets the retained position
+        model.move_to(7, false);
+        assert_eq!(down(&mut model, -1), 1);
+        assert_eq!(model.selection(), 1..1);
+    }
+
+    #[test]
+    fn selection() {
+        let mut model = EditModel::new("one two");
+        assert_eq!(model.edit_pos, 7);
+        assert!(!model.has_selection());
+
+        // Extending the selection (e.g. with Shift) retains the anchor
+        model.move_to(4, true);
+        assert_eq!(model.selection(), 4..7);
+        assert_eq!(model.slice(model.selection()), "two");
+        model.move_to(1, true);
+        assert_eq!(model.selection(), 1..7);
+
+        // Insertion replaces the selection
+        model.insert("n");
+        assert_eq!(model.text.to_string(), "on");
+        assert_eq!((model.edit_pos, model.sel_pos), (2, 2));
+
+        model.select_all();
+        assert_eq!(model.selection(), 0..2);
+        model.delete(model.selection());
+        assert_eq!(model.text.to_string(), "");
+
+        // Positions are clamped and snapped to char boundaries
+        let mut model = EditModel::new("é!");
+        model.set_cursor(1, 100);
+        assert_eq!((model.edit_pos, model.sel_pos), (0, 3));
+        assert_eq!(model.slice(model.selection()), "é!");
+
+        assert_eq!(clean_paste("a\r\nb\u{7}\tc"), "a\nb\tc");
+    }
+
+    #[test]
+    fn line_edits() {
+        let mut model = EditModel::new("ab\ncd\nef");
+        let edit = |first, old, new| LineEdit { first, old, new };
+
+        // Typing within a line touches only that line
+        model.set_cursor(4, 4);
+        assert_eq!(model.insert("x"), edit(1, 1, 1));
+        assert_eq!(model.insert("\n"), edit(1, 1, 2));
+        assert_eq!(model.text.to_string(), "ab\ncx\nd\nef");
+
+        // Replacing a selection spanning lines merges them
+        model.set_cursor(1, 7);
+        assert_eq!(model.insert("-"), edit(0, 3, 1));
+        assert_eq!(model.text.to_string(), "a-d\nef");
+        assert_eq!(model.delete(3..4), edit(0, 2, 1));
+        assert_eq!(model.text.to_string(), "a-def");
+
+        // Restoring a state replaces all lines
+        let state = EditModel::new("1\n2\n3").undo_state();
+        assert_eq!(model.restore(state), edit(0, 1, 3));
+    }
+
+    #[test]
+    fn undo() {
+        let id = WidgetId::FIRST;
+        let mut stack = UndoStack::default();
+        let mut model = EditModel::new("");
+
+        // Typing merges into one undo step
+        for s in &["a", "b"] {
+            stack.push(id, EditKind::Insert, || model.undo_state());
+            model.insert(s);
+        }
+        // Replacing a selection starts a new step (see TextEdit::push_undo)
+        model.select_all();
+        stack.close(id);
+        stack.push(id, EditKind::Insert, || model.undo_state());
+        model.insert("X");
+        assert_eq!(model.text.to_string(), "X");
+
+        // Undo restores text and selection
+        let state = stack.undo(id, model.undo_state()).unwrap();
+        model.restore(state);
+        assert_eq!(model.text.to_string(), "ab");
+        assert_eq!(model.selection(), 0..2);
+        assert_eq!(model.edit_pos, 2);
+
+        let state = stack.undo(id, model.undo_state()).unwrap();
+        model.restore(state);
+        assert_eq!(model.text.to_string(), "");
+        assert!(stack.undo(id, model.undo_state()).is_none());
+
+        let state = stack.redo(id, model.undo_state()).unwrap();
+        model.restore(state);
+        assert_eq!(model.text.to_string(), "ab");
+        assert_eq!(model.selection(), 0..2);
+    }
+}
@@ -0,0 +1,759 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! List view widget
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use super::{translate_press, Accessor, Selection, SelectionMode, ViewMsg, ViewWidget};
+use kas::access::Role;
+use kas::draw::{ClipRegion, TextClass};
+use kas::event::ScrollDelta::{LineDelta, PixelDelta};
+use kas::event::{ConfigureManager, ControlKey, GrabMode, ModifiersState};
+use kas::prelude::*;
+use kas::widget::ScrollBar;
+
+/// Number of identifiers reserved for growth of the widget pool
+const POOL_IDS: u32 = 4096;
+
+/// A virtualised list view
+///
+/// This widget displays a list of data items from an [`Accessor`], using
+/// widgets of type `W` (see [`ViewWidget`]) to view each item. Only enough
+/// widgets to cover the visible region are constructed; these are re-assigned
+/// to data items as the view scrolls. Thus the cost of configuring, resizing
+/// and drawing the view is independent of the number of data items.
+///
+/// Items are arranged in direction `D` (e.g. [`kas::Down`]). All items are
+/// assumed to have the same size along this axis (the largest ideal size of
+/// the constructed widgets).
+///
+/// The view scrolls itself (via mouse wheel, drag, keyboard and an optional
+/// scroll bar) and should not be placed within a [`kas::widget::ScrollRegion`].
+///
+/// Items may be selected according to the [`SelectionMode`]. With
+/// [`SelectionMode::Multiple`], Ctrl+click toggles selection of an item and
+/// Shift+click selects a range. Arrow keys, Home, End, PageUp and PageDown
+/// move the cursor (and selection); Ctrl+Space toggles selection of the
+/// cursor item.
+///
+/// Messages from item widgets are wrapped as [`ViewMsg::Item`] with the index
+/// of the data item viewed; changes to the selection are reported via
+/// [`ViewMsg::Selection`]. Double-clicking an item or pressing Space or Return
+/// yields [`ViewMsg::Activate`].
+///
+/// If the [`Accessor`] provides an update handle, the view re-reads data when
+/// this is triggered.
+///
+/// If the view is enlarged beyond the size for which widgets were constructed,
+/// extra widgets are constructed and configured in place once layout completes
+/// (see [`Event::Resized`]).
+#[widget(config=noauto, children=noauto)]
+#[handler(send=noauto, msg = ViewMsg<usize, <W as event::Handler>::Msg>)]
+#[derive(Clone, Debug, Widget)]
+pub struct ListView<D: Directional, A: Accessor<usize>, W: ViewWidget<A::Item>> {
+    #[widget_core]
+    core: CoreData,
+    data: A,
+    widgets: Vec<W>,
+    rows: Vec<Option<usize>>,
+    direction: D,
+    ideal_len: usize,
+    row_len: u32,
+    inner: Rect,
+    offset: u32,
+    max_offset: u32,
+    scroll_rate: f32,
+    show_bar: bool,
+    bar_width: u32,
+    bar: ScrollBar<D>,
+    selection: Selection,
+    pool_ids: WidgetId,
+}
+
+impl<D: Directional + Default, A: Accessor<usize>, W: ViewWidget<A::Item>> ListView<D, A, W> {
+    /// Construct a new instance
+    ///
+    /// This constructor is available where the direction is determined by the
+    /// type: for `D: Directional + Default`. In other cases, use
+    /// [`ListView::new_with_direction`].
+    pub fn new(data: A) -> Self {
+        Self::new_with_direction(D::default(), data)
+    }
+}
+
+impl<D: Directional, A: Accessor<usize>, W: ViewWidget<A::Item>> ListView<D, A, W> {
+    /// Construct a new instance with explicit direction
+    pub fn new_with_direction(direction: D, data: A) -> Self {
+        let mut view = ListView {
+            core: Default::default(),
+            data,
+            widgets: vec![],
+            rows: vec![],
+            direction,
+            ideal_len: 16,
+            row_len: 0,
+            inner: Rect::default(),
+            offset: 0,
+            max_offset: 0,
+            scroll_rate: 30.0,
+            show_bar: true,
+            bar_width: 0,
+            bar: ScrollBar::new_with_direction(direction),
+            selection: Default::default(),
+            pool_ids: Default::default(),
+        };
+        let _ = view.resize_pool();
+        view
+    }
+
+    /// Set the ideal number of visible items
+    ///
+    /// This is used to calculate the ideal size of the view. Default: 16.
+    pub fn with_num_visible(mut self, len: usize) -> Self {
+        self.ideal_len = len;
+        let _ = self.resize_pool();
+        self
+    }
+
+    /// Enable or disable the scroll bar (default: enabled)
+    #[inline]
+    pub fn with_bar(mut self, show: bool) -> Self {
+        self.show_bar = show;
+        self
+    }
+
+    /// Set the selection mode (default: [`SelectionMode::Single`])
+    #[inline]
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        let _ = self.selection.set_mode(mode);
+        self
+    }
+
+    /// Get the selection mode
+    #[inline]
+    pub fn selection_mode(&self) -> SelectionMode {
+        self.selection.mode
+    }
+
+    /// Set the selection mode
+    ///
+    /// The selection is cleared if incompatible with the new mode.
+    pub fn set_selection_mode(&mut self, mode: SelectionMode) -> TkAction {
+        self.selection.set_mode(mode)
+    }
+
+    /// Access the data accessor
+    #[inline]
+    pub fn data(&self) -> &A {
+        &self.data
+    }
+
+    /// Replace the data accessor
+    ///
+    /// The selection is cleared. Triggers a
+    /// [reconfigure action](Manager::send_action).
+    pub fn set_data(&mut self, data: A) -> TkAction {
+        self.data = data;
+        self.selection.truncate(0);
+        for row in &mut self.rows {
+            *row = None;
+        }
+        TkAction::Reconfigure
+    }
+
+    /// Get the indices of selected items
+    #[inline]
+    pub fn selection(&self) -> &BTreeSet<usize> {
+        &self.selection.selected
+    }
+
+    /// Check whether the item at `index` is selected
+    #[inline]
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selection.selected.contains(&index)
+    }
+
+    /// Clear the selection
+    pub fn clear_selection(&mut self) -> TkAction {
+        self.selection.clear()
+    }
+
+    /// Select the item at `index`
+    ///
+    /// With [`SelectionMode::Single`], this replaces any existing selection.
+    /// With [`SelectionMode::None`] or if `index` is out of range, this does
+    /// nothing.
+    pub fn select(&mut self, index: usize) -> TkAction {
+        if index >= self.data.len() {
+            return TkAction::None;
+        }
+        self.selection.select(index)
+    }
+
+    /// Get the current scroll offset
+    ///
+    /// This is the distance (along the list's direction) from the start of
+    /// the first item to the start of the view.
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Get the maximum scroll offset
+    #[inline]
+    pub fn max_offset(&self) -> u32 {
+        self.max_offset
+    }
+
+    /// Scroll such that the item at `index` is visible
+    pub fn scroll_to(&mut self, mgr: &mut Manager, index: usize) {
+        let start = (index as u32).saturating_mul(self.row_len);
+        let end = start.saturating_add(self.row_len);
+        let view_len = self.view_len();
+        let offset = if start < self.offset {
+            start
+        } else if end > self.offset + view_len {
+            end - view_len
+        } else {
+            return;
+        };
+        self.set_offset(mgr, offset);
+    }
+
+    /// Set the scroll offset
+    ///
+    /// The offset is clamped to the range `0..=self.max_offset()`.
+    pub fn set_offset(&mut self, mgr: &mut Manager, offset: u32) {
+        let offset = offset.min(self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            *mgr += self.bar.set_value(offset);
+            self.update_widgets(mgr);
+        }
+    }
+
+    // Length of the view along the list's direction
+    fn view_len(&self) -> u32 {
+        match self.direction.is_vertical() {
+            false => self.inner.size.0,
+            true => self.inner.size.1,
+        }
+    }
+
+    // Number of widgets to construct
+    fn pool_target(&self) -> usize {
+        let visible = match self.row_len {
+            0 => 0,
+            len => ((self.view_len() + len - 1) / len) as usize,
+        };
+        (self.ideal_len.max(visible) + 2).min(self.data.len())
+    }
+
+    // Construct or remove widgets to match the pool target
+    fn resize_pool(&mut self) -> TkAction {
+        let target = self.pool_target();
+        if target == self.widgets.len() {
+            return TkAction::None;
+        }
+
+        self.widgets.truncate(target);
+        while self.widgets.len() < target {
+            let index = self.widgets.len();
+            self.widgets.push(W::new(self.data.get(index)));
+        }
+        self.rows.clear();
+        self.rows.resize(target, None);
+        TkAction::Reconfigure
+    }
+
+    // Range of items at least partially visible
+    fn visible_range(&self) -> Range<usize> {
+        if self.row_len == 0 {
+            return 0..0;
+        }
+        let first = self.offset / self.row_len;
+        let last = (self.offset + self.view_len() + self.row_len - 1) / self.row_len;
+        (first as usize)..(last as usize).min(self.data.len())
+    }
+
+    // Assign data items to widgets
+    //
+    // Widgets are assigned to a contiguous range of items, including the
+    // visible range (unless there are insufficient widgets); item `i` is
+    // viewed by widget `i % n`.
+    fn assign_rows(&mut self) -> TkAction {
+        let n = self.widgets.len();
+        if n == 0 {
+            return TkAction::None;
+        }
+        let len = self.data.len();
+        let start = self.visible_range().start.min(len.saturating_sub(n));
+        let mut action = TkAction::None;
+        for row in start..(start + n).min(len) {
+            let slot = row % n;
+            if self.rows[slot] != Some(row) {
+                action += self.widgets[slot].set(self.data.get(row));
+                self.rows[slot] = Some(row);
+            }
+        }
+        action
+    }
+
+    // Re-assign widgets after scrolling or a data change
+    fn update_widgets(&mut self, mgr: &mut Manager) {
+        let action = self.assign_rows();
+        if action == TkAction::Resize {
+            self.size_widgets(mgr);
+        } else if action > TkAction::Resize {
+            *mgr += action;
+        }
+        self.set_widget_rects();
+        *mgr += TkAction::RegionMoved;
+    }
+
+    // Size widgets before calling set_rect
+    fn size_widgets(&mut self, mgr: &mut Manager) {
+        // Widget size is uniform, thus we need only resize widgets
+        let (horiz, vert) = match self.direction.is_vertical() {
+            false => (self.row_len, self.inner.size.1),
+            true => (self.inner.size.0, self.row_len),
+        };
+        let widgets = &mut self.widgets;
+        mgr.size_handle(|size_handle| {
+            for w in widgets.iter_mut() {
+                w.size_rules(size_handle, AxisInfo::new(false, Some(vert)));
+                w.size_rules(size_handle, AxisInfo::new(true, Some(horiz)));
+            }
+        });
+    }
+
+    // Construct widgets if the pool is too small, e.g. after enlargement
+    //
+    // New widgets are configured in place and sized immediately.
+    fn grow_pool(&mut self, mgr: &mut Manager) {
+        if self.pool_target() <= self.widgets.len() {
+            return;
+        }
+        let _ = self.resize_pool();
+        if !mgr.configure_children(self.pool_ids, self) {
+            *mgr += TkAction::Reconfigure;
+            return;
+        }
+        let _ = self.assign_rows();
+        self.size_widgets(mgr);
+        self.set_widget_rects();
+        *mgr += TkAction::RegionMoved;
+    }
+
+    // Rect of the item at `row`, in content coordinates
+    fn row_rect(&self, row: usize) -> Rect {
+        let rect = self.inner;
+        let len = self.row_len as i32;
+        let pos = row as i32 * len;
+        let (x, y) = (rect.pos.0, rect.pos.1);
+        let (w, h) = (rect.size.0 as i32, rect.size.1 as i32);
+        match self.direction.as_direction() {
+            Direction::Right => Rect::new(Coord(x + pos, y), Size(self.row_len, rect.size.1)),
+            Direction::Down => Rect::new(Coord(x, y + pos), Size(rect.size.0, self.row_len)),
+            Direction::Left => {
+                let pos = Coord(x + w - pos - len, y);
+                Rect::new(pos, Size(self.row_len, rect.size.1))
+            }
+            Direction::Up => {
+                let pos = Coord(x, y + h - pos - len);
+                Rect::new(pos, Size(rect.size.0, self.row_len))
+            }
+        }
+    }
+
+    fn set_widget_rects(&mut self) {
+        for slot in 0..self.widgets.len() {
+            if let Some(row) = self.rows[slot] {
+                let rect = self.row_rect(row);
+                self.widgets[slot].set_rect(rect, AlignHints::NONE);
+            }
+        }
+    }
+
+    // Translation of content (item widgets)
+    fn scroll_coord(&self) -> Coord {
+        let offset = self.offset as i32;
+        match self.direction.as_direction() {
+            Direction::Right => Coord(offset, 0),
+            Direction::Down => Coord(0, offset),
+            Direction::Left => Coord(-offset, 0),
+            Direction::Up => Coord(0, -offset),
+        }
+    }
+
+    // Component of `delta` in the direction of increasing index
+    fn main_delta(&self, delta: Coord) -> i32 {
+        match self.direction.as_direction() {
+            Direction::Right => delta.0,
+            Direction::Down => delta.1,
+            Direction::Left => -delta.0,
+            Direction::Up => -delta.1,
+        }
+    }
+
+    // Find the item at `coord` (in window coordinates)
+    fn row_at(&self, coord: Coord) -> Option<usize> {
+        if self.row_len == 0 || !self.inner.contains(coord) {
+            return None;
+        }
+        let rect = self.inner;
+        let end = rect.pos_end() - Coord(1, 1);
+        let dist = match self.direction.as_direction() {
+            Direction::Right => coord.0 - rect.pos.0,
+            Direction::Down => coord.1 - rect.pos.1,
+            Direction::Left => end.0 - coord.0,
+            Direction::Up => end.1 - coord.1,
+        };
+        let row = ((dist as u32 + self.offset) / self.row_len) as usize;
+        Some(row).filter(|row| *row < self.data.len())
+    }
+
+    // Index of the widget viewing `row`, if any
+    fn slot_of(&self, row: usize) -> Option<usize> {
+        let n = self.widgets.len();
+        if n > 0 && self.rows[row % n] == Some(row) {
+            Some(row % n)
+        } else {
+            None
+        }
+    }
+
+    fn update_limits(&mut self) -> TkAction {
+        let content = (self.data.len() as u64 * self.row_len as u64).min(i32::MAX as u64);
+        let view_len = self.view_len();
+        self.max_offset = (content as u32).saturating_sub(view_len);
+        self.offset = self.offset.min(self.max_offset);
+        self.bar.set_limits(self.max_offset, view_len) + self.bar.set_value(self.offset)
+    }
+
+    fn scroll_by(&mut self, mgr: &mut Manager, delta: Coord) -> bool {
+        let offset = (self.offset as i32 - self.main_delta(delta)).max(0) as u32;
+        let old_offset = self.offset;
+        self.set_offset(mgr, offset);
+        self.offset != old_offset
+    }
+
+    fn data_changed(&mut self, mgr: &mut Manager) {
+        self.selection.truncate(self.data.len());
+        *mgr += self.update_limits();
+
+        if self.pool_target() < self.widgets.len() {
+            *mgr += TkAction::Reconfigure;
+        } else {
+            for row in &mut self.rows {
+                *row = None;
+            }
+            self.grow_pool(mgr);
+            self.update_widgets(mgr);
+        }
+    }
+
+    // Select `row` in response to user input, with modifiers
+    fn select_row(
+        &mut self,
+        mgr: &mut Manager,
+        row: usize,
+        modifiers: ModifiersState,
+    ) -> Response<<Self as Handler>::Msg> {
+        *mgr += TkAction::Redraw;
+        match self.selection.press(row, modifiers) {
+            true => Response::Msg(ViewMsg::Selection),
+            false => Response::None,
+        }
+    }
+
+    fn control_key(
+        &mut self,
+        mgr: &mut Manager,
+        key: ControlKey,
+        modifiers: ModifiersState,
+    ) -> Response<<Self as Handler>::Msg> {
+        let keys = match self.direction.as_direction() {
+            Direction::Right => (ControlKey::Left, ControlKey::Right),
+            Direction::Down => (ControlKey::Up, ControlKey::Down),
+            Direction::Left => (ControlKey::Right, ControlKey::Left),
+            Direction::Up => (ControlKey::Down, ControlKey::Up),
+        };
+        let page = match self.row_len {
+            0 => 1,
+            row_len => (self.view_len() / row_len).max(1) as usize,
+        };
+        let row = match self.selection.nav_target(key, keys, self.data.len(), page) {
+            Some(row) => row,
+            None => return Response::Unhandled(Event::Control(key, modifiers)),
+        };
+
+        self.scroll_to(mgr, row);
+        *mgr += TkAction::Redraw;
+        match self.selection.navigate(row, modifiers) {
+            true => Response::Msg(ViewMsg::Selection),
+            false => Response::None,
+        }
+    }
+}
+
+impl<D: Directional, A: Accessor<usize>, W: ViewWidget<A::Item>> WidgetChildren
+    for ListView<D, A, W>
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len() + 1
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        if index < self.widgets.len() {
+            Some(self.widgets[index].as_widget())
+        } else if index == self.widgets.len() {
+            Some(self.bar.as_widget())
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        if index < self.widgets.len() {
+            Some(self.widgets[index].as_widget_mut())
+        } else if index == self.widgets.len() {
+            Some(self.bar.as_widget_mut())
+        } else {
+            None
+        }
+    }
+}
+
+impl<D: Directional, A: Accessor<usize>, W: ViewWidget<A::Item>> WidgetConfig
+    for ListView<D, A, W>
+{
+    fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
+        // Widgets are constructed here; the pool may later grow in place
+        let _ = self.resize_pool();
+        let _ = self.assign_rows();
+        self.pool_ids = cmgr.reserve(POOL_IDS);
+
+        for i in 0..WidgetChildren::len(self) {
+            if let Some(w) = self.get_mut(i) {
+                let key = w.key();
+                w.configure_recurse(cmgr.child(i, key));
+            }
+        }
+        self.core_data_mut().stable_id = cmgr.stable_id();
+        self.core_data_mut().id = cmgr.next_id(self.id());
+        self.configure(cmgr.mgr());
+    }
+
+    fn configure(&mut self, mgr: &mut Manager) {
+        if let Some(handle) = self.data.update_handle() {
+            mgr.update_on_handle(handle, self.id());
+        }
+        // Check for a shortage of widgets once sized
+        mgr.update_on_resize(self.id());
+    }
+
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn access_role(&self) -> Role {
+        Role::List
+    }
+}
+
+impl<D: Directional, A: Accessor<usize>, W: ViewWidget<A::Item>> Layout for ListView<D, A, W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let _ = self.assign_rows();
+        self.scroll_rate = 3.0 * size_handle.line_height(TextClass::Label) as f32;
+
+        let mut rules = SizeRules::EMPTY;
+        for w in &mut self.widgets {
+            rules.max_with(w.size_rules(size_handle, axis));
+        }
+
+        if axis.is_vertical() == self.direction.is_vertical() {
+            let margins = rules.margins();
+            self.row_len = rules.ideal_size() + margins.0 as u32 + margins.1 as u32;
+            let len = self.data.len();
+            let min = self.row_len * len.min(1) as u32;
+            let ideal = self.row_len * len.min(self.ideal_len) as u32;
+            SizeRules::new(min, ideal, (0, 0), StretchPolicy::HighUtility)
+        } else {
+            if self.show_bar {
+                self.bar_width = (size_handle.scrollbar().0).1;
+                rules.append(self.bar.size_rules(size_handle, axis));
+            }
+            rules
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        let mut inner = rect;
+        if self.show_bar {
+            let bar_rect;
+            if self.direction.is_vertical() {
+                inner.size.0 = inner.size.0.saturating_sub(self.bar_width);
+                let pos = Coord(rect.pos.0 + inner.size.0 as i32, rect.pos.1);
+                bar_rect = Rect::new(pos, Size(self.bar_width, rect.size.1));
+            } else {
+                inner.size.1 = inner.size.1.saturating_sub(self.bar_width);
+                let pos = Coord(rect.pos.0, rect.pos.1 + inner.size.1 as i32);
+                bar_rect = Rect::new(pos, Size(rect.size.0, self.bar_width));
+            }
+            self.bar.set_rect(bar_rect, AlignHints::NONE);
+        }
+        self.inner = inner;
+
+        let _ = self.update_limits();
+        self.set_widget_rects();
+    }
+
+    fn translation(&self, child_index: usize) -> Coord {
+        match child_index < self.widgets.len() {
+            true => self.scroll_coord(),
+            false => Coord::ZERO,
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+
+        if self.show_bar {
+            if let Some(id) = self.bar.find_id(coord) {
+                return Some(id);
+            }
+        }
+        if let Some(slot) = self.row_at(coord).and_then(|row| self.slot_of(row)) {
+            if let Some(id) = self.widgets[slot].find_id(coord + self.scroll_coord()) {
+                return Some(id);
+            }
+        }
+        Some(self.id())
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        if self.show_bar {
+            self.bar.draw(draw_handle, mgr, disabled);
+        }
+        let nav_focus = mgr.nav_focus(self.id());
+        let offset = self.scroll_coord();
+        draw_handle.clip_region(self.inner, offset, ClipRegion::Scroll, &mut |handle| {
+            for row in self.visible_range() {
+                if let Some(slot) = self.slot_of(row) {
+                    let w = &self.widgets[slot];
+                    let mut state = w.input_state(mgr, disabled);
+                    state.nav_focus = nav_focus && self.selection.cursor == Some(row);
+                    let selected = self.selection.selected.contains(&row);
+                    handle.view_row(w.rect(), selected, state);
+                    w.draw(handle, mgr, disabled);
+                }
+            }
+        });
+    }
+}
+
+impl<D: Directional, A: Accessor<usize>, W: ViewWidget<A::Item>> event::SendEvent
+    for ListView<D, A, W>
+{
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        let n = self.widgets.len();
+        let event = if n > 0 && id <= self.widgets[n - 1].id() {
+            let slot = match self.widgets.binary_search_by_key(&id, |w| w.id()) {
+                Ok(slot) => slot,
+                Err(slot) => slot,
+            };
+            let row = self.rows[slot];
+            let offset = self.scroll_coord();
+            let event = translate_press(event, offset);
+            match self.widgets[slot].send(mgr, id, event) {
+                Response::None => return Response::None,
+                Response::Unhandled(event) => translate_press(event, Coord::ZERO - offset),
+                Response::Focus(rect) => {
+                    if let Some(row) = row {
+                        self.scroll_to(mgr, row);
+                    }
+                    return Response::Focus(rect - self.scroll_coord());
+                }
+                Response::Msg(msg) => match row {
+                    Some(row) => return Response::Msg(ViewMsg::Item(row, msg)),
+                    None => return Response::None,
+                },
+            }
+        } else if id <= self.bar.id() {
+            match Response::<Self::Msg>::try_from(self.bar.send(mgr, id, event)) {
+                Ok(Response::Unhandled(event)) => event,
+                Ok(r) => return r,
+                Err(offset) => {
+                    self.set_offset(mgr, offset);
+                    return Response::None;
+                }
+            }
+        } else {
+            event
+        };
+
+        match event {
+            Event::Activate => match self.selection.cursor {
+                Some(row) if mgr.modifiers().ctrl() => {
+                    self.select_row(mgr, row, ModifiersState::CTRL)
+                }
+                Some(row) => Response::Msg(ViewMsg::Activate(row)),
+                None => Response::None,
+            },
+            Event::Control(key, modifiers) => self.control_key(mgr, key, modifiers),
+            Event::Scroll(delta) => {
+                let d = match delta {
+                    LineDelta(x, y) => Coord(
+                        (-self.scroll_rate * x) as i32,
+                        (self.scroll_rate * y) as i32,
+                    ),
+                    PixelDelta(d) => d,
+                };
+                match self.scroll_by(mgr, d) {
+                    true => Response::None,
+                    false => Response::Unhandled(Event::Scroll(delta)),
+                }
+            }
+            Event::PressStart { source, coord, .. } if source.is_primary() => {
+                mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                mgr.set_nav_focus(self.id());
+                match self.row_at(coord) {
+                    Some(row) if source.repetitions() == 2 => Response::Msg(ViewMsg::Activate(row)),
+                    Some(row) => {
+                        let modifiers = mgr.modifiers();
+                        self.select_row(mgr, row, modifiers)
+                    }
+                    None => Response::None,
+                }
+            }
+            Event::PressMove { delta, .. } => {
+                self.scroll_by(mgr, delta);
+                Response::None
+            }
+            Event::PressEnd { .. } => Response::None,
+            Event::Resized => {
+                self.grow_pool(mgr);
+                Response::None
+            }
+            Event::HandleUpdate { .. } => {
+                self.data_changed(mgr);
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
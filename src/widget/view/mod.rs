@@ -0,0 +1,373 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! View widgets
+//!
+//! View widgets display data from a model (an [`Accessor`]) without requiring
+//! a widget per data item. Instead, a small pool of widgets implementing
+//! [`ViewWidget`] is constructed and these are re-assigned to data items as
+//! the view is scrolled.
+//!
+//! [`TreeView`] instead constructs a widget for each visible (expanded) node,
+//! reading nodes from a [`TreeAccessor`] on demand.
+
+mod list_view;
+mod table;
+mod tree_view;
+
+pub use list_view::ListView;
+pub use table::Table;
+pub use tree_view::{TreeAccessor, TreeView};
+
+use std::cell::{Ref, RefCell};
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use super::Label;
+use kas::class::SetText;
+use kas::event::{ControlKey, ModifiersState, UpdateHandle};
+use kas::prelude::*;
+
+/// Data access
+///
+/// This trait is the interface between view widgets (e.g. [`ListView`]) and
+/// data. It is implemented for `Vec<T>` (where `T: Clone`), and may be
+/// implemented by user types to provide access to remote or computed data.
+pub trait Accessor<Index: Copy>: Debug + 'static {
+    /// Type of data item
+    type Item;
+
+    /// Number of data items available
+    fn len(&self) -> Index;
+
+    /// Get data at `index`
+    ///
+    /// This is only called with `index` less than `self.len()`.
+    fn get(&self, index: Index) -> Self::Item;
+
+    /// Get an update handle, if any is used
+    ///
+    /// Views subscribe to this handle (see [`Manager::update_on_handle`]).
+    /// Data changes should be reported by calling [`Manager::trigger_update`]
+    /// on this handle, whereupon views re-read data via [`Accessor::get`].
+    fn update_handle(&self) -> Option<UpdateHandle> {
+        None
+    }
+}
+
+impl<T: Clone + Debug + 'static> Accessor<usize> for Vec<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn get(&self, index: usize) -> T {
+        self[index].clone()
+    }
+}
+
+/// Table access: index `(col, row)` yields `self[row][col]`
+///
+/// The number of columns is taken from the first row.
+impl<T: Clone + Debug + 'static> Accessor<(usize, usize)> for Vec<Vec<T>> {
+    type Item = T;
+
+    fn len(&self) -> (usize, usize) {
+        let cols = self.first().map(|row| row.len()).unwrap_or(0);
+        (cols, Vec::len(self))
+    }
+
+    fn get(&self, (col, row): (usize, usize)) -> T {
+        self[row][col].clone()
+    }
+}
+
+impl<Index: Copy, A: Accessor<Index> + ?Sized> Accessor<Index> for Rc<A> {
+    type Item = A::Item;
+
+    fn len(&self) -> Index {
+        self.as_ref().len()
+    }
+
+    fn get(&self, index: Index) -> Self::Item {
+        self.as_ref().get(index)
+    }
+
+    fn update_handle(&self) -> Option<UpdateHandle> {
+        self.as_ref().update_handle()
+    }
+}
+
+/// A shared, mutable `Vec`
+///
+/// Clones of this type share the same data. Modifications must be made via
+/// [`SharedVec::update`], which notifies views of the change.
+#[derive(Clone, Debug)]
+pub struct SharedVec<T: Clone + Debug + 'static> {
+    data: Rc<RefCell<Vec<T>>>,
+    handle: UpdateHandle,
+}
+
+impl<T: Clone + Debug + 'static> SharedVec<T> {
+    /// Construct from a `Vec`
+    pub fn new(data: Vec<T>) -> Self {
+        SharedVec {
+            data: Rc::new(RefCell::new(data)),
+            handle: UpdateHandle::new(),
+        }
+    }
+
+    /// Borrow the data
+    pub fn borrow(&self) -> Ref<Vec<T>> {
+        self.data.borrow()
+    }
+
+    /// Modify the data via `f`, then notify views
+    pub fn update<F: FnOnce(&mut Vec<T>)>(&self, mgr: &mut Manager, f: F) {
+        f(&mut self.data.borrow_mut());
+        mgr.trigger_update(self.handle, 0);
+    }
+}
+
+impl<T: Clone + Debug + 'static> Accessor<usize> for SharedVec<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        self.data.borrow().len()
+    }
+
+    fn get(&self, index: usize) -> T {
+        self.data.borrow()[index].clone()
+    }
+
+    fn update_handle(&self) -> Option<UpdateHandle> {
+        Some(self.handle)
+    }
+}
+
+/// Widgets usable as items within a view
+///
+/// Widgets are constructed from and re-assigned to data items as the view
+/// scrolls; they should have uniform size.
+pub trait ViewWidget<T>: Widget {
+    /// Construct a widget displaying `data`
+    fn new(data: T) -> Self;
+
+    /// Set the data displayed
+    ///
+    /// Returns the action required (see [`TkAction`]). If this is
+    /// [`TkAction::Resize`], the view re-calculates the size of this widget
+    /// only; a greater action is passed on to the toolkit.
+    fn set(&mut self, data: T) -> TkAction;
+}
+
+impl<T: ToString> ViewWidget<T> for Label {
+    fn new(data: T) -> Self {
+        Label::new(data.to_string())
+    }
+
+    fn set(&mut self, data: T) -> TkAction {
+        self.set_text(data.to_string())
+    }
+}
+
+/// Selection mode of a view
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SelectionMode {
+    /// Items may not be selected
+    None,
+    /// At most one item may be selected
+    Single,
+    /// Multiple items may be selected (using Ctrl and Shift modifiers)
+    Multiple,
+}
+
+impl Default for SelectionMode {
+    fn default() -> Self {
+        SelectionMode::Single
+    }
+}
+
+/// Message type of view widgets
+#[derive(Clone, Debug, PartialEq)]
+pub enum ViewMsg<Index, M> {
+    /// A message from the widget viewing the data item at `Index`
+    Item(Index, M),
+    /// The data item at `Index` was activated
+    ///
+    /// This is emitted on double-click and when Return or Space is pressed.
+    Activate(Index),
+    /// The selection changed
+    ///
+    /// The new selection may be queried from the view.
+    Selection,
+}
+
+// Selection and cursor state over a sequence of items
+#[derive(Clone, Debug, Default)]
+struct Selection {
+    mode: SelectionMode,
+    selected: BTreeSet<usize>,
+    cursor: Option<usize>,
+    anchor: Option<usize>,
+}
+
+impl Selection {
+    fn set_mode(&mut self, mode: SelectionMode) -> TkAction {
+        self.mode = mode;
+        let keep = match mode {
+            SelectionMode::None => 0,
+            SelectionMode::Single => 1,
+            SelectionMode::Multiple => usize::MAX,
+        };
+        if self.selected.len() > keep {
+            self.selected.clear();
+            TkAction::Redraw
+        } else {
+            TkAction::None
+        }
+    }
+
+    fn clear(&mut self) -> TkAction {
+        match self.selected.is_empty() {
+            true => TkAction::None,
+            false => {
+                self.selected.clear();
+                TkAction::Redraw
+            }
+        }
+    }
+
+    fn select(&mut self, index: usize) -> TkAction {
+        match self.mode {
+            SelectionMode::None => return TkAction::None,
+            SelectionMode::Single => self.selected.clear(),
+            SelectionMode::Multiple => (),
+        }
+        self.selected.insert(index);
+        TkAction::Redraw
+    }
+
+    // Remove indices not less than len
+    fn truncate(&mut self, len: usize) {
+        let _ = self.selected.split_off(&len);
+        self.cursor = self.cursor.filter(|index| *index < len);
+        self.anchor = self.anchor.filter(|index| *index < len);
+    }
+
+    // Adjust selection in response to user input, returning true on change
+    //
+    // With `SelectionMode::Multiple`, Ctrl toggles and Shift selects a range.
+    fn press(&mut self, index: usize, modifiers: ModifiersState) -> bool {
+        self.cursor = Some(index);
+        match self.mode {
+            SelectionMode::None => false,
+            SelectionMode::Single => {
+                if self.selected.len() == 1 && self.selected.contains(&index) {
+                    return false;
+                }
+                self.selected.clear();
+                self.selected.insert(index);
+                true
+            }
+            SelectionMode::Multiple => {
+                if modifiers.shift() {
+                    let anchor = self.anchor.unwrap_or(index);
+                    if !modifiers.ctrl() {
+                        self.selected.clear();
+                    }
+                    self.selected.extend(anchor.min(index)..=anchor.max(index));
+                } else if modifiers.ctrl() {
+                    if !self.selected.remove(&index) {
+                        self.selected.insert(index);
+                    }
+                    self.anchor = Some(index);
+                } else {
+                    self.selected.clear();
+                    self.selected.insert(index);
+                    self.anchor = Some(index);
+                }
+                true
+            }
+        }
+    }
+
+    // Move the cursor to `index` in response to a navigation key
+    //
+    // Returns true if the selection changed.
+    fn navigate(&mut self, index: usize, modifiers: ModifiersState) -> bool {
+        if self.mode == SelectionMode::Multiple && modifiers.ctrl() && !modifiers.shift() {
+            // Move the cursor without changing the selection
+            self.cursor = Some(index);
+            false
+        } else {
+            self.press(index, modifiers)
+        }
+    }
+
+    // Get the cursor target of a navigation key
+    //
+    // Keys `back` and `forward` move by one item; Home, End, PageUp and
+    // PageDown are also supported.
+    fn nav_target(
+        &self,
+        key: ControlKey,
+        (back, forward): (ControlKey, ControlKey),
+        len: usize,
+        page: usize,
+    ) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let cursor = self.cursor;
+        Some(match key {
+            key if key == back => cursor.map(|i| i.saturating_sub(1)).unwrap_or(0),
+            key if key == forward => cursor.map(|i| (i + 1).min(len - 1)).unwrap_or(0),
+            ControlKey::Home => 0,
+            ControlKey::End => len - 1,
+            ControlKey::PageUp => cursor.unwrap_or(0).saturating_sub(page),
+            ControlKey::PageDown => (cursor.unwrap_or(0) + page).min(len - 1),
+            _ => return None,
+        })
+    }
+}
+
+// Translate press coordinates by `offset`
+fn translate_press(event: Event, offset: Coord) -> Event {
+    match event {
+        Event::PressStart {
+            source,
+            start_id,
+            coord,
+        } => Event::PressStart {
+            source,
+            start_id,
+            coord: coord + offset,
+        },
+        Event::PressMove {
+            source,
+            cur_id,
+            coord,
+            delta,
+        } => Event::PressMove {
+            source,
+            cur_id,
+            coord: coord + offset,
+            delta,
+        },
+        Event::PressEnd {
+            source,
+            end_id,
+            coord,
+        } => Event::PressEnd {
+            source,
+            end_id,
+            coord: coord + offset,
+        },
+        event => event,
+    }
+}
@@ -0,0 +1,1104 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Table view widget
+
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use super::{translate_press, Accessor, Selection, SelectionMode, ViewMsg, ViewWidget};
+use kas::access::Role;
+use kas::class::SetAccel;
+use kas::draw::{ClipRegion, TextClass};
+use kas::event::ScrollDelta::{LineDelta, PixelDelta};
+use kas::event::{ConfigureManager, ControlKey, GrabMode, ModifiersState};
+use kas::layout::{GridChildInfo, GridSetter, GridSolver, GridStorage, RulesSetter, RulesSolver};
+use kas::prelude::*;
+use kas::widget::{DragHandle, ScrollBar, TextButton};
+
+/// Number of identifiers reserved for growth of the widget pool
+const POOL_IDS: u32 = 4096;
+
+/// A virtualised table view with column headers
+///
+/// This widget displays a table of data items from an [`Accessor`] indexed by
+/// `(col, row)`, using widgets of type `W` (see [`ViewWidget`]) to view each
+/// cell. As with [`super::ListView`], only enough rows of widgets to cover the
+/// visible region are constructed. All rows are assumed to have the same
+/// height.
+///
+/// Each column has a header button. Clicking a header sorts rows by the value
+/// of that column's items (clicking again reverses the order); this requires
+/// reading all items of the column. Handles between headers may be dragged to
+/// adjust column widths. Column widths are shared between headers and body
+/// rows via a [`layout::DynGridStorage`].
+///
+/// Rows may be selected according to the [`SelectionMode`], with the same
+/// mouse and keyboard controls as [`super::ListView`].
+///
+/// Messages use index `(col, row)` where `row` is the index of the data row
+/// (independent of sorting). Messages from cell widgets are wrapped as
+/// [`ViewMsg::Item`]; double-clicking a cell yields [`ViewMsg::Activate`] with
+/// the cell's index, as does pressing Space or Return (using the column last
+/// clicked); changes to the selection are reported via [`ViewMsg::Selection`].
+///
+/// As with [`super::ListView`], extra rows of widgets are constructed in place
+/// when the table is enlarged.
+///
+/// Column widths set by dragging a handle are kept when the table is resized;
+/// other columns absorb any change in width.
+#[widget(config=noauto, children=noauto)]
+#[handler(send=noauto, msg = ViewMsg<(usize, usize), <W as event::Handler>::Msg>)]
+#[derive(Clone, Debug, Widget)]
+pub struct Table<A: Accessor<(usize, usize)>, W: ViewWidget<A::Item>>
+where
+    A::Item: PartialOrd,
+{
+    #[widget_core]
+    core: CoreData,
+    data: A,
+    titles: Vec<String>,
+    headers: Vec<TextButton<usize>>,
+    handles: Vec<DragHandle>,
+    // Cell widgets, row-major: slot * cols + col
+    widgets: Vec<W>,
+    // View row assigned to each slot
+    rows: Vec<Option<usize>>,
+    // Columns alternate with handles; row 0 is the header, row 1 the body
+    grid: layout::DynGridStorage,
+    // Position and width of each column
+    col_pos: Vec<(i32, u32)>,
+    // Width of each column, if set by dragging a handle
+    col_widths: Vec<Option<u32>>,
+    handle_width: u32,
+    header_h: u32,
+    ideal_len: usize,
+    row_len: u32,
+    body: Rect,
+    offset: u32,
+    max_offset: u32,
+    scroll_rate: f32,
+    show_bar: bool,
+    bar_width: u32,
+    bar: ScrollBar<kas::Down>,
+    // Selection over view rows
+    selection: Selection,
+    // Sort column and whether descending
+    sort_col: Option<(usize, bool)>,
+    // Map from view row to data row and inverse (empty when unsorted)
+    order: Vec<usize>,
+    inverse: Vec<usize>,
+    press_col: usize,
+    pool_ids: WidgetId,
+}
+
+impl<A: Accessor<(usize, usize)>, W: ViewWidget<A::Item>> Table<A, W>
+where
+    A::Item: PartialOrd,
+{
+    /// Construct a new instance
+    ///
+    /// The table has one column per entry of `titles`. Column `col` views
+    /// data items `(col, row)`; the number of columns should not exceed
+    /// `data.len().0`.
+    pub fn new<S: ToString>(titles: &[S], data: A) -> Self {
+        let titles: Vec<String> = titles.iter().map(|title| title.to_string()).collect();
+        let headers = (titles.iter().enumerate())
+            .map(|(col, title)| TextButton::new(title.clone(), col))
+            .collect();
+        let mut handles = Vec::new();
+        handles.resize_with(titles.len().saturating_sub(1), DragHandle::new);
+        let mut table = Table {
+            core: Default::default(),
+            data,
+            titles,
+            headers,
+            handles,
+            widgets: vec![],
+            rows: vec![],
+            grid: Default::default(),
+            col_pos: vec![],
+            col_widths: vec![],
+            handle_width: 0,
+            header_h: 0,
+            ideal_len: 16,
+            row_len: 0,
+            body: Rect::default(),
+            offset: 0,
+            max_offset: 0,
+            scroll_rate: 30.0,
+            show_bar: true,
+            bar_width: 0,
+            bar: ScrollBar::new(),
+            selection: Default::default(),
+            sort_col: None,
+            order: vec![],
+            inverse: vec![],
+            press_col: 0,
+            pool_ids: Default::default(),
+        };
+        let _ = table.resize_pool();
+        table
+    }
+
+    /// Set the ideal number of visible rows
+    ///
+    /// This is used to calculate the ideal size of the view. Default: 16.
+    pub fn with_num_visible(mut self, len: usize) -> Self {
+        self.ideal_len = len;
+        let _ = self.resize_pool();
+        self
+    }
+
+    /// Enable or disable the scroll bar (default: enabled)
+    #[inline]
+    pub fn with_bar(mut self, show: bool) -> Self {
+        self.show_bar = show;
+        self
+    }
+
+    /// Set the selection mode (default: [`SelectionMode::Single`])
+    #[inline]
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        let _ = self.selection.set_mode(mode);
+        self
+    }
+
+    /// Get the selection mode
+    #[inline]
+    pub fn selection_mode(&self) -> SelectionMode {
+        self.selection.mode
+    }
+
+    /// Set the selection mode
+    ///
+    /// The selection is cleared if incompatible with the new mode.
+    pub fn set_selection_mode(&mut self, mode: SelectionMode) -> TkAction {
+        self.selection.set_mode(mode)
+    }
+
+    /// Number of columns
+    #[inline]
+    pub fn num_cols(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Access the data accessor
+    #[inline]
+    pub fn data(&self) -> &A {
+        &self.data
+    }
+
+    /// Replace the data accessor
+    ///
+    /// The selection is cleared; any sorting is re-applied. Triggers a
+    /// [reconfigure action](Manager::send_action).
+    pub fn set_data(&mut self, data: A) -> TkAction {
+        self.data = data;
+        self.selection.truncate(0);
+        self.sort_rows();
+        TkAction::Reconfigure
+    }
+
+    /// Get the (data) indices of selected rows, in ascending order
+    pub fn selection(&self) -> Vec<usize> {
+        let iter = self.selection.selected.iter();
+        let mut rows: Vec<usize> = iter.map(|row| self.data_row(*row)).collect();
+        rows.sort();
+        rows
+    }
+
+    /// Check whether the data row `row` is selected
+    #[inline]
+    pub fn is_selected(&self, row: usize) -> bool {
+        self.selection.selected.contains(&self.view_row(row))
+    }
+
+    /// Clear the selection
+    pub fn clear_selection(&mut self) -> TkAction {
+        self.selection.clear()
+    }
+
+    /// Select the data row `row`
+    ///
+    /// With [`SelectionMode::Single`], this replaces any existing selection.
+    /// With [`SelectionMode::None`] or if `row` is out of range, this does
+    /// nothing.
+    pub fn select(&mut self, row: usize) -> TkAction {
+        if row >= self.data.len().1 {
+            return TkAction::None;
+        }
+        self.selection.select(self.view_row(row))
+    }
+
+    /// Get the sort column, if any, and whether the order is descending
+    #[inline]
+    pub fn sort_column(&self) -> Option<(usize, bool)> {
+        self.sort_col
+    }
+
+    /// Sort rows by the items of column `col`
+    ///
+    /// Rows are sorted in ascending order unless `descending`. Items which
+    /// cannot be compared are considered equal; otherwise the sort is stable.
+    /// Pass `None` to restore the data order.
+    ///
+    /// Widgets are updated in place; column widths are not affected.
+    pub fn set_sort_column(&mut self, mgr: &mut Manager, sort: Option<(usize, bool)>) {
+        self.sort_col = sort.filter(|(col, _)| *col < self.headers.len());
+        self.sort_rows();
+        let mut action = TkAction::None;
+        for (col, header) in self.headers.iter_mut().enumerate() {
+            let title = &self.titles[col];
+            action += header.set_accel(match self.sort_col {
+                Some((c, false)) if c == col => format!("{} ▲", title),
+                Some((c, true)) if c == col => format!("{} ▼", title),
+                _ => title.clone(),
+            });
+        }
+        if action == TkAction::Resize {
+            self.size_headers(mgr);
+            self.set_column_rects();
+        }
+        self.update_widgets(mgr);
+        *mgr += TkAction::Redraw;
+    }
+
+    /// Get the current scroll offset
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Get the maximum scroll offset
+    #[inline]
+    pub fn max_offset(&self) -> u32 {
+        self.max_offset
+    }
+
+    /// Scroll such that the data row `row` is visible
+    pub fn scroll_to(&mut self, mgr: &mut Manager, row: usize) {
+        let view_row = self.view_row(row);
+        self.scroll_to_view_row(mgr, view_row);
+    }
+
+    /// Set the scroll offset
+    ///
+    /// The offset is clamped to the range `0..=self.max_offset()`.
+    pub fn set_offset(&mut self, mgr: &mut Manager, offset: u32) {
+        let offset = offset.min(self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            *mgr += self.bar.set_value(offset);
+            self.update_widgets(mgr);
+        }
+    }
+
+    fn scroll_to_view_row(&mut self, mgr: &mut Manager, row: usize) {
+        let start = (row as u32).saturating_mul(self.row_len);
+        let end = start.saturating_add(self.row_len);
+        let view_len = self.body.size.1;
+        let offset = if start < self.offset {
+            start
+        } else if end > self.offset + view_len {
+            end - view_len
+        } else {
+            return;
+        };
+        self.set_offset(mgr, offset);
+    }
+
+    fn data_row(&self, view_row: usize) -> usize {
+        self.order.get(view_row).cloned().unwrap_or(view_row)
+    }
+
+    fn view_row(&self, data_row: usize) -> usize {
+        self.inverse.get(data_row).cloned().unwrap_or(data_row)
+    }
+
+    // Re-calculate the row order, preserving selection of data rows
+    fn sort_rows(&mut self) {
+        let len = self.data.len().1;
+        let to_data = |table: &Self, row: Option<usize>| {
+            row.map(|row| table.data_row(row)).filter(|row| *row < len)
+        };
+        let selected: Vec<usize> = (self.selection.selected.iter())
+            .filter_map(|row| to_data(self, Some(*row)))
+            .collect();
+        let cursor = to_data(self, self.selection.cursor);
+        let anchor = to_data(self, self.selection.anchor);
+
+        self.order.clear();
+        self.inverse.clear();
+        if let Some((col, descending)) = self.sort_col {
+            let keys: Vec<A::Item> = (0..len).map(|row| self.data.get((col, row))).collect();
+            let mut order: Vec<usize> = (0..len).collect();
+            order.sort_by(|a, b| {
+                let ord = keys[*a].partial_cmp(&keys[*b]).unwrap_or(Ordering::Equal);
+                match descending {
+                    false => ord,
+                    true => ord.reverse(),
+                }
+            });
+            self.inverse.resize(len, 0);
+            for (view_row, row) in order.iter().enumerate() {
+                self.inverse[*row] = view_row;
+            }
+            self.order = order;
+        }
+
+        self.selection.selected = selected.into_iter().map(|r| self.view_row(r)).collect();
+        self.selection.cursor = cursor.map(|row| self.view_row(row));
+        self.selection.anchor = anchor.map(|row| self.view_row(row));
+        for row in &mut self.rows {
+            *row = None;
+        }
+    }
+
+    // Number of rows of widgets to construct
+    fn pool_target(&self) -> usize {
+        let visible = match self.row_len {
+            0 => 0,
+            len => ((self.body.size.1 + len - 1) / len) as usize,
+        };
+        (self.ideal_len.max(visible) + 2).min(self.data.len().1)
+    }
+
+    // Construct or remove widgets to match the pool target
+    fn resize_pool(&mut self) -> TkAction {
+        let cols = self.headers.len();
+        let target = self.pool_target();
+        if target == self.rows.len() && self.widgets.len() == target * cols {
+            return TkAction::None;
+        }
+
+        self.widgets.truncate(target * cols);
+        while self.widgets.len() < target * cols {
+            let index = self.widgets.len();
+            let (slot, col) = (index / cols, index % cols);
+            let row = self.data_row(slot);
+            self.widgets.push(W::new(self.data.get((col, row))));
+        }
+        self.rows.clear();
+        self.rows.resize(target, None);
+        TkAction::Reconfigure
+    }
+
+    // Range of view rows at least partially visible
+    fn visible_range(&self) -> Range<usize> {
+        if self.row_len == 0 {
+            return 0..0;
+        }
+        let first = self.offset / self.row_len;
+        let last = (self.offset + self.body.size.1 + self.row_len - 1) / self.row_len;
+        (first as usize)..(last as usize).min(self.data.len().1)
+    }
+
+    // Assign data rows to widgets
+    //
+    // As with `ListView`, view row `i` is viewed by slot `i % n`.
+    fn assign_rows(&mut self) -> TkAction {
+        let (cols, n) = (self.headers.len(), self.rows.len());
+        if n == 0 {
+            return TkAction::None;
+        }
+        let len = self.data.len().1;
+        let start = self.visible_range().start.min(len.saturating_sub(n));
+        let mut action = TkAction::None;
+        for view_row in start..(start + n).min(len) {
+            let slot = view_row % n;
+            if self.rows[slot] != Some(view_row) {
+                let row = self.data_row(view_row);
+                for col in 0..cols {
+                    let item = self.data.get((col, row));
+                    action += self.widgets[slot * cols + col].set(item);
+                }
+                self.rows[slot] = Some(view_row);
+            }
+        }
+        action
+    }
+
+    // Re-assign widgets after scrolling or a data change
+    fn update_widgets(&mut self, mgr: &mut Manager) {
+        let action = self.assign_rows();
+        if action == TkAction::Resize {
+            self.size_widgets(mgr);
+        } else if action > TkAction::Resize {
+            *mgr += action;
+        }
+        self.set_widget_rects();
+        *mgr += TkAction::RegionMoved;
+    }
+
+    // Size cell widgets before calling set_rect
+    fn size_widgets(&mut self, mgr: &mut Manager) {
+        // Row height is uniform, thus we need only resize widgets
+        let cols = self.headers.len();
+        let (col_pos, row_len) = (&self.col_pos, self.row_len);
+        let widgets = &mut self.widgets;
+        mgr.size_handle(|size_handle| {
+            for (i, w) in widgets.iter_mut().enumerate() {
+                let width = col_pos.get(i % cols).map(|pos| pos.1).unwrap_or(0);
+                w.size_rules(size_handle, AxisInfo::new(false, Some(row_len)));
+                w.size_rules(size_handle, AxisInfo::new(true, Some(width)));
+            }
+        });
+    }
+
+    // Size headers (after a change of text) before calling set_rect
+    fn size_headers(&mut self, mgr: &mut Manager) {
+        let (col_pos, header_h) = (&self.col_pos, self.header_h);
+        let headers = &mut self.headers;
+        mgr.size_handle(|size_handle| {
+            for (header, pos) in headers.iter_mut().zip(col_pos.iter()) {
+                header.size_rules(size_handle, AxisInfo::new(false, Some(header_h)));
+                header.size_rules(size_handle, AxisInfo::new(true, Some(pos.1)));
+            }
+        });
+    }
+
+    // Construct rows of widgets if the pool is too small
+    //
+    // As with `ListView`, new widgets are configured in place and sized
+    // immediately.
+    fn grow_pool(&mut self, mgr: &mut Manager) {
+        if self.pool_target() <= self.rows.len() {
+            return;
+        }
+        let _ = self.resize_pool();
+        if !mgr.configure_children(self.pool_ids, self) {
+            *mgr += TkAction::Reconfigure;
+            return;
+        }
+        let _ = self.assign_rows();
+        self.size_widgets(mgr);
+        self.set_widget_rects();
+        *mgr += TkAction::RegionMoved;
+    }
+
+    // Gap between grid columns `i` and `i + 1`, from margins
+    fn gap(&mut self, i: usize) -> i32 {
+        let rules = self.grid.width_rules();
+        let margins = (rules[i].margins().1, rules[i + 1].margins().0);
+        margins.0.max(margins.1) as i32
+    }
+
+    // Set rects of headers, handles and cells from column positions
+    fn set_column_rects(&mut self) {
+        let pos = self.core.rect.pos;
+        let header_h = self.header_h;
+        for (header, (x, w)) in self.headers.iter_mut().zip(self.col_pos.iter()) {
+            let rect = Rect::new(Coord(*x, pos.1), Size(*w, header_h));
+            header.set_rect(rect, AlignHints::NONE);
+        }
+
+        for n in 0..self.handles.len() {
+            let (gap0, gap1) = (self.gap(2 * n), self.gap(2 * n + 1));
+            let (x0, w0) = self.col_pos[n];
+            let (x1, w1) = self.col_pos[n + 1];
+            let start = x0 + gap0;
+            let end = x1 + w1 as i32 - gap1;
+            let track = Rect::new(Coord(start, pos.1), Size((end - start) as u32, header_h));
+            self.handles[n].set_rect(track, AlignHints::NONE);
+            let offset = Coord(x0 + w0 as i32 + gap0 - start, 0);
+            let size = Size(self.handle_width, header_h);
+            let _ = self.handles[n].set_size_and_offset(size, offset);
+        }
+
+        self.set_widget_rects();
+    }
+
+    // Adjust columns `n` and `n + 1` after a move of handle `n`
+    fn adjust_columns(&mut self, n: usize) {
+        let (gap0, gap1) = (self.gap(2 * n), self.gap(2 * n + 1));
+        let x = self.handles[n].rect().pos.0;
+        let (x0, _) = self.col_pos[n];
+        let (x1, w1) = self.col_pos[n + 1];
+        let end = x1 + w1 as i32;
+        self.col_pos[n].1 = (x - gap0 - x0).max(0) as u32;
+        let x1 = x + self.handle_width as i32 + gap1;
+        self.col_pos[n + 1] = (x1, (end - x1).max(0) as u32);
+        self.col_widths[n] = Some(self.col_pos[n].1);
+        if self.col_widths[n + 1].is_some() {
+            self.col_widths[n + 1] = Some(self.col_pos[n + 1].1);
+        }
+        self.set_column_rects();
+    }
+
+    // Re-apply column widths set by dragging handles
+    //
+    // Spacing between columns is unchanged. Any difference in total width is
+    // absorbed by other columns (the last first), then by columns with a set
+    // width.
+    fn apply_col_widths(&mut self) {
+        let cols = self.col_pos.len();
+        self.col_widths.resize(cols, None);
+        if self.col_widths.iter().all(|w| w.is_none()) {
+            return;
+        }
+
+        let (start, (x, w)) = (self.col_pos[0].0, self.col_pos[cols - 1]);
+        let gaps: Vec<i32> = (1..cols)
+            .map(|i| self.col_pos[i].0 - self.col_pos[i - 1].0 - self.col_pos[i - 1].1 as i32)
+            .collect();
+        let total = x + w as i32 - start - gaps.iter().sum::<i32>();
+        let mut widths: Vec<i32> = (self.col_widths.iter().zip(self.col_pos.iter()))
+            .map(|(set, pos)| set.unwrap_or(pos.1) as i32)
+            .collect();
+
+        let mut excess = total - widths.iter().sum::<i32>();
+        let col_widths = &self.col_widths;
+        let free = (0..cols).rev().filter(|i| col_widths[*i].is_none());
+        let set = (0..cols).rev().filter(|i| col_widths[*i].is_some());
+        for i in free.chain(set) {
+            let w = (widths[i] + excess).max(0);
+            excess -= w - widths[i];
+            widths[i] = w;
+        }
+
+        let mut x = start;
+        for i in 0..cols {
+            self.col_pos[i] = (x, widths[i] as u32);
+            x += widths[i] + gaps.get(i).cloned().unwrap_or(0);
+        }
+    }
+
+    // Rect of the cell at `(col, view_row)`, in content coordinates
+    fn cell_rect(&self, col: usize, view_row: usize) -> Rect {
+        let (x, w) = self.col_pos[col];
+        let y = self.body.pos.1 + (view_row as u32 * self.row_len) as i32;
+        Rect::new(Coord(x, y), Size(w, self.row_len))
+    }
+
+    // Rect of the row at `view_row`, in content coordinates
+    fn row_rect(&self, view_row: usize) -> Rect {
+        let y = self.body.pos.1 + (view_row as u32 * self.row_len) as i32;
+        Rect::new(
+            Coord(self.body.pos.0, y),
+            Size(self.body.size.0, self.row_len),
+        )
+    }
+
+    fn set_widget_rects(&mut self) {
+        let cols = self.col_pos.len();
+        for slot in 0..self.rows.len() {
+            if let Some(view_row) = self.rows[slot] {
+                for col in 0..cols {
+                    let rect = self.cell_rect(col, view_row);
+                    self.widgets[slot * cols + col].set_rect(rect, AlignHints::NONE);
+                }
+            }
+        }
+    }
+
+    // Translation of content (cell widgets)
+    fn scroll_coord(&self) -> Coord {
+        Coord(0, self.offset as i32)
+    }
+
+    // Find the view row at `coord` (in window coordinates)
+    fn row_at(&self, coord: Coord) -> Option<usize> {
+        if self.row_len == 0 || !self.body.contains(coord) {
+            return None;
+        }
+        let dist = (coord.1 - self.body.pos.1) as u32;
+        let row = ((dist + self.offset) / self.row_len) as usize;
+        Some(row).filter(|row| *row < self.data.len().1)
+    }
+
+    // Find the column at `x` (in window coordinates)
+    fn col_at(&self, x: i32) -> Option<usize> {
+        (self.col_pos.iter()).position(|(x0, w)| *x0 <= x && x < *x0 + *w as i32)
+    }
+
+    // Index of the slot viewing `view_row`, if any
+    fn slot_of(&self, view_row: usize) -> Option<usize> {
+        let n = self.rows.len();
+        if n > 0 && self.rows[view_row % n] == Some(view_row) {
+            Some(view_row % n)
+        } else {
+            None
+        }
+    }
+
+    fn update_limits(&mut self) -> TkAction {
+        let content = (self.data.len().1 as u64 * self.row_len as u64).min(i32::MAX as u64);
+        let view_len = self.body.size.1;
+        self.max_offset = (content as u32).saturating_sub(view_len);
+        self.offset = self.offset.min(self.max_offset);
+        self.bar.set_limits(self.max_offset, view_len) + self.bar.set_value(self.offset)
+    }
+
+    fn scroll_by(&mut self, mgr: &mut Manager, delta: i32) -> bool {
+        let offset = (self.offset as i32 - delta).max(0) as u32;
+        let old_offset = self.offset;
+        self.set_offset(mgr, offset);
+        self.offset != old_offset
+    }
+
+    fn data_changed(&mut self, mgr: &mut Manager) {
+        self.selection.truncate(self.data.len().1);
+        self.sort_rows();
+        *mgr += self.update_limits();
+
+        if self.pool_target() < self.rows.len() {
+            *mgr += TkAction::Reconfigure;
+        } else {
+            for row in &mut self.rows {
+                *row = None;
+            }
+            self.grow_pool(mgr);
+            self.update_widgets(mgr);
+        }
+    }
+
+    // Select `view_row` in response to user input, with modifiers
+    fn select_row(
+        &mut self,
+        mgr: &mut Manager,
+        view_row: usize,
+        modifiers: ModifiersState,
+    ) -> Response<<Self as Handler>::Msg> {
+        *mgr += TkAction::Redraw;
+        match self.selection.press(view_row, modifiers) {
+            true => Response::Msg(ViewMsg::Selection),
+            false => Response::None,
+        }
+    }
+
+    fn control_key(
+        &mut self,
+        mgr: &mut Manager,
+        key: ControlKey,
+        modifiers: ModifiersState,
+    ) -> Response<<Self as Handler>::Msg> {
+        let keys = (ControlKey::Up, ControlKey::Down);
+        let page = match self.row_len {
+            0 => 1,
+            row_len => (self.body.size.1 / row_len).max(1) as usize,
+        };
+        let row = match self
+            .selection
+            .nav_target(key, keys, self.data.len().1, page)
+        {
+            Some(row) => row,
+            None => return Response::Unhandled(Event::Control(key, modifiers)),
+        };
+
+        self.scroll_to_view_row(mgr, row);
+        *mgr += TkAction::Redraw;
+        match self.selection.navigate(row, modifiers) {
+            true => Response::Msg(ViewMsg::Selection),
+            false => Response::None,
+        }
+    }
+
+    fn activate(&self, view_row: usize) -> Response<<Self as Handler>::Msg> {
+        Response::Msg(ViewMsg::Activate((self.press_col, self.data_row(view_row))))
+    }
+}
+
+fn cell_info(col: usize, row: u32) -> GridChildInfo {
+    let col = col as u32;
+    GridChildInfo {
+        col,
+        col_end: col + 1,
+        row,
+        row_end: row + 1,
+    }
+}
+
+impl<A: Accessor<(usize, usize)>, W: ViewWidget<A::Item>> WidgetChildren for Table<A, W>
+where
+    A::Item: PartialOrd,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.headers.len() + self.handles.len() + self.widgets.len() + 1
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        let mut index = index;
+        if index < self.headers.len() {
+            return Some(self.headers[index].as_widget());
+        }
+        index -= self.headers.len();
+        if index < self.handles.len() {
+            return Some(self.handles[index].as_widget());
+        }
+        index -= self.handles.len();
+        if index < self.widgets.len() {
+            Some(self.widgets[index].as_widget())
+        } else if index == self.widgets.len() {
+            Some(self.bar.as_widget())
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        let mut index = index;
+        if index < self.headers.len() {
+            return Some(self.headers[index].as_widget_mut());
+        }
+        index -= self.headers.len();
+        if index < self.handles.len() {
+            return Some(self.handles[index].as_widget_mut());
+        }
+        index -= self.handles.len();
+        if index < self.widgets.len() {
+            Some(self.widgets[index].as_widget_mut())
+        } else if index == self.widgets.len() {
+            Some(self.bar.as_widget_mut())
+        } else {
+            None
+        }
+    }
+}
+
+impl<A: Accessor<(usize, usize)>, W: ViewWidget<A::Item>> WidgetConfig for Table<A, W>
+where
+    A::Item: PartialOrd,
+{
+    fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
+        // Widgets are constructed here; the pool may later grow in place
+        let _ = self.resize_pool();
+        let _ = self.assign_rows();
+        self.pool_ids = cmgr.reserve(POOL_IDS);
+
+        for i in 0..WidgetChildren::len(self) {
+            if let Some(w) = self.get_mut(i) {
+                let key = w.key();
+                w.configure_recurse(cmgr.child(i, key));
+            }
+        }
+        self.core_data_mut().stable_id = cmgr.stable_id();
+        self.core_data_mut().id = cmgr.next_id(self.id());
+        self.configure(cmgr.mgr());
+    }
+
+    fn configure(&mut self, mgr: &mut Manager) {
+        if let Some(handle) = self.data.update_handle() {
+            mgr.update_on_handle(handle, self.id());
+        }
+        // Check for a shortage of widgets once sized
+        mgr.update_on_resize(self.id());
+    }
+
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn access_role(&self) -> Role {
+        Role::Table
+    }
+}
+
+impl<A: Accessor<(usize, usize)>, W: ViewWidget<A::Item>> Layout for Table<A, W>
+where
+    A::Item: PartialOrd,
+{
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let _ = self.assign_rows();
+        self.scroll_rate = 3.0 * size_handle.line_height(TextClass::Label) as f32;
+        self.bar_width = match self.show_bar {
+            false => 0,
+            true => (size_handle.scrollbar().0).1,
+        };
+        let cols = self.headers.len();
+        if cols == 0 {
+            return SizeRules::EMPTY;
+        }
+
+        self.handle_width = size_handle.frame().0;
+        let handle_rules = SizeRules::fixed(self.handle_width, (0, 0));
+
+        // The grid excludes the scroll bar
+        let grid_axis = match axis.is_vertical() {
+            false => axis,
+            true => AxisInfo::new(true, axis.other().map(|w| w.saturating_sub(self.bar_width))),
+        };
+        let dim = (2 * cols - 1, 2);
+        type Spans = [(SizeRules, u32, u32); 0];
+        let mut solver = GridSolver::<Spans, Spans, _>::new(grid_axis, dim, &mut self.grid);
+        for col in 0..cols {
+            let header = &mut self.headers[col];
+            solver.for_child(&mut self.grid, cell_info(2 * col, 0), |axis| {
+                header.size_rules(size_handle, axis)
+            });
+            if col + 1 < cols && axis.is_horizontal() {
+                solver.for_child(&mut self.grid, cell_info(2 * col + 1, 0), |_| handle_rules);
+            }
+            for slot in 0..self.rows.len() {
+                let w = &mut self.widgets[slot * cols + col];
+                solver.for_child(&mut self.grid, cell_info(2 * col, 1), |axis| {
+                    w.size_rules(size_handle, axis)
+                });
+            }
+        }
+        let mut rules = solver.finish(&mut self.grid);
+
+        if axis.is_horizontal() {
+            if self.show_bar {
+                rules.append(self.bar.size_rules(size_handle, axis));
+            }
+            rules
+        } else {
+            let header = self.grid.height_rules()[0];
+            let row = self.grid.height_rules()[1];
+            self.header_h = header.ideal_size();
+            let margins = row.margins();
+            self.row_len = row.ideal_size() + margins.0 as u32 + margins.1 as u32;
+            let len = self.data.len().1;
+            let min = self.row_len * len.min(1) as u32;
+            let ideal = self.row_len * len.min(self.ideal_len) as u32;
+            header.appended(SizeRules::new(
+                min,
+                ideal,
+                (0, 0),
+                StretchPolicy::HighUtility,
+            ))
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        let cols = self.headers.len();
+        let width = rect.size.0.saturating_sub(self.bar_width);
+        self.header_h = self.header_h.min(rect.size.1);
+        let body_pos = Coord(rect.pos.0, rect.pos.1 + self.header_h as i32);
+        self.body = Rect::new(body_pos, Size(width, rect.size.1 - self.header_h));
+        if self.show_bar {
+            let pos = Coord(body_pos.0 + width as i32, body_pos.1);
+            let bar_rect = Rect::new(pos, Size(self.bar_width, self.body.size.1));
+            self.bar.set_rect(bar_rect, AlignHints::NONE);
+        }
+
+        self.col_pos.clear();
+        if cols > 0 {
+            let grid_rect = Rect::new(rect.pos, Size(width, self.header_h + self.row_len));
+            let dim = (2 * cols - 1, 2);
+            let mut setter = GridSetter::<Vec<u32>, Vec<u32>, _>::new(
+                grid_rect,
+                dim,
+                AlignHints::NONE,
+                &mut self.grid,
+            );
+            for col in 0..cols {
+                let rect = setter.child_rect(&mut self.grid, cell_info(2 * col, 0));
+                self.col_pos.push((rect.pos.0, rect.size.0));
+            }
+            self.apply_col_widths();
+        }
+
+        let _ = self.update_limits();
+        self.set_column_rects();
+    }
+
+    fn translation(&self, child_index: usize) -> Coord {
+        let start = self.headers.len() + self.handles.len();
+        match child_index >= start && child_index < start + self.widgets.len() {
+            true => self.scroll_coord(),
+            false => Coord::ZERO,
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+
+        for handle in &self.handles {
+            if let Some(id) = handle.find_id(coord) {
+                return Some(id);
+            }
+        }
+        for header in &self.headers {
+            if let Some(id) = header.find_id(coord) {
+                return Some(id);
+            }
+        }
+        if self.show_bar {
+            if let Some(id) = self.bar.find_id(coord) {
+                return Some(id);
+            }
+        }
+        let cols = self.headers.len();
+        if let Some(slot) = self.row_at(coord).and_then(|row| self.slot_of(row)) {
+            if let Some(col) = self.col_at(coord.0) {
+                let w = &self.widgets[slot * cols + col];
+                if let Some(id) = w.find_id(coord + self.scroll_coord()) {
+                    return Some(id);
+                }
+            }
+        }
+        Some(self.id())
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        for header in &self.headers {
+            header.draw(draw_handle, mgr, disabled);
+        }
+        for handle in &self.handles {
+            draw_handle.separator(handle.rect());
+        }
+        if self.show_bar {
+            self.bar.draw(draw_handle, mgr, disabled);
+        }
+
+        let cols = self.headers.len();
+        let nav_focus = mgr.nav_focus(self.id());
+        let offset = self.scroll_coord();
+        draw_handle.clip_region(self.body, offset, ClipRegion::Scroll, &mut |handle| {
+            for row in self.visible_range() {
+                if let Some(slot) = self.slot_of(row) {
+                    let cells = &self.widgets[slot * cols..(slot + 1) * cols];
+                    let mut state = (cells.iter())
+                        .map(|w| w.input_state(mgr, disabled))
+                        .fold(Default::default(), |a, b| a | b);
+                    state.nav_focus = nav_focus && self.selection.cursor == Some(row);
+                    let selected = self.selection.selected.contains(&row);
+                    handle.view_row(self.row_rect(row), selected, state);
+                    for w in cells {
+                        w.draw(handle, mgr, disabled);
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl<A: Accessor<(usize, usize)>, W: ViewWidget<A::Item>> event::SendEvent for Table<A, W>
+where
+    A::Item: PartialOrd,
+{
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        let cols = self.headers.len();
+        let n = self.widgets.len();
+        let event = if cols > 0 && id <= self.headers[cols - 1].id() {
+            let col = match self.headers.binary_search_by_key(&id, |w| w.id()) {
+                Ok(col) => col,
+                Err(col) => col,
+            };
+            match Response::<Self::Msg>::try_from(self.headers[col].send(mgr, id, event)) {
+                Ok(Response::Unhandled(event)) => event,
+                Ok(r) => return r,
+                Err(col) => {
+                    let descending = self.sort_col == Some((col, false));
+                    self.set_sort_column(mgr, Some((col, descending)));
+                    return Response::None;
+                }
+            }
+        } else if !self.handles.is_empty() && id <= self.handles[self.handles.len() - 1].id() {
+            let index = match self.handles.binary_search_by_key(&id, |w| w.id()) {
+                Ok(index) => index,
+                Err(index) => index,
+            };
+            match Response::<Self::Msg>::try_from(self.handles[index].send(mgr, id, event)) {
+                Ok(Response::Unhandled(event)) => event,
+                Ok(r) => return r,
+                Err(_) => {
+                    // The handle has already adjusted its position
+                    self.adjust_columns(index);
+                    *mgr += TkAction::Redraw;
+                    return Response::None;
+                }
+            }
+        } else if n > 0 && id <= self.widgets[n - 1].id() {
+            let index = match self.widgets.binary_search_by_key(&id, |w| w.id()) {
+                Ok(index) => index,
+                Err(index) => index,
+            };
+            let (slot, col) = (index / cols, index % cols);
+            let view_row = self.rows[slot];
+            let offset = self.scroll_coord();
+            let event = translate_press(event, offset);
+            match self.widgets[index].send(mgr, id, event) {
+                Response::None => return Response::None,
+                Response::Unhandled(event) => translate_press(event, Coord::ZERO - offset),
+                Response::Focus(rect) => {
+                    if let Some(row) = view_row {
+                        self.scroll_to_view_row(mgr, row);
+                    }
+                    return Response::Focus(rect - self.scroll_coord());
+                }
+                Response::Msg(msg) => match view_row {
+                    Some(row) => {
+                        let index = (col, self.data_row(row));
+                        return Response::Msg(ViewMsg::Item(index, msg));
+                    }
+                    None => return Response::None,
+                },
+            }
+        } else if id <= self.bar.id() {
+            match Response::<Self::Msg>::try_from(self.bar.send(mgr, id, event)) {
+                Ok(Response::Unhandled(event)) => event,
+                Ok(r) => return r,
+                Err(offset) => {
+                    self.set_offset(mgr, offset);
+                    return Response::None;
+                }
+            }
+        } else {
+            event
+        };
+
+        match event {
+            Event::Activate => match self.selection.cursor {
+                Some(row) if mgr.modifiers().ctrl() => {
+                    self.select_row(mgr, row, ModifiersState::CTRL)
+                }
+                Some(row) => self.activate(row),
+                None => Response::None,
+            },
+            Event::Control(key, modifiers) => self.control_key(mgr, key, modifiers),
+            Event::Scroll(delta) => {
+                let d = match delta {
+                    LineDelta(_, y) => (self.scroll_rate * y) as i32,
+                    PixelDelta(d) => d.1,
+                };
+                match self.scroll_by(mgr, d) {
+                    true => Response::None,
+                    false => Response::Unhandled(Event::Scroll(delta)),
+                }
+            }
+            Event::PressStart { source, coord, .. } if source.is_primary() => {
+                mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                mgr.set_nav_focus(self.id());
+                if let Some(col) = self.col_at(coord.0) {
+                    self.press_col = col;
+                }
+                match self.row_at(coord) {
+                    Some(row) if source.repetitions() == 2 => self.activate(row),
+                    Some(row) => {
+                        let modifiers = mgr.modifiers();
+                        self.select_row(mgr, row, modifiers)
+                    }
+                    None => Response::None,
+                }
+            }
+            Event::PressMove { delta, .. } => {
+                self.scroll_by(mgr, delta.1);
+                Response::None
+            }
+            Event::PressEnd { .. } => Response::None,
+            Event::Resized => {
+                self.grow_pool(mgr);
+                Response::None
+            }
+            Event::HandleUpdate { .. } => {
+                self.data_changed(mgr);
+                Response::None
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
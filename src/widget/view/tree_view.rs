@@ -0,0 +1,451 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Tree view widget
+
+use std::fmt::Debug;
+
+use super::{ViewMsg, ViewWidget};
+use kas::access::Role;
+use kas::class::CloneText;
+use kas::event::ControlKey;
+use kas::prelude::*;
+use kas::widget::{List, ScrollRegion};
+
+/// Hierarchical data access
+///
+/// This trait is the interface between [`TreeView`] and data. Nodes are
+/// identified by keys; children of a node are requested only when the node is
+/// first expanded, thus large or slow hierarchies (such as a file system) may
+/// be browsed cheaply.
+pub trait TreeAccessor: Debug + 'static {
+    /// Type of node key
+    type Key: Clone + Debug + PartialEq + 'static;
+
+    /// Type of data item
+    type Item;
+
+    /// Get the keys of children of `parent`, or of root nodes if `None`
+    fn children(&self, parent: Option<&Self::Key>) -> Vec<Self::Key>;
+
+    /// Whether the node `key` may have children
+    ///
+    /// This controls whether an expander is shown. It is permissible to return
+    /// `true` for a node which turns out to have no children when expanded.
+    fn has_children(&self, key: &Self::Key) -> bool;
+
+    /// Get data for node `key`
+    fn get(&self, key: &Self::Key) -> Self::Item;
+}
+
+#[derive(Clone, Debug)]
+struct Node<K> {
+    key: K,
+    depth: u32,
+    expanded: bool,
+    // Indices of child nodes, once loaded
+    children: Option<Vec<usize>>,
+}
+
+#[derive(Clone, Debug)]
+enum RowMsg<M> {
+    Toggle(usize),
+    Activate(usize),
+    Item(usize, M),
+}
+
+// A row of a tree: indentation, expander and item widget
+#[widget(config=noauto)]
+#[handler(noauto)]
+#[derive(Clone, Debug, Widget)]
+struct TreeRow<W: Widget> {
+    #[widget_core]
+    core: CoreData,
+    node: usize,
+    depth: u32,
+    // None if the node has no children
+    expanded: Option<bool>,
+    expander: Rect,
+    expander_x: u32,
+    inner_x: u32,
+    #[widget]
+    inner: W,
+}
+
+impl<W: Widget> WidgetConfig for TreeRow<W> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn as_clone_text(&self) -> Option<&dyn CloneText> {
+        self.inner.as_clone_text()
+    }
+
+    fn access_role(&self) -> Role {
+        Role::TreeItem
+    }
+}
+
+impl<W: Widget> Layout for TreeRow<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let size = size_handle.checkbox();
+        let margins = size_handle.outer_margins();
+        self.expander.size = size;
+        if axis.is_horizontal() {
+            self.expander_x = self.depth * size.0 + margins.horiz.0 as u32;
+            self.inner_x = self.expander_x + size.0 + margins.horiz.1 as u32;
+            let rules = self.inner.size_rules(size_handle, axis);
+            SizeRules::fixed(self.inner_x, (0, 0)).appended(rules)
+        } else {
+            let other = axis.other().map(|w| w.saturating_sub(self.inner_x));
+            let rules = self
+                .inner
+                .size_rules(size_handle, AxisInfo::new(true, other));
+            SizeRules::extract_fixed(true, size, margins).max(rules)
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let y = rect.pos.1 + (rect.size.1.saturating_sub(self.expander.size.1) / 2) as i32;
+        self.expander.pos = Coord(rect.pos.0 + self.expander_x as i32, y);
+        let inner_x = self.inner_x.min(rect.size.0);
+        let pos = Coord(rect.pos.0 + inner_x as i32, rect.pos.1);
+        let size = Size(rect.size.0 - inner_x, rect.size.1);
+        self.inner.set_rect(Rect::new(pos, size), align);
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        self.inner.find_id(coord).or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        let state = self.input_state(mgr, disabled) | self.inner.input_state(mgr, disabled);
+        draw_handle.view_row(self.core.rect, false, state);
+        if let Some(expanded) = self.expanded {
+            draw_handle.expander(self.expander, expanded, state);
+        }
+        self.inner.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<W: Widget> event::Handler for TreeRow<W> {
+    type Msg = RowMsg<<W as event::Handler>::Msg>;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::Activate => Response::Msg(RowMsg::Activate(self.node)),
+            Event::PressStart { source, coord, .. } if source.is_primary() => {
+                mgr.set_nav_focus(self.id());
+                if self.expanded.is_some() && self.expander.contains(coord) {
+                    Response::Msg(RowMsg::Toggle(self.node))
+                } else if source.repetitions() == 2 {
+                    Response::Msg(RowMsg::Activate(self.node))
+                } else {
+                    Response::None
+                }
+            }
+            event => Response::Unhandled(event),
+        }
+    }
+}
+
+impl<W: Widget> event::SendEvent for TreeRow<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        let event = if id <= self.inner.id() {
+            match Response::<Self::Msg>::try_from(self.inner.send(mgr, id, event)) {
+                Ok(Response::Unhandled(event)) => event,
+                Ok(r) => return r,
+                Err(msg) => return Response::Msg(RowMsg::Item(self.node, msg)),
+            }
+        } else {
+            event
+        };
+        Manager::handle_generic(self, mgr, event)
+    }
+}
+
+/// A tree view
+///
+/// This widget displays hierarchical data from a [`TreeAccessor`] as a column
+/// of indented rows within a [`ScrollRegion`], using widgets of type `W` (see
+/// [`ViewWidget`]) to view each node. Nodes with children have an expander
+/// toggle; children are requested from the accessor when a node is first
+/// expanded and cached thereafter (see [`TreeView::reload`]).
+///
+/// Unlike [`super::ListView`], a widget is constructed for each visible row,
+/// thus this widget is not suitable for viewing very large numbers of nodes
+/// at once. Expanding or collapsing a node triggers a reconfigure.
+///
+/// Keyboard navigation: Up and Down move the focus to the previous and next
+/// row, Home and End move to the first and last row; Right expands a node or
+/// moves to its first child; Left collapses a node or moves to its parent.
+///
+/// Messages use the node key as index: messages from item widgets are wrapped
+/// as [`ViewMsg::Item`]; double-clicking a row or pressing Space or Return
+/// yields [`ViewMsg::Activate`].
+#[layout(single)]
+#[widget(config(role = Role::Tree))]
+#[handler(send=noauto, msg = ViewMsg<A::Key, <W as event::Handler>::Msg>)]
+#[derive(Clone, Debug, Widget)]
+pub struct TreeView<A: TreeAccessor, W: ViewWidget<A::Item>> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    scroll: ScrollRegion<List<kas::Down, TreeRow<W>>>,
+    data: A,
+    nodes: Vec<Node<A::Key>>,
+}
+
+impl<A: TreeAccessor, W: ViewWidget<A::Item>> TreeView<A, W> {
+    /// Construct a new instance
+    ///
+    /// Root nodes are read immediately; other nodes are read on expansion.
+    pub fn new(data: A) -> Self {
+        let mut view = TreeView {
+            core: Default::default(),
+            scroll: ScrollRegion::new(List::new(vec![])).with_bars(false, true),
+            data,
+            nodes: vec![],
+        };
+        let _ = view.reload();
+        view
+    }
+
+    /// Access the data accessor
+    #[inline]
+    pub fn data(&self) -> &A {
+        &self.data
+    }
+
+    /// Discard cached nodes and re-read root nodes
+    ///
+    /// All nodes are collapsed. Triggers a
+    /// [reconfigure action](Manager::send_action).
+    pub fn reload(&mut self) -> TkAction {
+        self.nodes.clear();
+        let roots = self.add_nodes(None, 0);
+        let rows = roots.into_iter().map(|node| self.make_row(node)).collect();
+        *self.scroll.inner_mut() = List::new(rows);
+        TkAction::Reconfigure
+    }
+
+    /// Check whether the node `key` is expanded
+    ///
+    /// Returns false if the node is not loaded.
+    pub fn is_expanded(&self, key: &A::Key) -> bool {
+        self.find_node(key)
+            .map(|node| self.nodes[node].expanded)
+            .unwrap_or(false)
+    }
+
+    /// Expand or collapse the node `key`
+    ///
+    /// This does nothing unless the node is currently visible.
+    pub fn set_expanded(&mut self, key: &A::Key, expanded: bool) -> TkAction {
+        match self.find_node(key) {
+            Some(node) if self.nodes[node].expanded != expanded => self.toggle(node),
+            _ => TkAction::None,
+        }
+    }
+
+    fn find_node(&self, key: &A::Key) -> Option<usize> {
+        self.nodes.iter().position(|node| node.key == *key)
+    }
+
+    // Read children of parent into new nodes, returning their indices
+    fn add_nodes(&mut self, parent: Option<&A::Key>, depth: u32) -> Vec<usize> {
+        let keys = self.data.children(parent);
+        let start = self.nodes.len();
+        self.nodes.extend(keys.into_iter().map(|key| Node {
+            key,
+            depth,
+            expanded: false,
+            children: None,
+        }));
+        (start..self.nodes.len()).collect()
+    }
+
+    fn make_row(&self, node: usize) -> TreeRow<W> {
+        let n = &self.nodes[node];
+        let has_children = match n.children {
+            Some(ref children) => !children.is_empty(),
+            None => self.data.has_children(&n.key),
+        };
+        TreeRow {
+            core: Default::default(),
+            node,
+            depth: n.depth,
+            expanded: Some(n.expanded).filter(|_| has_children),
+            expander: Rect::default(),
+            expander_x: 0,
+            inner_x: 0,
+            inner: W::new(self.data.get(&n.key)),
+        }
+    }
+
+    // Append visible descendants of node to rows, in order
+    fn visible_descendants(&self, node: usize, rows: &mut Vec<usize>) {
+        let n = &self.nodes[node];
+        if let (true, Some(children)) = (n.expanded, n.children.as_ref()) {
+            for child in children {
+                rows.push(*child);
+                self.visible_descendants(*child, rows);
+            }
+        }
+    }
+
+    // Index of the row viewing node, if visible
+    fn row_of(&self, node: usize) -> Option<usize> {
+        self.scroll.inner().iter().position(|row| row.node == node)
+    }
+
+    // Expand or collapse node
+    fn toggle(&mut self, node: usize) -> TkAction {
+        let index = match self.row_of(node) {
+            Some(index) => index,
+            None => return TkAction::None,
+        };
+
+        let mut action = TkAction::Redraw;
+        if self.nodes[node].expanded {
+            let mut rows = vec![];
+            self.visible_descendants(node, &mut rows);
+            self.nodes[node].expanded = false;
+            let list = self.scroll.inner_mut();
+            for _ in 0..rows.len() {
+                action += list.remove(index + 1).1;
+            }
+        } else {
+            if self.nodes[node].children.is_none() {
+                let (key, depth) = (self.nodes[node].key.clone(), self.nodes[node].depth);
+                let children = self.add_nodes(Some(&key), depth + 1);
+                self.nodes[node].children = Some(children);
+            }
+            self.nodes[node].expanded = true;
+            let mut rows = vec![];
+            self.visible_descendants(node, &mut rows);
+            if rows.is_empty() {
+                // The node turned out to be a leaf
+                self.nodes[node].expanded = false;
+            }
+            for (i, child) in rows.into_iter().enumerate() {
+                let row = self.make_row(child);
+                action += self.scroll.inner_mut().insert(index + 1 + i, row);
+            }
+        }
+
+        let expanded = self.nodes[node].expanded;
+        let has_children = self.nodes[node].children.as_ref().map(|c| !c.is_empty());
+        let row = &mut self.scroll.inner_mut()[index];
+        row.expanded = Some(expanded).filter(|_| has_children.unwrap_or(true));
+        action
+    }
+
+    // Move focus to row index and scroll it into view
+    fn focus_row(&mut self, mgr: &mut Manager, index: usize) -> Response<<Self as Handler>::Msg> {
+        let id = self.scroll.inner()[index].id();
+        mgr.set_nav_focus(id);
+        self.focus_response(mgr)
+    }
+
+    // Scroll the nav focus into view
+    fn focus_response(&mut self, mgr: &mut Manager) -> Response<<Self as Handler>::Msg> {
+        match mgr.nav_focus() {
+            Some(id) => match Response::try_from(self.scroll.send(mgr, id, Event::NavFocus)) {
+                Ok(r) => r,
+                Err(_) => Response::None,
+            },
+            None => Response::None,
+        }
+    }
+
+    // Handle a navigation key on the row at index
+    fn control_key(
+        &mut self,
+        mgr: &mut Manager,
+        index: usize,
+        key: ControlKey,
+    ) -> Option<Response<<Self as Handler>::Msg>> {
+        let len = self.scroll.inner().len();
+        let node = self.scroll.inner()[index].node;
+        let n = &self.nodes[node];
+        Some(match key {
+            ControlKey::Up if index > 0 => self.focus_row(mgr, index - 1),
+            ControlKey::Down if index + 1 < len => self.focus_row(mgr, index + 1),
+            ControlKey::Up | ControlKey::Down => Response::None,
+            ControlKey::Home => self.focus_row(mgr, 0),
+            ControlKey::End => self.focus_row(mgr, len - 1),
+            ControlKey::Right if self.scroll.inner()[index].expanded.is_some() => {
+                if n.expanded {
+                    self.focus_row(mgr, index + 1)
+                } else {
+                    *mgr += self.toggle(node);
+                    Response::None
+                }
+            }
+            ControlKey::Left if n.expanded => {
+                *mgr += self.toggle(node);
+                Response::None
+            }
+            ControlKey::Left if n.depth > 0 => {
+                let depth = n.depth;
+                let list = self.scroll.inner();
+                let parent = (0..index)
+                    .rev()
+                    .find(|i| self.nodes[list[*i].node].depth < depth);
+                match parent {
+                    Some(parent) => self.focus_row(mgr, parent),
+                    None => Response::None,
+                }
+            }
+            _ => return None,
+        })
+    }
+}
+
+impl<A: TreeAccessor, W: ViewWidget<A::Item>> event::SendEvent for TreeView<A, W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        // Navigation keys on rows must be intercepted before the scroll region
+        // uses them to scroll.
+        if let Event::Control(key, _) = event {
+            if let Some(index) = self.scroll.inner().iter().position(|row| row.id() == id) {
+                if let Some(r) = self.control_key(mgr, index, key) {
+                    return r;
+                }
+            }
+        }
+
+        if id <= self.scroll.id() {
+            match Response::<Self::Msg>::try_from(self.scroll.send(mgr, id, event)) {
+                Ok(r) => r,
+                Err(RowMsg::Toggle(node)) => {
+                    *mgr += self.toggle(node);
+                    Response::None
+                }
+                Err(RowMsg::Activate(node)) => {
+                    Response::Msg(ViewMsg::Activate(self.nodes[node].key.clone()))
+                }
+                Err(RowMsg::Item(node, msg)) => {
+                    Response::Msg(ViewMsg::Item(self.nodes[node].key.clone(), msg))
+                }
+            }
+        } else {
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+}
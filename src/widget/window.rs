@@ -5,15 +5,25 @@
 
 //! Window widgets
 
+use log::warn;
 use smallvec::SmallVec;
 use std::fmt::{self, Debug};
 
+use kas::access::Role;
 use kas::draw::ClipRegion;
-use kas::event::Callback;
+use kas::event::{Callback, ConfigureManager, ContextEntry, ContextMenu, ControlKey, GrabMode};
 use kas::prelude::*;
+use kas::widget::{Column, Menu, MenuEntry, MenuFrame, Separator};
 use kas::WindowId;
 
+/// Number of identifiers reserved for entries of the context menu
+const CONTEXT_MENU_IDS: u32 = 1024;
+
 /// The main instantiation of the [`Window`] trait.
+///
+/// The window owns a context menu, which is opened on
+/// [`Event::OpenContextMenu`] with entries contributed by the target widget
+/// and its ancestors (see [`WidgetConfig::context_menu`]).
 #[handler(send=noauto, generics = <> where W: Widget<Msg = VoidMsg>)]
 #[widget(config=noauto)]
 #[derive(Widget)]
@@ -22,8 +32,15 @@ pub struct Window<W: Widget + 'static> {
     core: CoreData,
     restrict_dimensions: (bool, bool),
     title: String,
+    state_key: Option<String>,
     #[widget]
     w: W,
+    #[widget]
+    menu: MenuFrame<Column<Box<dyn Menu<Msg = usize>>>>,
+    context: ContextMenu,
+    context_anchor: Rect,
+    context_popup: Option<WindowId>,
+    context_ids: WidgetId,
     popups: SmallVec<[(WindowId, kas::Popup); 16]>,
     fns: Vec<(Callback, &'static dyn Fn(&mut W, &mut Manager))>,
 }
@@ -52,8 +69,15 @@ impl<W: Widget + Clone> Clone for Window<W> {
             core: self.core.clone(),
             restrict_dimensions: self.restrict_dimensions.clone(),
             title: self.title.clone(),
+            state_key: self.state_key.clone(),
             w: self.w.clone(),
-            popups: Default::default(), // these are temporary; don't clone
+            // these are temporary; don't clone
+            menu: MenuFrame::new(Column::new(vec![])),
+            context: Default::default(),
+            context_anchor: Rect::default(),
+            context_popup: None,
+            context_ids: WidgetId::FIRST,
+            popups: Default::default(),
             fns: self.fns.clone(),
         }
     }
@@ -66,7 +90,13 @@ impl<W: Widget> Window<W> {
             core: Default::default(),
             restrict_dimensions: (true, false),
             title: title.to_string(),
+            state_key: None,
             w,
+            menu: MenuFrame::new(Column::new(vec![])),
+            context: Default::default(),
+            context_anchor: Rect::default(),
+            context_popup: None,
+            context_ids: WidgetId::FIRST,
             popups: Default::default(),
             fns: Vec::new(),
         }
@@ -79,6 +109,14 @@ impl<W: Widget> Window<W> {
         self.restrict_dimensions = (min, max);
     }
 
+    /// Set the key under which window state is persisted (chaining)
+    ///
+    /// See [`kas::Window::state_key`].
+    pub fn with_state_key<S: ToString>(mut self, key: S) -> Self {
+        self.state_key = Some(key.to_string());
+        self
+    }
+
     /// Add a closure to be called, with a reference to self, on the given
     /// condition. The closure must be passed by reference.
     // TODO: consider whether to keep this. The only functionality added is for
@@ -89,6 +127,21 @@ impl<W: Widget> Window<W> {
 }
 
 impl<W: Widget> WidgetConfig for Window<W> {
+    fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
+        let key = self.w.key();
+        self.w.configure_recurse(cmgr.child(0, key));
+        // Accelerator keys of the context menu are only active while it is open
+        cmgr.mgr().push_accel_layer(true);
+        self.context_ids = cmgr.reserve(CONTEXT_MENU_IDS);
+        let key = self.menu.key();
+        self.menu.configure_recurse(cmgr.child(1, key));
+        let id = self.menu.id();
+        cmgr.mgr().pop_accel_layer(id);
+        self.core_data_mut().stable_id = cmgr.stable_id();
+        self.core_data_mut().id = cmgr.next_id(self.id());
+        self.configure(cmgr.mgr());
+    }
+
     fn configure(&mut self, mgr: &mut Manager) {
         for (condition, f) in &self.fns {
             match condition {
@@ -97,6 +150,10 @@ impl<W: Widget> WidgetConfig for Window<W> {
             }
         }
     }
+
+    fn access_role(&self) -> Role {
+        Role::Window
+    }
 }
 
 impl<W: Widget> Layout for Window<W> {
@@ -118,7 +175,7 @@ impl<W: Widget> Layout for Window<W> {
             return None;
         }
         for popup in self.popups.iter().rev() {
-            if let Some(id) = self.w.find(popup.1.id).and_then(|w| w.find_id(coord)) {
+            if let Some(id) = self.find(popup.1.id).and_then(|w| w.find_id(coord)) {
                 return Some(id);
             }
         }
@@ -141,8 +198,27 @@ impl<W: Widget> Layout for Window<W> {
 
 impl<W: Widget<Msg = VoidMsg> + 'static> event::SendEvent for Window<W> {
     fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
-        if !self.is_disabled() && id <= self.w.id() {
+        if self.is_disabled() {
+            return Response::Unhandled(event);
+        }
+
+        if id <= self.w.id() {
             return self.w.send(mgr, id, event);
+        } else if id <= self.menu.id() {
+            return self.send_menu(mgr, id, event);
+        } else if id == self.id() {
+            match event {
+                Event::OpenContextMenu { menu, anchor } => {
+                    self.open_context_menu(mgr, menu, anchor);
+                    return Response::None;
+                }
+                // Commands without a registered handler
+                Event::Command(name) => {
+                    let id = self.w.id();
+                    return self.w.send(mgr, id, Event::Command(name));
+                }
+                event => return Response::Unhandled(event),
+            }
         }
         Response::Unhandled(event)
     }
@@ -157,6 +233,10 @@ impl<W: Widget<Msg = VoidMsg> + 'static> kas::Window for Window<W> {
         self.restrict_dimensions
     }
 
+    fn state_key(&self) -> Option<&str> {
+        self.state_key.as_deref()
+    }
+
     fn add_popup(&mut self, mgr: &mut Manager, id: WindowId, popup: kas::Popup) {
         let index = self.popups.len();
         self.popups.push((id, popup));
@@ -210,15 +290,156 @@ fn find_rect(widget: &dyn WidgetConfig, id: WidgetId) -> Option<Rect> {
     None
 }
 
+impl<W: Widget<Msg = VoidMsg>> Window<W> {
+    fn open_context_menu(&mut self, mgr: &mut Manager, menu: ContextMenu, anchor: Rect) {
+        if let Some(id) = self.context_popup {
+            mgr.close_window(id);
+        }
+
+        let entries = menu.entries().iter().enumerate().map(|(i, entry)| {
+            let w: Box<dyn Menu<Msg = usize>> = match entry {
+                ContextEntry::Action { label, enabled, .. } => {
+                    Box::new(MenuEntry::new(label.clone(), i).with_disabled(!enabled))
+                }
+                ContextEntry::Separator => Box::new(Separator::infer()),
+            };
+            w
+        });
+        // Entries are configured in place, using identifiers reserved for them
+        let _ = self.menu.inner.clear();
+        let _ = self.menu.inner.extend(entries);
+        mgr.push_accel_layer(true);
+        if !mgr.configure_children(self.context_ids, &mut self.menu) {
+            warn!("Window: context menu has too many entries");
+            let _ = self.menu.inner.clear();
+            mgr.configure_children(self.context_ids, &mut self.menu);
+            mgr.pop_accel_layer(self.menu.id());
+            return;
+        }
+        mgr.pop_accel_layer(self.menu.id());
+        self.context = menu;
+        self.context_anchor = anchor;
+
+        self.context_popup = Some(mgr.add_popup(kas::Popup {
+            id: self.menu.id(),
+            parent: self.menu.id(),
+            direction: Direction::Down,
+        }));
+        mgr.next_nav_focus(&self.menu, false);
+    }
+
+    // Handle events addressed to the context menu, which is also its own
+    // pop-up parent.
+    fn send_menu(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<VoidMsg> {
+        let r = if id == self.menu.id() {
+            match event {
+                Event::PressStart {
+                    source,
+                    start_id,
+                    coord,
+                } => {
+                    if !self.menu.is_ancestor_of(start_id) {
+                        // Close the menu and pass on the event
+                        return Response::Unhandled(Event::PressStart {
+                            source,
+                            start_id,
+                            coord,
+                        });
+                    }
+                    if source.is_primary()
+                        && mgr.request_grab(self.menu.id(), source, coord, GrabMode::Grab, None)
+                    {
+                        mgr.set_grab_depress(source, Some(start_id));
+                    }
+                    return Response::None;
+                }
+                Event::PressMove { source, cur_id, .. } => {
+                    let target = cur_id.filter(|id| self.menu.is_ancestor_of(*id));
+                    mgr.set_grab_depress(source, target);
+                    if let Some(w) = target.and_then(|id| self.menu.find(id)) {
+                        if w.key_nav() {
+                            mgr.set_nav_focus(w.id());
+                        }
+                    }
+                    return Response::None;
+                }
+                Event::PressEnd { end_id, .. } => {
+                    match end_id.filter(|id| self.menu.is_ancestor_of(*id)) {
+                        Some(id) => self.menu.send(mgr, id, Event::Activate),
+                        None => return Response::None,
+                    }
+                }
+                Event::NewPopup(popup) => {
+                    if popup != self.menu.id() {
+                        if let Some(id) = self.context_popup {
+                            mgr.close_window(id);
+                        }
+                    }
+                    return Response::None;
+                }
+                Event::PopupRemoved(id) => {
+                    // A replaced menu is removed after the new one is opened
+                    if self.context_popup == Some(id) {
+                        self.context_popup = None;
+                    }
+                    return Response::None;
+                }
+                event => self.menu.send(mgr, id, event),
+            }
+        } else {
+            self.menu.send(mgr, id, event)
+        };
+
+        match r {
+            Response::Msg(index) => {
+                if let Some(id) = self.context_popup {
+                    mgr.close_window(id);
+                }
+                self.activate_context_entry(mgr, index)
+            }
+            Response::Unhandled(Event::Control(key, modifiers)) => {
+                match key {
+                    ControlKey::Up => mgr.next_nav_focus(&self.menu, true),
+                    ControlKey::Down => mgr.next_nav_focus(&self.menu, false),
+                    ControlKey::Home | ControlKey::End => {
+                        mgr.clear_nav_focus();
+                        mgr.next_nav_focus(&self.menu, key == ControlKey::End)
+                    }
+                    key => return Response::Unhandled(Event::Control(key, modifiers)),
+                };
+                Response::None
+            }
+            r => Response::try_from(r).unwrap_or(Response::None),
+        }
+    }
+
+    // Send the event of context-menu entry `index` to its owner
+    fn activate_context_entry(&mut self, mgr: &mut Manager, index: usize) -> Response<VoidMsg> {
+        if let Some(ContextEntry::Action { owner, event, .. }) = self.context.get(index) {
+            let event = event.clone();
+            if let Some(id) = mgr.find_stable(*owner) {
+                if id <= self.w.id() {
+                    return self.w.send(mgr, id, event);
+                }
+            }
+        }
+        Response::None
+    }
+}
+
 impl<W: Widget> Window<W> {
     fn resize_popup(&mut self, size_handle: &mut dyn SizeHandle, index: usize) {
         // Notation: p=point/coord, s=size, m=margin
         // r=window/root rect, c=anchor rect
         let r = self.core.rect;
-        let popup = &mut self.popups[index].1;
+        let popup = self.popups[index].1.clone();
 
-        let c = find_rect(self.w.as_widget(), popup.parent).unwrap();
-        let widget = self.w.find_mut(popup.id).unwrap();
+        let (c, widget) = if popup.id == self.menu.id() {
+            (self.context_anchor, self.menu.as_widget_mut())
+        } else {
+            let c = find_rect(self.w.as_widget(), popup.parent).unwrap();
+            (c, self.w.find_mut(popup.id).unwrap())
+        };
         let mut cache = layout::SolveCache::find_constraints(widget, size_handle);
         let ideal = cache.ideal(false);
         let m = cache.margins();